@@ -1,5 +1,7 @@
 //! Check command - analyzes JavaScript/TypeScript files for issues
 
+use crate::commands::fix::{apply_fixes_to_files, fix_applicability, print_fix_summary};
+use crate::git::{ChangedFiles, overlaps};
 use crate::license::{LicenseSource, load_license};
 use crate::output::json::JsonFormatter;
 use crate::output::pretty::PrettyFormatter;
@@ -8,27 +10,30 @@ use anyhow::Result;
 use clap::Args;
 use colored::Colorize;
 use kaizen_core::analysis::AnalysisEngine;
-use kaizen_core::config::load_config_or_default_with_warnings;
+use kaizen_core::baseline::{
+    Baseline, BaselineComparison, BaselineEntry, BaselineState, Fingerprinter,
+};
+use kaizen_core::cache::{AnalysisCache, CACHE_DIRNAME, settings_key};
+use kaizen_core::config::{Config, load_config_or_default_with_warnings};
 use kaizen_core::diagnostic::Diagnostic;
+use kaizen_core::discovery::FileFilter;
 use kaizen_core::licensing::PremiumTier;
 use kaizen_core::parser::ParsedFile;
+use kaizen_core::project::Project;
 use kaizen_core::rules::{Confidence, Severity};
 use rayon::prelude::*;
 use rust_i18n::t;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::process::{self, Command};
-use walkdir::WalkDir;
-
-const SUPPORTED_EXTENSIONS: &[&str] = &["js", "jsx", "ts", "tsx", "mjs", "cjs", "mts", "cts"];
 
 #[derive(Args, Debug)]
 pub struct CheckArgs {
     #[arg(
         value_name = "PATH",
-        required_unless_present = "staged",
+        required_unless_present_any = ["staged", "changed_since"],
         help = "Path to file or directory to analyze"
     )]
     pub path: Option<PathBuf>,
@@ -36,6 +41,21 @@ pub struct CheckArgs {
     #[arg(long, help = "Analyze only git staged files")]
     pub staged: bool,
 
+    #[arg(
+        long,
+        value_name = "REF",
+        conflicts_with = "staged",
+        help = "Analyze only files changed since the merge base with REF (e.g. origin/main)"
+    )]
+    pub changed_since: Option<String>,
+
+    #[arg(
+        long,
+        requires = "changed_since",
+        help = "Report only diagnostics on lines changed since --changed-since"
+    )]
+    pub only_changed_lines: bool,
+
     #[arg(
         short,
         long,
@@ -64,6 +84,58 @@ pub struct CheckArgs {
 
     #[arg(long, help = "Disable colored output")]
     pub no_color: bool,
+
+    #[arg(long, help = "Apply automatic fixes before reporting remaining issues")]
+    pub fix: bool,
+
+    #[arg(
+        long,
+        requires = "fix",
+        help = "Print fixes as a unified diff instead of writing files"
+    )]
+    pub dry_run: bool,
+
+    #[arg(
+        long,
+        requires = "fix",
+        help = "Also apply suggested and unsafe fixes, which may change behavior"
+    )]
+    pub unsafe_fixes: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with = "baseline",
+        help = "Record current findings in a baseline file instead of reporting them"
+    )]
+    pub write_baseline: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Only report findings that are not recorded in the baseline file"
+    )]
+    pub baseline: Option<PathBuf>,
+
+    #[arg(long, help = "Analyze every file instead of reusing cached results")]
+    pub no_cache: bool,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with = "no_cache",
+        help = "Directory for the analysis cache (default: .kaizen-cache next to kaizen.toml)"
+    )]
+    pub cache_location: Option<PathBuf>,
+}
+
+/// Results for one file. `fresh` holds the unfiltered diagnostics when they were not cached.
+struct FileReport {
+    path: PathBuf,
+    content: String,
+    diagnostics: Vec<Diagnostic>,
+    baseline_entries: Vec<BaselineEntry>,
+    fresh: Option<Vec<Diagnostic>>,
 }
 
 impl CheckArgs {
@@ -80,15 +152,25 @@ impl CheckArgs {
         let license_result = load_license(&config.license);
         self.display_tier(&license_result.tier, &license_result.source);
 
+        let filter = FileFilter::new(&config);
+        let changed = self
+            .changed_since
+            .as_deref()
+            .map(ChangedFiles::since)
+            .transpose()?;
         let files = if self.staged {
-            get_staged_files()?
+            get_staged_files(&filter)?
+        } else if let Some(changed) = &changed {
+            select_changed_files(changed, self.path.as_deref(), &filter)
         } else {
-            discover_files(&config_path)?
+            discover_files(&config_path, &filter)?
         };
 
         if files.is_empty() {
             if self.staged {
                 println!("{}", t!("check.no_staged_files"));
+            } else if let Some(rev) = &self.changed_since {
+                println!("{}", t!("check.no_changed_files", rev = rev));
             } else {
                 println!("{}", t!("check.no_files"));
             }
@@ -99,33 +181,172 @@ impl CheckArgs {
         let min_severity = self.parse_severity()?;
         let min_confidence = self.parse_confidence()?;
 
-        let results: Vec<(PathBuf, String, Vec<Diagnostic>)> = files
+        let partial = self.staged || self.changed_since.is_some();
+        if self.fix {
+            let project = load_project(&engine, &files, &config_path, &filter, partial);
+            // Machine-readable reports own stdout, so their diffs go to stderr.
+            let mut diff_output: Box<dyn io::Write> = match self.format.as_str() {
+                "pretty" | "text" => Box::new(io::stdout()),
+                _ => Box::new(io::stderr()),
+            };
+            let summary = apply_fixes_to_files(
+                &files,
+                &engine,
+                &analyze_project(&engine, project.as_ref()),
+                fix_applicability(self.unsafe_fixes),
+                self.dry_run.then_some(diff_output.as_mut()),
+            )?;
+            print_fix_summary(&summary, self.dry_run);
+        }
+
+        let baseline_root = self
+            .write_baseline
+            .as_deref()
+            .or(self.baseline.as_deref())
+            .map(baseline_root);
+
+        let mut cache = (!self.no_cache).then(|| {
+            AnalysisCache::load(
+                &self.cache_dir(&config),
+                settings_key(&config, license_result.tier),
+            )
+        });
+
+        // Project rules need every file parsed up front; the per-file pass reuses them.
+        let project = load_project(&engine, &files, &config_path, &filter, partial);
+        let project_diagnostics = analyze_project(&engine, project.as_ref());
+
+        let mut results: Vec<FileReport> = files
             .par_iter()
             .filter_map(|file| {
-                let content = fs::read_to_string(file).ok()?;
-                let parsed = ParsedFile::from_source(&file.to_string_lossy(), &content);
-                let diagnostics = engine.analyze(&parsed);
-                Some((file.clone(), content, diagnostics))
+                let filename = file.to_string_lossy();
+                let project_file = project.as_ref().and_then(|p| p.file_named(&filename));
+                let content = match project_file {
+                    Some(parsed) => parsed.source().to_string(),
+                    None => fs::read_to_string(file).ok()?,
+                };
+                let mut parsed = None;
+                let (analyzed, fresh) = match cache
+                    .as_ref()
+                    .and_then(|c| c.get(file, &filename, &content))
+                {
+                    Some(cached) => (cached, false),
+                    None => {
+                        let file = parsed_file(project_file, &mut parsed, &filename, &content);
+                        (engine.analyze(file), true)
+                    }
+                };
+                let project_analyzed = project_diagnostics
+                    .get(filename.as_ref())
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+
+                let changed_lines = changed
+                    .as_ref()
+                    .filter(|_| self.only_changed_lines)
+                    .map(|changed| changed.hunks(file));
+                let diagnostics: Vec<Diagnostic> = analyzed
+                    .iter()
+                    .chain(project_analyzed)
+                    .filter(|d| meets_thresholds(d, min_severity, min_confidence))
+                    .filter(|d| changed_lines.is_none_or(|h| overlaps(h, d.line, d.end_line)))
+                    .cloned()
+                    .collect();
+                let baseline_entries = baseline_root
+                    .as_deref()
+                    .map(|root| {
+                        let parsed = parsed_file(project_file, &mut parsed, &filename, &content);
+                        baseline_entries(parsed, &diagnostics, file, root)
+                    })
+                    .unwrap_or_default();
+
+                Some(FileReport {
+                    path: file.clone(),
+                    content,
+                    diagnostics,
+                    baseline_entries,
+                    fresh: fresh.then_some(analyzed),
+                })
             })
             .collect();
 
+        if let Some(cache) = &mut cache {
+            for report in &mut results {
+                if let Some(fresh) = report.fresh.take() {
+                    cache.insert(&report.path, &report.content, fresh);
+                }
+            }
+            if let Err(e) = cache.save() {
+                eprintln!("{} {}", "warning:".yellow().bold(), e);
+            }
+        }
+
         let sources: HashMap<String, String> = results
             .iter()
-            .map(|(path, content, _)| (path.to_string_lossy().to_string(), content.clone()))
+            .map(|report| {
+                (
+                    report.path.to_string_lossy().to_string(),
+                    report.content.clone(),
+                )
+            })
             .collect();
 
-        let all_diagnostics: Vec<Diagnostic> = results
+        let (all_diagnostics, entries): (Vec<Vec<Diagnostic>>, Vec<Vec<BaselineEntry>>) = results
             .into_iter()
-            .flat_map(|(_, _, diags)| diags)
-            .filter(|d| severity_level(&d.severity) >= severity_level(&min_severity))
-            .filter(|d| d.confidence.level() >= min_confidence.level())
-            .collect();
+            .map(|report| (report.diagnostics, report.baseline_entries))
+            .unzip();
+        let all_diagnostics: Vec<Diagnostic> = all_diagnostics.into_iter().flatten().collect();
+        let entries: Vec<BaselineEntry> = entries.into_iter().flatten().collect();
+
+        if let Some(path) = &self.write_baseline {
+            let baseline = Baseline::new(entries);
+            baseline.save(path)?;
+            eprintln!(
+                "{}",
+                t!(
+                    "check.baseline_written",
+                    count = baseline.len(),
+                    path = path.display()
+                )
+            );
+            return Ok(());
+        }
+
+        let comparison = match &self.baseline {
+            Some(path) => {
+                let mut comparison = Baseline::load(path)?.compare(&entries);
+                // Entries outside what was analyzed this run are not known to be fixed.
+                if self.only_changed_lines {
+                    comparison.fixed.clear();
+                } else if let Some(root) = &baseline_root {
+                    let analyzed: HashSet<String> = files
+                        .iter()
+                        .map(|file| baseline_file_key(file, root))
+                        .collect();
+                    comparison
+                        .fixed
+                        .retain(|entry| analyzed.contains(&entry.file));
+                }
+                report_baseline(&comparison, path);
+                Some(comparison)
+            }
+            None => None,
+        };
+        let new_diagnostics: Vec<Diagnostic> = match &comparison {
+            Some(comparison) => all_diagnostics
+                .iter()
+                .zip(&comparison.states)
+                .filter(|(_, state)| **state == BaselineState::New)
+                .map(|(d, _)| d.clone())
+                .collect(),
+            None => all_diagnostics.clone(),
+        };
 
-        let error_count = all_diagnostics
+        let error_count = new_diagnostics
             .iter()
             .filter(|d| matches!(d.severity, Severity::Error))
             .count();
-        let warning_count = all_diagnostics
+        let warning_count = new_diagnostics
             .iter()
             .filter(|d| matches!(d.severity, Severity::Warning))
             .count();
@@ -133,18 +354,24 @@ impl CheckArgs {
         let total_files = files.len();
         let analyzed_path = if self.staged {
             "(staged files)".to_string()
+        } else if let Some(rev) = &self.changed_since {
+            format!("(changed since {})", rev)
         } else {
             config_path.to_string_lossy().to_string()
         };
 
         match self.format.as_str() {
-            "json" => self.output_json(&all_diagnostics, &engine, total_files, &analyzed_path),
+            "json" => self.output_json(&new_diagnostics, &engine, total_files, &analyzed_path),
             "ndjson" => {
-                self.output_ndjson(&all_diagnostics, &engine, total_files, &analyzed_path)?
+                self.output_ndjson(&new_diagnostics, &engine, total_files, &analyzed_path)?
             }
-            "sarif" => self.output_sarif(&all_diagnostics, &engine),
-            "text" => self.output_text(&all_diagnostics),
-            _ => self.output_pretty(&all_diagnostics, &sources),
+            "sarif" => self.output_sarif(
+                &all_diagnostics,
+                comparison.as_ref().map(|c| c.states.as_slice()),
+                &engine,
+            ),
+            "text" => self.output_text(&new_diagnostics),
+            _ => self.output_pretty(&new_diagnostics, &sources),
         }
 
         let has_errors = error_count > 0;
@@ -157,24 +384,18 @@ impl CheckArgs {
         Ok(())
     }
 
+    fn cache_dir(&self, config: &Config) -> PathBuf {
+        self.cache_location
+            .clone()
+            .unwrap_or_else(|| config.root.clone().unwrap_or_default().join(CACHE_DIRNAME))
+    }
+
     fn parse_severity(&self) -> Result<Severity> {
-        match self.severity.as_deref() {
-            Some("error") => Ok(Severity::Error),
-            Some("warning") => Ok(Severity::Warning),
-            Some("info") => Ok(Severity::Info),
-            Some("hint") => Ok(Severity::Hint),
-            Some(other) => anyhow::bail!("{}", t!("check.invalid_severity", value = other)),
-            None => Ok(Severity::Hint),
-        }
+        parse_severity(self.severity.as_deref())
     }
 
     fn parse_confidence(&self) -> Result<Confidence> {
-        match self.min_confidence.as_str() {
-            "high" => Ok(Confidence::High),
-            "medium" => Ok(Confidence::Medium),
-            "low" => Ok(Confidence::Low),
-            other => anyhow::bail!("{}", t!("check.invalid_confidence", value = other)),
-        }
+        parse_confidence(&self.min_confidence)
     }
 
     fn configure_colors(&self) {
@@ -288,13 +509,98 @@ impl CheckArgs {
         print!("{}", formatter.format(diagnostics));
     }
 
-    fn output_sarif(&self, diagnostics: &[Diagnostic], engine: &AnalysisEngine) {
-        let formatter = SarifFormatter::with_registry(engine.registry());
+    /// With a baseline, SARIF keeps baselined results and marks them through `baselineState`.
+    fn output_sarif(
+        &self,
+        diagnostics: &[Diagnostic],
+        baseline_states: Option<&[BaselineState]>,
+        engine: &AnalysisEngine,
+    ) {
+        let mut formatter = SarifFormatter::with_registry(engine.registry());
+        if let Some(states) = baseline_states {
+            formatter = formatter.with_baseline_states(states);
+        }
         println!("{}", formatter.format(diagnostics));
     }
 }
 
-fn get_staged_files() -> Result<Vec<PathBuf>> {
+/// The file as parsed for the project if there is one, otherwise parsed on first use.
+fn parsed_file<'a>(
+    project_file: Option<&'a ParsedFile>,
+    parsed: &'a mut Option<ParsedFile>,
+    filename: &str,
+    content: &str,
+) -> &'a ParsedFile {
+    match project_file {
+        Some(file) => file,
+        None => parsed.get_or_insert_with(|| ParsedFile::from_source(filename, content)),
+    }
+}
+
+/// Directory that baseline entries are stored relative to, so the file can be committed.
+fn baseline_root(baseline: &Path) -> PathBuf {
+    let dir = baseline
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf())
+}
+
+fn baseline_entries(
+    parsed: &ParsedFile,
+    diagnostics: &[Diagnostic],
+    file: &Path,
+    root: &Path,
+) -> Vec<BaselineEntry> {
+    let key = baseline_file_key(file, root);
+    let fingerprinter = Fingerprinter::new(parsed);
+    diagnostics
+        .iter()
+        .map(|d| BaselineEntry::new(key.clone(), d, fingerprinter.fingerprint(d)))
+        .collect()
+}
+
+fn baseline_file_key(file: &Path, root: &Path) -> String {
+    let full = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+    full.strip_prefix(root)
+        .unwrap_or(file)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Reports on stderr so machine-readable output on stdout stays intact.
+fn report_baseline(comparison: &BaselineComparison, path: &Path) {
+    let unchanged = comparison.unchanged_count();
+    if unchanged > 0 {
+        eprintln!(
+            "{}",
+            t!("check.baseline_matched", count = unchanged).dimmed()
+        );
+    }
+
+    if comparison.fixed.is_empty() {
+        return;
+    }
+    eprintln!(
+        "{}",
+        t!("check.baseline_fixed", count = comparison.fixed.len())
+    );
+    for entry in &comparison.fixed {
+        eprintln!(
+            "  {}:{} [{}] {}",
+            entry.file,
+            entry.line,
+            entry.rule_id.dimmed(),
+            entry.message
+        );
+    }
+    eprintln!(
+        "{}",
+        t!("check.baseline_update_hint", path = path.display()).dimmed()
+    );
+}
+
+fn get_staged_files(filter: &FileFilter) -> Result<Vec<PathBuf>> {
     let output = Command::new("git")
         .args(["diff", "--cached", "--name-only", "--diff-filter=ACMR"])
         .output()
@@ -306,57 +612,154 @@ fn get_staged_files() -> Result<Vec<PathBuf>> {
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    let files: Vec<PathBuf> = stdout
+    Ok(filter_staged_paths(&stdout, filter))
+}
+
+/// Staged paths go through the same filter as discovered files so hooks and CI agree.
+fn filter_staged_paths(output: &str, filter: &FileFilter) -> Vec<PathBuf> {
+    output
         .lines()
         .map(PathBuf::from)
-        .filter(|p| is_supported_file(p))
         .filter(|p| p.exists())
+        .filter(|p| filter.is_included(p))
+        .collect()
+}
+
+/// Changed files that pass the filter and lie under `path`, shown relative to the working directory.
+fn select_changed_files(
+    changed: &ChangedFiles,
+    path: Option<&Path>,
+    filter: &FileFilter,
+) -> Vec<PathBuf> {
+    let cwd = std::env::current_dir()
+        .and_then(|dir| dir.canonicalize())
+        .unwrap_or_default();
+    let scope = path.and_then(|p| p.canonicalize().ok());
+
+    let mut files: Vec<PathBuf> = changed
+        .files()
+        .filter(|file| file.exists() && filter.is_included(file))
+        .filter(|file| scope.as_ref().is_none_or(|scope| file.starts_with(scope)))
+        .map(|file| {
+            file.strip_prefix(&cwd)
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|_| file.to_path_buf())
+        })
         .collect();
+    files.sort();
+    files
+}
 
-    Ok(files)
+/// Parses the files project rules need, or returns `None` when none is enabled.
+///
+/// When `files` is only part of the project (`partial`, a single file, or a directory
+/// below the root), the rest of the root is still needed to know how they are imported.
+pub(crate) fn load_project(
+    engine: &AnalysisEngine,
+    files: &[PathBuf],
+    path: &Path,
+    filter: &FileFilter,
+    partial: bool,
+) -> Option<Project> {
+    if !engine.has_project_rules() {
+        return None;
+    }
+
+    let root = filter.root();
+    let mut all = files.to_vec();
+    if partial || path.canonicalize().ok() != root.canonicalize().ok() {
+        let analyzed: HashSet<PathBuf> = files
+            .iter()
+            .filter_map(|file| file.canonicalize().ok())
+            .collect();
+        all.extend(
+            discover_files(root, filter)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|file| {
+                    file.canonicalize()
+                        .is_ok_and(|file| !analyzed.contains(&file))
+                }),
+        );
+    }
+
+    Some(
+        Project::new(
+            all.par_iter()
+                .filter_map(|file| {
+                    let content = fs::read_to_string(file).ok()?;
+                    Some(ParsedFile::from_source(&file.to_string_lossy(), &content))
+                })
+                .collect(),
+        )
+        .with_root(root),
+    )
+}
+
+/// Runs the project rules over `project`, grouping their diagnostics by file.
+pub(crate) fn analyze_project(
+    engine: &AnalysisEngine,
+    project: Option<&Project>,
+) -> HashMap<String, Vec<Diagnostic>> {
+    let mut by_file: HashMap<String, Vec<Diagnostic>> = HashMap::new();
+    for diagnostic in project
+        .map(|p| engine.analyze_project(p))
+        .unwrap_or_default()
+    {
+        by_file
+            .entry(diagnostic.file.clone())
+            .or_default()
+            .push(diagnostic);
+    }
+    by_file
 }
 
-fn discover_files(path: &Path) -> Result<Vec<PathBuf>> {
+pub(crate) fn discover_files(path: &Path, filter: &FileFilter) -> Result<Vec<PathBuf>> {
     if !path.exists() {
         anyhow::bail!("{}", t!("check.path_not_exist", path = path.display()));
     }
 
     if path.is_file() {
-        if is_supported_file(path) {
+        if filter.is_included(path) {
             return Ok(vec![path.to_path_buf()]);
         } else {
             return Ok(vec![]);
         }
     }
 
-    let files: Vec<PathBuf> = WalkDir::new(path)
-        .into_iter()
-        .filter_entry(|e| !is_hidden(e))
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .filter(|e| is_supported_file(e.path()))
-        .map(|e| e.path().to_path_buf())
-        .collect();
-
-    Ok(files)
+    Ok(filter.walk(path))
 }
 
-fn is_supported_file(path: &Path) -> bool {
-    path.extension()
-        .and_then(|ext| ext.to_str())
-        .map(|ext| SUPPORTED_EXTENSIONS.contains(&ext))
-        .unwrap_or(false)
+/// Parses a `--severity` value; without one every severity is reported.
+pub(crate) fn parse_severity(value: Option<&str>) -> Result<Severity> {
+    match value {
+        Some("error") => Ok(Severity::Error),
+        Some("warning") => Ok(Severity::Warning),
+        Some("info") => Ok(Severity::Info),
+        Some("hint") => Ok(Severity::Hint),
+        Some(other) => anyhow::bail!("{}", t!("check.invalid_severity", value = other)),
+        None => Ok(Severity::Hint),
+    }
 }
 
-fn is_hidden(entry: &walkdir::DirEntry) -> bool {
-    if entry.depth() == 0 {
-        return false;
+/// Parses a `--min-confidence` value.
+pub(crate) fn parse_confidence(value: &str) -> Result<Confidence> {
+    match value {
+        "high" => Ok(Confidence::High),
+        "medium" => Ok(Confidence::Medium),
+        "low" => Ok(Confidence::Low),
+        other => anyhow::bail!("{}", t!("check.invalid_confidence", value = other)),
     }
-    entry
-        .file_name()
-        .to_str()
-        .map(|name| name.starts_with('.') || name == "node_modules")
-        .unwrap_or(false)
+}
+
+/// Whether `diagnostic` passes the `--severity` and `--min-confidence` filters.
+pub(crate) fn meets_thresholds(
+    diagnostic: &Diagnostic,
+    min_severity: Severity,
+    min_confidence: Confidence,
+) -> bool {
+    severity_level(&diagnostic.severity) >= severity_level(&min_severity)
+        && diagnostic.confidence.level() >= min_confidence.level()
 }
 
 fn severity_level(severity: &Severity) -> u8 {
@@ -371,6 +774,7 @@ fn severity_level(severity: &Severity) -> u8 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use kaizen_core::discovery::is_supported_file;
     use std::fs::File;
     use std::io::Write;
     use tempfile::tempdir;
@@ -381,7 +785,7 @@ mod tests {
         let file_path = dir.path().join("test.js");
         File::create(&file_path).unwrap();
 
-        let files = discover_files(&file_path).unwrap();
+        let files = discover_files(&file_path, &FileFilter::default()).unwrap();
 
         assert_eq!(files.len(), 1);
         assert_eq!(files[0], file_path);
@@ -394,7 +798,7 @@ mod tests {
         File::create(dir.path().join("b.ts")).unwrap();
         File::create(dir.path().join("c.tsx")).unwrap();
 
-        let files = discover_files(dir.path()).unwrap();
+        let files = discover_files(dir.path(), &FileFilter::default()).unwrap();
 
         assert_eq!(files.len(), 3);
     }
@@ -406,7 +810,7 @@ mod tests {
         File::create(dir.path().join("readme.md")).unwrap();
         File::create(dir.path().join("config.json")).unwrap();
 
-        let files = discover_files(dir.path()).unwrap();
+        let files = discover_files(dir.path(), &FileFilter::default()).unwrap();
 
         assert_eq!(files.len(), 1);
     }
@@ -419,7 +823,7 @@ mod tests {
         File::create(hidden_dir.join("hidden.js")).unwrap();
         File::create(dir.path().join("visible.js")).unwrap();
 
-        let files = discover_files(dir.path()).unwrap();
+        let files = discover_files(dir.path(), &FileFilter::default()).unwrap();
 
         assert_eq!(files.len(), 1);
         assert!(files[0].to_string_lossy().contains("visible.js"));
@@ -433,7 +837,7 @@ mod tests {
         File::create(nm_dir.join("dep.js")).unwrap();
         File::create(dir.path().join("src.js")).unwrap();
 
-        let files = discover_files(dir.path()).unwrap();
+        let files = discover_files(dir.path(), &FileFilter::default()).unwrap();
 
         assert_eq!(files.len(), 1);
         assert!(files[0].to_string_lossy().contains("src.js"));
@@ -447,11 +851,69 @@ mod tests {
         File::create(dir.path().join("root.js")).unwrap();
         File::create(subdir.join("nested.ts")).unwrap();
 
-        let files = discover_files(dir.path()).unwrap();
+        let files = discover_files(dir.path(), &FileFilter::default()).unwrap();
 
         assert_eq!(files.len(), 2);
     }
 
+    #[test]
+    fn discover_files_applies_config_and_ignore_files() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join("dist")).unwrap();
+        fs::write(dir.path().join(".gitignore"), "dist/\n").unwrap();
+        File::create(dir.path().join("src/app.ts")).unwrap();
+        File::create(dir.path().join("src/app.test.ts")).unwrap();
+        File::create(dir.path().join("dist/bundle.js")).unwrap();
+
+        let config = kaizen_core::config::Config {
+            exclude: vec!["*.test.ts".to_string()],
+            root: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let files = discover_files(dir.path(), &FileFilter::new(&config)).unwrap();
+
+        assert_eq!(files, vec![dir.path().join("src/app.ts")]);
+    }
+
+    #[test]
+    fn discover_files_skips_excluded_single_file() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("app.test.js");
+        File::create(&file_path).unwrap();
+
+        let config = kaizen_core::config::Config {
+            exclude: vec!["*.test.js".to_string()],
+            root: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let files = discover_files(&file_path, &FileFilter::new(&config)).unwrap();
+
+        assert!(files.is_empty());
+    }
+
+    #[test]
+    fn staged_paths_use_the_same_filter() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(".kaizenignore"), "generated.js\n").unwrap();
+        File::create(dir.path().join("app.js")).unwrap();
+        File::create(dir.path().join("generated.js")).unwrap();
+        File::create(dir.path().join("notes.md")).unwrap();
+
+        let config = kaizen_core::config::Config {
+            root: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let output = ["app.js", "generated.js", "notes.md", "deleted.js"]
+            .iter()
+            .map(|name| format!("{}\n", dir.path().join(name).display()))
+            .collect::<String>();
+
+        let files = filter_staged_paths(&output, &FileFilter::new(&config));
+
+        assert_eq!(files, vec![dir.path().join("app.js")]);
+    }
+
     #[test]
     fn is_supported_file_accepts_all_extensions() {
         assert!(is_supported_file(Path::new("test.js")));
@@ -483,11 +945,20 @@ mod tests {
         let args = CheckArgs {
             path: Some(PathBuf::from(".")),
             staged: false,
+            changed_since: None,
+            only_changed_lines: false,
             format: "pretty".to_string(),
             fail_on_warnings: false,
             severity: Some("error".to_string()),
             min_confidence: "medium".to_string(),
             no_color: false,
+            fix: false,
+            dry_run: false,
+            unsafe_fixes: false,
+            write_baseline: None,
+            baseline: None,
+            no_cache: true,
+            cache_location: None,
         };
 
         assert!(matches!(args.parse_severity().unwrap(), Severity::Error));
@@ -498,11 +969,20 @@ mod tests {
         let args = CheckArgs {
             path: Some(PathBuf::from(".")),
             staged: false,
+            changed_since: None,
+            only_changed_lines: false,
             format: "pretty".to_string(),
             fail_on_warnings: false,
             severity: Some("invalid".to_string()),
             min_confidence: "medium".to_string(),
             no_color: false,
+            fix: false,
+            dry_run: false,
+            unsafe_fixes: false,
+            write_baseline: None,
+            baseline: None,
+            no_cache: true,
+            cache_location: None,
         };
 
         assert!(args.parse_severity().is_err());
@@ -513,11 +993,20 @@ mod tests {
         let args = CheckArgs {
             path: Some(PathBuf::from(".")),
             staged: false,
+            changed_since: None,
+            only_changed_lines: false,
             format: "pretty".to_string(),
             fail_on_warnings: false,
             severity: None,
             min_confidence: "high".to_string(),
             no_color: false,
+            fix: false,
+            dry_run: false,
+            unsafe_fixes: false,
+            write_baseline: None,
+            baseline: None,
+            no_cache: true,
+            cache_location: None,
         };
 
         assert!(matches!(args.parse_confidence().unwrap(), Confidence::High));
@@ -528,16 +1017,51 @@ mod tests {
         let args = CheckArgs {
             path: Some(PathBuf::from(".")),
             staged: false,
+            changed_since: None,
+            only_changed_lines: false,
             format: "pretty".to_string(),
             fail_on_warnings: false,
             severity: None,
             min_confidence: "invalid".to_string(),
             no_color: false,
+            fix: false,
+            dry_run: false,
+            unsafe_fixes: false,
+            write_baseline: None,
+            baseline: None,
+            no_cache: true,
+            cache_location: None,
         };
 
         assert!(args.parse_confidence().is_err());
     }
 
+    #[test]
+    fn project_of_a_subdirectory_includes_the_rest_of_the_root() {
+        let dir = tempdir().unwrap();
+        let src = dir.path().join("src");
+        fs::create_dir_all(&src).unwrap();
+        fs::create_dir_all(dir.path().join("app")).unwrap();
+        fs::write(src.join("lib.js"), "export function greet() {}\n").unwrap();
+        fs::write(
+            dir.path().join("app/main.js"),
+            "import { greet } from '../src/lib.js';\ngreet();\n",
+        )
+        .unwrap();
+        let filter = FileFilter::new(&Config {
+            root: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        });
+
+        let engine = AnalysisEngine::new();
+        let files = discover_files(&src, &filter).unwrap();
+        let project = load_project(&engine, &files, &src, &filter, false);
+        let diagnostics = analyze_project(&engine, project.as_ref());
+
+        assert_eq!(project.map(|p| p.files().count()), Some(2));
+        assert!(diagnostics.values().flatten().all(|d| d.rule_id != "Q005"));
+    }
+
     #[test]
     fn check_runs_analysis_on_file() {
         let dir = tempdir().unwrap();
@@ -548,15 +1072,88 @@ mod tests {
         let args = CheckArgs {
             path: Some(file_path),
             staged: false,
+            changed_since: None,
+            only_changed_lines: false,
             format: "json".to_string(),
             fail_on_warnings: false,
             severity: None,
             min_confidence: "medium".to_string(),
             no_color: false,
+            fix: false,
+            dry_run: false,
+            unsafe_fixes: false,
+            write_baseline: None,
+            baseline: None,
+            no_cache: true,
+            cache_location: None,
         };
 
         // This will exit with code 0 since we're not checking exit in tests
         // but it exercises the code path
         let _ = args.run();
     }
+
+    #[test]
+    fn baseline_entries_are_relative_to_baseline_directory() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("src/app.js");
+        fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        fs::write(&file_path, "var x = 1;\n").unwrap();
+
+        let root = baseline_root(&dir.path().join("kaizen-baseline.json"));
+        let parsed = ParsedFile::from_source(&file_path.to_string_lossy(), "var x = 1;\n");
+        let diagnostics = AnalysisEngine::new().analyze(&parsed);
+        let entries = baseline_entries(&parsed, &diagnostics, &file_path, &root);
+
+        assert!(!entries.is_empty());
+        assert!(entries.iter().all(|e| e.file == "src/app.js"));
+    }
+
+    #[test]
+    fn baseline_root_defaults_to_current_directory() {
+        let root = baseline_root(Path::new("kaizen-baseline.json"));
+
+        assert_eq!(
+            root,
+            std::env::current_dir().unwrap().canonicalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn cache_dir_defaults_to_config_root() {
+        let mut args = CheckArgs {
+            path: Some(PathBuf::from(".")),
+            staged: false,
+            changed_since: None,
+            only_changed_lines: false,
+            format: "pretty".to_string(),
+            fail_on_warnings: false,
+            severity: None,
+            min_confidence: "medium".to_string(),
+            no_color: false,
+            fix: false,
+            dry_run: false,
+            unsafe_fixes: false,
+            write_baseline: None,
+            baseline: None,
+            no_cache: false,
+            cache_location: None,
+        };
+        let config = Config {
+            root: Some(PathBuf::from("/project")),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            args.cache_dir(&config),
+            Path::new("/project").join(CACHE_DIRNAME)
+        );
+        assert_eq!(
+            args.cache_dir(&Config::default()),
+            PathBuf::from(CACHE_DIRNAME)
+        );
+
+        args.cache_location = Some(PathBuf::from("/tmp/kaizen"));
+        assert_eq!(args.cache_dir(&config), PathBuf::from("/tmp/kaizen"));
+    }
 }
@@ -0,0 +1,295 @@
+//! Fix command - applies automatic fixes to JavaScript/TypeScript files
+
+use crate::commands::check::{analyze_project, discover_files, load_project};
+use crate::license::load_license;
+use anyhow::Result;
+use clap::Args;
+use colored::Colorize;
+use kaizen_core::analysis::AnalysisEngine;
+use kaizen_core::config::load_config_or_default_with_warnings;
+use kaizen_core::diagnostic::{Applicability, Diagnostic};
+use kaizen_core::discovery::FileFilter;
+use kaizen_core::fixer::{FixOutcome, fix_source_with_project};
+use rayon::prelude::*;
+use rust_i18n::t;
+use similar::TextDiff;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Args, Debug)]
+pub struct FixArgs {
+    #[arg(value_name = "PATH", help = "Path to file or directory to fix")]
+    pub path: PathBuf,
+
+    #[arg(long, help = "Print a unified diff instead of writing files")]
+    pub dry_run: bool,
+
+    #[arg(
+        long,
+        help = "Also apply suggested and unsafe fixes, which may change behavior"
+    )]
+    pub unsafe_fixes: bool,
+}
+
+impl FixArgs {
+    pub fn run(&self) -> Result<()> {
+        let config_result = load_config_or_default_with_warnings(&self.path);
+        for warning in &config_result.warnings {
+            eprintln!("{} {}", "warning:".yellow().bold(), warning);
+        }
+        let config = config_result.config;
+        let license_result = load_license(&config.license);
+
+        let filter = FileFilter::new(&config);
+        let files = discover_files(&self.path, &filter)?;
+        if files.is_empty() {
+            println!("{}", t!("check.no_files"));
+            return Ok(());
+        }
+
+        let engine = AnalysisEngine::with_config_and_tier(&config, license_result.tier);
+        let project = load_project(&engine, &files, &self.path, &filter, false);
+        let summary = apply_fixes_to_files(
+            &files,
+            &engine,
+            &analyze_project(&engine, project.as_ref()),
+            fix_applicability(self.unsafe_fixes),
+            self.dry_run.then_some(&mut io::stdout() as &mut dyn Write),
+        )?;
+        print_fix_summary(&summary, self.dry_run);
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FixSummary {
+    pub files_fixed: usize,
+    pub fixes_applied: usize,
+}
+
+/// Only safe fixes are applied unless the user opts into the rest.
+pub fn fix_applicability(unsafe_fixes: bool) -> Applicability {
+    if unsafe_fixes {
+        Applicability::Unsafe
+    } else {
+        Applicability::Safe
+    }
+}
+
+/// Fixes every file in `files`, writing the result back, or in dry-run mode writing a
+/// diff to `dry_run` instead.
+///
+/// `project_diagnostics` holds the diagnostics of project rules, by file.
+pub fn apply_fixes_to_files(
+    files: &[PathBuf],
+    engine: &AnalysisEngine,
+    project_diagnostics: &HashMap<String, Vec<Diagnostic>>,
+    max_applicability: Applicability,
+    mut dry_run: Option<&mut dyn Write>,
+) -> Result<FixSummary> {
+    let results: Vec<(PathBuf, String, FixOutcome)> = files
+        .par_iter()
+        .filter_map(|file| {
+            let content = fs::read_to_string(file).ok()?;
+            let filename = file.to_string_lossy();
+            let outcome = fix_source_with_project(
+                engine,
+                &filename,
+                &content,
+                project_diagnostics
+                    .get(filename.as_ref())
+                    .map(Vec::as_slice)
+                    .unwrap_or_default(),
+                max_applicability,
+            );
+            outcome.changed().then(|| (file.clone(), content, outcome))
+        })
+        .collect();
+
+    let mut summary = FixSummary::default();
+    for (file, original, outcome) in &results {
+        if let Some(out) = dry_run.as_mut() {
+            write!(out, "{}", unified_diff(file, original, &outcome.source))?;
+        } else {
+            fs::write(file, &outcome.source)?;
+        }
+        summary.files_fixed += 1;
+        summary.fixes_applied += outcome.applied;
+    }
+
+    Ok(summary)
+}
+
+pub fn print_fix_summary(summary: &FixSummary, dry_run: bool) {
+    if summary.fixes_applied == 0 {
+        eprintln!("{}", t!("fix.nothing_to_fix"));
+        return;
+    }
+
+    let key = if dry_run {
+        "fix.would_fix"
+    } else {
+        "fix.fixed"
+    };
+    eprintln!(
+        "{} {}",
+        "✓".green().bold(),
+        t!(
+            key,
+            fixes = summary.fixes_applied,
+            files = summary.files_fixed
+        )
+    );
+}
+
+fn unified_diff(path: &Path, original: &str, fixed: &str) -> String {
+    let display = path.to_string_lossy();
+    TextDiff::from_lines(original, fixed)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", display), &format!("b/{}", display))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaizen_core::config::Config;
+    use tempfile::tempdir;
+
+    #[test]
+    fn apply_fixes_rewrites_files() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("test.js");
+        fs::write(&file_path, "var x = 1;\nexport { x };\n").unwrap();
+
+        let engine = AnalysisEngine::new();
+        let summary = apply_fixes_to_files(
+            std::slice::from_ref(&file_path),
+            &engine,
+            &HashMap::new(),
+            Applicability::Unsafe,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(summary.files_fixed, 1);
+        assert_eq!(summary.fixes_applied, 2);
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "const x = 1;\nexport { x };\n"
+        );
+    }
+
+    #[test]
+    fn dry_run_leaves_files_untouched() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("test.js");
+        fs::write(&file_path, "var x = 1;\nexport { x };\n").unwrap();
+
+        let engine = AnalysisEngine::new();
+        let mut diff = Vec::new();
+        let summary = apply_fixes_to_files(
+            std::slice::from_ref(&file_path),
+            &engine,
+            &HashMap::new(),
+            Applicability::Unsafe,
+            Some(&mut diff),
+        )
+        .unwrap();
+
+        assert_eq!(summary.files_fixed, 1);
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "var x = 1;\nexport { x };\n"
+        );
+        assert!(String::from_utf8(diff).unwrap().contains("+const x = 1;"));
+    }
+
+    #[test]
+    fn files_without_fixes_are_not_counted() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("clean.js");
+        fs::write(&file_path, "export const x = 1;\n").unwrap();
+
+        let engine = AnalysisEngine::new();
+        let summary = apply_fixes_to_files(
+            std::slice::from_ref(&file_path),
+            &engine,
+            &HashMap::new(),
+            Applicability::Unsafe,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(summary, FixSummary::default());
+    }
+
+    #[test]
+    fn safe_mode_skips_unsafe_fixes() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("test.js");
+        let code = "var x = 1;\nexport const same = x == 1;\n";
+        fs::write(&file_path, code).unwrap();
+
+        let engine = AnalysisEngine::new();
+        let summary = apply_fixes_to_files(
+            std::slice::from_ref(&file_path),
+            &engine,
+            &HashMap::new(),
+            fix_applicability(false),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(summary, FixSummary::default());
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), code);
+    }
+
+    #[test]
+    fn applies_fixes_of_project_rules() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("lib.ts");
+        fs::write(&file_path, "export function unused() {}\n").unwrap();
+        let files = vec![file_path.clone()];
+
+        let engine = AnalysisEngine::new();
+        let filter = FileFilter::new(&Config {
+            root: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        });
+        let project = load_project(&engine, &files, dir.path(), &filter, false);
+        let summary = apply_fixes_to_files(
+            &files,
+            &engine,
+            &analyze_project(&engine, project.as_ref()),
+            Applicability::Unsafe,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(summary.fixes_applied, 1);
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "function unused() {}\n"
+        );
+    }
+
+    #[test]
+    fn unified_diff_shows_changed_lines() {
+        let diff = unified_diff(
+            Path::new("src/app.js"),
+            "var x = 1;\nexport { x };\n",
+            "const x = 1;\nexport { x };\n",
+        );
+
+        assert!(diff.contains("--- a/src/app.js"));
+        assert!(diff.contains("+++ b/src/app.js"));
+        assert!(diff.contains("-var x = 1;"));
+        assert!(diff.contains("+const x = 1;"));
+        assert!(diff.contains(" export { x };"));
+    }
+}
@@ -14,10 +14,14 @@ use std::os::unix::fs::PermissionsExt;
 const DEFAULT_CONFIG: &str = r#"# Kaizen configuration file
 # See https://github.com/kzn-tools/kaizen for documentation
 
+# Start from built-in presets or shared config files
+# extends = ["kaizen:recommended"]
+
 # File patterns to include in analysis
 # include = ["src/**/*.ts", "src/**/*.tsx"]
 
 # File patterns to exclude from analysis
+# (.gitignore and .kaizenignore files are honored as well)
 # exclude = ["**/*.test.ts", "**/*.spec.ts"]
 
 # Rule configuration
@@ -28,9 +32,21 @@ const DEFAULT_CONFIG: &str = r#"# Kaizen configuration file
 # Disable specific rules
 # disabled = ["no-console"]
 
+# Require a "-- reason" on disable comments: "never", "security" or "always"
+# require_disable_reason = "security"
+
 # Override rule severity
 # [rules.severity]
 # no-console = "hint"
+
+# Rule-specific options
+# [rules.options.max-complexity]
+# threshold = 15
+
+# Settings for a subset of files
+# [[overrides]]
+# files = ["scripts/**"]
+# disabled = ["no-console"]
 "#;
 
 const PRE_COMMIT_HOOK: &str = r#"#!/bin/sh
@@ -3,12 +3,16 @@
 pub mod auth;
 pub mod check;
 pub mod explain;
+pub mod fix;
 pub mod init;
+pub mod watch;
 
 pub use auth::AuthArgs;
 pub use check::CheckArgs;
 pub use explain::ExplainArgs;
+pub use fix::FixArgs;
 pub use init::InitArgs;
+pub use watch::WatchArgs;
 
 use clap::Subcommand;
 
@@ -20,9 +24,15 @@ pub enum Commands {
     #[command(about = "Analyze JavaScript/TypeScript files for issues")]
     Check(CheckArgs),
 
+    #[command(about = "Apply automatic fixes to JavaScript/TypeScript files")]
+    Fix(FixArgs),
+
     #[command(about = "Initialize Kaizen configuration in current directory")]
     Init(InitArgs),
 
     #[command(about = "Show detailed explanation for a specific rule")]
     Explain(ExplainArgs),
+
+    #[command(about = "Re-analyze files continuously as they change")]
+    Watch(WatchArgs),
 }
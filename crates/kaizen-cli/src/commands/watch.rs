@@ -0,0 +1,672 @@
+//! Watch command - re-analyzes files as they change on disk
+
+use crate::commands::check::{discover_files, meets_thresholds, parse_confidence, parse_severity};
+use crate::license::load_license;
+use crate::output::json::JsonFormatter;
+use crate::output::pretty::PrettyFormatter;
+use anyhow::Result;
+use clap::Args;
+use colored::Colorize;
+use kaizen_core::analysis::AnalysisEngine;
+use kaizen_core::config::{
+    CONFIG_FILENAME, load_config_or_default, load_config_or_default_with_warnings,
+};
+use kaizen_core::diagnostic::Diagnostic;
+use kaizen_core::discovery::{FileFilter, IGNORE_FILENAME};
+use kaizen_core::licensing::PremiumTier;
+use kaizen_core::parser::ParsedFile;
+use kaizen_core::project::Project;
+use kaizen_core::rules::{Confidence, Severity};
+use notify::{RecursiveMode, Watcher};
+use rayon::prelude::*;
+use rust_i18n::t;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// Matches the delay the language server waits after an edit before re-analyzing.
+const DEFAULT_DEBOUNCE_MS: u64 = 150;
+
+/// Files whose changes affect which files are analyzed or how.
+const SETTINGS_FILENAMES: &[&str] = &[CONFIG_FILENAME, IGNORE_FILENAME, ".gitignore"];
+
+#[derive(Args, Debug)]
+pub struct WatchArgs {
+    #[arg(
+        value_name = "PATH",
+        default_value = ".",
+        help = "Path to file or directory to watch"
+    )]
+    pub path: PathBuf,
+
+    #[arg(
+        short,
+        long,
+        default_value = "pretty",
+        value_parser = ["pretty", "ndjson"],
+        help = "Output format (pretty, ndjson)"
+    )]
+    pub format: String,
+
+    #[arg(
+        long,
+        value_name = "MS",
+        default_value_t = DEFAULT_DEBOUNCE_MS,
+        help = "Milliseconds to wait after the last change before re-checking"
+    )]
+    pub debounce: u64,
+
+    #[arg(
+        long,
+        value_name = "LEVEL",
+        help = "Filter diagnostics by minimum severity level (error, warning, info, hint)"
+    )]
+    pub severity: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "LEVEL",
+        default_value = "medium",
+        help = "Filter diagnostics by minimum confidence level (high, medium, low)"
+    )]
+    pub min_confidence: String,
+
+    #[arg(long, help = "Disable colored output")]
+    pub no_color: bool,
+}
+
+impl WatchArgs {
+    pub fn run(&self) -> Result<()> {
+        self.configure_colors();
+        let min_severity = parse_severity(self.severity.as_deref())?;
+        let min_confidence = parse_confidence(&self.min_confidence)?;
+
+        let tier = load_license(&load_config_or_default(&self.path).license).tier;
+        let started = Instant::now();
+        let mut session =
+            WatchSession::load(&self.path, tier)?.with_thresholds(min_severity, min_confidence);
+        self.render_all(&session, started.elapsed())?;
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher =
+            notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+                if let Ok(event) = result {
+                    for path in event.paths {
+                        let _ = tx.send(path);
+                    }
+                }
+            })?;
+        // Editors often save by renaming over the file, so a single file is watched
+        // through its directory.
+        match session.root.parent().filter(|_| session.root.is_file()) {
+            Some(dir) => watcher.watch(dir, RecursiveMode::NonRecursive)?,
+            None => watcher.watch(&session.root, RecursiveMode::Recursive)?,
+        }
+        if let Some(config_file) = &session.config_file
+            && !config_file.starts_with(&session.root)
+        {
+            watcher.watch(config_file, RecursiveMode::NonRecursive)?;
+        }
+
+        let mut debouncer = Debouncer::new(Duration::from_millis(self.debounce));
+        loop {
+            let event = match debouncer.deadline() {
+                Some(deadline) => {
+                    rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                }
+                None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match event {
+                Ok(path) if session.is_relevant(&path) => debouncer.schedule(path, Instant::now()),
+                Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            let ready = debouncer.take_ready(Instant::now());
+            if ready.is_empty() {
+                continue;
+            }
+            let started = Instant::now();
+            let changed = session.update(&ready);
+            if !changed.is_empty() {
+                self.render_update(&session, &changed, started.elapsed())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn configure_colors(&self) {
+        let no_color_env = std::env::var("NO_COLOR").is_ok();
+        if self.no_color || no_color_env {
+            colored::control::set_override(false);
+        }
+    }
+
+    fn render_all(&self, session: &WatchSession, elapsed: Duration) -> Result<()> {
+        if self.format == "ndjson" {
+            let formatter = JsonFormatter::with_registry(session.engine.registry());
+            let mut stdout = io::stdout().lock();
+            formatter.format_ndjson(
+                &session.diagnostics(),
+                session.files.len(),
+                &session.path.to_string_lossy(),
+                &mut stdout,
+            )?;
+            stdout.flush()?;
+            self.print_warnings(session);
+            return Ok(());
+        }
+        self.render_pretty(session, elapsed)
+    }
+
+    /// NDJSON streams a `file` record per changed file; pretty output redraws everything.
+    fn render_update(
+        &self,
+        session: &WatchSession,
+        changed: &[PathBuf],
+        elapsed: Duration,
+    ) -> Result<()> {
+        if self.format != "ndjson" {
+            return self.render_pretty(session, elapsed);
+        }
+
+        let formatter = JsonFormatter::with_registry(session.engine.registry());
+        let mut stdout = io::stdout().lock();
+        for path in changed {
+            let diagnostics = session.file_diagnostics(path);
+            formatter.format_ndjson_file(&path.to_string_lossy(), &diagnostics, &mut stdout)?;
+        }
+        formatter.format_ndjson_summary(
+            &session.diagnostics(),
+            session.files.len(),
+            &mut stdout,
+        )?;
+        stdout.flush()?;
+        self.print_warnings(session);
+        Ok(())
+    }
+
+    fn render_pretty(&self, session: &WatchSession, elapsed: Duration) -> Result<()> {
+        let mut stdout = io::stdout().lock();
+        if stdout.is_terminal() {
+            // Clear the screen and move the cursor to the top-left corner.
+            write!(stdout, "\x1b[2J\x1b[H")?;
+        }
+
+        let diagnostics = session.diagnostics();
+        let formatter = PrettyFormatter::with_sources(session.sources());
+        write!(stdout, "{}", formatter.format(&diagnostics))?;
+        if diagnostics.is_empty() {
+            writeln!(stdout, "{}", t!("watch.no_problems").green())?;
+        }
+        writeln!(
+            stdout,
+            "{}",
+            t!(
+                "watch.watching",
+                files = session.files.len(),
+                ms = elapsed.as_millis()
+            )
+            .dimmed()
+        )?;
+        stdout.flush()?;
+        self.print_warnings(session);
+        Ok(())
+    }
+
+    fn print_warnings(&self, session: &WatchSession) {
+        for warning in &session.warnings {
+            eprintln!("{} {}", "warning:".yellow().bold(), warning);
+        }
+    }
+}
+
+/// Collects paths from filesystem events until none arrive for `delay`. As with the
+/// language server's debouncer, every event restarts the wait, so a burst of saves or a
+/// branch switch is checked once.
+#[derive(Debug)]
+struct Debouncer {
+    delay: Duration,
+    pending: BTreeSet<PathBuf>,
+    deadline: Option<Instant>,
+}
+
+impl Debouncer {
+    fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            pending: BTreeSet::new(),
+            deadline: None,
+        }
+    }
+
+    fn schedule(&mut self, path: PathBuf, now: Instant) {
+        self.pending.insert(path);
+        self.deadline = Some(now + self.delay);
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Takes every pending path once the quiet period has passed.
+    fn take_ready(&mut self, now: Instant) -> Vec<PathBuf> {
+        if self.deadline.is_none_or(|deadline| now < deadline) {
+            return Vec::new();
+        }
+        self.deadline = None;
+        std::mem::take(&mut self.pending).into_iter().collect()
+    }
+}
+
+#[derive(Debug)]
+struct FileState {
+    content: String,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Diagnostics of every watched file, kept up to date as files change.
+struct WatchSession {
+    /// The watched path as given, which analyzed file paths are relative to.
+    path: PathBuf,
+    /// Canonical form of `path`, the prefix of paths reported by the watcher.
+    root: PathBuf,
+    config_file: Option<PathBuf>,
+    tier: PremiumTier,
+    warnings: Vec<String>,
+    engine: AnalysisEngine,
+    filter: FileFilter,
+    files: BTreeMap<PathBuf, FileState>,
+    /// Diagnostics of project rules, which may change when any file does.
+    project_diagnostics: BTreeMap<PathBuf, Vec<Diagnostic>>,
+    min_severity: Severity,
+    min_confidence: Confidence,
+}
+
+impl WatchSession {
+    fn load(path: &Path, tier: PremiumTier) -> Result<Self> {
+        let config_result = load_config_or_default_with_warnings(path);
+        let config = config_result.config;
+        let filter = FileFilter::new(&config);
+        let discovered = discover_files(path, &filter)?;
+
+        let mut session = Self {
+            path: path.to_path_buf(),
+            root: path.canonicalize()?,
+            config_file: config
+                .root
+                .as_deref()
+                .and_then(|root| root.join(CONFIG_FILENAME).canonicalize().ok()),
+            tier,
+            warnings: config_result.warnings,
+            engine: AnalysisEngine::with_config_and_tier(&config, tier),
+            filter,
+            files: BTreeMap::new(),
+            project_diagnostics: BTreeMap::new(),
+            min_severity: Severity::Hint,
+            min_confidence: Confidence::Medium,
+        };
+        session.check_files(discovered);
+        session.check_project();
+        Ok(session)
+    }
+
+    /// Reports only diagnostics at least as severe and confident as given, like `check`.
+    fn with_thresholds(mut self, min_severity: Severity, min_confidence: Confidence) -> Self {
+        self.min_severity = min_severity;
+        self.min_confidence = min_confidence;
+        self
+    }
+
+    fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.files
+            .keys()
+            .flat_map(|path| self.file_diagnostics(path))
+            .collect()
+    }
+
+    fn file_diagnostics(&self, path: &Path) -> Vec<Diagnostic> {
+        let file = self
+            .files
+            .get(path)
+            .map(|state| state.diagnostics.as_slice());
+        let project = self.project_diagnostics.get(path).map(Vec::as_slice);
+        file.unwrap_or_default()
+            .iter()
+            .chain(project.unwrap_or_default())
+            .filter(|d| meets_thresholds(d, self.min_severity, self.min_confidence))
+            .cloned()
+            .collect()
+    }
+
+    fn sources(&self) -> HashMap<String, String> {
+        self.files
+            .iter()
+            .map(|(path, state)| (path.to_string_lossy().into_owned(), state.content.clone()))
+            .collect()
+    }
+
+    /// Whether a watcher event for `event_path` can change what is reported.
+    fn is_relevant(&self, event_path: &Path) -> bool {
+        if self.is_settings_file(event_path) {
+            return true;
+        }
+        let path = self.local_path(event_path);
+        if !path.starts_with(&self.path) {
+            return false;
+        }
+        if path.is_dir() {
+            return !self.filter.in_skipped_location(&path);
+        }
+        self.filter.is_included(&path) || self.files.keys().any(|file| file.starts_with(&path))
+    }
+
+    /// Re-checks the paths reported by the watcher and returns the files whose
+    /// diagnostics were replaced or removed.
+    fn update(&mut self, event_paths: &[PathBuf]) -> Vec<PathBuf> {
+        if event_paths.iter().any(|path| self.is_settings_file(path)) {
+            return self.reload();
+        }
+
+        let mut candidates = BTreeSet::new();
+        for event_path in event_paths {
+            let path = self.local_path(event_path);
+            if !path.starts_with(&self.path) {
+                continue;
+            }
+            if path.is_dir() {
+                candidates.extend(self.filter.walk(&path));
+            }
+            // Files under a directory that was removed or renamed away.
+            candidates.extend(
+                self.files
+                    .keys()
+                    .filter(|file| file.starts_with(&path))
+                    .cloned(),
+            );
+            candidates.insert(path);
+        }
+
+        let mut changed: BTreeSet<PathBuf> = self
+            .check_files(candidates.into_iter().collect())
+            .into_iter()
+            .collect();
+        if !changed.is_empty() {
+            changed.extend(self.check_project());
+        }
+        changed.into_iter().collect()
+    }
+
+    /// Rebuilds the session after a configuration or ignore file changed.
+    fn reload(&mut self) -> Vec<PathBuf> {
+        let mut changed: BTreeSet<PathBuf> = self.files.keys().cloned().collect();
+        match Self::load(&self.path, self.tier) {
+            Ok(session) => *self = session.with_thresholds(self.min_severity, self.min_confidence),
+            Err(e) => {
+                self.warnings = vec![e.to_string()];
+                return Vec::new();
+            }
+        }
+        changed.extend(self.files.keys().cloned());
+        changed.into_iter().collect()
+    }
+
+    fn check_files(&mut self, paths: Vec<PathBuf>) -> Vec<PathBuf> {
+        let results: Vec<(PathBuf, Option<FileState>)> = paths
+            .into_par_iter()
+            .filter_map(|path| {
+                let state = self.check_file(&path)?;
+                Some((path, state))
+            })
+            .collect();
+
+        let mut changed = Vec::new();
+        for (path, state) in results {
+            match state {
+                Some(state) => {
+                    self.files.insert(path.clone(), state);
+                }
+                None => {
+                    self.files.remove(&path);
+                }
+            }
+            changed.push(path);
+        }
+        changed
+    }
+
+    /// Re-runs project rules over every file and returns the files whose project
+    /// diagnostics changed.
+    fn check_project(&mut self) -> Vec<PathBuf> {
+        if !self.engine.has_project_rules() {
+            return Vec::new();
+        }
+        let project = Project::new(
+            self.files
+                .par_iter()
+                .map(|(path, state)| {
+                    ParsedFile::from_source(&path.to_string_lossy(), &state.content)
+                })
+                .collect(),
+        )
+        .with_root(self.filter.root());
+        let mut updated: BTreeMap<PathBuf, Vec<Diagnostic>> = BTreeMap::new();
+        for diagnostic in self.engine.analyze_project(&project) {
+            updated
+                .entry(PathBuf::from(&diagnostic.file))
+                .or_default()
+                .push(diagnostic);
+        }
+
+        let changed: BTreeSet<PathBuf> = updated
+            .keys()
+            .chain(self.project_diagnostics.keys())
+            .filter(|path| updated.get(*path) != self.project_diagnostics.get(*path))
+            .cloned()
+            .collect();
+        self.project_diagnostics = updated;
+        changed.into_iter().collect()
+    }
+
+    /// Returns the new state of `path`, `Some(None)` if it is no longer analyzed, or
+    /// `None` if nothing changed since it was last checked.
+    fn check_file(&self, path: &Path) -> Option<Option<FileState>> {
+        let content = (path.is_file() && self.filter.is_included(path))
+            .then(|| fs::read_to_string(path).ok())
+            .flatten();
+        let previous = self.files.get(path).map(|state| state.content.as_str());
+        if content.as_deref() == previous {
+            return None;
+        }
+
+        Some(content.map(|content| {
+            let parsed = ParsedFile::from_source(&path.to_string_lossy(), &content);
+            let diagnostics = self.engine.analyze(&parsed);
+            FileState {
+                content,
+                diagnostics,
+            }
+        }))
+    }
+
+    fn is_settings_file(&self, event_path: &Path) -> bool {
+        if self.config_file.as_deref() == Some(event_path) {
+            return true;
+        }
+        let is_settings_name = event_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| SETTINGS_FILENAMES.contains(&name));
+        let path = self.local_path(event_path);
+
+        is_settings_name
+            && path.starts_with(&self.path)
+            && path
+                .parent()
+                .is_none_or(|dir| !self.filter.in_skipped_location(dir))
+    }
+
+    /// Maps a canonical path from the watcher to the form used for analyzed files.
+    fn local_path(&self, event_path: &Path) -> PathBuf {
+        match event_path.strip_prefix(&self.root) {
+            Ok(relative) if relative.as_os_str().is_empty() => self.path.clone(),
+            Ok(relative) => self.path.join(relative),
+            Err(_) => event_path.to_path_buf(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(&path, content).unwrap();
+        path.canonicalize().unwrap()
+    }
+
+    /// A config file roots the filter at the temporary directory.
+    fn project() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), CONFIG_FILENAME, "");
+        dir
+    }
+
+    fn rule_ids(session: &WatchSession) -> Vec<String> {
+        session
+            .diagnostics()
+            .into_iter()
+            .map(|d| d.rule_id)
+            .collect()
+    }
+
+    #[test]
+    fn debouncer_waits_for_quiet_period() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(Duration::from_millis(100));
+        assert!(debouncer.deadline().is_none());
+
+        debouncer.schedule(PathBuf::from("a.js"), start);
+        debouncer.schedule(PathBuf::from("b.js"), start + Duration::from_millis(80));
+        debouncer.schedule(PathBuf::from("a.js"), start + Duration::from_millis(90));
+
+        assert!(
+            debouncer
+                .take_ready(start + Duration::from_millis(150))
+                .is_empty()
+        );
+        assert_eq!(
+            debouncer.take_ready(start + Duration::from_millis(190)),
+            vec![PathBuf::from("a.js"), PathBuf::from("b.js")]
+        );
+        assert!(debouncer.deadline().is_none());
+        assert!(
+            debouncer
+                .take_ready(start + Duration::from_secs(1))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn session_rechecks_modified_and_deleted_files() {
+        let dir = project();
+        let app = write(
+            dir.path(),
+            "src/app.js",
+            "var x = 1;\nmodule.exports = { x };\n",
+        );
+        write(
+            dir.path(),
+            "src/other.js",
+            "var y = 1;\nmodule.exports = { y };\n",
+        );
+
+        let mut session = WatchSession::load(dir.path(), PremiumTier::Free).unwrap();
+        assert_eq!(session.files.len(), 2);
+        assert_eq!(rule_ids(&session), vec!["Q030", "Q030"]);
+
+        let local = session.local_path(&app);
+        assert!(session.update(std::slice::from_ref(&app)).is_empty());
+
+        fs::write(&app, "const x = 1;\nmodule.exports = { x };\n").unwrap();
+        assert_eq!(
+            session.update(std::slice::from_ref(&app)),
+            vec![local.clone()]
+        );
+        assert_eq!(rule_ids(&session), vec!["Q030"]);
+
+        fs::remove_file(&app).unwrap();
+        assert!(session.is_relevant(&app));
+        assert_eq!(session.update(&[app]), vec![local]);
+        assert_eq!(session.files.len(), 1);
+    }
+
+    #[test]
+    fn session_ignores_files_outside_the_filter() {
+        let dir = project();
+        write(dir.path(), "app.js", "const x = 1;\n");
+        let dependency = write(
+            dir.path(),
+            "node_modules/lib/index.js",
+            "var x = 1;\nexport { x };\n",
+        );
+        let readme = write(dir.path(), "README.md", "# App\n");
+
+        let mut session = WatchSession::load(dir.path(), PremiumTier::Free).unwrap();
+
+        assert!(!session.is_relevant(&dependency));
+        assert!(!session.is_relevant(&readme));
+        assert!(
+            session
+                .update(&[dependency, dir.path().join("node_modules")])
+                .is_empty()
+        );
+        assert_eq!(session.files.len(), 1);
+    }
+
+    #[test]
+    fn session_reports_only_diagnostics_above_thresholds() {
+        let dir = project();
+        write(
+            dir.path(),
+            "audit.js",
+            "var x = 1;\nfunction audit(req) {\n  const claims = jwt.decode(req.headers.authorization);\n  logger.info(claims.sub);\n}\nmodule.exports = { audit, x };\n",
+        );
+
+        let session = WatchSession::load(dir.path(), PremiumTier::Free).unwrap();
+        assert_eq!(rule_ids(&session), vec!["Q030"]);
+
+        let session = session.with_thresholds(Severity::Hint, Confidence::Low);
+        assert_eq!(rule_ids(&session), vec!["Q030", "S009"]);
+
+        let session = session.with_thresholds(Severity::Error, Confidence::Low);
+        assert!(session.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn session_reloads_when_config_changes() {
+        let dir = project();
+        write(
+            dir.path(),
+            "app.js",
+            "var x = 1;\nmodule.exports = { x };\n",
+        );
+        let config = dir.path().join(CONFIG_FILENAME).canonicalize().unwrap();
+
+        let mut session = WatchSession::load(dir.path(), PremiumTier::Free).unwrap();
+        assert_eq!(rule_ids(&session), vec!["Q030"]);
+
+        fs::write(&config, "[rules]\ndisabled = [\"Q030\"]\n").unwrap();
+        assert!(session.is_relevant(&config));
+        assert_eq!(session.update(&[config]).len(), 1);
+        assert!(session.diagnostics().is_empty());
+    }
+}
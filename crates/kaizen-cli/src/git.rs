@@ -0,0 +1,262 @@
+//! Git queries for diff-aware analysis
+
+use anyhow::Result;
+use rust_i18n::t;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Files changed since a revision, with the line ranges added or modified in each.
+#[derive(Debug, Default)]
+pub struct ChangedFiles {
+    hunks: HashMap<PathBuf, Vec<RangeInclusive<usize>>>,
+}
+
+impl ChangedFiles {
+    /// Compares the working tree with the merge base of `rev` and `HEAD`, like a pull request.
+    /// Untracked files count as entirely changed.
+    pub fn since(rev: &str) -> Result<Self> {
+        let root = PathBuf::from(run_git(&["rev-parse", "--show-toplevel"], None)?.trim());
+        let root = root.canonicalize().unwrap_or(root);
+        let base = run_git(&["merge-base", rev, "HEAD"], Some(&root))?;
+        let diff = run_git(
+            &[
+                // Keep non-ASCII names readable instead of octal-escaped
+                "-c",
+                "core.quotePath=false",
+                "diff",
+                "--unified=0",
+                "--no-color",
+                "--no-ext-diff",
+                "--diff-filter=ACMR",
+                // `diff.noprefix` and `diff.mnemonicPrefix` would change the `b/` that `parse_diff` strips
+                "--src-prefix=a/",
+                "--dst-prefix=b/",
+                base.trim(),
+            ],
+            Some(&root),
+        )?;
+        let untracked = run_git(
+            &["ls-files", "-z", "--others", "--exclude-standard"],
+            Some(&root),
+        )?;
+
+        let mut changed = parse_diff(&diff, &root);
+        for path in untracked.split('\0').filter(|path| !path.is_empty()) {
+            changed
+                .hunks
+                .entry(root.join(path))
+                .or_default()
+                .push(1..=usize::MAX);
+        }
+        Ok(changed)
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.hunks.keys().map(PathBuf::as_path)
+    }
+
+    /// Changed line ranges of `file`, which may be relative to the working directory.
+    pub fn hunks(&self, file: &Path) -> &[RangeInclusive<usize>] {
+        let file = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+        self.hunks.get(&file).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+/// Returns true if lines `start..=end` overlap one of `hunks`.
+pub fn overlaps(hunks: &[RangeInclusive<usize>], start: usize, end: usize) -> bool {
+    hunks
+        .iter()
+        .any(|hunk| *hunk.start() <= end.max(start) && start <= *hunk.end())
+}
+
+fn run_git(args: &[&str], dir: Option<&Path>) -> Result<String> {
+    let mut command = Command::new("git");
+    command.args(args);
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    let output = command
+        .output()
+        .map_err(|e| anyhow::anyhow!("{}", t!("check.git_failed", error = e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{}", t!("check.git_error", error = stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parses `git diff --unified=0` output into added line ranges per file, joined to `root`.
+fn parse_diff(diff: &str, root: &Path) -> ChangedFiles {
+    let mut changed = ChangedFiles::default();
+    let mut current: Option<PathBuf> = None;
+    // `+++` only names a file in the header; inside a hunk it may be an added line.
+    let mut in_header = false;
+
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            in_header = true;
+            current = None;
+        } else if in_header && let Some(path) = line.strip_prefix("+++ ") {
+            // Git ends paths that contain spaces with a tab
+            current = unquote_path(path.trim_end_matches('\t'))
+                .strip_prefix("b/")
+                .map(|path| root.join(path));
+            if let Some(path) = &current {
+                changed.hunks.entry(path.clone()).or_default();
+            }
+        } else if line.starts_with("@@ ") {
+            in_header = false;
+            if let (Some(path), Some(range)) = (&current, parse_hunk_header(line)) {
+                changed.hunks.entry(path.clone()).or_default().push(range);
+            }
+        }
+    }
+
+    changed
+}
+
+/// Decodes a path git printed in double quotes, which it does for names with control
+/// characters, quotes or backslashes, escaping them the way C does.
+fn unquote_path(path: &str) -> String {
+    let Some(quoted) = path.strip_prefix('"').and_then(|p| p.strip_suffix('"')) else {
+        return path.to_string();
+    };
+
+    let mut bytes = Vec::with_capacity(quoted.len());
+    let mut rest = quoted.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        let Some((&escaped, tail)) = rest.split_first() else {
+            bytes.push(byte);
+            break;
+        };
+        rest = tail;
+        bytes.push(match escaped {
+            b'0'..=b'7' => {
+                let digits = rest.iter().take(2).take_while(|d| matches!(d, b'0'..=b'7'));
+                let mut value = u32::from(escaped - b'0');
+                for &digit in digits {
+                    value = value * 8 + u32::from(digit - b'0');
+                    rest = &rest[1..];
+                }
+                value as u8
+            }
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b'f' => 0x0c,
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b't' => b'\t',
+            b'v' => 0x0b,
+            other => other,
+        });
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Reads the new-file range of `@@ -a,b +c,d @@`. Pure deletions add no lines and yield None.
+fn parse_hunk_header(line: &str) -> Option<RangeInclusive<usize>> {
+    let added = line
+        .split_whitespace()
+        .find_map(|part| part.strip_prefix('+'))?;
+    let (start, count) = match added.split_once(',') {
+        Some((start, count)) => (start.parse().ok()?, count.parse().ok()?),
+        None => (added.parse().ok()?, 1),
+    };
+
+    (count > 0).then(|| start..=start + count - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/src/app.js b/src/app.js
+index 1111111..2222222 100644
+--- a/src/app.js
++++ b/src/app.js
+@@ -3 +3 @@ function main() {
+-  old();
++  next();
+@@ -10,0 +11,3 @@ function main() {
++++ counter;
++  b();
++  c();
+@@ -20,2 +23,0 @@
+-  gone();
+-  gone();
+diff --git a/src/new.ts b/src/new.ts
+new file mode 100644
+--- /dev/null
++++ b/src/new.ts
+@@ -0,0 +1,2 @@
++export const x = 1;
++export const y = 2;
+";
+
+    #[test]
+    fn strips_tab_after_paths_with_spaces() {
+        let diff = "\
+diff --git a/my app.js b/my app.js
+--- a/my app.js\t
++++ b/my app.js\t
+@@ -1 +1 @@
+-old();
++next();
+";
+        let changed = parse_diff(diff, Path::new("/repo"));
+
+        assert!(overlaps(changed.hunks(Path::new("/repo/my app.js")), 1, 1));
+    }
+
+    #[test]
+    fn decodes_quoted_paths() {
+        let diff = "\
+diff --git \"a/caf\\303\\251.js\" \"b/caf\\303\\251.js\"
+--- \"a/caf\\303\\251.js\"
++++ \"b/caf\\303\\251.js\"
+@@ -1 +1 @@
+-old();
++next();
+";
+        let changed = parse_diff(diff, Path::new("/repo"));
+
+        assert!(overlaps(changed.hunks(Path::new("/repo/café.js")), 1, 1));
+        assert_eq!(unquote_path(r#""b/say \"hi\".js""#), "b/say \"hi\".js");
+        assert_eq!(unquote_path(r#""b/tab\there.js""#), "b/tab\there.js");
+        assert_eq!(unquote_path("b/plain.js"), "b/plain.js");
+    }
+
+    #[test]
+    fn parses_hunk_headers() {
+        assert_eq!(parse_hunk_header("@@ -3 +3 @@"), Some(3..=3));
+        assert_eq!(parse_hunk_header("@@ -10,0 +11,3 @@ fn"), Some(11..=13));
+        assert_eq!(parse_hunk_header("@@ -20,2 +23,0 @@"), None);
+    }
+
+    #[test]
+    fn collects_files_and_added_ranges() {
+        let changed = parse_diff(DIFF, Path::new("/repo"));
+        let app = Path::new("/repo/src/app.js");
+
+        let mut files: Vec<&Path> = changed.files().collect();
+        files.sort();
+        assert_eq!(files, vec![app, Path::new("/repo/src/new.ts")]);
+
+        let hunks = changed.hunks(app);
+        assert!(overlaps(hunks, 3, 3));
+        assert!(overlaps(hunks, 9, 11));
+        assert!(!overlaps(hunks, 4, 10));
+        assert!(!overlaps(hunks, 23, 23));
+        assert!(overlaps(changed.hunks(Path::new("/repo/src/new.ts")), 2, 2));
+        assert!(changed.hunks(Path::new("/repo/src/other.ts")).is_empty());
+    }
+}
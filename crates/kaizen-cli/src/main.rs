@@ -3,6 +3,7 @@
 //! Ultra-fast JavaScript/TypeScript static analyzer written in Rust.
 
 mod commands;
+mod git;
 mod i18n;
 mod license;
 mod output;
@@ -38,8 +39,10 @@ fn main() -> anyhow::Result<()> {
     match cli.command {
         Commands::Auth(args) => args.run(),
         Commands::Check(args) => args.run(),
+        Commands::Fix(args) => args.run(),
         Commands::Init(args) => args.run(),
         Commands::Explain(args) => args.run(),
+        Commands::Watch(args) => args.run(),
     }
 }
 
@@ -71,6 +74,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cli_parses_check_changed_since() {
+        let cli = Cli::try_parse_from([
+            "kaizen",
+            "check",
+            "--changed-since",
+            "origin/main",
+            "--only-changed-lines",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Check(args) => {
+                assert_eq!(args.changed_since.as_deref(), Some("origin/main"));
+                assert!(args.only_changed_lines);
+                assert!(args.path.is_none());
+            }
+            _ => panic!("Expected Check command"),
+        }
+    }
+
+    #[test]
+    fn cli_rejects_only_changed_lines_without_changed_since() {
+        assert!(Cli::try_parse_from(["kaizen", "check", ".", "--only-changed-lines"]).is_err());
+        assert!(
+            Cli::try_parse_from(["kaizen", "check", "--staged", "--changed-since", "main"])
+                .is_err()
+        );
+    }
+
     #[test]
     fn cli_parses_init_with_hook() {
         let cli = Cli::try_parse_from(["kaizen", "init", "--hook", "pre-commit"]).unwrap();
@@ -93,6 +125,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cli_parses_fix_command() {
+        let cli = Cli::try_parse_from(["kaizen", "fix", "./src", "--dry-run"]).unwrap();
+        match cli.command {
+            Commands::Fix(args) => {
+                assert_eq!(args.path.to_str().unwrap(), "./src");
+                assert!(args.dry_run);
+            }
+            _ => panic!("Expected Fix command"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_check_with_fix() {
+        let cli =
+            Cli::try_parse_from(["kaizen", "check", "./src", "--fix", "--unsafe-fixes"]).unwrap();
+        match cli.command {
+            Commands::Check(args) => {
+                assert!(args.fix);
+                assert!(!args.dry_run);
+                assert!(args.unsafe_fixes);
+            }
+            _ => panic!("Expected Check command"),
+        }
+    }
+
+    #[test]
+    fn cli_rejects_dry_run_without_fix() {
+        let result = Cli::try_parse_from(["kaizen", "check", "./src", "--dry-run"]);
+        assert!(result.is_err());
+
+        let result = Cli::try_parse_from(["kaizen", "check", "./src", "--unsafe-fixes"]);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn cli_parses_init_command() {
         let cli = Cli::try_parse_from(["kaizen", "init"]).unwrap();
@@ -133,6 +200,7 @@ mod tests {
         let help = cmd.render_help().to_string();
         assert!(help.contains("auth"));
         assert!(help.contains("check"));
+        assert!(help.contains("fix"));
         assert!(help.contains("init"));
         assert!(help.contains("explain"));
     }
@@ -199,4 +267,19 @@ mod tests {
             _ => panic!("Expected Explain command"),
         }
     }
+
+    #[test]
+    fn cli_parses_watch_with_defaults() {
+        let cli = Cli::try_parse_from(["kaizen", "watch"]).unwrap();
+        match cli.command {
+            Commands::Watch(args) => {
+                assert_eq!(args.path, std::path::PathBuf::from("."));
+                assert_eq!(args.format, "pretty");
+                assert_eq!(args.debounce, 150);
+            }
+            _ => panic!("Expected Watch command"),
+        }
+
+        assert!(Cli::try_parse_from(["kaizen", "watch", "--format", "sarif"]).is_err());
+    }
 }
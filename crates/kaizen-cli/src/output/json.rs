@@ -2,7 +2,7 @@
 //!
 //! Provides structured JSON and NDJSON output formats for programmatic integration.
 
-use kaizen_core::diagnostic::{Diagnostic, Fix, FixKind};
+use kaizen_core::diagnostic::{Diagnostic, Fix, FixKind, RelatedLocation, TextEdit};
 use kaizen_core::rules::{RuleCategory, RuleRegistry, Severity};
 use serde::Serialize;
 use std::collections::HashMap;
@@ -61,6 +61,16 @@ pub struct JsonDiagnostic {
     pub suggestion: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub fixes: Vec<JsonFix>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<JsonRelatedLocation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trace: Vec<JsonRelatedLocation>,
+}
+
+#[derive(Serialize)]
+pub struct JsonRelatedLocation {
+    pub message: String,
+    pub location: JsonLocation,
 }
 
 #[derive(Serialize)]
@@ -79,6 +89,12 @@ pub struct JsonPosition {
 #[derive(Serialize)]
 pub struct JsonFix {
     pub title: String,
+    pub applicability: String,
+    pub edits: Vec<JsonEdit>,
+}
+
+#[derive(Serialize)]
+pub struct JsonEdit {
     pub kind: String,
     pub start: JsonPosition,
     pub end: JsonPosition,
@@ -88,13 +104,22 @@ pub struct JsonFix {
     pub insert_text: Option<String>,
 }
 
+/// Current diagnostics of one file, replacing any earlier ones, as streamed by `kaizen watch`.
+#[derive(Serialize)]
+pub struct JsonFileUpdate {
+    pub file: String,
+    pub diagnostics: Vec<JsonDiagnostic>,
+}
+
 #[derive(Serialize)]
 #[serde(tag = "type")]
 pub enum NdjsonRecord {
     #[serde(rename = "metadata")]
     Metadata(JsonMetadata),
     #[serde(rename = "diagnostic")]
-    Diagnostic(JsonDiagnostic),
+    Diagnostic(Box<JsonDiagnostic>),
+    #[serde(rename = "file")]
+    File(JsonFileUpdate),
     #[serde(rename = "summary")]
     Summary(JsonSummary),
 }
@@ -143,18 +168,46 @@ impl<'a> JsonFormatter<'a> {
             writeln!(
                 writer,
                 "{}",
-                serde_json::to_string(&NdjsonRecord::Diagnostic(json_diag))?
+                serde_json::to_string(&NdjsonRecord::Diagnostic(Box::new(json_diag)))?
             )?;
         }
 
+        self.format_ndjson_summary(diagnostics, total_files, writer)
+    }
+
+    /// Writes a `file` record holding every diagnostic currently reported for `file`.
+    pub fn format_ndjson_file<W: Write>(
+        &self,
+        file: &str,
+        diagnostics: &[Diagnostic],
+        writer: &mut W,
+    ) -> io::Result<()> {
+        let update = JsonFileUpdate {
+            file: file.to_string(),
+            diagnostics: diagnostics
+                .iter()
+                .map(|d| self.convert_diagnostic(d))
+                .collect(),
+        };
+        writeln!(
+            writer,
+            "{}",
+            serde_json::to_string(&NdjsonRecord::File(update))?
+        )
+    }
+
+    pub fn format_ndjson_summary<W: Write>(
+        &self,
+        diagnostics: &[Diagnostic],
+        total_files: usize,
+        writer: &mut W,
+    ) -> io::Result<()> {
         let summary = self.build_summary(diagnostics, total_files);
         writeln!(
             writer,
             "{}",
             serde_json::to_string(&NdjsonRecord::Summary(summary))?
-        )?;
-
-        Ok(())
+        )
     }
 
     fn build_output(
@@ -247,6 +300,8 @@ impl<'a> JsonFormatter<'a> {
             },
             suggestion: diag.suggestion.clone(),
             fixes: diag.fixes.iter().map(convert_fix).collect(),
+            related: diag.related.iter().map(convert_related).collect(),
+            trace: diag.trace.iter().map(convert_related).collect(),
         }
     }
 
@@ -278,21 +333,46 @@ impl Default for JsonFormatter<'_> {
 }
 
 fn convert_fix(fix: &Fix) -> JsonFix {
-    let (kind, new_text, insert_text) = match &fix.kind {
+    JsonFix {
+        title: fix.title.clone(),
+        applicability: fix.applicability.as_str().to_string(),
+        edits: fix.edits.iter().map(convert_edit).collect(),
+    }
+}
+
+fn convert_related(related: &RelatedLocation) -> JsonRelatedLocation {
+    JsonRelatedLocation {
+        message: related.message.clone(),
+        location: JsonLocation {
+            file: related.file.clone(),
+            start: JsonPosition {
+                line: related.line,
+                column: related.column,
+            },
+            end: JsonPosition {
+                line: related.end_line,
+                column: related.end_column,
+            },
+        },
+    }
+}
+
+fn convert_edit(edit: &TextEdit) -> JsonEdit {
+    let (kind, new_text, insert_text) = match &edit.kind {
         FixKind::ReplaceWith { new_text } => ("replace", Some(new_text.clone()), None),
         FixKind::InsertBefore { text } => ("insert_before", None, Some(text.clone())),
+        FixKind::Delete => ("delete", None, None),
     };
 
-    JsonFix {
-        title: fix.title.clone(),
+    JsonEdit {
         kind: kind.to_string(),
         start: JsonPosition {
-            line: fix.line,
-            column: fix.column,
+            line: edit.line,
+            column: edit.column,
         },
         end: JsonPosition {
-            line: fix.end_line,
-            column: fix.end_column,
+            line: edit.end_line,
+            column: edit.end_column,
         },
         new_text,
         insert_text,
@@ -382,6 +462,62 @@ mod tests {
         assert_eq!(diag["suggestion"], "Use 'let' or 'const' instead");
     }
 
+    #[test]
+    fn format_includes_fix_edits_and_applicability() {
+        use kaizen_core::diagnostic::Applicability;
+
+        let formatter = JsonFormatter::new();
+        let diagnostic = sample_diagnostic().with_fix(
+            Fix::replace("Replace 'var' with 'let'", "let", 10, 1, 10, 3)
+                .with_edit(TextEdit::delete(11, 1, 11, 4))
+                .with_applicability(Applicability::Suggestion),
+        );
+
+        let output = formatter.format(&[diagnostic], 1, "./src");
+
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let fix = &parsed["diagnostics"][0]["fixes"][0];
+        assert_eq!(fix["applicability"], "suggestion");
+        assert_eq!(fix["edits"][0]["kind"], "replace");
+        assert_eq!(fix["edits"][0]["new_text"], "let");
+        assert_eq!(fix["edits"][1]["kind"], "delete");
+        assert_eq!(fix["edits"][1]["end"]["column"], 4);
+        assert!(fix["edits"][1].get("new_text").is_none());
+    }
+
+    #[test]
+    fn format_includes_related_locations() {
+        let formatter = JsonFormatter::new();
+        let diagnostic = sample_diagnostic()
+            .with_related(RelatedLocation::new("imports 'b.js'", "a.js", 2, 1).with_end(2, 20));
+
+        let output = formatter.format(&[diagnostic, sample_diagnostic()], 1, "./src");
+
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let related = &parsed["diagnostics"][0]["related"][0];
+        assert_eq!(related["message"], "imports 'b.js'");
+        assert_eq!(related["location"]["file"], "a.js");
+        assert_eq!(related["location"]["end"]["column"], 20);
+        assert!(parsed["diagnostics"][1].get("related").is_none());
+    }
+
+    #[test]
+    fn format_includes_trace() {
+        let formatter = JsonFormatter::new();
+        let diagnostic = sample_diagnostic().with_trace(vec![
+            RelatedLocation::new("Untrusted data enters from 'req.query.id'", "a.js", 3, 14),
+            RelatedLocation::new("Reaches the sink: Database query execution", "a.js", 5, 5),
+        ]);
+
+        let output = formatter.format(&[diagnostic, sample_diagnostic()], 1, "./src");
+
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let trace = parsed["diagnostics"][0]["trace"].as_array().unwrap();
+        assert_eq!(trace.len(), 2);
+        assert_eq!(trace[1]["location"]["start"]["line"], 5);
+        assert!(parsed["diagnostics"][1].get("trace").is_none());
+    }
+
     #[test]
     fn ndjson_format_produces_lines() {
         let formatter = JsonFormatter::new();
@@ -406,6 +542,30 @@ mod tests {
         assert_eq!(summary["type"], "summary");
     }
 
+    #[test]
+    fn ndjson_file_record_replaces_diagnostics_of_one_file() {
+        let formatter = JsonFormatter::new();
+        let mut output = Vec::new();
+
+        formatter
+            .format_ndjson_file("test.js", &[sample_diagnostic()], &mut output)
+            .unwrap();
+        formatter
+            .format_ndjson_file("clean.js", &[], &mut output)
+            .unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        let lines: Vec<serde_json::Value> = output_str
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines[0]["type"], "file");
+        assert_eq!(lines[0]["file"], "test.js");
+        assert_eq!(lines[0]["diagnostics"][0]["rule_id"], "Q030");
+        assert_eq!(lines[1]["file"], "clean.js");
+        assert!(lines[1]["diagnostics"].as_array().unwrap().is_empty());
+    }
+
     #[test]
     fn empty_diagnostics_produces_valid_output() {
         let formatter = JsonFormatter::new();
@@ -96,6 +96,41 @@ impl PrettyFormatter {
             lines.push(format!("{} {}", padding, "|".blue()));
         }
 
+        for related in &diag.related {
+            let padding = " ".repeat(diag.line.to_string().len());
+            lines.push(format!(
+                "{} {} {} {}:{}:{}: {}",
+                padding,
+                "=".blue(),
+                t!("output.note").cyan(),
+                related.file,
+                related.line,
+                related.column,
+                related.message
+            ));
+        }
+
+        if !diag.trace.is_empty() {
+            let padding = " ".repeat(diag.line.to_string().len());
+            lines.push(format!(
+                "{} {} {}",
+                padding,
+                "=".blue(),
+                t!("output.trace").cyan()
+            ));
+            for (index, step) in diag.trace.iter().enumerate() {
+                lines.push(format!(
+                    "{}     {}. {}:{}:{}: {}",
+                    padding,
+                    index + 1,
+                    step.file,
+                    step.line,
+                    step.column,
+                    step.message
+                ));
+            }
+        }
+
         if let Some(suggestion) = &diag.suggestion {
             let line_num_width = diag.line.to_string().len();
             let padding = " ".repeat(line_num_width);
@@ -192,6 +227,7 @@ impl Default for PrettyFormatter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use kaizen_core::diagnostic::RelatedLocation;
 
     fn create_test_diagnostic(severity: Severity, line: usize, column: usize) -> Diagnostic {
         Diagnostic::new(
@@ -343,6 +379,36 @@ mod tests {
         assert!(output.contains("Use 'let' or 'const' instead of 'var'"));
     }
 
+    #[test]
+    fn shows_related_locations_as_notes() {
+        let diag = create_test_diagnostic(Severity::Warning, 1, 2)
+            .with_related(RelatedLocation::new("imports 'b.js'", "a.js", 3, 1))
+            .with_related(RelatedLocation::new("imports 'a.js'", "b.js", 1, 1));
+
+        let formatter = PrettyFormatter::new();
+        let output = formatter.format(&[diag]);
+
+        assert!(output.contains("note: a.js:3:1: imports 'b.js'"));
+        assert!(output.contains("note: b.js:1:1: imports 'a.js'"));
+    }
+
+    #[test]
+    fn shows_trace_as_numbered_steps() {
+        let diag = create_test_diagnostic(Severity::Error, 1, 2).with_trace(vec![
+            RelatedLocation::new("Untrusted data enters from 'req'", "a.js", 2, 18),
+            RelatedLocation::new("Assigned to 'query'", "a.js", 3, 11),
+            RelatedLocation::new("Reaches the sink: Database query execution", "a.js", 4, 5),
+        ]);
+
+        let formatter = PrettyFormatter::new();
+        let output = formatter.format(&[diag]);
+
+        assert!(output.contains("trace:"));
+        assert!(output.contains("1. a.js:2:18: Untrusted data enters from 'req'"));
+        assert!(output.contains("2. a.js:3:11: Assigned to 'query'"));
+        assert!(output.contains("3. a.js:4:5: Reaches the sink: Database query execution"));
+    }
+
     #[test]
     fn empty_diagnostics_produces_empty_output() {
         let formatter = PrettyFormatter::new();
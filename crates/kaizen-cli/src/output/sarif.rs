@@ -3,7 +3,8 @@
 //! Provides SARIF 2.1.0 output format for integration with GitHub Code Scanning
 //! and other static analysis tools that support the SARIF standard.
 
-use kaizen_core::diagnostic::{Diagnostic, Fix, FixKind};
+use kaizen_core::baseline::BaselineState;
+use kaizen_core::diagnostic::{Diagnostic, Fix, FixKind, TextEdit};
 use kaizen_core::rules::{RuleCategory, RuleRegistry, Severity};
 use serde::Serialize;
 use std::collections::HashSet;
@@ -94,7 +95,13 @@ pub struct SarifResult {
     pub level: String,
     pub message: SarifMessage,
     pub locations: Vec<SarifLocation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related_locations: Vec<SarifLocation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub code_flows: Vec<SarifCodeFlow>,
     pub partial_fingerprints: SarifPartialFingerprints,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline_state: Option<&'static str>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub fixes: Vec<SarifFix>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -104,7 +111,29 @@ pub struct SarifResult {
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SarifLocation {
+    /// Set on related locations, which results may refer to by id.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<usize>,
     pub physical_location: SarifPhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<SarifMessage>,
+}
+
+/// The steps of a data flow, such as a taint trace from source to sink.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifCodeFlow {
+    pub thread_flows: Vec<SarifThreadFlow>,
+}
+
+#[derive(Serialize)]
+pub struct SarifThreadFlow {
+    pub locations: Vec<SarifThreadFlowLocation>,
+}
+
+#[derive(Serialize)]
+pub struct SarifThreadFlowLocation {
+    pub location: SarifLocation,
 }
 
 #[derive(Serialize)]
@@ -185,19 +214,30 @@ pub struct SarifResultProperties {
 
 pub struct SarifFormatter<'a> {
     registry: Option<&'a RuleRegistry>,
+    baseline_states: Option<&'a [BaselineState]>,
 }
 
 impl<'a> SarifFormatter<'a> {
     pub fn new() -> Self {
-        Self { registry: None }
+        Self {
+            registry: None,
+            baseline_states: None,
+        }
     }
 
     pub fn with_registry(registry: &'a RuleRegistry) -> Self {
         Self {
             registry: Some(registry),
+            baseline_states: None,
         }
     }
 
+    /// Sets `baselineState` on each result, given one state per diagnostic in format order.
+    pub fn with_baseline_states(mut self, states: &'a [BaselineState]) -> Self {
+        self.baseline_states = Some(states);
+        self
+    }
+
     pub fn format(&self, diagnostics: &[Diagnostic]) -> String {
         let output = self.build_output(diagnostics);
         serde_json::to_string_pretty(&output).unwrap_or_else(|_| "{}".to_string())
@@ -206,7 +246,18 @@ impl<'a> SarifFormatter<'a> {
     fn build_output(&self, diagnostics: &[Diagnostic]) -> SarifOutput {
         let rule_ids: HashSet<&str> = diagnostics.iter().map(|d| d.rule_id.as_str()).collect();
         let rules = self.build_rules(&rule_ids);
-        let results = diagnostics.iter().map(|d| self.convert_result(d)).collect();
+        let results = diagnostics
+            .iter()
+            .enumerate()
+            .map(|(index, d)| {
+                let mut result = self.convert_result(d);
+                result.baseline_state = self
+                    .baseline_states
+                    .and_then(|states| states.get(index))
+                    .map(baseline_state_to_sarif);
+                result
+            })
+            .collect();
         let artifacts = self.build_artifacts(diagnostics);
 
         SarifOutput {
@@ -288,28 +339,57 @@ impl<'a> SarifFormatter<'a> {
         let (level, _) = self.get_diagnostic_level(diag);
 
         let locations = vec![SarifLocation {
-            physical_location: SarifPhysicalLocation {
-                artifact_location: SarifArtifactLocation {
-                    uri: normalize_path(&diag.file),
-                    uri_base_id: Some("%SRCROOT%".to_string()),
-                },
-                region: SarifRegion {
-                    start_line: diag.line,
-                    start_column: diag.column,
-                    end_line: if diag.end_line != diag.line {
-                        Some(diag.end_line)
-                    } else {
-                        None
-                    },
-                    end_column: if diag.end_column != diag.column || diag.end_line != diag.line {
-                        Some(diag.end_column)
-                    } else {
-                        None
-                    },
-                },
-            },
+            id: None,
+            physical_location: physical_location(
+                &diag.file,
+                (diag.line, diag.column),
+                (diag.end_line, diag.end_column),
+            ),
+            message: None,
         }];
 
+        let related_locations = diag
+            .related
+            .iter()
+            .enumerate()
+            .map(|(index, related)| SarifLocation {
+                id: Some(index),
+                physical_location: physical_location(
+                    &related.file,
+                    (related.line, related.column),
+                    (related.end_line, related.end_column),
+                ),
+                message: Some(SarifMessage {
+                    text: related.message.clone(),
+                }),
+            })
+            .collect();
+
+        let code_flows = if diag.trace.is_empty() {
+            Vec::new()
+        } else {
+            let locations = diag
+                .trace
+                .iter()
+                .map(|step| SarifThreadFlowLocation {
+                    location: SarifLocation {
+                        id: None,
+                        physical_location: physical_location(
+                            &step.file,
+                            (step.line, step.column),
+                            (step.end_line, step.end_column),
+                        ),
+                        message: Some(SarifMessage {
+                            text: step.message.clone(),
+                        }),
+                    },
+                })
+                .collect();
+            vec![SarifCodeFlow {
+                thread_flows: vec![SarifThreadFlow { locations }],
+            }]
+        };
+
         let fixes = diag
             .fixes
             .iter()
@@ -336,38 +416,18 @@ impl<'a> SarifFormatter<'a> {
                 text: diag.message.clone(),
             },
             locations,
+            related_locations,
+            code_flows,
             partial_fingerprints: SarifPartialFingerprints {
                 primary_location_line_hash: fingerprint,
             },
+            baseline_state: None,
             fixes,
             properties,
         }
     }
 
     fn convert_fix(&self, fix: &Fix, file: &str) -> SarifFix {
-        let (deleted_region, inserted_content) = match &fix.kind {
-            FixKind::ReplaceWith { new_text } => (
-                SarifRegion {
-                    start_line: fix.line,
-                    start_column: fix.column,
-                    end_line: Some(fix.end_line),
-                    end_column: Some(fix.end_column),
-                },
-                Some(SarifArtifactContent {
-                    text: new_text.clone(),
-                }),
-            ),
-            FixKind::InsertBefore { text } => (
-                SarifRegion {
-                    start_line: fix.line,
-                    start_column: fix.column,
-                    end_line: None,
-                    end_column: None,
-                },
-                Some(SarifArtifactContent { text: text.clone() }),
-            ),
-        };
-
         SarifFix {
             description: SarifMessage {
                 text: fix.title.clone(),
@@ -377,10 +437,7 @@ impl<'a> SarifFormatter<'a> {
                     uri: normalize_path(file),
                     uri_base_id: Some("%SRCROOT%".to_string()),
                 },
-                replacements: vec![SarifReplacement {
-                    deleted_region,
-                    inserted_content,
-                }],
+                replacements: fix.edits.iter().map(convert_edit).collect(),
             }],
         }
     }
@@ -450,6 +507,33 @@ impl Default for SarifFormatter<'_> {
     }
 }
 
+fn baseline_state_to_sarif(state: &BaselineState) -> &'static str {
+    match state {
+        BaselineState::New => "new",
+        BaselineState::Unchanged => "unchanged",
+    }
+}
+
+/// A region of `file`, leaving out the end when it matches the start.
+fn physical_location(
+    file: &str,
+    (line, column): (usize, usize),
+    (end_line, end_column): (usize, usize),
+) -> SarifPhysicalLocation {
+    SarifPhysicalLocation {
+        artifact_location: SarifArtifactLocation {
+            uri: normalize_path(file),
+            uri_base_id: Some("%SRCROOT%".to_string()),
+        },
+        region: SarifRegion {
+            start_line: line,
+            start_column: column,
+            end_line: (end_line != line).then_some(end_line),
+            end_column: (end_column != column || end_line != line).then_some(end_column),
+        },
+    }
+}
+
 fn normalize_path(path: &str) -> String {
     path.trim_start_matches("./").to_string()
 }
@@ -465,6 +549,39 @@ fn generate_fingerprint(rule_id: &str, file: &str, line: usize) -> String {
     format!("{:x}", hasher.finish())
 }
 
+fn convert_edit(edit: &TextEdit) -> SarifReplacement {
+    let range = SarifRegion {
+        start_line: edit.line,
+        start_column: edit.column,
+        end_line: Some(edit.end_line),
+        end_column: Some(edit.end_column),
+    };
+
+    let (deleted_region, inserted_content) = match &edit.kind {
+        FixKind::ReplaceWith { new_text } => (
+            range,
+            Some(SarifArtifactContent {
+                text: new_text.clone(),
+            }),
+        ),
+        FixKind::InsertBefore { text } => (
+            SarifRegion {
+                start_line: edit.line,
+                start_column: edit.column,
+                end_line: None,
+                end_column: None,
+            },
+            Some(SarifArtifactContent { text: text.clone() }),
+        ),
+        FixKind::Delete => (range, None),
+    };
+
+    SarifReplacement {
+        deleted_region,
+        inserted_content,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -676,6 +793,91 @@ mod tests {
         assert_eq!(fixes[0]["description"]["text"], "Replace with let");
     }
 
+    #[test]
+    fn format_includes_related_locations() {
+        use kaizen_core::diagnostic::RelatedLocation;
+
+        let formatter = SarifFormatter::new();
+        let diag = sample_diagnostic()
+            .with_related(RelatedLocation::new("imports 'b.js'", "./a.js", 2, 1))
+            .with_related(RelatedLocation::new("imports 'a.js'", "./b.js", 1, 1));
+
+        let output = formatter.format(&[diag]);
+
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let result = &parsed["runs"][0]["results"][0];
+        let related = result["relatedLocations"].as_array().unwrap();
+        assert_eq!(related.len(), 2);
+        assert_eq!(related[1]["id"], 1);
+        assert_eq!(related[1]["message"]["text"], "imports 'a.js'");
+        assert_eq!(
+            related[1]["physicalLocation"]["artifactLocation"]["uri"],
+            "b.js"
+        );
+        assert!(result["locations"][0].get("id").is_none());
+    }
+
+    #[test]
+    fn format_includes_trace_as_code_flow() {
+        use kaizen_core::diagnostic::RelatedLocation;
+
+        let formatter = SarifFormatter::new();
+        let diag = sample_diagnostic().with_trace(vec![
+            RelatedLocation::new("Untrusted data enters from 'req'", "./routes.js", 3, 15),
+            RelatedLocation::new("Passed to 'findUser()'", "./routes.js", 4, 3),
+            RelatedLocation::new(
+                "Reaches the sink: Database query execution",
+                "./repo.js",
+                2,
+                10,
+            ),
+        ]);
+
+        let output = formatter.format(&[diag, sample_diagnostic()]);
+
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let results = &parsed["runs"][0]["results"];
+        let steps = results[0]["codeFlows"][0]["threadFlows"][0]["locations"]
+            .as_array()
+            .unwrap();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(
+            steps[1]["location"]["message"]["text"],
+            "Passed to 'findUser()'"
+        );
+        assert_eq!(
+            steps[2]["location"]["physicalLocation"]["artifactLocation"]["uri"],
+            "repo.js"
+        );
+        assert_eq!(
+            steps[2]["location"]["physicalLocation"]["region"]["startLine"],
+            2
+        );
+        assert!(results[1].get("codeFlows").is_none());
+    }
+
+    #[test]
+    fn format_includes_every_fix_edit() {
+        use kaizen_core::diagnostic::{Fix, TextEdit};
+
+        let formatter = SarifFormatter::new();
+        let diag =
+            Diagnostic::new("Q003", Severity::Warning, "Unused", "test.js", 1, 10).with_fix(
+                Fix::delete("Remove unused import", 1, 10, 1, 12)
+                    .with_edit(TextEdit::insert_before("// removed\n", 1, 1)),
+            );
+
+        let output = formatter.format(&[diag]);
+
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let replacements =
+            &parsed["runs"][0]["results"][0]["fixes"][0]["artifactChanges"][0]["replacements"];
+        assert_eq!(replacements.as_array().unwrap().len(), 2);
+        assert_eq!(replacements[0]["deletedRegion"]["endColumn"], 12);
+        assert!(replacements[0].get("insertedContent").is_none());
+        assert_eq!(replacements[1]["insertedContent"]["text"], "// removed\n");
+    }
+
     #[test]
     fn properties_include_confidence_when_not_high() {
         let formatter = SarifFormatter::new();
@@ -701,4 +903,24 @@ mod tests {
         let props = &parsed["runs"][0]["results"][0]["properties"];
         assert_eq!(props["suggestion"], "Try this instead");
     }
+
+    #[test]
+    fn baseline_state_only_set_with_baseline() {
+        let diagnostics = vec![sample_diagnostic(), sample_diagnostic()];
+        let states = [BaselineState::Unchanged, BaselineState::New];
+
+        let plain: serde_json::Value =
+            serde_json::from_str(&SarifFormatter::new().format(&diagnostics)).unwrap();
+        let with_baseline: serde_json::Value = serde_json::from_str(
+            &SarifFormatter::new()
+                .with_baseline_states(&states)
+                .format(&diagnostics),
+        )
+        .unwrap();
+
+        assert!(plain["runs"][0]["results"][0]["baselineState"].is_null());
+        let results = &with_baseline["runs"][0]["results"];
+        assert_eq!(results[0]["baselineState"], "unchanged");
+        assert_eq!(results[1]["baselineState"], "new");
+    }
 }
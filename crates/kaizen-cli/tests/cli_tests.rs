@@ -0,0 +1,37 @@
+//! Integration tests running the `kaizen-cli` binary
+
+use std::fs;
+use std::process::Command;
+
+use tempfile::tempdir;
+
+#[test]
+fn fix_dry_run_keeps_json_output_parseable() {
+    let dir = tempdir().unwrap();
+    let source = "var x = 1;\nmodule.exports = { x };\n";
+    fs::write(dir.path().join("app.js"), source).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_kaizen-cli"))
+        .current_dir(dir.path())
+        .args([
+            "check",
+            ".",
+            "--fix",
+            "--dry-run",
+            "--unsafe-fixes",
+            "--format",
+            "json",
+            "--no-cache",
+        ])
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(report.is_object());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("+const x = 1;"));
+    assert_eq!(
+        fs::read_to_string(dir.path().join("app.js")).unwrap(),
+        source
+    );
+}
@@ -2,43 +2,55 @@
 //!
 //! Provides the core analysis functionality for CLI and other consumers.
 
-use crate::config::Config;
+use std::collections::HashMap;
+
+use crate::config::{Config, DisableReasonPolicy};
 use crate::diagnostic::Diagnostic;
 use crate::licensing::PremiumTier;
 use crate::parser::ParsedFile;
+use crate::project::Project;
 use crate::rules::RuleRegistry;
 use crate::rules::quality::{
-    Eqeqeq, FloatingPromises, MaxComplexity, MaxDepth, NoConsole, NoEval, NoUnreachable,
-    NoUnusedImports, NoUnusedVars, NoVar, PreferConst, PreferNullishCoalescing,
-    PreferOptionalChaining, PreferUsing,
+    Eqeqeq, FloatingPromises, MaxComplexity, MaxDepth, NoConsole, NoEval, NoImportCycles,
+    NoUnreachable, NoUnusedExports, NoUnusedImports, NoUnusedVars, NoVar, PreferConst,
+    PreferNullishCoalescing, PreferOptionalChaining, PreferUsing, UnknownDisableRule,
+    UnusedDisableDirective,
 };
 use crate::rules::security::{
-    CommandInjection, EvalInjection, HardcodedSecrets, InsecureRandom, PrototypePollution, ReDoS,
-    SqlInjection, UnsafeDeserialization, WeakHashing, Xss,
+    CommandInjection, EvalInjection, HardcodedSecrets, InsecureJwt, InsecureRandom, NoSqlInjection,
+    OpenRedirect, PathTraversal, PrototypePollution, ReDoS, SqlInjection, Ssrf,
+    UnsafeDeserialization, WeakHashing, Xss,
 };
+use crate::suppressions::{filter_disabled, filter_disabled_and_report};
 
 pub struct AnalysisEngine {
     registry: RuleRegistry,
+    disable_reason: DisableReasonPolicy,
 }
 
 impl AnalysisEngine {
     pub fn new() -> Self {
         Self {
             registry: create_default_registry(),
+            disable_reason: DisableReasonPolicy::default(),
         }
     }
 
     pub fn with_config(config: &Config) -> Self {
-        let mut registry = create_default_registry();
-        registry.configure(&config.rules);
-        Self { registry }
+        let (registry, _) = configured_registry(config);
+        Self {
+            registry,
+            disable_reason: config.rules.require_disable_reason,
+        }
     }
 
     pub fn with_config_and_tier(config: &Config, tier: PremiumTier) -> Self {
-        let mut registry = create_default_registry();
-        registry.configure(&config.rules);
+        let (mut registry, _) = configured_registry(config);
         registry.set_tier(tier);
-        Self { registry }
+        Self {
+            registry,
+            disable_reason: config.rules.require_disable_reason,
+        }
     }
 
     pub fn registry(&self) -> &RuleRegistry {
@@ -46,30 +58,54 @@ impl AnalysisEngine {
     }
 
     pub fn analyze(&self, file: &ParsedFile) -> Vec<Diagnostic> {
-        let mut diagnostics = Vec::new();
-        let disable_directives = file.disable_directives();
-
-        for error in file.errors() {
-            let diagnostic = Diagnostic::new(
-                "PARSE",
-                crate::rules::Severity::Error,
-                &error.message,
-                &file.metadata().filename,
-                error.line,
-                error.column,
-            );
-            if !disable_directives.is_disabled(diagnostic.line, &diagnostic.rule_id) {
-                diagnostics.push(diagnostic);
-            }
+        let mut diagnostics: Vec<Diagnostic> = file
+            .errors()
+            .iter()
+            .map(|error| {
+                Diagnostic::new(
+                    "PARSE",
+                    crate::rules::Severity::Error,
+                    &error.message,
+                    &file.metadata().filename,
+                    error.line,
+                    error.column,
+                )
+            })
+            .collect();
+        diagnostics.extend(self.registry.run_all(file));
+
+        filter_disabled_and_report(file, &self.registry, self.disable_reason, diagnostics)
+    }
+
+    /// True if [`AnalysisEngine::analyze_project`] has rules to run.
+    pub fn has_project_rules(&self) -> bool {
+        self.registry.has_project_rules()
+    }
+
+    /// Runs the project rules over `project`, which should hold every analyzed file.
+    /// These diagnostics come in addition to those of [`AnalysisEngine::analyze`].
+    pub fn analyze_project(&self, project: &Project) -> Vec<Diagnostic> {
+        let mut by_file: HashMap<String, Vec<Diagnostic>> = HashMap::new();
+        for diagnostic in self.registry.run_project(project) {
+            by_file
+                .entry(diagnostic.file.clone())
+                .or_default()
+                .push(diagnostic);
         }
 
-        let rule_diagnostics = self.registry.run_all(file);
-        for diagnostic in rule_diagnostics {
-            if !disable_directives.is_disabled(diagnostic.line, &diagnostic.rule_id) {
-                diagnostics.push(diagnostic);
+        let mut diagnostics = Vec::new();
+        for (filename, file_diagnostics) in by_file {
+            match project.file_named(&filename) {
+                Some(file) => diagnostics.extend(filter_disabled(
+                    file,
+                    &self.registry,
+                    self.disable_reason,
+                    file_diagnostics,
+                )),
+                None => diagnostics.extend(file_diagnostics),
             }
         }
-
+        diagnostics.sort_by(|a, b| (&a.file, a.line, a.column).cmp(&(&b.file, b.line, b.column)));
         diagnostics
     }
 }
@@ -80,6 +116,15 @@ impl Default for AnalysisEngine {
     }
 }
 
+/// Builds the default registry configured from `config`, along with configuration warnings.
+pub(crate) fn configured_registry(config: &Config) -> (RuleRegistry, Vec<String>) {
+    let mut registry = create_default_registry();
+    registry.configure_taint(&config.taint);
+    let mut warnings = registry.configure(&config.rules);
+    warnings.extend(registry.configure_overrides(config));
+    (registry, warnings)
+}
+
 fn create_default_registry() -> RuleRegistry {
     let mut registry = RuleRegistry::new();
 
@@ -88,6 +133,8 @@ fn create_default_registry() -> RuleRegistry {
     registry.register(Box::new(MaxDepth::new()));
     registry.register(Box::new(NoUnreachable::new()));
     registry.register(Box::new(NoUnusedImports::new()));
+    registry.register(Box::new(NoUnusedExports::new()));
+    registry.register(Box::new(NoImportCycles::new()));
     registry.register(Box::new(NoVar::new()));
     registry.register(Box::new(Eqeqeq::new()));
     registry.register(Box::new(NoConsole::new()));
@@ -98,11 +145,18 @@ fn create_default_registry() -> RuleRegistry {
     registry.register(Box::new(PreferOptionalChaining::new()));
     registry.register(Box::new(PreferNullishCoalescing::new()));
     registry.register(Box::new(PreferConst::new()));
+    registry.register(Box::new(UnusedDisableDirective::new()));
+    registry.register(Box::new(UnknownDisableRule::new()));
 
     // Security rules
     registry.register(Box::new(SqlInjection::new()));
     registry.register(Box::new(Xss::new()));
     registry.register(Box::new(CommandInjection::new()));
+    registry.register(Box::new(PathTraversal::new()));
+    registry.register(Box::new(Ssrf::new()));
+    registry.register(Box::new(OpenRedirect::new()));
+    registry.register(Box::new(NoSqlInjection::new()));
+    registry.register(Box::new(InsecureJwt::new()));
     registry.register(Box::new(EvalInjection::new()));
     registry.register(Box::new(HardcodedSecrets::new()));
     registry.register(Box::new(WeakHashing::new()));
@@ -272,4 +326,122 @@ var y = 2;"#,
         assert!(!line_2_q030, "Q030 on line 2 should be suppressed");
         assert!(line_3_q030, "Q030 on line 3 should NOT be suppressed");
     }
+
+    #[test]
+    fn disable_directive_matches_rule_name() {
+        let engine = AnalysisEngine::new();
+        let file = make_parsed_file(
+            "test.js",
+            "// kaizen-disable-next-line no-var\nvar x = 1;\nexport { x };\n",
+        );
+
+        let diagnostics = engine.analyze(&file);
+
+        assert!(!diagnostics.iter().any(|d| d.rule_id == "Q030"));
+    }
+
+    #[test]
+    fn security_directive_requires_reason_when_configured() {
+        let config: Config =
+            toml::from_str("[rules]\nrequire_disable_reason = \"security\"").expect("valid config");
+        let engine = AnalysisEngine::with_config(&config);
+        let source = r#"
+const { exec } = require('child_process');
+// kaizen-disable-next-line no-command-injection
+exec(process.argv[2]);
+// kaizen-disable-next-line S* -- argv comes from a trusted wrapper script
+exec(process.argv[3]);
+// kaizen-disable-next-line no-var
+var x = 1;
+"#;
+        let file = make_parsed_file("test.js", source);
+
+        let diagnostics = engine.analyze(&file);
+        let injections: Vec<_> = diagnostics.iter().filter(|d| d.rule_id == "S003").collect();
+
+        assert!(!injections.is_empty());
+        for diagnostic in injections {
+            assert_eq!(diagnostic.line, 4);
+            assert!(diagnostic.message.contains("`-- reason` is required"));
+        }
+        assert!(!diagnostics.iter().any(|d| d.rule_id == "Q030"));
+    }
+
+    #[test]
+    fn directive_text_in_a_template_is_not_reported_as_unused() {
+        let source = "const help = `\n// kaizen-disable-next-line\n`;\nconsole.log(help);\n";
+        let file = make_parsed_file("help.js", source);
+
+        let diagnostics = AnalysisEngine::new().analyze(&file);
+
+        assert!(!diagnostics.iter().any(|d| d.rule_id == "M004"));
+    }
+
+    #[test]
+    fn project_diagnostics_honour_disable_directives() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lib.ts");
+        let source = "// kaizen-disable-next-line no-unused-exports\nexport const a = 1;\nexport const b = 2;\n";
+        std::fs::write(&path, source).unwrap();
+        let file = make_parsed_file(&path.to_string_lossy(), source);
+        let engine = AnalysisEngine::new();
+
+        // The directive suppresses a project diagnostic, so it is not reported as unused
+        let diagnostics = engine.analyze(&file);
+        assert!(!diagnostics.iter().any(|d| d.rule_id == "M004"));
+
+        let diagnostics = engine.analyze_project(&Project::new(vec![file]));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            (diagnostics[0].rule_id.as_str(), diagnostics[0].line),
+            ("Q005", 3)
+        );
+    }
+
+    #[test]
+    fn taint_rules_use_configured_sources_sinks_and_sanitizers() {
+        let config: Config = toml::from_str(
+            r#"
+[[taint.sources]]
+path = "job.data.*"
+
+[[taint.sinks]]
+path = "legacyDb.run"
+category = "sql_injection"
+args = [0]
+
+[[taint.sanitizers]]
+path = "ourLib.escapeSql"
+category = "sql_injection"
+"#,
+        )
+        .unwrap();
+        let engine = AnalysisEngine::with_config(&config);
+        let file = make_parsed_file(
+            "worker.js",
+            r#"function process(job) {
+    legacyDb.run("DELETE FROM jobs WHERE id = " + job.data.id);
+    legacyDb.run("SELECT 1", [job.data.id]);
+    legacyDb.run(ourLib.escapeSql(job.data.id));
+    exec(ourLib.escapeSql(job.data.id));
+}
+"#,
+        );
+
+        let diagnostics = engine.analyze(&file);
+        let reported: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.rule_id.starts_with('S'))
+            .map(|d| (d.rule_id.as_str(), d.line))
+            .collect();
+
+        // Only the first argument is checked, and the sanitizer only protects queries
+        assert_eq!(reported, vec![("S001", 2), ("S003", 5)]);
+        assert!(
+            AnalysisEngine::new()
+                .analyze(&file)
+                .iter()
+                .all(|d| !d.rule_id.starts_with('S'))
+        );
+    }
 }
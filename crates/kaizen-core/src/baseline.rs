@@ -0,0 +1,442 @@
+//! Baselines of accepted findings for adopting Kaizen on existing code
+//!
+//! A baseline records the findings present when it was written. Later runs compare
+//! against it and only report findings it does not contain. Findings are matched by
+//! fingerprints built from the rule, the enclosing named functions and the normalized
+//! source line, so unrelated edits that shift lines keep them matched.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use swc_common::Span;
+use swc_ecma_ast::{
+    ClassDecl, ClassMethod, Expr, FnDecl, KeyValueProp, MethodProp, Pat, PropName, VarDeclarator,
+};
+use swc_ecma_visit::{Visit, VisitWith};
+
+use crate::diagnostic::Diagnostic;
+use crate::hash::stable_hash;
+use crate::parser::ParsedFile;
+
+/// Version written to new baseline files.
+pub const BASELINE_VERSION: u32 = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BaselineError {
+    #[error("Failed to read baseline '{path}': {source}")]
+    ReadError {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Failed to write baseline '{path}': {source}")]
+    WriteError {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid baseline '{path}': {message}")]
+    ParseError { path: PathBuf, message: String },
+    #[error("Unsupported baseline version {version} in '{path}' (expected {BASELINE_VERSION})")]
+    UnsupportedVersion { path: PathBuf, version: u32 },
+}
+
+/// A recorded finding. `line` and `message` are informational and not used for matching.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaselineEntry {
+    pub file: String,
+    pub rule_id: String,
+    pub fingerprint: String,
+    pub line: usize,
+    pub message: String,
+}
+
+impl BaselineEntry {
+    /// Builds an entry for `diagnostic`, stored under `file` (usually a path relative to the baseline).
+    pub fn new(file: impl Into<String>, diagnostic: &Diagnostic, fingerprint: String) -> Self {
+        Self {
+            file: file.into(),
+            rule_id: diagnostic.rule_id.clone(),
+            fingerprint,
+            line: diagnostic.line,
+            message: diagnostic.message.clone(),
+        }
+    }
+
+    fn key(&self) -> (&str, &str, &str) {
+        (&self.file, &self.rule_id, &self.fingerprint)
+    }
+}
+
+/// Whether a current finding was already recorded in the baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaselineState {
+    New,
+    Unchanged,
+}
+
+/// Result of comparing current findings with a baseline.
+#[derive(Debug, Default)]
+pub struct BaselineComparison {
+    /// One state per current finding, in the order they were given.
+    pub states: Vec<BaselineState>,
+    /// Baseline entries with no matching current finding.
+    pub fixed: Vec<BaselineEntry>,
+}
+
+impl BaselineComparison {
+    pub fn new_count(&self) -> usize {
+        self.states
+            .iter()
+            .filter(|s| **s == BaselineState::New)
+            .count()
+    }
+
+    pub fn unchanged_count(&self) -> usize {
+        self.states.len() - self.new_count()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    version: u32,
+    entries: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+    /// Creates a baseline from `entries`, sorted so the file diffs cleanly between runs.
+    pub fn new(mut entries: Vec<BaselineEntry>) -> Self {
+        entries.sort_by(|a, b| {
+            (&a.file, a.line, &a.rule_id, &a.fingerprint).cmp(&(
+                &b.file,
+                b.line,
+                &b.rule_id,
+                &b.fingerprint,
+            ))
+        });
+        Self {
+            version: BASELINE_VERSION,
+            entries,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, BaselineError> {
+        let content = std::fs::read_to_string(path).map_err(|e| BaselineError::ReadError {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        let baseline: Baseline =
+            serde_json::from_str(&content).map_err(|e| BaselineError::ParseError {
+                path: path.to_path_buf(),
+                message: e.to_string(),
+            })?;
+
+        if baseline.version != BASELINE_VERSION {
+            return Err(BaselineError::UnsupportedVersion {
+                path: path.to_path_buf(),
+                version: baseline.version,
+            });
+        }
+        Ok(baseline)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), BaselineError> {
+        let mut content =
+            serde_json::to_string_pretty(self).map_err(|e| BaselineError::ParseError {
+                path: path.to_path_buf(),
+                message: e.to_string(),
+            })?;
+        content.push('\n');
+        std::fs::write(path, content).map_err(|e| BaselineError::WriteError {
+            path: path.to_path_buf(),
+            source: e,
+        })
+    }
+
+    pub fn entries(&self) -> &[BaselineEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Matches `current` findings against the baseline.
+    ///
+    /// Each baseline entry matches at most one finding, so a second copy of a
+    /// baselined problem in the same function is reported as new.
+    pub fn compare(&self, current: &[BaselineEntry]) -> BaselineComparison {
+        let mut remaining: HashMap<(&str, &str, &str), Vec<usize>> = HashMap::new();
+        for (index, entry) in self.entries.iter().enumerate() {
+            remaining.entry(entry.key()).or_default().push(index);
+        }
+
+        let states = current
+            .iter()
+            .map(
+                |entry| match remaining.get_mut(&entry.key()).and_then(Vec::pop) {
+                    Some(_) => BaselineState::Unchanged,
+                    None => BaselineState::New,
+                },
+            )
+            .collect();
+
+        let mut fixed: Vec<usize> = remaining.into_values().flatten().collect();
+        fixed.sort_unstable();
+
+        BaselineComparison {
+            states,
+            fixed: fixed
+                .into_iter()
+                .map(|index| self.entries[index].clone())
+                .collect(),
+        }
+    }
+}
+
+/// Computes line-independent fingerprints for diagnostics in one file.
+pub struct Fingerprinter<'a> {
+    file: &'a ParsedFile,
+    line_starts: Vec<usize>,
+    scopes: Vec<NamedScope>,
+}
+
+impl<'a> Fingerprinter<'a> {
+    pub fn new(file: &'a ParsedFile) -> Self {
+        let source = file.source();
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        let mut collector = ScopeCollector::default();
+        if let Some(module) = file.module() {
+            module.visit_with(&mut collector);
+        }
+
+        Self {
+            file,
+            line_starts,
+            scopes: collector.scopes,
+        }
+    }
+
+    /// Hashes the rule, the enclosing named functions and the diagnostic's source line.
+    pub fn fingerprint(&self, diagnostic: &Diagnostic) -> String {
+        let snippet = self
+            .file
+            .get_line(diagnostic.line)
+            .map(normalize_snippet)
+            .unwrap_or_default();
+        let scope = self.enclosing_scope(diagnostic.line, diagnostic.column);
+
+        stable_hash([diagnostic.rule_id.as_str(), scope, &snippet])
+    }
+
+    /// Dotted path of the innermost named function, method or class containing the position.
+    pub fn enclosing_scope(&self, line: usize, column: usize) -> &str {
+        let Some(offset) = self
+            .line_starts
+            .get(line.saturating_sub(1))
+            .map(|start| start + column.saturating_sub(1))
+        else {
+            return "";
+        };
+
+        self.scopes
+            .iter()
+            .filter(|scope| scope.start <= offset && offset < scope.end)
+            .min_by_key(|scope| scope.end - scope.start)
+            .map(|scope| scope.path.as_str())
+            .unwrap_or("")
+    }
+}
+
+/// Collapses whitespace so re-indenting a line keeps its fingerprint.
+fn normalize_snippet(line: &str) -> String {
+    line.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+struct NamedScope {
+    start: usize,
+    end: usize,
+    path: String,
+}
+
+#[derive(Default)]
+struct ScopeCollector {
+    names: Vec<String>,
+    scopes: Vec<NamedScope>,
+}
+
+impl ScopeCollector {
+    fn enter(&mut self, name: String, span: Span, node: &impl VisitWith<Self>) {
+        self.names.push(name);
+        self.scopes.push(NamedScope {
+            // swc's BytePos is 1-indexed
+            start: span.lo.0.saturating_sub(1) as usize,
+            end: span.hi.0.saturating_sub(1) as usize,
+            path: self.names.join("."),
+        });
+        node.visit_children_with(self);
+        self.names.pop();
+    }
+}
+
+fn prop_name(name: &PropName) -> Option<String> {
+    match name {
+        PropName::Ident(ident) => Some(ident.sym.to_string()),
+        PropName::Str(s) => Some(s.value.to_string()),
+        _ => None,
+    }
+}
+
+fn is_function_like(expr: &Expr) -> bool {
+    matches!(expr, Expr::Fn(_) | Expr::Arrow(_) | Expr::Class(_))
+}
+
+impl Visit for ScopeCollector {
+    fn visit_fn_decl(&mut self, node: &FnDecl) {
+        self.enter(node.ident.sym.to_string(), node.function.span, node);
+    }
+
+    fn visit_class_decl(&mut self, node: &ClassDecl) {
+        self.enter(node.ident.sym.to_string(), node.class.span, node);
+    }
+
+    fn visit_class_method(&mut self, node: &ClassMethod) {
+        match prop_name(&node.key) {
+            Some(name) => self.enter(name, node.span, node),
+            None => node.visit_children_with(self),
+        }
+    }
+
+    fn visit_method_prop(&mut self, node: &MethodProp) {
+        match prop_name(&node.key) {
+            Some(name) => self.enter(name, node.function.span, node),
+            None => node.visit_children_with(self),
+        }
+    }
+
+    fn visit_key_value_prop(&mut self, node: &KeyValueProp) {
+        match prop_name(&node.key) {
+            Some(name) if is_function_like(&node.value) => {
+                self.enter(name, swc_common::Spanned::span(&*node.value), node)
+            }
+            _ => node.visit_children_with(self),
+        }
+    }
+
+    fn visit_var_declarator(&mut self, node: &VarDeclarator) {
+        match (&node.name, node.init.as_deref()) {
+            (Pat::Ident(ident), Some(init)) if is_function_like(init) => {
+                self.enter(ident.id.sym.to_string(), node.span, node)
+            }
+            _ => node.visit_children_with(self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::AnalysisEngine;
+
+    /// Baseline entries for the `no-var` findings in `source`.
+    fn entries(file: &str, source: &str) -> Vec<BaselineEntry> {
+        let parsed = ParsedFile::from_source(file, source);
+        let fingerprinter = Fingerprinter::new(&parsed);
+        AnalysisEngine::new()
+            .analyze(&parsed)
+            .iter()
+            .filter(|d| d.rule_id == "Q030")
+            .map(|d| BaselineEntry::new(file, d, fingerprinter.fingerprint(d)))
+            .collect()
+    }
+
+    #[test]
+    fn enclosing_scope_uses_innermost_named_function() {
+        let source = "class Api {\n  load() {\n    const parse = () => {\n      x();\n    };\n  }\n}\nfunction top() {}\n";
+        let parsed = ParsedFile::from_source("test.js", source);
+        let fingerprinter = Fingerprinter::new(&parsed);
+
+        assert_eq!(fingerprinter.enclosing_scope(4, 7), "Api.load.parse");
+        assert_eq!(fingerprinter.enclosing_scope(6, 3), "Api.load");
+        assert_eq!(fingerprinter.enclosing_scope(8, 1), "top");
+        assert_eq!(fingerprinter.enclosing_scope(9, 1), "");
+    }
+
+    #[test]
+    fn fingerprints_survive_line_shifts_and_reindentation() {
+        let before = entries("a.js", "function f() {\n  var x = 1;\n  return x;\n}\n");
+        let after = entries(
+            "a.js",
+            "import './setup';\n\n\nfunction f() {\n      var x = 1;\n  return x;\n}\n",
+        );
+
+        let comparison = Baseline::new(before).compare(&after);
+
+        assert_eq!(comparison.states, vec![BaselineState::Unchanged]);
+        assert!(comparison.fixed.is_empty());
+    }
+
+    #[test]
+    fn fingerprints_depend_on_enclosing_function() {
+        let before = entries("a.js", "function f() {\n  var x = 1;\n  return x;\n}\n");
+        let after = entries("a.js", "function g() {\n  var x = 1;\n  return x;\n}\n");
+
+        let comparison = Baseline::new(before).compare(&after);
+
+        assert_eq!(comparison.states, vec![BaselineState::New]);
+        assert_eq!(comparison.fixed.len(), 1);
+    }
+
+    #[test]
+    fn duplicates_beyond_baseline_count_are_new() {
+        let baseline = Baseline::new(entries("a.js", "var x = 1;\nexport { x };\n"));
+        let current = entries("a.js", "var x = 1;\nvar x = 1;\nexport { x };\n");
+
+        let comparison = baseline.compare(&current);
+
+        assert_eq!(comparison.new_count(), 1);
+        assert_eq!(comparison.unchanged_count(), 1);
+    }
+
+    #[test]
+    fn fixed_entries_are_reported() {
+        let baseline = Baseline::new(entries("a.js", "var x = 1;\nexport { x };\n"));
+        let current = entries("a.js", "export const x = 1;\n");
+
+        let comparison = baseline.compare(&current);
+
+        assert!(comparison.states.is_empty());
+        assert_eq!(comparison.fixed.len(), 1);
+        assert_eq!(comparison.fixed[0].rule_id, "Q030");
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kaizen-baseline.json");
+        let baseline = Baseline::new(entries("a.js", "var x = 1;\nexport { x };\n"));
+
+        baseline.save(&path).unwrap();
+
+        assert_eq!(Baseline::load(&path).unwrap(), baseline);
+    }
+
+    #[test]
+    fn load_rejects_unknown_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kaizen-baseline.json");
+        std::fs::write(&path, r#"{"version": 99, "entries": []}"#).unwrap();
+
+        assert!(matches!(
+            Baseline::load(&path),
+            Err(BaselineError::UnsupportedVersion { version: 99, .. })
+        ));
+    }
+}
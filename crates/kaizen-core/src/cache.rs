@@ -0,0 +1,312 @@
+//! On-disk cache of per-file diagnostics between runs
+//!
+//! Entries are keyed by file path and validated by a hash of the file contents. The
+//! whole cache is tied to a settings key covering the Kaizen version, the resolved
+//! configuration and the license tier, and is discarded when any of them changes.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::diagnostic::Diagnostic;
+use crate::hash::{StableHasher, stable_hash};
+use crate::licensing::PremiumTier;
+
+/// Default cache directory, created next to the configuration file.
+pub const CACHE_DIRNAME: &str = ".kaizen-cache";
+
+const CACHE_FILENAME: &str = "diagnostics.json";
+const CACHE_FORMAT: u32 = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CacheError {
+    #[error("Failed to write cache '{path}': {source}")]
+    WriteError {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Failed to serialize cache '{path}': {message}")]
+    SerializeError { path: PathBuf, message: String },
+}
+
+/// Hash of everything besides file contents that can change the diagnostics of a file.
+pub fn settings_key(config: &Config, tier: PremiumTier) -> String {
+    let config_json = serde_json::to_value(config)
+        .map(|value| canonical_json(value).to_string())
+        .unwrap_or_default();
+    let root = config
+        .root
+        .as_deref()
+        .map(|root| root.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    stable_hash([
+        env!("CARGO_PKG_VERSION"),
+        &config_json,
+        &root,
+        tier.as_str(),
+    ])
+}
+
+/// Sorts object keys so maps hash the same regardless of iteration order.
+fn canonical_json(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, canonical_json(value)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().map(canonical_json).collect())
+        }
+        other => other,
+    }
+}
+
+fn content_hash(content: &str) -> String {
+    let mut hasher = StableHasher::new();
+    hasher.write(content.as_bytes());
+    format!("{}-{}", hasher.finish_hex(), content.len())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheFile {
+    format: u32,
+    settings: String,
+    files: HashMap<String, CachedFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedFile {
+    hash: String,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Diagnostics from earlier runs, reused for files whose contents did not change.
+#[derive(Debug)]
+pub struct AnalysisCache {
+    dir: PathBuf,
+    settings: String,
+    files: HashMap<String, CachedFile>,
+    dirty: bool,
+}
+
+impl AnalysisCache {
+    /// Loads the cache in `dir`, starting empty if it is missing, unreadable or was
+    /// written with different settings.
+    pub fn load(dir: &Path, settings: String) -> Self {
+        let files = std::fs::read_to_string(dir.join(CACHE_FILENAME))
+            .ok()
+            .and_then(|content| serde_json::from_str::<CacheFile>(&content).ok())
+            .filter(|cache| cache.format == CACHE_FORMAT && cache.settings == settings)
+            .map(|cache| cache.files)
+            .unwrap_or_default();
+
+        Self {
+            dir: dir.to_path_buf(),
+            settings,
+            files,
+            dirty: false,
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Returns the stored diagnostics for `path` if `content` is unchanged, reported under `filename`.
+    pub fn get(&self, path: &Path, filename: &str, content: &str) -> Option<Vec<Diagnostic>> {
+        let cached = self.files.get(&cache_key(path))?;
+        if cached.hash != content_hash(content) {
+            return None;
+        }
+
+        Some(
+            cached
+                .diagnostics
+                .iter()
+                .cloned()
+                .map(|mut diagnostic| {
+                    diagnostic.file = filename.to_string();
+                    diagnostic
+                })
+                .collect(),
+        )
+    }
+
+    pub fn insert(&mut self, path: &Path, content: &str, diagnostics: Vec<Diagnostic>) {
+        self.files.insert(
+            cache_key(path),
+            CachedFile {
+                hash: content_hash(content),
+                diagnostics,
+            },
+        );
+        self.dirty = true;
+    }
+
+    /// Writes the cache if it changed, dropping entries for files that no longer exist.
+    pub fn save(&mut self) -> Result<(), CacheError> {
+        if !self.dirty {
+            return Ok(());
+        }
+        self.files.retain(|path, _| Path::new(path).exists());
+
+        let write_error = |path: &Path, source| CacheError::WriteError {
+            path: path.to_path_buf(),
+            source,
+        };
+        std::fs::create_dir_all(&self.dir).map_err(|e| write_error(&self.dir, e))?;
+        let gitignore = self.dir.join(".gitignore");
+        if !gitignore.exists() {
+            std::fs::write(&gitignore, "*\n").map_err(|e| write_error(&gitignore, e))?;
+        }
+
+        let path = self.dir.join(CACHE_FILENAME);
+        let cache = CacheFile {
+            format: CACHE_FORMAT,
+            settings: self.settings.clone(),
+            files: std::mem::take(&mut self.files),
+        };
+        let content = serde_json::to_string(&cache);
+        self.files = cache.files;
+        let content = content.map_err(|e| CacheError::SerializeError {
+            path: path.clone(),
+            message: e.to_string(),
+        })?;
+
+        // Write then rename so an interrupted run never leaves a truncated cache behind.
+        let temp = self.dir.join(format!("{}.tmp", CACHE_FILENAME));
+        std::fs::write(&temp, content).map_err(|e| write_error(&temp, e))?;
+        std::fs::rename(&temp, &path).map_err(|e| write_error(&path, e))?;
+
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+/// Absolute path, so runs started from different directories share entries.
+fn cache_key(path: &Path) -> String {
+    path.canonicalize()
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Severity;
+
+    fn diagnostic(file: &str) -> Diagnostic {
+        Diagnostic::new("Q030", Severity::Warning, "Unexpected var", file, 1, 1)
+    }
+
+    fn source_file(dir: &Path, content: &str) -> PathBuf {
+        let path = dir.join("app.js");
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn reuses_diagnostics_for_unchanged_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = source_file(dir.path(), "var x = 1;");
+        let cache_dir = dir.path().join(CACHE_DIRNAME);
+
+        let mut cache = AnalysisCache::load(&cache_dir, "settings".to_string());
+        cache.insert(&file, "var x = 1;", vec![diagnostic("./app.js")]);
+        cache.save().unwrap();
+
+        let cache = AnalysisCache::load(&cache_dir, "settings".to_string());
+        let cached = cache.get(&file, "app.js", "var x = 1;").unwrap();
+        assert_eq!(cached, vec![diagnostic("app.js")]);
+        assert!(cache.get(&file, "app.js", "var x = 2;").is_none());
+    }
+
+    #[test]
+    fn different_settings_discard_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = source_file(dir.path(), "var x = 1;");
+        let cache_dir = dir.path().join(CACHE_DIRNAME);
+
+        let mut cache = AnalysisCache::load(&cache_dir, "before".to_string());
+        cache.insert(&file, "var x = 1;", vec![diagnostic("app.js")]);
+        cache.save().unwrap();
+
+        assert!(AnalysisCache::load(&cache_dir, "after".to_string()).is_empty());
+        assert_eq!(
+            AnalysisCache::load(&cache_dir, "before".to_string()).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn corrupt_cache_starts_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(CACHE_FILENAME), "{not json").unwrap();
+
+        assert!(AnalysisCache::load(dir.path(), "settings".to_string()).is_empty());
+    }
+
+    #[test]
+    fn save_drops_deleted_files_and_ignores_itself() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = source_file(dir.path(), "");
+        let cache_dir = dir.path().join(CACHE_DIRNAME);
+
+        let mut cache = AnalysisCache::load(&cache_dir, "settings".to_string());
+        cache.insert(&file, "", Vec::new());
+        cache.insert(&dir.path().join("deleted.js"), "", Vec::new());
+        cache.save().unwrap();
+
+        assert_eq!(cache.len(), 1);
+        assert_eq!(
+            std::fs::read_to_string(cache_dir.join(".gitignore")).unwrap(),
+            "*\n"
+        );
+    }
+
+    #[test]
+    fn settings_key_tracks_config_and_tier() {
+        let config = Config::default();
+        let mut changed = Config::default();
+        changed.rules.disabled.push("no-console".to_string());
+
+        let key = settings_key(&config, PremiumTier::Free);
+
+        assert_eq!(key, settings_key(&config.clone(), PremiumTier::Free));
+        assert_ne!(key, settings_key(&changed, PremiumTier::Free));
+        assert_ne!(key, settings_key(&config, PremiumTier::Pro));
+    }
+
+    #[test]
+    fn settings_key_ignores_map_order() {
+        let a: Config =
+            toml::from_str("[rules.severity]\nQ030 = \"error\"\nQ032 = \"hint\"\nS001 = \"info\"")
+                .unwrap();
+        let b: Config =
+            toml::from_str("[rules.severity]\nS001 = \"info\"\nQ032 = \"hint\"\nQ030 = \"error\"")
+                .unwrap();
+
+        assert_eq!(
+            settings_key(&a, PremiumTier::Free),
+            settings_key(&b, PremiumTier::Free)
+        );
+    }
+}
@@ -2,15 +2,24 @@
 //!
 //! Provides functionality to load and parse `kaizen.toml` configuration files.
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
-use crate::rules::{Confidence, Severity};
+use crate::presets::{PRESET_PREFIX, builtin_preset, preset_names};
+use crate::rules::{Confidence, RuleCategory, Severity};
+use crate::taint::{SanitizerCategory, TaintCategory, TaintSinkCategory};
 
 pub const CONFIG_FILENAME: &str = "kaizen.toml";
 
-const KNOWN_TOP_LEVEL_KEYS: &[&str] = &["include", "exclude", "rules", "license"];
+const KNOWN_TOP_LEVEL_KEYS: &[&str] = &[
+    "include",
+    "exclude",
+    "rules",
+    "license",
+    "overrides",
+    "taint",
+];
 const KNOWN_RULES_KEYS: &[&str] = &[
     "enabled",
     "disabled",
@@ -18,7 +27,10 @@ const KNOWN_RULES_KEYS: &[&str] = &[
     "quality",
     "security",
     "min_confidence",
+    "options",
+    "require_disable_reason",
 ];
+const KNOWN_OVERRIDE_KEYS: &[&str] = &["files", "disabled", "enabled", "severity", "options"];
 
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
@@ -29,6 +41,14 @@ pub enum ConfigError {
     },
     #[error("Invalid TOML in '{path}': {message}")]
     ParseError { path: PathBuf, message: String },
+    #[error("Unknown preset '{name}' in '{path}' (available: {available})")]
+    UnknownPreset {
+        path: PathBuf,
+        name: String,
+        available: String,
+    },
+    #[error("Config '{path}' extends itself through: {chain}")]
+    ExtendsCycle { path: PathBuf, chain: String },
 }
 
 #[derive(Debug, Clone, Default)]
@@ -37,22 +57,27 @@ pub struct ConfigResult {
     pub warnings: Vec<String>,
 }
 
-#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 pub struct Config {
     pub include: Vec<String>,
     pub exclude: Vec<String>,
     pub rules: RulesConfig,
     pub license: LicenseConfig,
+    pub overrides: Vec<OverrideConfig>,
+    pub taint: TaintConfig,
+    /// Directory containing the config file, used to resolve relative patterns.
+    #[serde(skip)]
+    pub root: Option<PathBuf>,
 }
 
-#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 pub struct LicenseConfig {
     pub api_key: Option<String>,
 }
 
-#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 pub struct RulesConfig {
     pub enabled: Vec<String>,
@@ -62,9 +87,88 @@ pub struct RulesConfig {
     pub quality: Option<bool>,
     pub security: Option<bool>,
     pub min_confidence: Option<ConfidenceValue>,
+    /// Per-rule option tables keyed by rule ID or name.
+    pub options: HashMap<String, toml::Table>,
+    pub require_disable_reason: DisableReasonPolicy,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+/// Which disable directives must carry a `-- reason` to take effect.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DisableReasonPolicy {
+    #[default]
+    Never,
+    Security,
+    Always,
+}
+
+impl DisableReasonPolicy {
+    pub fn requires_reason(&self, category: RuleCategory) -> bool {
+        match self {
+            DisableReasonPolicy::Never => false,
+            DisableReasonPolicy::Security => category == RuleCategory::Security,
+            DisableReasonPolicy::Always => true,
+        }
+    }
+}
+
+/// Project-specific taint sources, sinks and sanitizers, used by every taint rule
+/// in addition to the built-in ones.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct TaintConfig {
+    pub sources: Vec<TaintSourceConfig>,
+    pub sinks: Vec<TaintSinkConfig>,
+    pub sanitizers: Vec<TaintSanitizerConfig>,
+}
+
+/// A property that holds untrusted data, such as `ctx.request.payload`, or
+/// `ctx.request.*` for every property of `ctx.request`. A single name taints
+/// parameters and variables of that name.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaintSourceConfig {
+    pub path: String,
+    #[serde(default = "default_source_category")]
+    pub category: TaintCategory,
+    pub description: Option<String>,
+}
+
+fn default_source_category() -> TaintCategory {
+    TaintCategory::UserInput
+}
+
+/// A function or method, such as `legacyDb.run`, that must not receive untrusted
+/// data in the arguments at `args`, or in any argument when `args` is empty.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaintSinkConfig {
+    pub path: String,
+    pub category: TaintSinkCategory,
+    #[serde(default)]
+    pub args: Vec<usize>,
+    pub description: Option<String>,
+}
+
+/// A function or method, such as `ourLib.escapeSql`, whose result is safe for the
+/// sinks of `category`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaintSanitizerConfig {
+    pub path: String,
+    pub category: SanitizerCategory,
+    pub description: Option<String>,
+}
+
+/// Rule settings applied to files matching `files`, in declaration order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OverrideConfig {
+    pub files: Vec<String>,
+    pub disabled: Vec<String>,
+    pub enabled: Vec<String>,
+    pub severity: HashMap<String, SeverityValue>,
+    pub options: HashMap<String, toml::Table>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum SeverityValue {
     Error,
@@ -84,7 +188,7 @@ impl From<SeverityValue> for Severity {
     }
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ConfidenceValue {
     High,
@@ -116,40 +220,187 @@ pub fn find_config_file(start_dir: &Path) -> Option<PathBuf> {
 }
 
 pub fn load_config(path: &Path) -> Result<Config, ConfigError> {
-    let content = std::fs::read_to_string(path).map_err(|e| ConfigError::ReadError {
-        path: path.to_path_buf(),
-        source: e,
-    })?;
-
-    toml::from_str(&content).map_err(|e| ConfigError::ParseError {
-        path: path.to_path_buf(),
-        message: e.message().to_string(),
-    })
+    let table = read_config_table(path)?;
+    parse_config(table, path)
 }
 
 pub fn load_config_with_warnings(path: &Path) -> Result<ConfigResult, ConfigError> {
+    let table = read_config_table(path)?;
+    let mut warnings = detect_unknown_keys(&table);
+    let config = parse_config(table, path)?;
+
+    warnings.extend(crate::discovery::pattern_warnings(&config));
+    warnings.extend(crate::analysis::configured_registry(&config).1);
+
+    Ok(ConfigResult { config, warnings })
+}
+
+fn parse_config(table: toml::Table, path: &Path) -> Result<Config, ConfigError> {
+    let mut config: Config =
+        toml::Value::Table(table)
+            .try_into()
+            .map_err(|e: toml::de::Error| ConfigError::ParseError {
+                path: path.to_path_buf(),
+                message: e.message().to_string(),
+            })?;
+    config.root = path.parent().map(Path::to_path_buf);
+
+    Ok(config)
+}
+
+/// Reads `path` and every config it extends, merged into a single table.
+fn read_config_table(path: &Path) -> Result<toml::Table, ConfigError> {
+    resolve_file(path, &mut Vec::new())
+}
+
+fn resolve_file(path: &Path, chain: &mut Vec<String>) -> Result<toml::Table, ConfigError> {
     let content = std::fs::read_to_string(path).map_err(|e| ConfigError::ReadError {
         path: path.to_path_buf(),
         source: e,
     })?;
+    let table: toml::Table =
+        content
+            .parse()
+            .map_err(|e: toml::de::Error| ConfigError::ParseError {
+                path: path.to_path_buf(),
+                message: e.message().to_string(),
+            })?;
+
+    let key = path
+        .canonicalize()
+        .unwrap_or_else(|_| path.to_path_buf())
+        .display()
+        .to_string();
+    resolve_extends(table, key, path, path.parent(), chain)
+}
 
-    let config: Config = toml::from_str(&content).map_err(|e| ConfigError::ParseError {
-        path: path.to_path_buf(),
-        message: e.message().to_string(),
+/// Merges the configs listed in `extends` in order, then `table` on top of them.
+///
+/// Built-in presets may only extend other presets; file paths are resolved relative to
+/// `base_dir`, the directory of the config that lists them.
+fn resolve_extends(
+    mut table: toml::Table,
+    key: String,
+    origin: &Path,
+    base_dir: Option<&Path>,
+    chain: &mut Vec<String>,
+) -> Result<toml::Table, ConfigError> {
+    if chain.contains(&key) {
+        chain.push(key);
+        return Err(ConfigError::ExtendsCycle {
+            path: origin.to_path_buf(),
+            chain: chain.join(" -> "),
+        });
+    }
+
+    let Some(extends) = table.remove("extends") else {
+        return Ok(table);
+    };
+    let entries = extends_entries(extends).ok_or_else(|| ConfigError::ParseError {
+        path: origin.to_path_buf(),
+        message: "'extends' must be a string or a list of strings".to_string(),
     })?;
 
-    let warnings = detect_unknown_keys(&content);
+    chain.push(key);
+    let mut merged = toml::Table::new();
+    for entry in entries {
+        let base = if let Some(name) = entry.strip_prefix(PRESET_PREFIX) {
+            let source = builtin_preset(name).ok_or_else(|| ConfigError::UnknownPreset {
+                path: origin.to_path_buf(),
+                name: entry.clone(),
+                available: preset_names()
+                    .map(|name| format!("{}{}", PRESET_PREFIX, name))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            })?;
+            let preset: toml::Table =
+                source
+                    .parse()
+                    .map_err(|e: toml::de::Error| ConfigError::ParseError {
+                        path: PathBuf::from(&entry),
+                        message: e.message().to_string(),
+                    })?;
+            resolve_extends(preset, entry.clone(), Path::new(&entry), None, chain)?
+        } else {
+            let dir = base_dir.unwrap_or_else(|| Path::new("."));
+            resolve_file(&dir.join(&entry), chain)?
+        };
+        merge_tables(&mut merged, base, Section::Top);
+    }
+    chain.pop();
+
+    merge_tables(&mut merged, table, Section::Top);
+    Ok(merged)
+}
 
-    Ok(ConfigResult { config, warnings })
+fn extends_entries(value: toml::Value) -> Option<Vec<String>> {
+    match value {
+        toml::Value::String(entry) => Some(vec![entry]),
+        toml::Value::Array(items) => items
+            .into_iter()
+            .map(|item| item.as_str().map(str::to_string))
+            .collect(),
+        _ => None,
+    }
 }
 
-fn detect_unknown_keys(content: &str) -> Vec<String> {
-    let mut warnings = Vec::new();
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Top,
+    Rules,
+    Nested,
+}
 
-    let table: toml::Table = match content.parse() {
-        Ok(t) => t,
-        Err(_) => return warnings,
-    };
+/// Layers `overlay` over `base`: tables merge key by key, `[[overrides]]` blocks are
+/// appended, and other values are replaced.
+///
+/// In `[rules]`, `disabled` accumulates across layers and a later `enabled` removes
+/// rules from it, so a project can turn back on a rule that a preset disabled.
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table, section: Section) {
+    if section == Section::Rules {
+        let enabled = string_values(overlay.get("enabled"));
+        if let Some(toml::Value::Array(disabled)) = base.get_mut("disabled") {
+            disabled.retain(|rule| !rule.as_str().is_some_and(|r| enabled.contains(&r)));
+        }
+    }
+
+    for (key, value) in overlay {
+        let appends = section == Section::Top && key == "overrides";
+        let accumulates = section == Section::Rules && key == "disabled";
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Array(existing)), toml::Value::Array(items))
+                if appends || accumulates =>
+            {
+                for item in items {
+                    if appends || !existing.contains(&item) {
+                        existing.push(item);
+                    }
+                }
+            }
+            (Some(toml::Value::Table(existing)), toml::Value::Table(nested)) => {
+                let nested_section = if section == Section::Top && key == "rules" {
+                    Section::Rules
+                } else {
+                    Section::Nested
+                };
+                merge_tables(existing, nested, nested_section);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn string_values(value: Option<&toml::Value>) -> Vec<&str> {
+    value
+        .and_then(toml::Value::as_array)
+        .map(|items| items.iter().filter_map(toml::Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+fn detect_unknown_keys(table: &toml::Table) -> Vec<String> {
+    let mut warnings = Vec::new();
 
     let known_top: HashSet<&str> = KNOWN_TOP_LEVEL_KEYS.iter().copied().collect();
     for key in table.keys() {
@@ -167,6 +418,17 @@ fn detect_unknown_keys(content: &str) -> Vec<String> {
         }
     }
 
+    if let Some(toml::Value::Array(overrides)) = table.get("overrides") {
+        let known_override: HashSet<&str> = KNOWN_OVERRIDE_KEYS.iter().copied().collect();
+        for entry in overrides.iter().filter_map(toml::Value::as_table) {
+            for key in entry.keys() {
+                if !known_override.contains(key.as_str()) {
+                    warnings.push(format!("Unknown config option in [[overrides]]: '{}'", key));
+                }
+            }
+        }
+    }
+
     warnings
 }
 
@@ -178,7 +440,10 @@ pub fn load_config_or_default(start_dir: &Path) -> Config {
 
 pub fn load_config_or_default_with_warnings(start_dir: &Path) -> ConfigResult {
     match find_config_file(start_dir) {
-        Some(path) => load_config_with_warnings(&path).unwrap_or_default(),
+        Some(path) => load_config_with_warnings(&path).unwrap_or_else(|e| ConfigResult {
+            config: Config::default(),
+            warnings: vec![format!("{}; using default configuration", e)],
+        }),
         None => ConfigResult::default(),
     }
 }
@@ -310,7 +575,13 @@ no-eval = "error"
 
         let config = load_config(&config_path).unwrap();
 
-        assert_eq!(config, Config::default());
+        assert_eq!(
+            config,
+            Config {
+                root: Some(dir.path().to_path_buf()),
+                ..Config::default()
+            }
+        );
     }
 
     #[test]
@@ -476,6 +747,118 @@ no-eval = "error"
         assert!(result.warnings.is_empty());
     }
 
+    #[test]
+    fn rule_options_parse_correctly() {
+        let dir = create_temp_dir();
+        let config_path = dir.path().join(CONFIG_FILENAME);
+        fs::write(
+            &config_path,
+            r#"
+[rules.options.max-complexity]
+threshold = 15
+
+[rules.options.no-console]
+allow = ["warn"]
+"#,
+        )
+        .unwrap();
+
+        let result = load_config_with_warnings(&config_path).unwrap();
+
+        let options = &result.config.rules.options;
+        assert_eq!(
+            options["max-complexity"].get("threshold"),
+            Some(&toml::Value::Integer(15))
+        );
+        assert!(options.contains_key("no-console"));
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn warns_on_unknown_and_invalid_rule_options() {
+        let dir = create_temp_dir();
+        let config_path = dir.path().join(CONFIG_FILENAME);
+        fs::write(
+            &config_path,
+            r#"
+[rules.options.max-complexity]
+threshold = "high"
+limit = 3
+
+[rules.options.no-var]
+strict = true
+
+[rules.options.not-a-rule]
+threshold = 1
+"#,
+        )
+        .unwrap();
+
+        let result = load_config_with_warnings(&config_path).unwrap();
+
+        assert_eq!(
+            result.warnings,
+            vec![
+                "Invalid option 'threshold' for rule 'max-complexity': expected a positive integer",
+                "Unknown option 'limit' for rule 'max-complexity' in [rules.options]",
+                "Unknown option 'strict' for rule 'no-var' in [rules.options]",
+                "Unknown rule in [rules.options]: 'not-a-rule'",
+            ]
+        );
+    }
+
+    #[test]
+    fn overrides_parse_in_order() {
+        let dir = create_temp_dir();
+        let config_path = dir.path().join(CONFIG_FILENAME);
+        fs::write(
+            &config_path,
+            r#"
+[[overrides]]
+files = ["scripts/**"]
+disabled = ["no-console"]
+
+[[overrides]]
+files = ["tests/fixtures/**"]
+
+[overrides.severity]
+no-hardcoded-secrets = "info"
+"#,
+        )
+        .unwrap();
+
+        let result = load_config_with_warnings(&config_path).unwrap();
+
+        let overrides = &result.config.overrides;
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides[0].files, vec!["scripts/**"]);
+        assert_eq!(overrides[0].disabled, vec!["no-console"]);
+        assert_eq!(
+            overrides[1].severity.get("no-hardcoded-secrets"),
+            Some(&SeverityValue::Info)
+        );
+        assert_eq!(result.config.root.as_deref(), Some(dir.path()));
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn warns_on_unknown_override_option() {
+        let dir = create_temp_dir();
+        let config_path = dir.path().join(CONFIG_FILENAME);
+        fs::write(
+            &config_path,
+            "[[overrides]]\nfiles = [\"scripts/**\"]\ndisable = [\"no-console\"]\n",
+        )
+        .unwrap();
+
+        let result = load_config_with_warnings(&config_path).unwrap();
+
+        assert_eq!(
+            result.warnings,
+            vec!["Unknown config option in [[overrides]]: 'disable'"]
+        );
+    }
+
     #[test]
     fn load_config_or_default_with_warnings_returns_warnings() {
         let dir = create_temp_dir();
@@ -519,6 +902,58 @@ api_key = "test-license-key-123"
         );
     }
 
+    #[test]
+    fn taint_config_parses_correctly() {
+        let dir = create_temp_dir();
+        let config_path = dir.path().join(CONFIG_FILENAME);
+        fs::write(
+            &config_path,
+            r#"
+[[taint.sources]]
+path = "ctx.request.payload"
+
+[[taint.sinks]]
+path = "legacyDb.run"
+category = "sql_injection"
+args = [0]
+
+[[taint.sanitizers]]
+path = "ourLib.escapeSql"
+category = "sql_injection"
+description = "Internal SQL escaping"
+"#,
+        )
+        .unwrap();
+
+        let result = load_config_with_warnings(&config_path).unwrap();
+        let taint = &result.config.taint;
+
+        assert!(result.warnings.is_empty());
+        assert_eq!(taint.sources[0].category, TaintCategory::UserInput);
+        assert_eq!(taint.sinks[0].category, TaintSinkCategory::SqlInjection);
+        assert_eq!(taint.sinks[0].args, vec![0]);
+        assert_eq!(
+            taint.sanitizers[0].category,
+            SanitizerCategory::SqlInjection
+        );
+    }
+
+    #[test]
+    fn error_on_unknown_taint_category() {
+        let dir = create_temp_dir();
+        let config_path = dir.path().join(CONFIG_FILENAME);
+        fs::write(
+            &config_path,
+            "[[taint.sinks]]\npath = \"legacyDb.run\"\ncategory = \"sql\"\n",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            load_config(&config_path),
+            Err(ConfigError::ParseError { .. })
+        ));
+    }
+
     #[test]
     fn license_config_defaults_to_none() {
         let dir = create_temp_dir();
@@ -529,4 +964,207 @@ api_key = "test-license-key-123"
 
         assert_eq!(config.license.api_key, None);
     }
+
+    #[test]
+    fn extends_merges_local_files_in_order() {
+        let dir = create_temp_dir();
+        fs::create_dir(dir.path().join("configs")).unwrap();
+        fs::write(
+            dir.path().join("configs/base.toml"),
+            r#"
+exclude = ["dist"]
+
+[rules]
+disabled = ["no-console"]
+
+[rules.severity]
+no-var = "error"
+eqeqeq = "error"
+
+[[overrides]]
+files = ["scripts/**"]
+disabled = ["no-eval"]
+"#,
+        )
+        .unwrap();
+        let config_path = dir.path().join(CONFIG_FILENAME);
+        fs::write(
+            &config_path,
+            r#"
+extends = ["./configs/base.toml"]
+
+[rules]
+disabled = ["max-depth"]
+
+[rules.severity]
+eqeqeq = "hint"
+
+[[overrides]]
+files = ["tests/**"]
+disabled = ["max-complexity"]
+"#,
+        )
+        .unwrap();
+
+        let result = load_config_with_warnings(&config_path).unwrap();
+        let config = result.config;
+
+        assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+        assert_eq!(config.exclude, vec!["dist"]);
+        assert_eq!(config.rules.disabled, vec!["no-console", "max-depth"]);
+        assert_eq!(
+            config.rules.severity.get("no-var"),
+            Some(&SeverityValue::Error)
+        );
+        assert_eq!(
+            config.rules.severity.get("eqeqeq"),
+            Some(&SeverityValue::Hint)
+        );
+        assert_eq!(config.overrides.len(), 2);
+        assert_eq!(config.overrides[0].files, vec!["scripts/**"]);
+        assert_eq!(config.root, Some(dir.path().to_path_buf()));
+    }
+
+    #[test]
+    fn extends_builtin_preset() {
+        let dir = create_temp_dir();
+        let config_path = dir.path().join(CONFIG_FILENAME);
+        fs::write(
+            &config_path,
+            r#"
+extends = ["kaizen:node-backend", "kaizen:security-strict"]
+
+[rules.severity]
+no-redos = "warning"
+"#,
+        )
+        .unwrap();
+
+        let result = load_config_with_warnings(&config_path).unwrap();
+        let config = result.config;
+
+        assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+        assert_eq!(
+            config.rules.severity.get("prefer-optional-chaining"),
+            Some(&SeverityValue::Hint)
+        );
+        assert_eq!(
+            config.rules.severity.get("no-xss"),
+            Some(&SeverityValue::Error)
+        );
+        assert_eq!(
+            config.rules.severity.get("no-redos"),
+            Some(&SeverityValue::Warning)
+        );
+        assert!(config.rules.options.contains_key("no-console"));
+    }
+
+    #[test]
+    fn enabled_reenables_rule_disabled_by_base() {
+        let dir = create_temp_dir();
+        fs::write(
+            dir.path().join("base.toml"),
+            "[rules]\ndisabled = [\"no-console\", \"no-var\"]\n",
+        )
+        .unwrap();
+        let config_path = dir.path().join(CONFIG_FILENAME);
+        fs::write(
+            &config_path,
+            "extends = \"base.toml\"\n\n[rules]\nenabled = [\"no-console\"]\n",
+        )
+        .unwrap();
+
+        let config = load_config(&config_path).unwrap();
+
+        assert_eq!(config.rules.disabled, vec!["no-var"]);
+    }
+
+    #[test]
+    fn extended_file_resolves_its_own_relative_extends() {
+        let dir = create_temp_dir();
+        fs::create_dir(dir.path().join("shared")).unwrap();
+        fs::write(
+            dir.path().join("shared/root.toml"),
+            "[rules]\ndisabled = [\"no-var\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("shared/team.toml"),
+            "extends = [\"root.toml\"]\n",
+        )
+        .unwrap();
+        let config_path = dir.path().join(CONFIG_FILENAME);
+        fs::write(&config_path, "extends = [\"shared/team.toml\"]\n").unwrap();
+
+        let config = load_config(&config_path).unwrap();
+
+        assert_eq!(config.rules.disabled, vec!["no-var"]);
+    }
+
+    #[test]
+    fn error_on_unknown_preset() {
+        let dir = create_temp_dir();
+        let config_path = dir.path().join(CONFIG_FILENAME);
+        fs::write(&config_path, "extends = [\"kaizen:strictest\"]\n").unwrap();
+
+        let err = load_config(&config_path).unwrap_err();
+
+        match &err {
+            ConfigError::UnknownPreset { name, .. } => assert_eq!(name, "kaizen:strictest"),
+            _ => panic!("Expected UnknownPreset, got {:?}", err),
+        }
+        assert!(err.to_string().contains("kaizen:recommended"));
+    }
+
+    #[test]
+    fn error_on_missing_extended_file() {
+        let dir = create_temp_dir();
+        let config_path = dir.path().join(CONFIG_FILENAME);
+        fs::write(&config_path, "extends = [\"./missing.toml\"]\n").unwrap();
+
+        let err = load_config(&config_path).unwrap_err();
+
+        match err {
+            ConfigError::ReadError { path, .. } => assert!(path.ends_with("missing.toml")),
+            _ => panic!("Expected ReadError"),
+        }
+    }
+
+    #[test]
+    fn error_on_extends_cycle() {
+        let dir = create_temp_dir();
+        fs::write(dir.path().join("a.toml"), "extends = [\"b.toml\"]\n").unwrap();
+        fs::write(dir.path().join("b.toml"), "extends = [\"a.toml\"]\n").unwrap();
+
+        let err = load_config(&dir.path().join("a.toml")).unwrap_err();
+
+        assert!(matches!(err, ConfigError::ExtendsCycle { .. }));
+    }
+
+    #[test]
+    fn error_on_invalid_extends_value() {
+        let dir = create_temp_dir();
+        let config_path = dir.path().join(CONFIG_FILENAME);
+        fs::write(&config_path, "extends = 42\n").unwrap();
+
+        let err = load_config(&config_path).unwrap_err();
+
+        assert!(err.to_string().contains("'extends'"));
+    }
+
+    #[test]
+    fn load_config_or_default_with_warnings_reports_load_errors() {
+        let dir = create_temp_dir();
+        fs::write(
+            dir.path().join(CONFIG_FILENAME),
+            "extends = [\"kaizen:nope\"]\n",
+        )
+        .unwrap();
+
+        let result = load_config_or_default_with_warnings(dir.path());
+
+        assert_eq!(result.config, Config::default());
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("kaizen:nope"));
+    }
 }
@@ -2,17 +2,56 @@
 //!
 //! Provides structured diagnostic information for issues found during analysis.
 
+use serde::{Deserialize, Serialize};
+
 use crate::rules::{Confidence, Severity};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FixKind {
     ReplaceWith { new_text: String },
     InsertBefore { text: String },
+    Delete,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Fix {
-    pub title: String,
+/// How safe it is to apply a fix without review.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Applicability {
+    /// Preserves behavior and can be applied automatically.
+    #[default]
+    Safe,
+    /// Likely what the user wants, but should be reviewed.
+    Suggestion,
+    /// May change runtime behavior.
+    Unsafe,
+}
+
+impl Applicability {
+    pub fn level(&self) -> u8 {
+        match self {
+            Applicability::Safe => 0,
+            Applicability::Suggestion => 1,
+            Applicability::Unsafe => 2,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Applicability::Safe => "safe",
+            Applicability::Suggestion => "suggestion",
+            Applicability::Unsafe => "unsafe",
+        }
+    }
+
+    /// Returns true if a fix with this applicability may be applied under `max`.
+    pub fn is_allowed_by(&self, max: Applicability) -> bool {
+        self.level() <= max.level()
+    }
+}
+
+/// A single text edit. Lines and columns are 1-based, and the range includes
+/// the character at `end_column`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextEdit {
     pub kind: FixKind,
     pub line: usize,
     pub column: usize,
@@ -20,9 +59,8 @@ pub struct Fix {
     pub end_column: usize,
 }
 
-impl Fix {
+impl TextEdit {
     pub fn replace(
-        title: impl Into<String>,
         new_text: impl Into<String>,
         line: usize,
         column: usize,
@@ -30,7 +68,6 @@ impl Fix {
         end_column: usize,
     ) -> Self {
         Self {
-            title: title.into(),
             kind: FixKind::ReplaceWith {
                 new_text: new_text.into(),
             },
@@ -41,24 +78,134 @@ impl Fix {
         }
     }
 
+    pub fn insert_before(text: impl Into<String>, line: usize, column: usize) -> Self {
+        Self {
+            kind: FixKind::InsertBefore { text: text.into() },
+            line,
+            column,
+            end_line: line,
+            end_column: column,
+        }
+    }
+
+    pub fn delete(line: usize, column: usize, end_line: usize, end_column: usize) -> Self {
+        Self {
+            kind: FixKind::Delete,
+            line,
+            column,
+            end_line,
+            end_column,
+        }
+    }
+}
+
+/// A titled set of edits that are applied together.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fix {
+    pub title: String,
+    pub edits: Vec<TextEdit>,
+    pub applicability: Applicability,
+}
+
+impl Fix {
+    pub fn new(title: impl Into<String>, edits: Vec<TextEdit>) -> Self {
+        Self {
+            title: title.into(),
+            edits,
+            applicability: Applicability::default(),
+        }
+    }
+
+    pub fn replace(
+        title: impl Into<String>,
+        new_text: impl Into<String>,
+        line: usize,
+        column: usize,
+        end_line: usize,
+        end_column: usize,
+    ) -> Self {
+        Self::new(
+            title,
+            vec![TextEdit::replace(
+                new_text, line, column, end_line, end_column,
+            )],
+        )
+    }
+
     pub fn insert_before(
         title: impl Into<String>,
         text: impl Into<String>,
         line: usize,
         column: usize,
+    ) -> Self {
+        Self::new(title, vec![TextEdit::insert_before(text, line, column)])
+    }
+
+    pub fn delete(
+        title: impl Into<String>,
+        line: usize,
+        column: usize,
+        end_line: usize,
+        end_column: usize,
+    ) -> Self {
+        Self::new(
+            title,
+            vec![TextEdit::delete(line, column, end_line, end_column)],
+        )
+    }
+
+    pub fn with_edit(mut self, edit: TextEdit) -> Self {
+        self.edits.push(edit);
+        self
+    }
+
+    pub fn with_applicability(mut self, applicability: Applicability) -> Self {
+        self.applicability = applicability;
+        self
+    }
+
+    pub fn is_safe(&self) -> bool {
+        self.applicability == Applicability::Safe
+    }
+}
+
+/// Another place in the code that helps explain a diagnostic, such as a step of an
+/// import cycle or of a taint trace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelatedLocation {
+    pub message: String,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl RelatedLocation {
+    pub fn new(
+        message: impl Into<String>,
+        file: impl Into<String>,
+        line: usize,
+        column: usize,
     ) -> Self {
         Self {
-            title: title.into(),
-            kind: FixKind::InsertBefore { text: text.into() },
+            message: message.into(),
+            file: file.into(),
             line,
             column,
             end_line: line,
             end_column: column,
         }
     }
+
+    pub fn with_end(mut self, end_line: usize, end_column: usize) -> Self {
+        self.end_line = end_line;
+        self.end_column = end_column;
+        self
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Diagnostic {
     pub rule_id: String,
     pub severity: Severity,
@@ -71,6 +218,12 @@ pub struct Diagnostic {
     pub end_column: usize,
     pub suggestion: Option<String>,
     pub fixes: Vec<Fix>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<RelatedLocation>,
+    /// Ordered steps of the data flow that leads to the problem, from its
+    /// source to the reported location.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trace: Vec<RelatedLocation>,
 }
 
 impl Diagnostic {
@@ -94,6 +247,8 @@ impl Diagnostic {
             end_column: column,
             suggestion: None,
             fixes: Vec::new(),
+            related: Vec::new(),
+            trace: Vec::new(),
         }
     }
 
@@ -122,4 +277,61 @@ impl Diagnostic {
         self.fixes = fixes;
         self
     }
+
+    pub fn with_related(mut self, related: RelatedLocation) -> Self {
+        self.related.push(related);
+        self
+    }
+
+    pub fn with_trace(mut self, trace: Vec<RelatedLocation>) -> Self {
+        self.trace = trace;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fix_defaults_to_safe() {
+        let fix = Fix::replace("Replace", "let", 1, 1, 1, 3);
+
+        assert_eq!(fix.applicability, Applicability::Safe);
+        assert!(fix.is_safe());
+        assert_eq!(fix.edits.len(), 1);
+    }
+
+    #[test]
+    fn fix_with_multiple_edits() {
+        let fix = Fix::insert_before("Add 'await'", "await ", 3, 5)
+            .with_edit(TextEdit::insert_before("async ", 1, 1))
+            .with_applicability(Applicability::Unsafe);
+
+        assert_eq!(fix.edits.len(), 2);
+        assert_eq!(
+            fix.edits[1].kind,
+            FixKind::InsertBefore {
+                text: "async ".into()
+            }
+        );
+        assert!(!fix.is_safe());
+    }
+
+    #[test]
+    fn delete_fix_has_delete_kind() {
+        let fix = Fix::delete("Remove", 1, 5, 1, 7);
+
+        assert_eq!(fix.edits[0].kind, FixKind::Delete);
+        assert_eq!(fix.edits[0].end_column, 7);
+    }
+
+    #[test]
+    fn applicability_ordering() {
+        assert!(Applicability::Safe.is_allowed_by(Applicability::Safe));
+        assert!(!Applicability::Suggestion.is_allowed_by(Applicability::Safe));
+        assert!(Applicability::Suggestion.is_allowed_by(Applicability::Unsafe));
+        assert!(Applicability::Unsafe.is_allowed_by(Applicability::Unsafe));
+        assert_eq!(Applicability::default(), Applicability::Safe);
+    }
 }
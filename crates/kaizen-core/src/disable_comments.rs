@@ -6,39 +6,103 @@
 //! - `// kaizen-disable-next-line` - disable all rules for the next line
 //! - `// kaizen-disable-line` - disable all rules for the current line
 //! - `// kaizen-disable-next-line Q030, Q031` - disable multiple rules
+//! - `/* kaizen-disable S001 */ ... /* kaizen-enable */` - disable S001 between the comments
+//! - `// kaizen-disable-file no-console` - disable no-console for the whole file
+//!
+//! Every directive also works as a block comment, including `{/* ... */}` in JSX.
+//! Rules are given by ID or name, `*` matches any characters (`S*`, `prefer-*`),
+//! and an optional `-- reason` suffix records why the rule is disabled.
+
+use crate::parser::{Parser, SourceComment};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectiveKind {
+    NextLine,
+    Line,
+    /// Opened by `kaizen-disable` and closed by `kaizen-enable` or the end of the file.
+    Range,
+    File,
+}
 
-use std::collections::HashMap;
+/// Where a directive comment sits in the source, using 1-based byte columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommentPosition {
+    pub line: usize,
+    pub column: usize,
+    /// Column of the comment's last character, or `None` for a block comment that
+    /// continues on later lines.
+    pub end_column: Option<usize>,
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DisableDirective {
+    pub kind: DirectiveKind,
+    /// Line the directive applies to, or the first line of a range.
     pub line: usize,
+    /// Last line covered by a range, or `None` when it runs to the end of the file.
+    pub end_line: Option<usize>,
+    /// Rule IDs, names or wildcard patterns; empty means every rule.
     pub rule_ids: Vec<String>,
+    pub reason: Option<String>,
+    pub comment: CommentPosition,
+    /// The `kaizen-enable` comment that closed a range.
+    pub enable_comment: Option<CommentPosition>,
 }
 
 impl DisableDirective {
     pub fn new(line: usize, rule_ids: Vec<String>) -> Self {
-        Self { line, rule_ids }
-    }
-
-    pub fn for_all_rules(line: usize) -> Self {
         Self {
+            kind: DirectiveKind::Line,
             line,
-            rule_ids: Vec::new(),
+            end_line: Some(line),
+            rule_ids,
+            reason: None,
+            comment: CommentPosition {
+                line,
+                column: 1,
+                end_column: None,
+            },
+            enable_comment: None,
         }
     }
 
+    pub fn for_all_rules(line: usize) -> Self {
+        Self::new(line, Vec::new())
+    }
+
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+
     pub fn disables_all(&self) -> bool {
         self.rule_ids.is_empty()
     }
 
     pub fn disables_rule(&self, rule_id: &str) -> bool {
-        self.rule_ids.is_empty() || self.rule_ids.iter().any(|id| id == rule_id)
+        self.rule_ids.is_empty()
+            || self
+                .rule_ids
+                .iter()
+                .any(|pattern| wildcard_match(pattern, rule_id))
+    }
+
+    /// Like [`DisableDirective::disables_rule`], also matching patterns against the rule name.
+    pub fn disables(&self, rule_id: &str, rule_name: Option<&str>) -> bool {
+        self.disables_rule(rule_id) || rule_name.is_some_and(|name| self.disables_rule(name))
+    }
+
+    pub fn covers_line(&self, line: usize) -> bool {
+        match self.kind {
+            DirectiveKind::File => true,
+            _ => line >= self.line && self.end_line.is_none_or(|end| line <= end),
+        }
     }
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct DisableDirectives {
-    by_line: HashMap<usize, DisableDirective>,
+    directives: Vec<DisableDirective>,
 }
 
 impl DisableDirectives {
@@ -46,63 +110,165 @@ impl DisableDirectives {
         Self::default()
     }
 
+    /// Parses `source` as JavaScript and reads the directives in its comments.
     pub fn from_source(source: &str) -> Self {
-        let mut directives = Self::new();
-
-        for (line_idx, line) in source.lines().enumerate() {
-            let line_num = line_idx + 1;
-
-            if let Some(comment_start) = line.find("//") {
-                let comment = &line[comment_start + 2..].trim();
+        let comments = Parser::new().parse_module_recovering(source).comments;
+        Self::from_comments(source, &comments)
+    }
 
-                if let Some(rest) = comment.strip_prefix("kaizen-disable-next-line") {
-                    let rule_ids = parse_rule_ids(rest);
-                    let target_line = line_num + 1;
-                    directives.add(DisableDirective::new(target_line, rule_ids));
-                } else if let Some(rest) = comment.strip_prefix("kaizen-disable-line") {
-                    let rule_ids = parse_rule_ids(rest);
-                    directives.add(DisableDirective::new(line_num, rule_ids));
+    /// Reads directives from the parser's `comments`, so text inside strings and
+    /// templates is never mistaken for one.
+    pub fn from_comments(source: &str, comments: &[SourceComment]) -> Self {
+        let mut directives = Self::new();
+        let mut open_ranges: Vec<usize> = Vec::new();
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        for comment in comments {
+            let line_num = line_starts.partition_point(|&start| start <= comment.start);
+            let line_start = line_starts[line_num - 1];
+            // A block comment spanning lines is read from its first line only.
+            let text = comment.text.lines().next().unwrap_or_default();
+            let Some(body) = text.trim().strip_prefix("kaizen-") else {
+                continue;
+            };
+            let (keyword, rest) = split_keyword(body);
+            let (rule_ids, reason) = parse_rules_and_reason(rest);
+            let position = CommentPosition {
+                line: line_num,
+                column: comment.start - line_start + 1,
+                end_column: (!source[comment.start..comment.end].contains('\n'))
+                    .then(|| comment.end - line_start),
+            };
+
+            let (kind, line, end_line) = match keyword {
+                "disable-next-line" => (DirectiveKind::NextLine, line_num + 1, Some(line_num + 1)),
+                "disable-line" => (DirectiveKind::Line, line_num, Some(line_num)),
+                "disable-file" => (DirectiveKind::File, line_num, None),
+                "disable" if comment.is_block => {
+                    open_ranges.push(directives.directives.len());
+                    (DirectiveKind::Range, line_num, None)
                 }
-            }
+                "enable" if comment.is_block => {
+                    directives.close_ranges(&mut open_ranges, &rule_ids, position);
+                    continue;
+                }
+                _ => continue,
+            };
+            directives.add(DisableDirective {
+                kind,
+                line,
+                end_line,
+                rule_ids,
+                reason,
+                comment: position,
+                enable_comment: None,
+            });
         }
 
         directives
     }
 
+    /// Ends open ranges at `line`: all of them for a bare `kaizen-enable`, otherwise
+    /// those listing one of `rule_ids`.
+    fn close_ranges(
+        &mut self,
+        open_ranges: &mut Vec<usize>,
+        rule_ids: &[String],
+        enable: CommentPosition,
+    ) {
+        open_ranges.retain(|&index| {
+            let directive = &mut self.directives[index];
+            let closes = rule_ids.is_empty()
+                || directive
+                    .rule_ids
+                    .iter()
+                    .any(|rule| rule_ids.contains(rule));
+            if closes {
+                directive.end_line = Some(enable.line);
+                directive.enable_comment = Some(enable);
+            }
+            !closes
+        });
+    }
+
     pub fn add(&mut self, directive: DisableDirective) {
-        self.by_line.insert(directive.line, directive);
+        self.directives.push(directive);
     }
 
     pub fn is_disabled(&self, line: usize, rule_id: &str) -> bool {
-        self.by_line
-            .get(&line)
-            .is_some_and(|d| d.disables_rule(rule_id))
+        self.directives
+            .iter()
+            .any(|d| d.covers_line(line) && d.disables_rule(rule_id))
+    }
+
+    /// Directives suppressing `rule_id` (or `rule_name`) at `line`.
+    pub fn matching<'a>(
+        &'a self,
+        line: usize,
+        rule_id: &'a str,
+        rule_name: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a DisableDirective> {
+        self.directives
+            .iter()
+            .filter(move |d| d.covers_line(line) && d.disables(rule_id, rule_name))
     }
 
     pub fn directives(&self) -> impl Iterator<Item = &DisableDirective> {
-        self.by_line.values()
+        self.directives.iter()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.by_line.is_empty()
+        self.directives.is_empty()
     }
 
     pub fn len(&self) -> usize {
-        self.by_line.len()
+        self.directives.len()
     }
 }
 
-fn parse_rule_ids(rest: &str) -> Vec<String> {
-    let trimmed = rest.trim();
-    if trimmed.is_empty() {
-        return Vec::new();
-    }
+/// Splits `disable-next-line Q030` into the keyword and the rest, requiring whitespace
+/// or the end of the comment after the keyword.
+fn split_keyword(body: &str) -> (&str, &str) {
+    let end = body.find(char::is_whitespace).unwrap_or(body.len());
+    body.split_at(end)
+}
 
-    trimmed
+fn parse_rules_and_reason(rest: &str) -> (Vec<String>, Option<String>) {
+    let (rules, reason) = match rest.split_once("--") {
+        Some((rules, reason)) => (rules, Some(reason.trim())),
+        None => (rest, None),
+    };
+
+    let rule_ids = rules
         .split(',')
         .map(|s| s.trim().to_string())
         .filter(|s| !s.is_empty())
-        .collect()
+        .collect();
+    let reason = reason.filter(|r| !r.is_empty()).map(str::to_string);
+
+    (rule_ids, reason)
+}
+
+/// Matches `value` against `pattern`, where `*` stands for any run of characters.
+pub(crate) fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return pattern == value;
+    };
+    let Some(mut remaining) = value.strip_prefix(prefix) else {
+        return false;
+    };
+
+    let mut parts: Vec<&str> = rest.split('*').collect();
+    let suffix = parts.pop().unwrap_or_default();
+    for part in parts {
+        match remaining.find(part) {
+            Some(index) => remaining = &remaining[index + part.len()..],
+            None => return false,
+        }
+    }
+    remaining.len() >= suffix.len() && remaining.ends_with(suffix)
 }
 
 #[cfg(test)]
@@ -291,6 +457,48 @@ var x = 1;
         assert!(directives.is_empty());
     }
 
+    #[test]
+    fn directive_text_in_strings_is_ignored() {
+        let source = r#"
+const s = "/* kaizen-disable */";
+const t = '// kaizen-disable-line Q030';
+var x = 1;
+"#;
+        let directives = DisableDirectives::from_source(source);
+
+        assert!(directives.is_empty());
+    }
+
+    #[test]
+    fn directive_text_in_template_literals_is_ignored() {
+        let source = "const help = `\n// kaizen-disable-next-line\n${name}\n`;\nvar x = 1; // kaizen-disable-line Q030\n";
+        let directives = DisableDirectives::from_source(source);
+
+        assert_eq!(directives.len(), 1);
+        assert!(directives.is_disabled(5, "Q030"));
+        assert!(!directives.is_disabled(3, "Q030"));
+    }
+
+    #[test]
+    fn comment_positions_are_relative_to_their_line() {
+        let source = "var a = 1;\nvar x = 1; /* kaizen-disable-line Q030 */ var y = 2;\n/* kaizen-disable\n   Q033 */\n";
+        let directives: Vec<_> = DisableDirectives::from_source(source)
+            .directives()
+            .cloned()
+            .collect();
+
+        assert_eq!(
+            directives[0].comment,
+            CommentPosition {
+                line: 2,
+                column: 12,
+                end_column: Some(41),
+            }
+        );
+        assert_eq!(directives[1].comment.line, 3);
+        assert_eq!(directives[1].comment.end_column, None);
+    }
+
     #[test]
     fn directive_struct_disables_rule() {
         let directive = DisableDirective::new(5, vec!["Q030".to_string(), "Q033".to_string()]);
@@ -330,4 +538,153 @@ var y = 2;
 
         assert_eq!(directives.len(), 2);
     }
+
+    #[test]
+    fn block_range_until_enable() {
+        let source = r#"var a = 1;
+/* kaizen-disable S001 */
+query(a);
+query(b);
+/* kaizen-enable */
+query(c);
+"#;
+        let directives = DisableDirectives::from_source(source);
+
+        assert!(!directives.is_disabled(1, "S001"));
+        assert!(directives.is_disabled(3, "S001"));
+        assert!(directives.is_disabled(4, "S001"));
+        assert!(!directives.is_disabled(4, "Q030"));
+        assert!(!directives.is_disabled(6, "S001"));
+    }
+
+    #[test]
+    fn unclosed_range_runs_to_end_of_file() {
+        let source = "/* kaizen-disable */\nvar a = 1;\n\n\nvar b = 2;\n";
+        let directives = DisableDirectives::from_source(source);
+
+        assert!(directives.is_disabled(2, "Q030"));
+        assert!(directives.is_disabled(500, "Q030"));
+    }
+
+    #[test]
+    fn enable_with_rules_closes_only_matching_ranges() {
+        let source = r#"/* kaizen-disable Q030 */
+/* kaizen-disable Q033 */
+var a = 1;
+/* kaizen-enable Q030 */
+var b = 2;
+"#;
+        let directives = DisableDirectives::from_source(source);
+
+        assert!(directives.is_disabled(3, "Q030"));
+        assert!(!directives.is_disabled(5, "Q030"));
+        assert!(directives.is_disabled(5, "Q033"));
+    }
+
+    #[test]
+    fn line_comment_does_not_open_range() {
+        let source = "// kaizen-disable Q030\nvar x = 1;\n";
+        let directives = DisableDirectives::from_source(source);
+
+        assert!(!directives.is_disabled(2, "Q030"));
+    }
+
+    #[test]
+    fn disable_file_applies_everywhere() {
+        let source = r#"var a = 1;
+console.log(a);
+// kaizen-disable-file no-console
+"#;
+        let directives = DisableDirectives::from_source(source);
+
+        let directive = directives.directives().next().unwrap();
+        assert_eq!(directive.kind, DirectiveKind::File);
+        assert!(directive.covers_line(1));
+        assert!(directive.covers_line(1000));
+        assert!(directives.is_disabled(2, "no-console"));
+        assert!(!directives.is_disabled(2, "Q032"));
+    }
+
+    #[test]
+    fn jsx_block_comment_directive() {
+        let source = r#"const App = () => (
+  <div>
+    {/* kaizen-disable-next-line S002 */}
+    <div dangerouslySetInnerHTML={{ __html: html }} />
+  </div>
+);
+"#;
+        let directives = DisableDirectives::from_source(source);
+
+        assert!(directives.is_disabled(4, "S002"));
+        assert!(!directives.is_disabled(3, "S002"));
+    }
+
+    #[test]
+    fn block_comment_line_directives() {
+        let source = "var x = 1; /* kaizen-disable-line Q030 */ var y = 2;\n";
+        let directives = DisableDirectives::from_source(source);
+
+        assert!(directives.is_disabled(1, "Q030"));
+    }
+
+    #[test]
+    fn matches_rule_names_and_wildcards() {
+        let source = r#"
+// kaizen-disable-next-line no-console
+console.log(1);
+// kaizen-disable-next-line S*
+query(input);
+// kaizen-disable-next-line prefer-*-chaining
+a && a.b;
+"#;
+        let directives = DisableDirectives::from_source(source);
+
+        let on = |line: usize, id: &str, name: &str| {
+            directives.matching(line, id, Some(name)).next().is_some()
+        };
+        assert!(on(3, "Q032", "no-console"));
+        assert!(!on(3, "Q030", "no-var"));
+        assert!(on(5, "S001", "no-sql-injection"));
+        assert!(!on(5, "Q030", "no-var"));
+        assert!(on(7, "Q040", "prefer-optional-chaining"));
+        assert!(!on(7, "Q041", "prefer-nullish-coalescing"));
+    }
+
+    #[test]
+    fn reason_suffix_is_parsed() {
+        let source = r#"
+// kaizen-disable-next-line S001, S003 -- input is validated by the schema
+query(input);
+// kaizen-disable-next-line -- generated code
+var x = 1;
+// kaizen-disable-next-line Q030 --
+var y = 2;
+"#;
+        let directives: Vec<_> = DisableDirectives::from_source(source)
+            .directives()
+            .cloned()
+            .collect();
+
+        assert_eq!(directives[0].rule_ids, vec!["S001", "S003"]);
+        assert_eq!(
+            directives[0].reason.as_deref(),
+            Some("input is validated by the schema")
+        );
+        assert!(directives[1].disables_all());
+        assert_eq!(directives[1].reason.as_deref(), Some("generated code"));
+        assert_eq!(directives[2].rule_ids, vec!["Q030"]);
+        assert_eq!(directives[2].reason, None);
+    }
+
+    #[test]
+    fn wildcard_patterns() {
+        assert!(wildcard_match("*", "S001"));
+        assert!(wildcard_match("S*", "S001"));
+        assert!(wildcard_match("*-console", "no-console"));
+        assert!(wildcard_match("no-*-injection", "no-sql-injection"));
+        assert!(!wildcard_match("S*", "Q030"));
+        assert!(!wildcard_match("no-*-injection", "no-injection"));
+        assert!(!wildcard_match("S001", "S0011"));
+    }
 }
@@ -0,0 +1,341 @@
+//! Source file discovery driven by `include`/`exclude` patterns and ignore files
+//!
+//! Directory walks and single-path checks (staged files, editor documents) share the
+//! same rules: supported extensions only, hidden entries and `node_modules` skipped,
+//! `.gitignore` and `.kaizenignore` honored, then `include` and `exclude` applied.
+
+use std::path::{Component, Path, PathBuf};
+
+use ignore::WalkBuilder;
+use ignore::gitignore::Gitignore;
+
+use crate::config::Config;
+use crate::globs::GlobMatcher;
+
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["js", "jsx", "ts", "tsx", "mjs", "cjs", "mts", "cts"];
+
+/// Name of the Kaizen-specific ignore file, using `.gitignore` syntax.
+pub const IGNORE_FILENAME: &str = ".kaizenignore";
+
+const SKIPPED_DIRS: &[&str] = &["node_modules"];
+
+pub fn is_supported_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| SUPPORTED_EXTENSIONS.contains(&ext))
+        .unwrap_or(false)
+}
+
+/// Decides which files are analyzed for a given configuration.
+#[derive(Debug, Clone)]
+pub struct FileFilter {
+    root: PathBuf,
+    include: Option<GlobMatcher>,
+    exclude: Option<GlobMatcher>,
+}
+
+impl Default for FileFilter {
+    fn default() -> Self {
+        Self {
+            root: current_dir(),
+            include: None,
+            exclude: None,
+        }
+    }
+}
+
+impl FileFilter {
+    /// Builds a filter rooted at the config directory, or the working directory without one.
+    ///
+    /// Invalid patterns are skipped; they are reported by [`pattern_warnings`] at config load.
+    pub fn new(config: &Config) -> Self {
+        let root = config
+            .root
+            .as_deref()
+            .map(absolute)
+            .unwrap_or_else(current_dir);
+
+        Self {
+            include: build_matcher(&config.include, &root),
+            exclude: build_matcher(&config.exclude, &root),
+            root,
+        }
+    }
+
+    /// The config directory, or the working directory without a config.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns true if a single file would be analyzed, as if found by [`FileFilter::walk`].
+    pub fn is_included(&self, path: &Path) -> bool {
+        is_supported_file(path)
+            && !self.in_skipped_location(path)
+            && self.matches_patterns(path)
+            && !self.is_ignored(path)
+    }
+
+    /// Walks `dir` and returns every file that passes the filter.
+    pub fn walk(&self, dir: &Path) -> Vec<PathBuf> {
+        WalkBuilder::new(dir)
+            .hidden(true)
+            .parents(true)
+            .git_ignore(true)
+            .git_exclude(true)
+            .git_global(false)
+            .require_git(false)
+            .add_custom_ignore_filename(IGNORE_FILENAME)
+            .filter_entry(|entry| {
+                entry.depth() == 0
+                    || !entry
+                        .file_name()
+                        .to_str()
+                        .is_some_and(|name| SKIPPED_DIRS.contains(&name))
+            })
+            .build()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+            .map(ignore::DirEntry::into_path)
+            .filter(|path| is_supported_file(path) && self.matches_patterns(path))
+            .collect()
+    }
+
+    fn matches_patterns(&self, path: &Path) -> bool {
+        let included = self.include.as_ref().is_none_or(|m| m.is_match(path));
+        let excluded = self.exclude.as_ref().is_some_and(|m| m.is_match(path));
+        included && !excluded
+    }
+
+    /// Hidden and `node_modules` components below the root, matching what a walk skips.
+    pub fn in_skipped_location(&self, path: &Path) -> bool {
+        let full = absolute(path);
+        let relative = full
+            .strip_prefix(&self.root)
+            .unwrap_or_else(|_| Path::new(full.file_name().unwrap_or_default()));
+
+        relative.components().any(|component| match component {
+            Component::Normal(name) => name
+                .to_str()
+                .is_some_and(|name| name.starts_with('.') || SKIPPED_DIRS.contains(&name)),
+            _ => false,
+        })
+    }
+
+    /// Checks `.kaizenignore` and `.gitignore` files from the file's directory upwards,
+    /// stopping at the root or at the top of a git repository.
+    fn is_ignored(&self, path: &Path) -> bool {
+        let full = absolute(path);
+        let is_dir = full.is_dir();
+
+        for dir in full.ancestors().skip(1) {
+            for name in [IGNORE_FILENAME, ".gitignore"] {
+                let ignore_file = dir.join(name);
+                if !ignore_file.is_file() {
+                    continue;
+                }
+                let (matcher, _) = Gitignore::new(&ignore_file);
+                let matched = matcher.matched_path_or_any_parents(&full, is_dir);
+                if matched.is_ignore() {
+                    return true;
+                }
+                if matched.is_whitelist() {
+                    return false;
+                }
+            }
+
+            if dir == self.root || dir.join(".git").exists() {
+                break;
+            }
+        }
+
+        false
+    }
+}
+
+/// Warnings for `include`/`exclude` patterns that fail to compile.
+pub fn pattern_warnings(config: &Config) -> Vec<String> {
+    [("include", &config.include), ("exclude", &config.exclude)]
+        .into_iter()
+        .flat_map(|(key, patterns)| {
+            patterns.iter().filter_map(move |pattern| {
+                GlobMatcher::new(std::slice::from_ref(pattern), None)
+                    .err()
+                    .map(|e| format!("Invalid pattern in {}: {}", key, e))
+            })
+        })
+        .collect()
+}
+
+fn build_matcher(patterns: &[String], root: &Path) -> Option<GlobMatcher> {
+    let valid: Vec<String> = patterns
+        .iter()
+        .filter(|pattern| GlobMatcher::new(std::slice::from_ref(pattern), None).is_ok())
+        .cloned()
+        .collect();
+
+    if valid.is_empty() {
+        return None;
+    }
+    GlobMatcher::new(&valid, Some(root)).ok()
+}
+
+fn current_dir() -> PathBuf {
+    std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    let path: PathBuf = path
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect();
+    if path.is_absolute() {
+        path
+    } else {
+        current_dir().join(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn project(files: &[(&str, &str)]) -> TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, content) in files {
+            let path = dir.path().join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    fn filter(dir: &TempDir, include: &[&str], exclude: &[&str]) -> FileFilter {
+        let config = Config {
+            include: include.iter().map(|p| p.to_string()).collect(),
+            exclude: exclude.iter().map(|p| p.to_string()).collect(),
+            root: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        FileFilter::new(&config)
+    }
+
+    fn walked(dir: &TempDir, filter: &FileFilter) -> Vec<String> {
+        let mut files: Vec<String> = filter
+            .walk(dir.path())
+            .iter()
+            .map(|p| {
+                p.strip_prefix(dir.path())
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn include_and_exclude_patterns_limit_walk() {
+        let dir = project(&[
+            ("src/app.ts", ""),
+            ("src/app.test.ts", ""),
+            ("scripts/build.js", ""),
+        ]);
+        let filter = filter(&dir, &["src/**"], &["*.test.ts"]);
+
+        assert_eq!(walked(&dir, &filter), vec!["src/app.ts"]);
+    }
+
+    #[test]
+    fn walk_honors_gitignore_without_repository() {
+        let dir = project(&[
+            (".gitignore", "dist/\n"),
+            ("dist/bundle.js", ""),
+            ("src/app.js", ""),
+        ]);
+        let filter = filter(&dir, &[], &[]);
+
+        assert_eq!(walked(&dir, &filter), vec!["src/app.js"]);
+    }
+
+    #[test]
+    fn walk_honors_kaizenignore() {
+        let dir = project(&[
+            (".kaizenignore", "generated/\n*.min.js\n"),
+            ("generated/api.ts", ""),
+            ("src/vendor.min.js", ""),
+            ("src/app.js", ""),
+        ]);
+        let filter = filter(&dir, &[], &[]);
+
+        assert_eq!(walked(&dir, &filter), vec!["src/app.js"]);
+    }
+
+    #[test]
+    fn walk_skips_hidden_and_node_modules() {
+        let dir = project(&[
+            (".cache/a.js", ""),
+            ("node_modules/pkg/index.js", ""),
+            ("src/app.js", ""),
+        ]);
+        let filter = filter(&dir, &[], &[]);
+
+        assert_eq!(walked(&dir, &filter), vec!["src/app.js"]);
+    }
+
+    #[test]
+    fn single_paths_follow_the_same_rules() {
+        let dir = project(&[
+            (".gitignore", "dist/\n"),
+            (".kaizenignore", "legacy.js\n"),
+            ("dist/bundle.js", ""),
+            ("src/legacy.js", ""),
+            ("src/app.test.ts", ""),
+            ("src/app.ts", ""),
+            ("node_modules/pkg/index.js", ""),
+            ("src/styles.css", ""),
+        ]);
+        let filter = filter(&dir, &[], &["*.test.ts"]);
+        let path = |name: &str| dir.path().join(name);
+
+        assert!(filter.is_included(&path("src/app.ts")));
+        assert!(!filter.is_included(&path("dist/bundle.js")));
+        assert!(!filter.is_included(&path("src/legacy.js")));
+        assert!(!filter.is_included(&path("src/app.test.ts")));
+        assert!(!filter.is_included(&path("node_modules/pkg/index.js")));
+        assert!(!filter.is_included(&path("src/styles.css")));
+    }
+
+    #[test]
+    fn negated_kaizenignore_pattern_reincludes_file() {
+        let dir = project(&[
+            (".kaizenignore", "src/*.js\n!src/keep.js\n"),
+            ("src/drop.js", ""),
+            ("src/keep.js", ""),
+        ]);
+        let filter = filter(&dir, &[], &[]);
+
+        assert_eq!(walked(&dir, &filter), vec!["src/keep.js"]);
+        assert!(filter.is_included(&dir.path().join("src/keep.js")));
+        assert!(!filter.is_included(&dir.path().join("src/drop.js")));
+    }
+
+    #[test]
+    fn invalid_patterns_are_reported_and_skipped() {
+        let dir = project(&[("src/app.js", "")]);
+        let config = Config {
+            include: vec!["src/[".to_string(), "src/**".to_string()],
+            root: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+
+        let warnings = pattern_warnings(&config);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("Invalid pattern in include"));
+
+        let filter = FileFilter::new(&config);
+        assert_eq!(walked(&dir, &filter), vec!["src/app.js"]);
+    }
+}
@@ -0,0 +1,473 @@
+//! Automatic fix application
+//!
+//! Applies the `Fix` edits attached to diagnostics back onto source text.
+//! All edits of a fix are applied together or not at all, a fix that overlaps
+//! one accepted earlier in source order is skipped, and fixing runs in repeated
+//! passes until the source stops changing. Every pass is re-parsed so that a fix
+//! never leaves a file that no longer parses.
+
+use crate::analysis::AnalysisEngine;
+use crate::diagnostic::{Applicability, Diagnostic, Fix, FixKind, TextEdit};
+use crate::parser::ParsedFile;
+
+/// Upper bound on analysis/fix passes for a single file.
+pub const MAX_FIX_PASSES: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixOutcome {
+    pub source: String,
+    pub applied: usize,
+    pub passes: usize,
+}
+
+impl FixOutcome {
+    pub fn changed(&self) -> bool {
+        self.applied > 0
+    }
+}
+
+/// A resolved edit expressed as a byte range of the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ByteEdit {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+impl ByteEdit {
+    fn conflicts_with(&self, other: &ByteEdit) -> bool {
+        self.start == other.start || (self.start < other.end && other.start < self.end)
+    }
+}
+
+/// Repeatedly analyzes `source` and applies, for every diagnostic, the first fix
+/// whose applicability is allowed by `max_applicability`.
+pub fn fix_source(
+    engine: &AnalysisEngine,
+    filename: &str,
+    source: &str,
+    max_applicability: Applicability,
+) -> FixOutcome {
+    let mut current = source.to_string();
+    let mut applied = 0;
+    let mut passes = 0;
+
+    while passes < MAX_FIX_PASSES {
+        let parsed = ParsedFile::from_source(filename, &current);
+        let diagnostics = engine.analyze(&parsed);
+        let fixes = preferred_fixes(&diagnostics, max_applicability);
+        if fixes.is_empty() {
+            break;
+        }
+
+        let (next, count) =
+            apply_fixes_checked(filename, &current, &fixes, parsed.metadata().has_errors);
+        if count == 0 || next == current {
+            break;
+        }
+
+        current = next;
+        applied += count;
+        passes += 1;
+    }
+
+    FixOutcome {
+        source: current,
+        applied,
+        passes,
+    }
+}
+
+/// Applies the fixes of `project_diagnostics`, found by
+/// [`AnalysisEngine::analyze_project`] for this file, then fixes it as [`fix_source`] does.
+///
+/// Project fixes are applied in a single pass, since finding them again needs every file.
+pub fn fix_source_with_project(
+    engine: &AnalysisEngine,
+    filename: &str,
+    source: &str,
+    project_diagnostics: &[Diagnostic],
+    max_applicability: Applicability,
+) -> FixOutcome {
+    let fixes = preferred_fixes(project_diagnostics, max_applicability);
+    let (fixed, count) = if fixes.is_empty() {
+        (source.to_string(), 0)
+    } else {
+        let had_errors = ParsedFile::from_source(filename, source)
+            .metadata()
+            .has_errors;
+        apply_fixes_checked(filename, source, &fixes, had_errors)
+    };
+
+    let mut outcome = fix_source(engine, filename, &fixed, max_applicability);
+    if count > 0 {
+        outcome.applied += count;
+        outcome.passes += 1;
+    }
+    outcome
+}
+
+/// Applies `fixes` to `source`, skipping fixes that conflict with an earlier one.
+///
+/// Returns the new source and the number of fixes that were applied.
+pub fn apply_fixes(source: &str, fixes: &[&Fix]) -> (String, usize) {
+    let mut candidates: Vec<Vec<ByteEdit>> = fixes
+        .iter()
+        .filter_map(|fix| fix_to_edits(source, fix))
+        .collect();
+    candidates.sort_by_key(|edits| edits.iter().map(|edit| (edit.start, edit.end)).min());
+
+    let mut accepted: Vec<ByteEdit> = Vec::new();
+    let mut count = 0;
+    for edits in candidates {
+        let conflicts = edits
+            .iter()
+            .any(|edit| accepted.iter().any(|other| edit.conflicts_with(other)));
+        if conflicts {
+            continue;
+        }
+        accepted.extend(edits);
+        count += 1;
+    }
+    accepted.sort_by_key(|edit| (edit.start, edit.end));
+
+    let mut output = String::with_capacity(source.len());
+    let mut cursor = 0;
+    for edit in &accepted {
+        output.push_str(&source[cursor..edit.start]);
+        output.push_str(&edit.text);
+        cursor = edit.end;
+    }
+    output.push_str(&source[cursor..]);
+
+    (output, count)
+}
+
+fn preferred_fixes(diagnostics: &[Diagnostic], max_applicability: Applicability) -> Vec<&Fix> {
+    diagnostics
+        .iter()
+        .filter_map(|d| {
+            d.fixes
+                .iter()
+                .find(|fix| fix.applicability.is_allowed_by(max_applicability))
+        })
+        .collect()
+}
+
+/// Applies a pass of fixes and verifies the result still parses.
+///
+/// When the combined pass introduces parse errors, each fix is checked on its own
+/// and only the ones that keep the file parseable are applied.
+fn apply_fixes_checked(
+    filename: &str,
+    source: &str,
+    fixes: &[&Fix],
+    had_errors: bool,
+) -> (String, usize) {
+    let (combined, count) = apply_fixes(source, fixes);
+    if had_errors || parses_cleanly(filename, &combined) {
+        return (combined, count);
+    }
+
+    let valid: Vec<&Fix> = fixes
+        .iter()
+        .copied()
+        .filter(|fix| {
+            let (candidate, count) = apply_fixes(source, &[fix]);
+            count > 0 && parses_cleanly(filename, &candidate)
+        })
+        .collect();
+
+    let (combined, count) = apply_fixes(source, &valid);
+    if parses_cleanly(filename, &combined) {
+        (combined, count)
+    } else {
+        (source.to_string(), 0)
+    }
+}
+
+fn parses_cleanly(filename: &str, source: &str) -> bool {
+    !ParsedFile::from_source(filename, source)
+        .metadata()
+        .has_errors
+}
+
+/// Resolves every edit of `fix`, or returns `None` if any of them is invalid.
+fn fix_to_edits(source: &str, fix: &Fix) -> Option<Vec<ByteEdit>> {
+    if fix.edits.is_empty() {
+        return None;
+    }
+
+    let edits: Vec<ByteEdit> = fix
+        .edits
+        .iter()
+        .map(|edit| text_edit_to_byte_edit(source, edit))
+        .collect::<Option<_>>()?;
+
+    let overlapping = edits.iter().enumerate().any(|(i, edit)| {
+        edits[i + 1..]
+            .iter()
+            .any(|other| edit.conflicts_with(other))
+    });
+
+    (!overlapping).then_some(edits)
+}
+
+fn text_edit_to_byte_edit(source: &str, edit: &TextEdit) -> Option<ByteEdit> {
+    let start = location_to_offset(source, edit.line, edit.column)?;
+    // Edit ranges are inclusive of the character at `end_column`.
+    let inclusive_end = || {
+        location_to_offset(source, edit.end_line, edit.end_column)
+            .map(|end| (end + 1).min(source.len()))
+    };
+    let (end, text) = match &edit.kind {
+        FixKind::ReplaceWith { new_text } => (inclusive_end()?, new_text.clone()),
+        FixKind::InsertBefore { text } => (start, text.clone()),
+        FixKind::Delete => (inclusive_end()?, String::new()),
+    };
+
+    if end < start || !source.is_char_boundary(start) || !source.is_char_boundary(end) {
+        return None;
+    }
+
+    Some(ByteEdit { start, end, text })
+}
+
+/// Converts a 1-based line and byte column into a byte offset into `source`.
+fn location_to_offset(source: &str, line: usize, column: usize) -> Option<usize> {
+    if line == 0 || column == 0 {
+        return None;
+    }
+
+    let mut line_start = 0;
+    for _ in 1..line {
+        line_start += source[line_start..].find('\n')? + 1;
+    }
+
+    let line_end = source[line_start..]
+        .find('\n')
+        .map_or(source.len(), |i| line_start + i);
+    let offset = line_start + column - 1;
+
+    (offset <= line_end).then_some(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Severity;
+
+    fn fix_all(code: &str, max_applicability: Applicability) -> FixOutcome {
+        let engine = AnalysisEngine::new();
+        fix_source(&engine, "test.js", code, max_applicability)
+    }
+
+    #[test]
+    fn applies_replace_fix() {
+        let fix = Fix::replace("Replace 'var' with 'let'", "let", 1, 1, 1, 3);
+
+        let (output, count) = apply_fixes("var x = 1;", &[&fix]);
+
+        assert_eq!(output, "let x = 1;");
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn applies_insert_fix() {
+        let fix = Fix::insert_before("Add 'void'", "void ", 2, 5);
+
+        let (output, count) = apply_fixes("a();\n    run();", &[&fix]);
+
+        assert_eq!(output, "a();\n    void run();");
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn applies_multiple_fixes_on_same_line() {
+        let first = Fix::replace("eq", "===", 1, 7, 1, 8);
+        let second = Fix::replace("eq", "===", 1, 17, 1, 18);
+
+        let (output, count) = apply_fixes("if (a == b && c == d) {}", &[&second, &first]);
+
+        assert_eq!(output, "if (a === b && c === d) {}");
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn skips_overlapping_fixes() {
+        let first = Fix::replace("first", "let", 1, 1, 1, 3);
+        let second = Fix::replace("second", "const", 1, 2, 1, 4);
+
+        let (output, count) = apply_fixes("var x = 1;", &[&first, &second]);
+
+        assert_eq!(output, "let x = 1;");
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn skips_duplicate_insertions_at_same_position() {
+        let first = Fix::insert_before("await", "await ", 1, 1);
+        let second = Fix::insert_before("void", "void ", 1, 1);
+
+        let (output, count) = apply_fixes("run();", &[&first, &second]);
+
+        assert_eq!(count, 1);
+        assert_eq!(output, "await run();");
+    }
+
+    #[test]
+    fn applies_all_edits_of_a_fix() {
+        let fix = Fix::insert_before("Add 'await'", "await ", 2, 3)
+            .with_edit(TextEdit::insert_before("async ", 1, 1));
+
+        let (output, count) = apply_fixes("function f() {\n  run();\n}", &[&fix]);
+
+        assert_eq!(output, "async function f() {\n  await run();\n}");
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn applies_delete_fix() {
+        let fix = Fix::delete("Remove 'b'", 1, 11, 1, 13);
+
+        let (output, count) = apply_fixes("import { a, b } from 'x';", &[&fix]);
+
+        assert_eq!(output, "import { a } from 'x';");
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn skips_whole_fix_when_any_edit_conflicts() {
+        let first = Fix::replace("first", "let", 1, 1, 1, 3);
+        let second = Fix::replace("second", "const", 2, 1, 2, 3)
+            .with_edit(TextEdit::replace("const", 1, 1, 1, 3));
+
+        let (output, count) = apply_fixes("var a;\nvar b;", &[&first, &second]);
+
+        assert_eq!(output, "let a;\nvar b;");
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn ignores_fix_with_overlapping_edits() {
+        let fix = Fix::replace("a", "let", 1, 1, 1, 3).with_edit(TextEdit::delete(1, 2, 1, 4));
+
+        let (output, count) = apply_fixes("var x = 1;", &[&fix]);
+
+        assert_eq!(output, "var x = 1;");
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn ignores_fix_outside_source() {
+        let fix = Fix::replace("out of range", "x", 5, 1, 5, 2);
+
+        let (output, count) = apply_fixes("const a = 1;", &[&fix]);
+
+        assert_eq!(output, "const a = 1;");
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn fix_source_runs_until_stable() {
+        let outcome = fix_all("var x = 1;\nexport { x };", Applicability::Suggestion);
+
+        assert_eq!(outcome.source, "const x = 1;\nexport { x };");
+        assert_eq!(outcome.applied, 2);
+        assert_eq!(outcome.passes, 2);
+        assert!(outcome.changed());
+    }
+
+    #[test]
+    fn fix_source_without_fixable_issues_is_unchanged() {
+        let code = "const x = 1;\nexport { x };";
+        let outcome = fix_all(code, Applicability::Unsafe);
+
+        assert_eq!(outcome.source, code);
+        assert!(!outcome.changed());
+        assert_eq!(outcome.passes, 0);
+    }
+
+    #[test]
+    fn fix_source_applies_eqeqeq_only_when_unsafe_allowed() {
+        let code = "export const same = (a, b) => a == b;";
+
+        assert_eq!(fix_all(code, Applicability::Safe).source, code);
+        assert_eq!(
+            fix_all(code, Applicability::Unsafe).source,
+            "export const same = (a, b) => a === b;"
+        );
+    }
+
+    #[test]
+    fn fix_source_safe_only_skips_suggestions() {
+        let code = "var x = 1;\nexport { x };";
+        let outcome = fix_all(code, Applicability::Safe);
+
+        assert_eq!(outcome.source, code);
+        assert!(!outcome.changed());
+    }
+
+    #[test]
+    fn fix_source_with_project_applies_project_fixes_first() {
+        let engine = AnalysisEngine::new();
+        let unused_export = Diagnostic::new("Q005", Severity::Warning, "unused", "test.js", 1, 1)
+            .with_fix(
+                Fix::delete("Remove 'export'", 1, 1, 1, 7)
+                    .with_applicability(Applicability::Suggestion),
+            );
+
+        let code = "export var x = 1;\nconsole.info(x);";
+        let outcome = fix_source_with_project(
+            &engine,
+            "test.js",
+            code,
+            std::slice::from_ref(&unused_export),
+            Applicability::Suggestion,
+        );
+        assert_eq!(outcome.source, "const x = 1;\nconsole.info(x);");
+        assert_eq!(outcome.applied, 3);
+
+        let outcome = fix_source_with_project(
+            &engine,
+            "test.js",
+            code,
+            &[unused_export],
+            Applicability::Safe,
+        );
+        assert_eq!(outcome.source, code);
+    }
+
+    #[test]
+    fn fix_source_respects_disable_comments() {
+        let code = "// kaizen-disable-next-line Q030\nvar x = 1;\nexport { x };";
+        let outcome = fix_all(code, Applicability::Unsafe);
+
+        assert_eq!(outcome.source, code);
+    }
+
+    #[test]
+    fn fix_source_adds_await_and_async() {
+        let code = "function run() {\n  fetch('/a');\n}\nexport { run };";
+        let outcome = fix_all(code, Applicability::Unsafe);
+
+        assert_eq!(
+            outcome.source,
+            "async function run() {\n  await fetch('/a');\n}\nexport { run };"
+        );
+        let reparsed = ParsedFile::from_source("test.js", &outcome.source);
+        assert!(!reparsed.metadata().has_errors);
+    }
+
+    #[test]
+    fn location_to_offset_handles_lines() {
+        let source = "ab\ncd\n";
+
+        assert_eq!(location_to_offset(source, 1, 1), Some(0));
+        assert_eq!(location_to_offset(source, 2, 2), Some(4));
+        assert_eq!(location_to_offset(source, 2, 3), Some(5));
+        assert_eq!(location_to_offset(source, 2, 4), None);
+        assert_eq!(location_to_offset(source, 4, 1), None);
+        assert_eq!(location_to_offset(source, 0, 1), None);
+    }
+}
@@ -0,0 +1,178 @@
+//! Glob matching for file patterns in configuration
+//!
+//! Patterns follow `.gitignore` conventions: a pattern without a slash matches at any
+//! depth, a pattern with a slash is anchored to the configuration root, and a pattern
+//! naming a directory matches everything below it.
+
+use std::path::{Component, Path, PathBuf};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+#[derive(Debug, Clone)]
+pub struct GlobMatcher {
+    set: GlobSet,
+    root: Option<PathBuf>,
+    canonical_root: Option<PathBuf>,
+}
+
+impl GlobMatcher {
+    /// Builds a matcher for `patterns`, resolved relative to `root` when given.
+    pub fn new(patterns: &[String], root: Option<&Path>) -> Result<Self, globset::Error> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            for expanded in expand_pattern(pattern) {
+                builder.add(
+                    GlobBuilder::new(&expanded)
+                        .literal_separator(true)
+                        .build()?,
+                );
+            }
+        }
+
+        let root = root.map(absolute);
+        let canonical_root = root.as_ref().and_then(|r| r.canonicalize().ok());
+
+        Ok(Self {
+            set: builder.build()?,
+            root,
+            canonical_root,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    pub fn is_match(&self, path: impl AsRef<Path>) -> bool {
+        !self.set.is_empty() && self.set.is_match(self.relative_path(path.as_ref()))
+    }
+
+    /// Returns `path` relative to the root, or `path` without `./` prefixes if it lies outside.
+    fn relative_path(&self, path: &Path) -> PathBuf {
+        let path: PathBuf = path
+            .components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .collect();
+
+        let Some(root) = &self.root else {
+            return path;
+        };
+
+        let full = absolute(&path);
+        if let Ok(relative) = full.strip_prefix(root) {
+            return relative.to_path_buf();
+        }
+
+        if let (Some(canonical_root), Ok(canonical)) = (&self.canonical_root, full.canonicalize())
+            && let Ok(relative) = canonical.strip_prefix(canonical_root)
+        {
+            return relative.to_path_buf();
+        }
+
+        path
+    }
+}
+
+fn expand_pattern(pattern: &str) -> Vec<String> {
+    let pattern = pattern.trim_start_matches("./");
+    let trimmed = pattern.trim_end_matches('/');
+
+    let base = if let Some(anchored) = trimmed.strip_prefix('/') {
+        anchored.to_string()
+    } else if trimmed.contains('/') || trimmed.starts_with("**") {
+        trimmed.to_string()
+    } else {
+        format!("**/{}", trimmed)
+    };
+
+    vec![base.clone(), format!("{}/**", base)]
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(patterns: &[&str]) -> GlobMatcher {
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+        GlobMatcher::new(&patterns, None).unwrap()
+    }
+
+    #[test]
+    fn anchored_pattern_matches_from_root() {
+        let m = matcher(&["src/**/*.ts"]);
+
+        assert!(m.is_match("src/app.ts"));
+        assert!(m.is_match("src/a/b/app.ts"));
+        assert!(m.is_match("./src/app.ts"));
+        assert!(!m.is_match("lib/src/app.ts"));
+        assert!(!m.is_match("src/app.js"));
+    }
+
+    #[test]
+    fn pattern_without_slash_matches_at_any_depth() {
+        let m = matcher(&["*.test.js"]);
+
+        assert!(m.is_match("app.test.js"));
+        assert!(m.is_match("src/deep/app.test.js"));
+        assert!(!m.is_match("src/app.js"));
+    }
+
+    #[test]
+    fn directory_pattern_matches_contents() {
+        let m = matcher(&["scripts/"]);
+
+        assert!(m.is_match("scripts/build.js"));
+        assert!(m.is_match("tools/scripts/build.js"));
+        assert!(!m.is_match("src/build.js"));
+    }
+
+    #[test]
+    fn leading_slash_anchors_pattern() {
+        let m = matcher(&["/fixtures"]);
+
+        assert!(m.is_match("fixtures/a.js"));
+        assert!(!m.is_match("tests/fixtures/a.js"));
+    }
+
+    #[test]
+    fn star_does_not_cross_directories() {
+        let m = matcher(&["src/*.js"]);
+
+        assert!(m.is_match("src/a.js"));
+        assert!(!m.is_match("src/nested/a.js"));
+    }
+
+    #[test]
+    fn paths_are_made_relative_to_root() {
+        let root = Path::new("/project");
+        let patterns = vec!["tests/**".to_string()];
+        let m = GlobMatcher::new(&patterns, Some(root)).unwrap();
+
+        assert!(m.is_match("/project/tests/a.test.js"));
+        assert!(!m.is_match("/project/src/a.js"));
+        assert!(!m.is_match("/elsewhere/tests/a.js"));
+    }
+
+    #[test]
+    fn empty_matcher_matches_nothing() {
+        let m = matcher(&[]);
+
+        assert!(m.is_empty());
+        assert!(!m.is_match("src/a.js"));
+    }
+
+    #[test]
+    fn invalid_pattern_is_an_error() {
+        assert!(GlobMatcher::new(&["src/[".to_string()], None).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+//! Hashing for values persisted to disk, such as baseline fingerprints and cache keys
+//!
+//! `std`'s hashers may change between Rust releases, so stored hashes use FNV-1a.
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// 64-bit FNV-1a hasher whose output is stable across builds and platforms.
+#[derive(Debug, Clone, Copy)]
+pub struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(FNV_OFFSET)
+    }
+}
+
+impl StableHasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    /// Writes `part` followed by a separator, so `("ab", "c")` and `("a", "bc")` differ.
+    pub fn write_part(&mut self, part: &str) {
+        self.write(part.as_bytes());
+        self.write(&[0]);
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+
+    pub fn finish_hex(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
+
+/// Hashes `parts` in order and returns the hash as 16 hex digits.
+pub fn stable_hash<'a>(parts: impl IntoIterator<Item = &'a str>) -> String {
+    let mut hasher = StableHasher::new();
+    for part in parts {
+        hasher.write_part(part);
+    }
+    hasher.finish_hex()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_reference_fnv1a() {
+        let mut hasher = StableHasher::new();
+        hasher.write(b"a");
+
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(StableHasher::new().finish_hex(), "cbf29ce484222325");
+    }
+
+    #[test]
+    fn parts_are_separated() {
+        assert_ne!(stable_hash(["ab", "c"]), stable_hash(["a", "bc"]));
+        assert_eq!(stable_hash(["ab", "c"]), stable_hash(["ab", "c"]));
+    }
+}
@@ -6,16 +6,30 @@
 //! - Rule system (quality, security)
 //! - Taint analysis for security vulnerabilities
 //! - Diagnostic reporting
+//! - Automatic fix application
 //! - Configuration loading
+//! - Source file discovery
+//! - Baselines of accepted findings
+//! - Incremental analysis cache
+//! - Project-wide module graph for cross-file rules
 
 pub mod analysis;
+pub mod baseline;
+pub mod cache;
 pub mod config;
 pub mod diagnostic;
 pub mod disable_comments;
+pub mod discovery;
+pub mod fixer;
+pub mod globs;
+pub mod hash;
 pub mod licensing;
 pub mod parser;
+pub mod presets;
+pub mod project;
 pub mod rules;
 pub mod semantic;
+pub mod suppressions;
 pub mod taint;
 pub mod visitor;
 
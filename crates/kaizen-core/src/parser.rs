@@ -5,6 +5,7 @@
 use std::ops::Range;
 use std::sync::OnceLock;
 
+use swc_common::comments::{CommentKind, SingleThreadedComments};
 use swc_common::sync::Lrc;
 use swc_common::{FileName, SourceMap, Spanned};
 use swc_ecma_parser::{
@@ -44,10 +45,22 @@ pub struct ParseError {
     pub message: String,
 }
 
+/// A comment the lexer found, with byte offsets into the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceComment {
+    /// Text between the delimiters.
+    pub text: String,
+    pub is_block: bool,
+    pub start: usize,
+    pub end: usize,
+}
+
 #[derive(Debug)]
 pub struct ParseResult {
     pub module: Option<Module>,
     pub errors: Vec<ParseError>,
+    /// Comments in source order.
+    pub comments: Vec<SourceComment>,
 }
 
 impl ParseResult {
@@ -92,7 +105,7 @@ impl ParsedFile {
         let language = detect_language(filename);
         let parser = Parser::for_file(filename);
         let parse_result = parser.parse_module_recovering(source);
-        let disable_directives = DisableDirectives::from_source(source);
+        let disable_directives = DisableDirectives::from_comments(source, &parse_result.comments);
 
         let line_count = if source.is_empty() {
             0
@@ -326,14 +339,16 @@ impl Parser {
             .new_source_file(FileName::Custom("input.js".into()).into(), code.to_string());
 
         let mut recovered_errors = Vec::new();
+        let comments = SingleThreadedComments::default();
 
         let result = parse_file_as_module(
             &fm,
             self.syntax,
             EsVersion::latest(),
-            None,
+            Some(&comments),
             &mut recovered_errors,
         );
+        let comments = source_comments(comments, fm.start_pos.0);
 
         let errors: Vec<ParseError> = recovered_errors
             .into_iter()
@@ -354,6 +369,7 @@ impl Parser {
             Ok(module) => ParseResult {
                 module: Some(module),
                 errors,
+                comments,
             },
             Err(e) => {
                 let span = e.span();
@@ -370,12 +386,34 @@ impl Parser {
                 ParseResult {
                     module: None,
                     errors: all_errors,
+                    comments,
                 }
             }
         }
     }
 }
 
+/// Flattens the lexer's leading and trailing comments into source order, with offsets
+/// relative to `start_pos`.
+fn source_comments(comments: SingleThreadedComments, start_pos: u32) -> Vec<SourceComment> {
+    let (leading, trailing) = comments.take_all();
+    let mut comments: Vec<SourceComment> = leading
+        .borrow()
+        .values()
+        .chain(trailing.borrow().values())
+        .flatten()
+        .map(|comment| SourceComment {
+            text: comment.text.to_string(),
+            is_block: comment.kind == CommentKind::Block,
+            start: (comment.span.lo.0 - start_pos) as usize,
+            end: (comment.span.hi.0 - start_pos) as usize,
+        })
+        .collect();
+    comments.sort_by_key(|comment| comment.start);
+    comments.dedup_by_key(|comment| comment.start);
+    comments
+}
+
 impl Default for Parser {
     fn default() -> Self {
         Self::new()
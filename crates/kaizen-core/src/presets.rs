@@ -0,0 +1,85 @@
+//! Built-in configuration presets referenced as `extends = ["kaizen:<name>"]`
+
+/// Prefix marking an `extends` entry as a built-in preset rather than a file path.
+pub const PRESET_PREFIX: &str = "kaizen:";
+
+const PRESETS: &[(&str, &str)] = &[
+    ("recommended", include_str!("../presets/recommended.toml")),
+    (
+        "security-strict",
+        include_str!("../presets/security-strict.toml"),
+    ),
+    ("node-backend", include_str!("../presets/node-backend.toml")),
+    (
+        "react-frontend",
+        include_str!("../presets/react-frontend.toml"),
+    ),
+];
+
+/// Returns the TOML source of the preset called `name`, without the `kaizen:` prefix.
+pub fn builtin_preset(name: &str) -> Option<&'static str> {
+    PRESETS
+        .iter()
+        .find(|(preset, _)| *preset == name)
+        .map(|(_, source)| *source)
+}
+
+pub fn preset_names() -> impl Iterator<Item = &'static str> {
+    PRESETS.iter().map(|(name, _)| *name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CONFIG_FILENAME, Config, SeverityValue, load_config_with_warnings};
+    use crate::rules::RuleCategory;
+
+    #[test]
+    fn every_preset_loads_without_warnings() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join(CONFIG_FILENAME);
+
+        for name in preset_names() {
+            std::fs::write(
+                &config_path,
+                format!("extends = [\"{}{}\"]\n", PRESET_PREFIX, name),
+            )
+            .unwrap();
+
+            let result = load_config_with_warnings(&config_path).unwrap();
+            assert!(
+                result.warnings.is_empty(),
+                "{}: {:?}",
+                name,
+                result.warnings
+            );
+        }
+    }
+
+    #[test]
+    fn security_strict_reports_every_security_rule_as_error() {
+        let preset: Config = toml::from_str(builtin_preset("security-strict").unwrap()).unwrap();
+        let (registry, _) = crate::analysis::configured_registry(&Config::default());
+
+        for rule in registry.rules() {
+            let metadata = rule.metadata();
+            if metadata.category != RuleCategory::Security {
+                continue;
+            }
+            assert!(
+                matches!(
+                    preset.rules.severity.get(metadata.name),
+                    Some(SeverityValue::Error)
+                ),
+                "{} is missing from security-strict",
+                metadata.name
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_preset_is_none() {
+        assert!(builtin_preset("recommended").is_some());
+        assert!(builtin_preset("does-not-exist").is_none());
+    }
+}
@@ -0,0 +1,221 @@
+//! Module dependency graph over the analyzed files
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::parser::ParsedFile;
+
+use super::module_info::ModuleInfo;
+use super::resolver::{Resolution, Resolver};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ModuleId(pub(crate) usize);
+
+impl ModuleId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// Where an import of a module leads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedImport {
+    /// Another analyzed file.
+    Module(ModuleId),
+    /// A file that is not part of the analyzed set, such as a dependency or an ignored file.
+    File(PathBuf),
+    /// A package or built-in module that was not found on disk, by package name.
+    External(String),
+    Unresolved,
+}
+
+#[derive(Debug)]
+pub struct Module {
+    /// Canonical path, used to match resolved imports.
+    pub path: PathBuf,
+    /// The filename diagnostics are reported under.
+    pub filename: String,
+    pub info: ModuleInfo,
+    /// Resolution of each entry in `info.imports`, by index.
+    pub resolved: Vec<ResolvedImport>,
+}
+
+impl Module {
+    /// Analyzed modules imported by this one, with the index of each import record.
+    pub fn dependencies(&self) -> impl Iterator<Item = (usize, ModuleId)> + '_ {
+        self.resolved
+            .iter()
+            .enumerate()
+            .filter_map(|(index, resolved)| match resolved {
+                ResolvedImport::Module(id) => Some((index, *id)),
+                _ => None,
+            })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ModuleGraph {
+    modules: Vec<Module>,
+    ids: HashMap<PathBuf, ModuleId>,
+    dependents: Vec<Vec<ModuleId>>,
+}
+
+impl ModuleGraph {
+    /// Extracts the imports and exports of `files` and resolves them against the filesystem.
+    pub fn build(files: &[ParsedFile]) -> Self {
+        let mut graph = Self::default();
+        for file in files {
+            let filename = &file.metadata().filename;
+            let path = absolute(Path::new(filename));
+            let id = ModuleId(graph.modules.len());
+            graph.ids.insert(path.clone(), id);
+            graph.modules.push(Module {
+                path,
+                filename: filename.clone(),
+                info: file
+                    .module()
+                    .map(ModuleInfo::from_module)
+                    .unwrap_or_default(),
+                resolved: Vec::new(),
+            });
+        }
+
+        let mut resolver = Resolver::new();
+        for index in 0..graph.modules.len() {
+            let module = &graph.modules[index];
+            let resolved = module
+                .info
+                .imports
+                .iter()
+                .map(|import| {
+                    match resolver.resolve(&module.path, &import.specifier, import.kind) {
+                        Resolution::File(path) => match graph.ids.get(&path) {
+                            Some(id) => ResolvedImport::Module(*id),
+                            None => ResolvedImport::File(path),
+                        },
+                        Resolution::External(name) => ResolvedImport::External(name),
+                        Resolution::Unresolved => ResolvedImport::Unresolved,
+                    }
+                })
+                .collect();
+            graph.modules[index].resolved = resolved;
+        }
+
+        graph.dependents = vec![Vec::new(); graph.modules.len()];
+        for (index, module) in graph.modules.iter().enumerate() {
+            for (_, dependency) in module.dependencies() {
+                let dependents = &mut graph.dependents[dependency.0];
+                if !dependents.contains(&ModuleId(index)) {
+                    dependents.push(ModuleId(index));
+                }
+            }
+        }
+
+        graph
+    }
+
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    pub fn module(&self, id: ModuleId) -> &Module {
+        &self.modules[id.0]
+    }
+
+    pub fn modules(&self) -> impl Iterator<Item = (ModuleId, &Module)> {
+        self.modules
+            .iter()
+            .enumerate()
+            .map(|(index, module)| (ModuleId(index), module))
+    }
+
+    /// Looks up an analyzed module by path, relative to the working directory or absolute.
+    pub fn module_id(&self, path: &Path) -> Option<ModuleId> {
+        self.ids.get(&absolute(path)).copied()
+    }
+
+    /// Analyzed modules that import `id`.
+    pub fn dependents(&self, id: ModuleId) -> &[ModuleId] {
+        &self.dependents[id.0]
+    }
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| {
+        std::env::current_dir()
+            .map(|dir| dir.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(files: &[(&str, &str)]) -> (tempfile::TempDir, Vec<ParsedFile>) {
+        let dir = tempfile::tempdir().unwrap();
+        let parsed = files
+            .iter()
+            .map(|(name, source)| {
+                let path = dir.path().join(name);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(&path, source).unwrap();
+                ParsedFile::from_source(&path.to_string_lossy(), source)
+            })
+            .collect();
+        (dir, parsed)
+    }
+
+    #[test]
+    fn links_imports_between_analyzed_files() {
+        let (dir, files) = project(&[
+            (
+                "src/app.ts",
+                "import { helper } from './util';\nimport React from 'react';",
+            ),
+            (
+                "src/util/index.ts",
+                "export const helper = require('../config');",
+            ),
+            ("src/config.js", "module.exports = {};"),
+        ]);
+        let graph = ModuleGraph::build(&files);
+
+        let app = graph.module_id(&dir.path().join("src/app.ts")).unwrap();
+        let util = graph
+            .module_id(&dir.path().join("src/util/index.ts"))
+            .unwrap();
+        let config = graph.module_id(&dir.path().join("src/config.js")).unwrap();
+
+        assert_eq!(
+            graph.module(app).dependencies().collect::<Vec<_>>(),
+            vec![(0, util)]
+        );
+        assert_eq!(
+            graph.module(app).resolved[1],
+            ResolvedImport::External("react".to_string())
+        );
+        assert_eq!(
+            graph.module(util).dependencies().collect::<Vec<_>>(),
+            vec![(0, config)]
+        );
+        assert_eq!(graph.dependents(util), &[app]);
+        assert!(graph.dependents(app).is_empty());
+    }
+
+    #[test]
+    fn files_outside_the_analyzed_set_are_not_modules() {
+        let (dir, files) = project(&[("app.js", "import './vendor.js';\nimport './gone.js';")]);
+        std::fs::write(dir.path().join("vendor.js"), "").unwrap();
+        let graph = ModuleGraph::build(&files);
+
+        let app = graph.module(ModuleId(0));
+        assert!(matches!(app.resolved[0], ResolvedImport::File(_)));
+        assert_eq!(app.resolved[1], ResolvedImport::Unresolved);
+        assert_eq!(graph.len(), 1);
+    }
+}
@@ -0,0 +1,104 @@
+//! Project model for cross-file analysis
+//!
+//! [`Project`] holds every analyzed file together with a [`ModuleGraph`] of the imports
+//! between them. Rules with [`RuleScope::Project`](crate::rules::RuleScope) receive it
+//! through [`Rule::check_project`](crate::rules::Rule::check_project).
+
+pub mod graph;
+pub mod module_info;
+pub mod resolver;
+
+pub use graph::{Module, ModuleGraph, ModuleId, ResolvedImport};
+pub use module_info::{
+    DEFAULT_EXPORT, Export, ExportKind, Import, ImportBinding, ImportKind, ModuleInfo,
+};
+pub use resolver::{PackageEntries, Resolution, Resolver};
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::parser::ParsedFile;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug)]
+pub struct Project {
+    id: u64,
+    files: Vec<ParsedFile>,
+    graph: ModuleGraph,
+    by_name: HashMap<String, ModuleId>,
+    /// Filenames relative to the project root, by module.
+    relative_names: Vec<Option<String>>,
+}
+
+impl Project {
+    /// Builds the module graph of `files`. Filenames must be valid paths, relative to the
+    /// working directory or absolute, so that imports can be resolved on disk.
+    pub fn new(files: Vec<ParsedFile>) -> Self {
+        let graph = ModuleGraph::build(&files);
+        let by_name = graph
+            .modules()
+            .map(|(id, module)| (module.filename.clone(), id))
+            .collect();
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            files,
+            graph,
+            by_name,
+            relative_names: Vec::new(),
+        }
+    }
+
+    /// Names the files under `root` relative to it in [`Self::display_name`].
+    pub fn with_root(mut self, root: &Path) -> Self {
+        let Ok(root) = root.canonicalize() else {
+            return self;
+        };
+        self.relative_names = self
+            .files
+            .iter()
+            .map(|file| {
+                let path = Path::new(&file.metadata().filename).canonicalize().ok()?;
+                let relative = path.strip_prefix(&root).ok()?;
+                Some(relative.to_string_lossy().into_owned())
+            })
+            .collect();
+        self
+    }
+
+    /// Tells this project apart from every other one built by the process, so
+    /// that results computed for it can be reused.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn graph(&self) -> &ModuleGraph {
+        &self.graph
+    }
+
+    pub fn file(&self, id: ModuleId) -> &ParsedFile {
+        &self.files[id.index()]
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = (ModuleId, &ParsedFile)> {
+        self.files
+            .iter()
+            .enumerate()
+            .map(|(index, file)| (ModuleId(index), file))
+    }
+
+    /// Looks up a file by the filename it was parsed with.
+    pub fn file_named(&self, filename: &str) -> Option<&ParsedFile> {
+        self.by_name.get(filename).map(|id| self.file(*id))
+    }
+
+    /// How `filename` is shown to users: relative to the project root given to
+    /// [`Self::with_root`] when it is under it, as parsed otherwise.
+    pub fn display_name<'a>(&'a self, filename: &'a str) -> &'a str {
+        self.by_name
+            .get(filename)
+            .and_then(|id| self.relative_names.get(id.index())?.as_deref())
+            .unwrap_or(filename)
+    }
+}
@@ -0,0 +1,813 @@
+//! Imports and exports declared by a single module
+//!
+//! Covers ES module syntax, CommonJS `require`/`module.exports`, dynamic `import()` and
+//! TypeScript `import x = require()` / `export =`.
+
+use swc_common::{Span, Spanned};
+use swc_ecma_ast::{
+    AssignExpr, AssignOp, AssignTarget, CallExpr, Callee, Decl, DefaultDecl, ExportSpecifier, Expr,
+    ImportSpecifier, Lit, MemberExpr, MemberProp, Module, ModuleDecl, ModuleExportName, ModuleItem,
+    ObjectPatProp, Pat, Prop, PropName, PropOrSpread, SimpleAssignTarget, TsModuleName,
+    TsModuleRef, VarDeclarator,
+};
+use swc_ecma_visit::{Visit, VisitWith};
+
+/// Name under which default exports are recorded.
+pub const DEFAULT_EXPORT: &str = "default";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportKind {
+    /// `import ... from "m"` and `import "m"`
+    Static,
+    /// `export ... from "m"`
+    ReExport,
+    /// `import("m")`
+    Dynamic,
+    /// `require("m")` and `import x = require("m")`
+    Require,
+}
+
+/// A name bound from another module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportBinding {
+    /// The exported name used, `"default"` for default imports, or `None` when the whole
+    /// module object is used.
+    pub imported: Option<String>,
+    /// The local binding, or for re-exports the name it is exported under. Empty when the
+    /// value is used without being bound, as in `foo(require("m"))`.
+    pub local: String,
+    pub type_only: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    pub specifier: String,
+    pub kind: ImportKind,
+    /// Empty for side-effect imports and `export * from`.
+    pub bindings: Vec<ImportBinding>,
+    /// True when the whole statement only imports types and is erased at runtime.
+    pub type_only: bool,
+    pub span: Span,
+}
+
+impl Import {
+    /// True when the importing module may use any export, so none can be considered unused.
+    pub fn uses_whole_module(&self) -> bool {
+        self.bindings
+            .iter()
+            .any(|binding| binding.imported.is_none())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportKind {
+    /// `export const a = 1`, `export function f() {}`, `export default ...`
+    Declaration,
+    /// `export { a }` and `export { a as b }`
+    Local,
+    /// `export { a } from "m"` and `export * as ns from "m"`, with the index of the
+    /// import record and the name taken from it (`None` for the whole namespace).
+    ReExport {
+        import: usize,
+        imported: Option<String>,
+    },
+    /// `module.exports = ...`, `exports.a = ...` and `export = ...`
+    CommonJs,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Export {
+    /// The exported name, `"default"` for default exports.
+    pub name: String,
+    /// The local binding the export refers to, when it names one.
+    pub local: Option<String>,
+    pub kind: ExportKind,
+    pub type_only: bool,
+    /// The whole export statement, or the assignment for CommonJS exports.
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModuleInfo {
+    pub imports: Vec<Import>,
+    pub exports: Vec<Export>,
+    /// Indices into `imports` of `export * from "m"` statements.
+    pub star_reexports: Vec<usize>,
+}
+
+impl ModuleInfo {
+    pub fn from_module(module: &Module) -> Self {
+        let mut collector = Collector::default();
+        for item in &module.body {
+            match item {
+                ModuleItem::ModuleDecl(decl) => collector.module_decl(decl),
+                ModuleItem::Stmt(stmt) => stmt.visit_with(&mut collector),
+            }
+        }
+        collector.info
+    }
+
+    /// True when the module uses CommonJS exports, whose full set of names is unknown.
+    pub fn is_commonjs(&self) -> bool {
+        self.exports
+            .iter()
+            .any(|export| export.kind == ExportKind::CommonJs)
+    }
+
+    pub fn export(&self, name: &str) -> Option<&Export> {
+        self.exports.iter().find(|export| export.name == name)
+    }
+}
+
+#[derive(Default)]
+struct Collector {
+    info: ModuleInfo,
+    /// `require()` calls already recorded with their bindings by a declarator.
+    handled_requires: Vec<Span>,
+}
+
+impl Collector {
+    fn import(&mut self, specifier: String, kind: ImportKind, span: Span) -> usize {
+        self.info.imports.push(Import {
+            specifier,
+            kind,
+            bindings: Vec::new(),
+            type_only: false,
+            span,
+        });
+        self.info.imports.len() - 1
+    }
+
+    fn export(
+        &mut self,
+        name: String,
+        local: Option<String>,
+        kind: ExportKind,
+        span: Span,
+    ) -> &mut Export {
+        self.info.exports.push(Export {
+            name,
+            local,
+            kind,
+            type_only: false,
+            span,
+        });
+        self.info.exports.last_mut().unwrap()
+    }
+
+    fn module_decl(&mut self, decl: &ModuleDecl) {
+        match decl {
+            ModuleDecl::Import(import) => {
+                let index = self.import(str_value(&import.src), ImportKind::Static, import.span);
+                let record = &mut self.info.imports[index];
+                record.type_only = import.type_only;
+                record.bindings = import
+                    .specifiers
+                    .iter()
+                    .map(|specifier| match specifier {
+                        ImportSpecifier::Named(named) => ImportBinding {
+                            imported: Some(
+                                named
+                                    .imported
+                                    .as_ref()
+                                    .map(export_name)
+                                    .unwrap_or_else(|| named.local.sym.to_string()),
+                            ),
+                            local: named.local.sym.to_string(),
+                            type_only: import.type_only || named.is_type_only,
+                        },
+                        ImportSpecifier::Default(default) => ImportBinding {
+                            imported: Some(DEFAULT_EXPORT.to_string()),
+                            local: default.local.sym.to_string(),
+                            type_only: import.type_only,
+                        },
+                        ImportSpecifier::Namespace(namespace) => ImportBinding {
+                            imported: None,
+                            local: namespace.local.sym.to_string(),
+                            type_only: import.type_only,
+                        },
+                    })
+                    .collect();
+            }
+            ModuleDecl::ExportDecl(export) => {
+                let type_only = is_type_decl(&export.decl);
+                for name in decl_names(&export.decl) {
+                    self.export(
+                        name.clone(),
+                        Some(name),
+                        ExportKind::Declaration,
+                        export.span,
+                    )
+                    .type_only = type_only;
+                }
+                export.decl.visit_with(self);
+            }
+            ModuleDecl::ExportDefaultDecl(export) => {
+                let (local, type_only) = match &export.decl {
+                    DefaultDecl::Class(class) => (class.ident.as_ref(), false),
+                    DefaultDecl::Fn(function) => (function.ident.as_ref(), false),
+                    DefaultDecl::TsInterfaceDecl(interface) => (Some(&interface.id), true),
+                };
+                self.export(
+                    DEFAULT_EXPORT.to_string(),
+                    local.map(|ident| ident.sym.to_string()),
+                    ExportKind::Declaration,
+                    export.span,
+                )
+                .type_only = type_only;
+                export.decl.visit_with(self);
+            }
+            ModuleDecl::ExportDefaultExpr(export) => {
+                let local = match &*export.expr {
+                    Expr::Ident(ident) => Some(ident.sym.to_string()),
+                    _ => None,
+                };
+                self.export(
+                    DEFAULT_EXPORT.to_string(),
+                    local,
+                    ExportKind::Declaration,
+                    export.span,
+                );
+                export.expr.visit_with(self);
+            }
+            ModuleDecl::ExportNamed(named) => match &named.src {
+                Some(src) => {
+                    let index = self.import(str_value(src), ImportKind::ReExport, named.span);
+                    self.info.imports[index].type_only = named.type_only;
+                    for specifier in &named.specifiers {
+                        let (imported, name, type_only) = match specifier {
+                            ExportSpecifier::Named(specifier) => (
+                                Some(export_name(&specifier.orig)),
+                                export_name(specifier.exported.as_ref().unwrap_or(&specifier.orig)),
+                                specifier.is_type_only,
+                            ),
+                            ExportSpecifier::Namespace(specifier) => {
+                                (None, export_name(&specifier.name), false)
+                            }
+                            ExportSpecifier::Default(specifier) => (
+                                Some(DEFAULT_EXPORT.to_string()),
+                                specifier.exported.sym.to_string(),
+                                false,
+                            ),
+                        };
+                        self.info.imports[index].bindings.push(ImportBinding {
+                            imported: imported.clone(),
+                            local: name.clone(),
+                            type_only: named.type_only || type_only,
+                        });
+                        self.export(
+                            name,
+                            None,
+                            ExportKind::ReExport {
+                                import: index,
+                                imported,
+                            },
+                            named.span,
+                        )
+                        .type_only = named.type_only || type_only;
+                    }
+                }
+                None => {
+                    for specifier in &named.specifiers {
+                        let ExportSpecifier::Named(specifier) = specifier else {
+                            continue;
+                        };
+                        let local = export_name(&specifier.orig);
+                        let name = specifier
+                            .exported
+                            .as_ref()
+                            .map(export_name)
+                            .unwrap_or_else(|| local.clone());
+                        self.export(name, Some(local), ExportKind::Local, named.span)
+                            .type_only = named.type_only || specifier.is_type_only;
+                    }
+                }
+            },
+            ModuleDecl::ExportAll(export) => {
+                let index = self.import(str_value(&export.src), ImportKind::ReExport, export.span);
+                self.info.imports[index].type_only = export.type_only;
+                self.info.star_reexports.push(index);
+            }
+            ModuleDecl::TsImportEquals(import) => {
+                if let TsModuleRef::TsExternalModuleRef(module_ref) = &import.module_ref {
+                    let index = self.import(
+                        str_value(&module_ref.expr),
+                        ImportKind::Require,
+                        import.span,
+                    );
+                    let record = &mut self.info.imports[index];
+                    record.type_only = import.is_type_only;
+                    record.bindings.push(ImportBinding {
+                        imported: None,
+                        local: import.id.sym.to_string(),
+                        type_only: import.is_type_only,
+                    });
+                }
+                if import.is_export {
+                    let name = import.id.sym.to_string();
+                    self.export(
+                        name.clone(),
+                        Some(name),
+                        ExportKind::Declaration,
+                        import.span,
+                    );
+                }
+            }
+            ModuleDecl::TsExportAssignment(export) => {
+                self.export(
+                    DEFAULT_EXPORT.to_string(),
+                    None,
+                    ExportKind::CommonJs,
+                    export.span,
+                );
+                export.expr.visit_with(self);
+            }
+            ModuleDecl::TsNamespaceExport(_) => {}
+        }
+    }
+
+    /// Records `module.exports = ...`, `module.exports.a = ...` and `exports.a = ...`.
+    fn commonjs_export(&mut self, assign: &AssignExpr) {
+        let AssignTarget::Simple(SimpleAssignTarget::Member(member)) = &assign.left else {
+            return;
+        };
+        let local = match &*assign.right {
+            Expr::Ident(ident) => Some(ident.sym.to_string()),
+            _ => None,
+        };
+
+        if is_module_exports(member) {
+            self.export(
+                DEFAULT_EXPORT.to_string(),
+                local,
+                ExportKind::CommonJs,
+                assign.span,
+            );
+            if let Expr::Object(object) = &*assign.right {
+                for prop in &object.props {
+                    let PropOrSpread::Prop(prop) = prop else {
+                        continue;
+                    };
+                    let (name, local) = match &**prop {
+                        Prop::Shorthand(ident) => {
+                            (ident.sym.to_string(), Some(ident.sym.to_string()))
+                        }
+                        Prop::KeyValue(kv) => match (prop_name(&kv.key), &*kv.value) {
+                            (Some(name), Expr::Ident(ident)) => (name, Some(ident.sym.to_string())),
+                            (Some(name), _) => (name, None),
+                            _ => continue,
+                        },
+                        Prop::Method(method) => match prop_name(&method.key) {
+                            Some(name) => (name, None),
+                            None => continue,
+                        },
+                        _ => continue,
+                    };
+                    self.export(name, local, ExportKind::CommonJs, prop.span());
+                }
+            }
+        } else if (is_ident(&member.obj, "exports")
+            || matches!(&*member.obj, Expr::Member(inner) if is_module_exports(inner)))
+            && let MemberProp::Ident(name) = &member.prop
+        {
+            self.export(
+                name.sym.to_string(),
+                local,
+                ExportKind::CommonJs,
+                assign.span,
+            );
+        }
+    }
+
+    /// Records `const x = require("m")` and `const { a, b: c } = require("m")`.
+    fn require_declarator(&mut self, declarator: &VarDeclarator) -> bool {
+        let Some(init) = &declarator.init else {
+            return false;
+        };
+        let (call, member) = match &**init {
+            Expr::Call(call) => (call, None),
+            Expr::Member(member) => match &*member.obj {
+                Expr::Call(call) => (call, Some(&member.prop)),
+                _ => return false,
+            },
+            _ => return false,
+        };
+        let Some(specifier) = require_specifier(call) else {
+            return false;
+        };
+
+        let bindings = match (member, &declarator.name) {
+            (Some(MemberProp::Ident(prop)), Pat::Ident(local)) => vec![ImportBinding {
+                imported: Some(prop.sym.to_string()),
+                local: local.id.sym.to_string(),
+                type_only: false,
+            }],
+            (None, Pat::Ident(local)) => vec![ImportBinding {
+                imported: None,
+                local: local.id.sym.to_string(),
+                type_only: false,
+            }],
+            (None, Pat::Object(object)) => object_pattern_bindings(object),
+            _ => vec![ImportBinding {
+                imported: None,
+                local: String::new(),
+                type_only: false,
+            }],
+        };
+
+        let index = self.import(specifier, ImportKind::Require, call.span);
+        self.info.imports[index].bindings = bindings;
+        self.handled_requires.push(call.span);
+        true
+    }
+}
+
+impl Visit for Collector {
+    fn visit_var_declarator(&mut self, node: &VarDeclarator) {
+        self.require_declarator(node);
+        node.visit_children_with(self);
+    }
+
+    fn visit_assign_expr(&mut self, node: &AssignExpr) {
+        if node.op == AssignOp::Assign {
+            self.commonjs_export(node);
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_member_expr(&mut self, node: &MemberExpr) {
+        // `require("m").a` outside a declarator
+        if let (Expr::Call(call), MemberProp::Ident(prop)) = (&*node.obj, &node.prop)
+            && !self.handled_requires.contains(&call.span)
+            && let Some(specifier) = require_specifier(call)
+        {
+            let index = self.import(specifier, ImportKind::Require, call.span);
+            self.info.imports[index].bindings.push(ImportBinding {
+                imported: Some(prop.sym.to_string()),
+                local: String::new(),
+                type_only: false,
+            });
+            self.handled_requires.push(call.span);
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_call_expr(&mut self, node: &CallExpr) {
+        if !self.handled_requires.contains(&node.span) {
+            let (specifier, kind) = match &node.callee {
+                Callee::Import(_) => (first_string_arg(node), ImportKind::Dynamic),
+                _ => (require_specifier(node), ImportKind::Require),
+            };
+            if let Some(specifier) = specifier {
+                let index = self.import(specifier, kind, node.span);
+                // A bare `require("m");` statement only runs the module, which the
+                // statement visitor cannot tell apart here; treat the value as used.
+                self.info.imports[index].bindings.push(ImportBinding {
+                    imported: None,
+                    local: String::new(),
+                    type_only: false,
+                });
+            }
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_stmt(&mut self, node: &swc_ecma_ast::Stmt) {
+        // `require("m");` as a statement is a side-effect import without bindings.
+        if let swc_ecma_ast::Stmt::Expr(expr) = node
+            && let Expr::Call(call) = &*expr.expr
+            && let Some(specifier) = require_specifier(call)
+        {
+            self.import(specifier, ImportKind::Require, call.span);
+            self.handled_requires.push(call.span);
+            for arg in &call.args {
+                arg.visit_with(self);
+            }
+            return;
+        }
+        node.visit_children_with(self);
+    }
+}
+
+fn object_pattern_bindings(object: &swc_ecma_ast::ObjectPat) -> Vec<ImportBinding> {
+    let mut bindings = Vec::new();
+    for prop in &object.props {
+        let (imported, local) = match prop {
+            ObjectPatProp::Assign(assign) => {
+                (Some(assign.key.sym.to_string()), assign.key.sym.to_string())
+            }
+            ObjectPatProp::KeyValue(kv) => {
+                let local = match &*kv.value {
+                    Pat::Ident(ident) => ident.id.sym.to_string(),
+                    Pat::Assign(assign) => match &*assign.left {
+                        Pat::Ident(ident) => ident.id.sym.to_string(),
+                        _ => String::new(),
+                    },
+                    _ => String::new(),
+                };
+                (prop_name(&kv.key), local)
+            }
+            // `...rest` can read any export.
+            ObjectPatProp::Rest(_) => (None, String::new()),
+        };
+        bindings.push(ImportBinding {
+            imported,
+            local,
+            type_only: false,
+        });
+    }
+    bindings
+}
+
+/// Names bound by an exported declaration.
+fn decl_names(decl: &Decl) -> Vec<String> {
+    match decl {
+        Decl::Class(class) => vec![class.ident.sym.to_string()],
+        Decl::Fn(function) => vec![function.ident.sym.to_string()],
+        Decl::Var(var) => {
+            let mut names = Vec::new();
+            for declarator in &var.decls {
+                pat_names(&declarator.name, &mut names);
+            }
+            names
+        }
+        Decl::Using(using) => {
+            let mut names = Vec::new();
+            for declarator in &using.decls {
+                pat_names(&declarator.name, &mut names);
+            }
+            names
+        }
+        Decl::TsInterface(interface) => vec![interface.id.sym.to_string()],
+        Decl::TsTypeAlias(alias) => vec![alias.id.sym.to_string()],
+        Decl::TsEnum(ts_enum) => vec![ts_enum.id.sym.to_string()],
+        Decl::TsModule(module) => match &module.id {
+            TsModuleName::Ident(ident) => vec![ident.sym.to_string()],
+            TsModuleName::Str(_) => Vec::new(),
+        },
+    }
+}
+
+fn is_type_decl(decl: &Decl) -> bool {
+    match decl {
+        Decl::TsInterface(_) | Decl::TsTypeAlias(_) => true,
+        Decl::Class(class) => class.declare,
+        Decl::Fn(function) => function.declare,
+        Decl::Var(var) => var.declare,
+        Decl::TsEnum(ts_enum) => ts_enum.declare,
+        Decl::TsModule(module) => module.declare,
+        Decl::Using(_) => false,
+    }
+}
+
+fn pat_names(pat: &Pat, names: &mut Vec<String>) {
+    match pat {
+        Pat::Ident(ident) => names.push(ident.id.sym.to_string()),
+        Pat::Array(array) => {
+            for element in array.elems.iter().flatten() {
+                pat_names(element, names);
+            }
+        }
+        Pat::Object(object) => {
+            for prop in &object.props {
+                match prop {
+                    ObjectPatProp::KeyValue(kv) => pat_names(&kv.value, names),
+                    ObjectPatProp::Assign(assign) => names.push(assign.key.sym.to_string()),
+                    ObjectPatProp::Rest(rest) => pat_names(&rest.arg, names),
+                }
+            }
+        }
+        Pat::Rest(rest) => pat_names(&rest.arg, names),
+        Pat::Assign(assign) => pat_names(&assign.left, names),
+        Pat::Invalid(_) | Pat::Expr(_) => {}
+    }
+}
+
+fn require_specifier(call: &CallExpr) -> Option<String> {
+    match &call.callee {
+        Callee::Expr(callee) if is_ident(callee, "require") => first_string_arg(call),
+        _ => None,
+    }
+}
+
+/// The first argument when it is a string literal or a template without expressions.
+fn first_string_arg(call: &CallExpr) -> Option<String> {
+    let arg = call.args.first()?;
+    if arg.spread.is_some() {
+        return None;
+    }
+    match &*arg.expr {
+        Expr::Lit(Lit::Str(s)) => Some(str_value(s)),
+        Expr::Tpl(tpl) if tpl.exprs.is_empty() => tpl
+            .quasis
+            .first()
+            .and_then(|quasi| quasi.cooked.as_ref())
+            .map(|cooked| cooked.to_string()),
+        _ => None,
+    }
+}
+
+fn is_module_exports(member: &MemberExpr) -> bool {
+    is_ident(&member.obj, "module") && member.prop.is_ident_with("exports")
+}
+
+fn is_ident(expr: &Expr, name: &str) -> bool {
+    matches!(expr, Expr::Ident(ident) if ident.sym == *name)
+}
+
+fn str_value(s: &swc_ecma_ast::Str) -> String {
+    s.value.to_string()
+}
+
+fn export_name(name: &ModuleExportName) -> String {
+    match name {
+        ModuleExportName::Ident(ident) => ident.sym.to_string(),
+        ModuleExportName::Str(s) => str_value(s),
+    }
+}
+
+fn prop_name(name: &PropName) -> Option<String> {
+    match name {
+        PropName::Ident(ident) => Some(ident.sym.to_string()),
+        PropName::Str(s) => Some(str_value(s)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ParsedFile;
+
+    fn info(filename: &str, source: &str) -> ModuleInfo {
+        let file = ParsedFile::from_source(filename, source);
+        ModuleInfo::from_module(file.module().unwrap())
+    }
+
+    fn names(info: &ModuleInfo) -> Vec<&str> {
+        info.exports.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    fn binding(imported: Option<&str>, local: &str) -> ImportBinding {
+        ImportBinding {
+            imported: imported.map(str::to_string),
+            local: local.to_string(),
+            type_only: false,
+        }
+    }
+
+    #[test]
+    fn collects_es_imports() {
+        let info = info(
+            "app.ts",
+            r#"
+import def, { a, b as c } from "./lib";
+import * as ns from "./ns";
+import type { T } from "./types";
+import "./polyfill";
+"#,
+        );
+
+        let specifiers: Vec<&str> = info.imports.iter().map(|i| i.specifier.as_str()).collect();
+        assert_eq!(specifiers, vec!["./lib", "./ns", "./types", "./polyfill"]);
+        assert_eq!(
+            info.imports[0].bindings,
+            vec![
+                binding(Some("default"), "def"),
+                binding(Some("a"), "a"),
+                binding(Some("b"), "c"),
+            ]
+        );
+        assert!(info.imports[1].uses_whole_module());
+        assert!(info.imports[2].type_only);
+        assert!(info.imports[3].bindings.is_empty());
+    }
+
+    #[test]
+    fn collects_es_exports() {
+        let info = info(
+            "lib.ts",
+            r#"
+export const a = 1, { b, c: [d] } = obj;
+export function f() {}
+export interface Shape {}
+const local = 2;
+export { local as renamed };
+export default class Widget {}
+"#,
+        );
+
+        assert_eq!(
+            names(&info),
+            vec!["a", "b", "d", "f", "Shape", "renamed", "default"]
+        );
+        assert!(info.export("Shape").unwrap().type_only);
+        assert_eq!(
+            info.export("renamed").unwrap().local.as_deref(),
+            Some("local")
+        );
+        assert_eq!(
+            info.export("default").unwrap().local.as_deref(),
+            Some("Widget")
+        );
+    }
+
+    #[test]
+    fn collects_reexports() {
+        let info = info(
+            "index.ts",
+            r#"
+export { a, b as c } from "./lib";
+export * as ns from "./ns";
+export * from "./all";
+"#,
+        );
+
+        assert_eq!(names(&info), vec!["a", "c", "ns"]);
+        assert_eq!(
+            info.export("c").unwrap().kind,
+            ExportKind::ReExport {
+                import: 0,
+                imported: Some("b".to_string())
+            }
+        );
+        assert_eq!(info.imports[0].kind, ImportKind::ReExport);
+        assert!(info.imports[1].uses_whole_module());
+        assert_eq!(info.star_reexports, vec![2]);
+    }
+
+    #[test]
+    fn collects_commonjs_requires() {
+        let info = info(
+            "app.js",
+            r#"
+const fs = require("fs");
+const { join, resolve: res } = require("path");
+const parse = require("./parser").parse;
+require("./setup");
+run(require("./plugin"));
+async function load() { return import("./lazy"); }
+"#,
+        );
+
+        let specifiers: Vec<&str> = info.imports.iter().map(|i| i.specifier.as_str()).collect();
+        assert_eq!(
+            specifiers,
+            vec!["fs", "path", "./parser", "./setup", "./plugin", "./lazy"]
+        );
+        assert!(info.imports.iter().all(|i| i.kind != ImportKind::Static));
+        assert_eq!(info.imports[0].bindings, vec![binding(None, "fs")]);
+        assert_eq!(
+            info.imports[1].bindings,
+            vec![
+                binding(Some("join"), "join"),
+                binding(Some("resolve"), "res")
+            ]
+        );
+        assert_eq!(
+            info.imports[2].bindings,
+            vec![binding(Some("parse"), "parse")]
+        );
+        assert!(info.imports[3].bindings.is_empty());
+        assert!(info.imports[4].uses_whole_module());
+        assert_eq!(info.imports[5].kind, ImportKind::Dynamic);
+    }
+
+    #[test]
+    fn collects_commonjs_exports() {
+        let info = info(
+            "lib.js",
+            r#"
+function helper() {}
+module.exports = { helper, version: "1", run() {} };
+exports.extra = 1;
+module.exports.more = helper;
+"#,
+        );
+
+        assert!(info.is_commonjs());
+        assert_eq!(
+            names(&info),
+            vec!["default", "helper", "version", "run", "extra", "more"]
+        );
+        assert_eq!(
+            info.export("more").unwrap().local.as_deref(),
+            Some("helper")
+        );
+    }
+
+    #[test]
+    fn collects_typescript_import_equals_and_export_assignment() {
+        let info = info(
+            "legacy.ts",
+            r#"
+import fs = require("fs");
+export = fs;
+"#,
+        );
+
+        assert_eq!(info.imports[0].kind, ImportKind::Require);
+        assert_eq!(info.imports[0].bindings, vec![binding(None, "fs")]);
+        assert_eq!(info.exports[0].kind, ExportKind::CommonJs);
+    }
+}
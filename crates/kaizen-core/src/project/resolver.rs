@@ -0,0 +1,514 @@
+//! Module specifier resolution following Node.js and TypeScript conventions
+//!
+//! Relative specifiers are probed with the supported extensions, then as directories
+//! through `package.json` and `index` files. Bare specifiers are looked up in
+//! `node_modules`, honouring `package.json` `exports` and `main`, and stay external
+//! unless they lead back into the project, as workspace packages do.
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Component, Path, PathBuf};
+
+use serde_json::Value;
+
+use super::module_info::ImportKind;
+
+/// Extensions tried, in order, when a specifier omits one.
+const EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+/// TypeScript sources may be imported under the extension they compile to.
+const COMPILED_EXTENSIONS: &[(&str, &[&str])] = &[
+    ("js", &["ts", "tsx"]),
+    ("jsx", &["tsx"]),
+    ("mjs", &["mts"]),
+    ("cjs", &["cts"]),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution {
+    /// A file on disk, canonicalized.
+    File(PathBuf),
+    /// A package or built-in module outside the project, by package name.
+    External(String),
+    /// A relative specifier, or an `exports` entry, that matches no file.
+    Unresolved,
+}
+
+/// What a package exposes to its consumers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageEntries {
+    /// Files named by `main`, `module`, `types`, `bin` and `exports`, canonicalized.
+    pub files: Vec<PathBuf>,
+    /// Directories exposed through `exports` subpath patterns, relative to the package.
+    pub directories: Vec<PathBuf>,
+}
+
+/// Resolves specifiers, caching the `package.json` files it reads.
+#[derive(Debug, Default)]
+pub struct Resolver {
+    manifests: HashMap<PathBuf, Option<Value>>,
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves `specifier` as imported from the file `from`.
+    pub fn resolve(&mut self, from: &Path, specifier: &str, kind: ImportKind) -> Resolution {
+        let dir = from.parent().unwrap_or(Path::new(""));
+        if is_relative(specifier) {
+            return self
+                .resolve_path(&dir.join(specifier), kind)
+                .map_or(Resolution::Unresolved, Resolution::File);
+        }
+        if specifier.starts_with("node:") {
+            return Resolution::External(specifier.to_string());
+        }
+
+        let Some((package, subpath)) = split_package(specifier) else {
+            return Resolution::External(specifier.to_string());
+        };
+        for ancestor in dir.ancestors() {
+            let package_dir = ancestor.join("node_modules").join(package);
+            if package_dir.is_dir() {
+                return self
+                    .resolve_package(&package_dir, subpath, kind)
+                    .map_or_else(
+                        || Resolution::External(package.to_string()),
+                        Resolution::File,
+                    );
+            }
+            // A package may import itself by name through its own `exports`.
+            if self.manifest(ancestor).is_some_and(|manifest| {
+                manifest["name"] == package && manifest.get("exports").is_some()
+            }) && let Some(file) = self.resolve_package(ancestor, subpath, kind)
+            {
+                return Resolution::File(file);
+            }
+        }
+        Resolution::External(package.to_string())
+    }
+
+    /// The directory of the nearest `package.json` above `file`.
+    pub fn package_dir(&mut self, file: &Path) -> Option<PathBuf> {
+        file.ancestors()
+            .skip(1)
+            .find(|dir| self.manifest(dir).is_some())
+            .map(Path::to_path_buf)
+    }
+
+    /// Lists the entry points declared by the `package.json` in `package_dir`.
+    pub fn package_entries(&mut self, package_dir: &Path) -> PackageEntries {
+        let Some(manifest) = self.manifest(package_dir).cloned() else {
+            return PackageEntries::default();
+        };
+
+        let mut targets = Vec::new();
+        for field in ["main", "module", "types", "typings", "bin", "exports"] {
+            if let Some(value) = manifest.get(field) {
+                collect_targets(value, &mut targets);
+            }
+        }
+
+        let mut entries = PackageEntries::default();
+        for target in targets {
+            if let Some((prefix, _)) = target.split_once('*') {
+                let directory = prefix.rsplit_once('/').map_or("", |(dir, _)| dir);
+                entries
+                    .directories
+                    .push(normalize(&PathBuf::from(directory)));
+            } else if let Some(file) =
+                self.resolve_path(&package_dir.join(&target), ImportKind::Static)
+                && !entries.files.contains(&file)
+            {
+                entries.files.push(file);
+            }
+        }
+        entries
+    }
+
+    /// Resolves `subpath` (empty for the package itself) inside `package_dir`.
+    fn resolve_package(
+        &mut self,
+        package_dir: &Path,
+        subpath: &str,
+        kind: ImportKind,
+    ) -> Option<PathBuf> {
+        let manifest = self.manifest(package_dir).cloned();
+        if let Some(exports) = manifest.as_ref().and_then(|m| m.get("exports")) {
+            let key = if subpath.is_empty() {
+                ".".to_string()
+            } else {
+                format!("./{}", subpath)
+            };
+            let target = match_exports(exports, &key, conditions(kind))?;
+            return self.resolve_path(&package_dir.join(target), kind);
+        }
+
+        if subpath.is_empty() {
+            self.resolve_directory(package_dir, kind)
+        } else {
+            self.resolve_path(&package_dir.join(subpath), kind)
+        }
+    }
+
+    /// Tries `path` as a file, with each extension, then as a directory.
+    fn resolve_path(&mut self, path: &Path, kind: ImportKind) -> Option<PathBuf> {
+        let path = normalize(path);
+        if path.is_file() {
+            return canonical(&path);
+        }
+        if let Some(file) = probe_extensions(&path) {
+            return Some(file);
+        }
+        if let Some(extension) = path.extension().and_then(|e| e.to_str())
+            && let Some((_, sources)) = COMPILED_EXTENSIONS.iter().find(|(e, _)| *e == extension)
+        {
+            for source in *sources {
+                let candidate = path.with_extension(source);
+                if candidate.is_file() {
+                    return canonical(&candidate);
+                }
+            }
+        }
+        if path.is_dir() {
+            return self.resolve_directory(&path, kind);
+        }
+        None
+    }
+
+    fn resolve_directory(&mut self, dir: &Path, kind: ImportKind) -> Option<PathBuf> {
+        let manifest = self.manifest(dir).cloned();
+        if let Some(manifest) = &manifest {
+            let entry = match manifest.get("exports") {
+                Some(exports) => match_exports(exports, ".", conditions(kind)),
+                None => manifest
+                    .get("main")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+            };
+            if let Some(entry) = entry
+                && let Some(file) = self.resolve_file_or_index(&dir.join(entry))
+            {
+                return Some(file);
+            }
+        }
+        probe_extensions(&dir.join("index"))
+    }
+
+    /// Like [`Resolver::resolve_path`] without consulting `package.json` again.
+    fn resolve_file_or_index(&self, path: &Path) -> Option<PathBuf> {
+        let path = normalize(path);
+        if path.is_file() {
+            return canonical(&path);
+        }
+        probe_extensions(&path).or_else(|| probe_extensions(&path.join("index")))
+    }
+
+    fn manifest(&mut self, dir: &Path) -> Option<&Value> {
+        self.manifests
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| {
+                let content = std::fs::read_to_string(dir.join("package.json")).ok()?;
+                serde_json::from_str(&content).ok()
+            })
+            .as_ref()
+    }
+}
+
+fn is_relative(specifier: &str) -> bool {
+    specifier == "."
+        || specifier == ".."
+        || specifier.starts_with("./")
+        || specifier.starts_with("../")
+        || specifier.starts_with('/')
+}
+
+/// Splits `@scope/name/sub/path` into the package name and the subpath.
+fn split_package(specifier: &str) -> Option<(&str, &str)> {
+    let mut end = specifier.find('/').unwrap_or(specifier.len());
+    if specifier.starts_with('@') {
+        let rest = specifier.get(end + 1..)?;
+        end += 1 + rest.find('/').unwrap_or(rest.len());
+    }
+    let (package, subpath) = specifier.split_at(end);
+    (!package.is_empty()).then(|| (package, subpath.trim_start_matches('/')))
+}
+
+/// Conditions checked in `exports`, most specific first.
+fn conditions(kind: ImportKind) -> &'static [&'static str] {
+    match kind {
+        ImportKind::Require => &["require", "node", "default"],
+        _ => &["import", "module", "node", "default"],
+    }
+}
+
+/// Finds the target of `key` (`"."` or `"./sub"`) in a `package.json` `exports` field.
+fn match_exports(exports: &Value, key: &str, conditions: &[&str]) -> Option<String> {
+    let is_subpath_map = exports
+        .as_object()
+        .is_some_and(|map| map.keys().any(|k| k.starts_with('.')));
+    if !is_subpath_map {
+        return (key == ".")
+            .then(|| export_target(exports, conditions, None))
+            .flatten();
+    }
+    let map = exports.as_object()?;
+
+    if let Some(target) = map.get(key) {
+        return export_target(target, conditions, None);
+    }
+    // Patterns such as `"./features/*"`; the longest matching prefix wins.
+    let (pattern, star) = map
+        .keys()
+        .filter_map(|pattern| {
+            let (prefix, suffix) = pattern.split_once('*')?;
+            let star = key.strip_prefix(prefix)?.strip_suffix(suffix)?;
+            Some((pattern, star))
+        })
+        .max_by_key(|(pattern, _)| pattern.len())?;
+    export_target(&map[pattern.as_str()], conditions, Some(star))
+}
+
+fn export_target(target: &Value, conditions: &[&str], star: Option<&str>) -> Option<String> {
+    match target {
+        Value::String(path) => {
+            let path = match star {
+                Some(star) => path.replace('*', star),
+                None => path.clone(),
+            };
+            path.starts_with("./").then_some(path)
+        }
+        Value::Array(targets) => targets
+            .iter()
+            .find_map(|target| export_target(target, conditions, star)),
+        Value::Object(map) => conditions
+            .iter()
+            .filter_map(|condition| map.get(*condition))
+            .find_map(|target| export_target(target, conditions, star)),
+        _ => None,
+    }
+}
+
+/// Collects every path in a `package.json` field, whatever the conditions or subpaths.
+fn collect_targets(value: &Value, targets: &mut Vec<String>) {
+    match value {
+        Value::String(path) => targets.push(path.clone()),
+        Value::Array(values) => values.iter().for_each(|v| collect_targets(v, targets)),
+        Value::Object(map) => map.values().for_each(|v| collect_targets(v, targets)),
+        _ => {}
+    }
+}
+
+fn probe_extensions(path: &Path) -> Option<PathBuf> {
+    EXTENSIONS.iter().find_map(|extension| {
+        let mut candidate = OsString::from(path.as_os_str());
+        candidate.push(".");
+        candidate.push(extension);
+        let candidate = PathBuf::from(candidate);
+        candidate.is_file().then(|| canonical(&candidate)).flatten()
+    })
+}
+
+fn canonical(path: &Path) -> Option<PathBuf> {
+    path.canonicalize().ok()
+}
+
+/// Removes `.` and `..` components without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn resolve(root: &Path, from: &str, specifier: &str) -> Resolution {
+        Resolver::new().resolve(&root.join(from), specifier, ImportKind::Static)
+    }
+
+    fn file(root: &Path, path: &str) -> Resolution {
+        Resolution::File(root.join(path).canonicalize().unwrap())
+    }
+
+    #[test]
+    fn splits_package_names() {
+        assert_eq!(split_package("lodash"), Some(("lodash", "")));
+        assert_eq!(split_package("lodash/fp/map"), Some(("lodash", "fp/map")));
+        assert_eq!(split_package("@scope/pkg"), Some(("@scope/pkg", "")));
+        assert_eq!(split_package("@scope/pkg/sub"), Some(("@scope/pkg", "sub")));
+    }
+
+    #[test]
+    fn probes_extensions_and_index_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "src/app.ts", "");
+        write(root, "src/util.ts", "");
+        write(root, "src/helpers.mjs", "");
+        write(root, "src/components/index.tsx", "");
+
+        assert_eq!(
+            resolve(root, "src/app.ts", "./util"),
+            file(root, "src/util.ts")
+        );
+        assert_eq!(
+            resolve(root, "src/app.ts", "./util.js"),
+            file(root, "src/util.ts")
+        );
+        assert_eq!(
+            resolve(root, "src/app.ts", "./helpers"),
+            file(root, "src/helpers.mjs")
+        );
+        assert_eq!(
+            resolve(root, "src/app.ts", "./components"),
+            file(root, "src/components/index.tsx")
+        );
+        assert_eq!(
+            resolve(root, "src/components/index.tsx", "../util"),
+            file(root, "src/util.ts")
+        );
+        assert_eq!(
+            resolve(root, "src/app.ts", "./missing"),
+            Resolution::Unresolved
+        );
+    }
+
+    #[test]
+    fn uses_package_json_main_for_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "lib/package.json", r#"{ "main": "./entry.js" }"#);
+        write(root, "lib/entry.js", "");
+        write(root, "lib/index.js", "");
+
+        assert_eq!(resolve(root, "app.js", "./lib"), file(root, "lib/entry.js"));
+    }
+
+    #[test]
+    fn resolves_packages_through_exports() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "node_modules/pkg/package.json",
+            r#"{
+                "exports": {
+                    ".": { "require": "./cjs/index.cjs", "import": "./esm/index.js" },
+                    "./features/*": "./src/features/*.js"
+                }
+            }"#,
+        );
+        write(root, "node_modules/pkg/cjs/index.cjs", "");
+        write(root, "node_modules/pkg/esm/index.js", "");
+        write(root, "node_modules/pkg/src/features/a.js", "");
+
+        let mut resolver = Resolver::new();
+        let from = root.join("src/app.js");
+        assert_eq!(
+            resolver.resolve(&from, "pkg", ImportKind::Static),
+            file(root, "node_modules/pkg/esm/index.js")
+        );
+        assert_eq!(
+            resolver.resolve(&from, "pkg", ImportKind::Require),
+            file(root, "node_modules/pkg/cjs/index.cjs")
+        );
+        assert_eq!(
+            resolver.resolve(&from, "pkg/features/a", ImportKind::Static),
+            file(root, "node_modules/pkg/src/features/a.js")
+        );
+        assert_eq!(
+            resolver.resolve(&from, "pkg/private", ImportKind::Static),
+            Resolution::External("pkg".to_string())
+        );
+    }
+
+    #[test]
+    fn bare_specifiers_without_packages_are_external() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        assert_eq!(
+            resolve(root, "app.js", "react/jsx-runtime"),
+            Resolution::External("react".to_string())
+        );
+        assert_eq!(
+            resolve(root, "app.js", "node:fs"),
+            Resolution::External("node:fs".to_string())
+        );
+    }
+
+    #[test]
+    fn packages_can_import_themselves_by_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "package.json",
+            r#"{ "name": "@acme/app", "exports": { "./utils": "./src/utils.ts" } }"#,
+        );
+        write(root, "src/utils.ts", "");
+
+        assert_eq!(
+            resolve(root, "src/app.ts", "@acme/app/utils"),
+            file(root, "src/utils.ts")
+        );
+    }
+
+    #[test]
+    fn lists_package_entry_points() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "package.json",
+            r#"{
+                "main": "./src/index.js",
+                "bin": { "tool": "./bin/tool.js" },
+                "exports": {
+                    ".": { "import": "./src/index.js", "types": "./src/types.ts" },
+                    "./features/*": "./src/features/*.js"
+                }
+            }"#,
+        );
+        write(root, "src/index.ts", "");
+        write(root, "src/types.ts", "");
+        write(root, "bin/tool.js", "");
+        write(root, "src/app/main.ts", "");
+
+        let mut resolver = Resolver::new();
+        let package_dir = resolver.package_dir(&root.join("src/app/main.ts")).unwrap();
+        assert_eq!(package_dir, root);
+
+        let entries = resolver.package_entries(&package_dir);
+        let canonical = |path: &str| root.join(path).canonicalize().unwrap();
+        assert_eq!(
+            entries.files,
+            vec![
+                canonical("src/index.ts"),
+                canonical("bin/tool.js"),
+                canonical("src/types.ts"),
+            ]
+        );
+        assert_eq!(entries.directories, vec![PathBuf::from("src/features")]);
+    }
+}
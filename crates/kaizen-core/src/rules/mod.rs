@@ -3,16 +3,24 @@
 //! Provides quality and security rules for analyzing JavaScript/TypeScript code.
 
 pub mod helpers;
+pub mod options;
 pub mod quality;
 pub mod security;
 
-use crate::config::RulesConfig;
+pub use options::RuleOptions;
+
+use crate::config::{Config, RulesConfig, TaintConfig};
 use crate::diagnostic::Diagnostic;
+use crate::globs::GlobMatcher;
 use crate::licensing::PremiumTier;
 use crate::parser::ParsedFile;
+use crate::project::Project;
+use crate::taint::TaintAnalyzer;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Severity {
     Error,
     Warning,
@@ -20,7 +28,7 @@ pub enum Severity {
     Hint,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub enum Confidence {
     #[default]
     High,
@@ -44,6 +52,17 @@ pub enum RuleCategory {
     Security,
 }
 
+/// Whether a rule looks at one file at a time or at the whole project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RuleScope {
+    #[default]
+    File,
+    Project,
+    /// Checks each file on its own, and also the whole project for findings that span
+    /// files.
+    FileAndProject,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RuleMetadata {
     pub id: &'static str,
@@ -59,6 +78,30 @@ pub struct RuleMetadata {
 pub trait Rule: Send + Sync {
     fn metadata(&self) -> &RuleMetadata;
     fn check(&self, file: &ParsedFile) -> Vec<Diagnostic>;
+
+    /// Returns a copy of this rule configured from its `[rules.options.<rule>]` table.
+    ///
+    /// Rules without options keep the default, which leaves every key unread so
+    /// that it is reported as unknown.
+    fn with_options(&self, _options: &mut RuleOptions) -> Option<Box<dyn Rule>> {
+        None
+    }
+
+    /// Returns a copy of this rule that runs taint analysis with `analyzer`, for
+    /// rules built on it.
+    fn with_taint_analyzer(&self, _analyzer: &Arc<TaintAnalyzer>) -> Option<Box<dyn Rule>> {
+        None
+    }
+
+    fn scope(&self) -> RuleScope {
+        RuleScope::File
+    }
+
+    /// Checks every analyzed file at once, for rules with [`RuleScope::Project`] or
+    /// [`RuleScope::FileAndProject`]. [`Rule::check`] is never called for the former.
+    fn check_project(&self, _project: &Project) -> Vec<Diagnostic> {
+        Vec::new()
+    }
 }
 
 pub struct RuleRegistry {
@@ -68,6 +111,27 @@ pub struct RuleRegistry {
     quality_enabled: bool,
     security_enabled: bool,
     current_tier: PremiumTier,
+    overrides: Vec<RuleOverride>,
+}
+
+/// A resolved `[[overrides]]` block.
+struct RuleOverride {
+    matcher: GlobMatcher,
+    disabled: HashSet<String>,
+    enabled: HashSet<String>,
+    severity: HashMap<String, Severity>,
+    /// Rules configured with this block's options, keyed by index into `RuleRegistry::rules`.
+    configured_rules: HashMap<usize, Box<dyn Rule>>,
+}
+
+fn contains_rule(set: &HashSet<String>, metadata: &RuleMetadata) -> bool {
+    set.contains(metadata.id) || set.contains(metadata.name)
+}
+
+fn rule_severity(map: &HashMap<String, Severity>, metadata: &RuleMetadata) -> Option<Severity> {
+    map.get(metadata.id)
+        .or_else(|| map.get(metadata.name))
+        .copied()
 }
 
 impl RuleRegistry {
@@ -79,6 +143,7 @@ impl RuleRegistry {
             quality_enabled: true,
             security_enabled: true,
             current_tier: PremiumTier::Free,
+            overrides: Vec::new(),
         }
     }
 
@@ -90,7 +155,22 @@ impl RuleRegistry {
         self.rules.push(rule);
     }
 
-    pub fn configure(&mut self, config: &RulesConfig) {
+    /// Makes the taint rules use the sources, sinks and sanitizers of `config`
+    /// along with the built-in ones.
+    pub fn configure_taint(&mut self, config: &TaintConfig) {
+        if *config == TaintConfig::default() {
+            return;
+        }
+        let analyzer = Arc::new(TaintAnalyzer::with_config(config));
+        for rule in &mut self.rules {
+            if let Some(configured) = rule.with_taint_analyzer(&analyzer) {
+                *rule = configured;
+            }
+        }
+    }
+
+    /// Applies `config` to the registry and returns warnings about rule options.
+    pub fn configure(&mut self, config: &RulesConfig) -> Vec<String> {
         self.disabled_rules.clear();
         self.severity_overrides.clear();
 
@@ -105,6 +185,121 @@ impl RuleRegistry {
 
         self.quality_enabled = config.quality.unwrap_or(true);
         self.security_enabled = config.security.unwrap_or(true);
+
+        self.configure_options(config)
+    }
+
+    fn configure_options(&mut self, config: &RulesConfig) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        let mut rule_refs: Vec<&String> = config.options.keys().collect();
+        rule_refs.sort();
+
+        for rule_ref in rule_refs {
+            let Some(index) = self.rule_index(rule_ref) else {
+                warnings.push(format!("Unknown rule in [rules.options]: '{}'", rule_ref));
+                continue;
+            };
+
+            let mut options = RuleOptions::new(rule_ref, &config.options[rule_ref]);
+            if let Some(configured) = self.rules[index].with_options(&mut options) {
+                self.rules[index] = configured;
+            }
+            warnings.extend(options.finish());
+        }
+
+        warnings
+    }
+
+    /// Resolves the `[[overrides]]` blocks of `config` and returns warnings about them.
+    ///
+    /// Options set in an override are layered over the rule's `[rules.options]` table.
+    /// When several matching overrides set options for the same rule, the last one wins.
+    pub fn configure_overrides(&mut self, config: &Config) -> Vec<String> {
+        let mut warnings = Vec::new();
+        self.overrides.clear();
+
+        for (position, block) in config.overrides.iter().enumerate() {
+            if block.files.is_empty() {
+                warnings.push(format!(
+                    "[[overrides]] block {} has no 'files' patterns and is ignored",
+                    position + 1
+                ));
+                continue;
+            }
+
+            let matcher = match GlobMatcher::new(&block.files, config.root.as_deref()) {
+                Ok(matcher) => matcher,
+                Err(e) => {
+                    warnings.push(format!("Invalid pattern in [[overrides]]: {}", e));
+                    continue;
+                }
+            };
+
+            let mut rule_refs: Vec<&String> = block.options.keys().collect();
+            rule_refs.sort();
+
+            let mut configured_rules = HashMap::new();
+            for rule_ref in rule_refs {
+                let Some(index) = self.rule_index(rule_ref) else {
+                    warnings.push(format!(
+                        "Unknown rule in [[overrides]] options: '{}'",
+                        rule_ref
+                    ));
+                    continue;
+                };
+
+                let table = &block.options[rule_ref];
+                // Validate the override's own table so that warnings name only its keys
+                let mut options = RuleOptions::new(rule_ref, table);
+                let _ = self.rules[index].with_options(&mut options);
+                warnings.extend(options.finish());
+
+                let mut merged = self.base_options(config, index);
+                merged.extend(table.iter().map(|(k, v)| (k.clone(), v.clone())));
+                let mut options = RuleOptions::new(rule_ref, &merged);
+                if let Some(configured) = self.rules[index].with_options(&mut options) {
+                    configured_rules.insert(index, configured);
+                }
+            }
+
+            self.overrides.push(RuleOverride {
+                matcher,
+                disabled: block.disabled.iter().cloned().collect(),
+                enabled: block.enabled.iter().cloned().collect(),
+                severity: block
+                    .severity
+                    .iter()
+                    .map(|(rule_ref, value)| (rule_ref.clone(), (*value).into()))
+                    .collect(),
+                configured_rules,
+            });
+        }
+
+        warnings
+    }
+
+    fn rule_index(&self, id_or_name: &str) -> Option<usize> {
+        self.rules
+            .iter()
+            .position(|rule| rule.metadata().id == id_or_name || rule.metadata().name == id_or_name)
+    }
+
+    fn base_options(&self, config: &Config, index: usize) -> toml::Table {
+        config
+            .rules
+            .options
+            .iter()
+            .find(|(rule_ref, _)| self.rule_index(rule_ref) == Some(index))
+            .map(|(_, table)| table.clone())
+            .unwrap_or_default()
+    }
+
+    fn matching_overrides(&self, filename: &str) -> Vec<&RuleOverride> {
+        self.overrides
+            .iter()
+            .filter(|o| o.matcher.is_match(filename))
+            .collect()
     }
 
     pub fn rules(&self) -> impl Iterator<Item = &dyn Rule> {
@@ -112,18 +307,91 @@ impl RuleRegistry {
     }
 
     pub fn run_all(&self, file: &ParsedFile) -> Vec<Diagnostic> {
+        let overrides = self.matching_overrides(&file.metadata().filename);
+
         self.rules
             .iter()
-            .filter(|rule| self.should_run_rule(rule.as_ref()))
-            .flat_map(|rule| {
+            .enumerate()
+            .filter(|(_, rule)| rule.scope() != RuleScope::Project)
+            .filter(|(_, rule)| self.should_run_rule_with(rule.as_ref(), &overrides))
+            .flat_map(|(index, rule)| {
+                let rule = overrides
+                    .iter()
+                    .rev()
+                    .find_map(|o| o.configured_rules.get(&index))
+                    .unwrap_or(rule);
                 let mut diagnostics = rule.check(file);
-                self.apply_severity_overrides(rule.as_ref(), &mut diagnostics);
+                self.apply_severity_overrides(rule.as_ref(), &overrides, &mut diagnostics);
                 diagnostics
             })
             .collect()
     }
 
+    /// True if any project rule may run, so that building a [`Project`] is worthwhile.
+    pub fn has_project_rules(&self) -> bool {
+        self.project_rules().next().is_some()
+    }
+
+    /// Runs the project rules that are enabled for some file. Each diagnostic is then kept
+    /// or dropped, and its severity set, by the settings for the file it is reported in;
+    /// options from `[[overrides]]` do not apply since the rule sees all files at once.
+    pub fn run_project(&self, project: &Project) -> Vec<Diagnostic> {
+        self.project_rules()
+            .flat_map(|rule| {
+                rule.check_project(project)
+                    .into_iter()
+                    .flat_map(|diagnostic| {
+                        let file = diagnostic.file.clone();
+                        self.report(rule.metadata().id, &file, vec![diagnostic])
+                    })
+            })
+            .collect()
+    }
+
+    fn project_rules(&self) -> impl Iterator<Item = &dyn Rule> {
+        self.rules
+            .iter()
+            .map(|rule| rule.as_ref())
+            .filter(|rule| rule.scope() != RuleScope::File)
+            .filter(|rule| {
+                self.should_run_rule(*rule)
+                    || self
+                        .overrides
+                        .iter()
+                        .any(|o| contains_rule(&o.enabled, rule.metadata()))
+            })
+    }
+
+    /// Returns true if `rule` runs for `filename`, taking `[[overrides]]` into account.
+    pub fn is_rule_active_for(&self, rule: &dyn Rule, filename: &str) -> bool {
+        self.should_run_rule_with(rule, &self.matching_overrides(filename))
+    }
+
+    /// Applies the enablement and severity settings of `rule_id` to diagnostics produced
+    /// outside [`Rule::check`]; returns nothing when the rule is off for `filename`.
+    pub fn report(
+        &self,
+        rule_id: &str,
+        filename: &str,
+        mut diagnostics: Vec<Diagnostic>,
+    ) -> Vec<Diagnostic> {
+        let Some(rule) = self.get_rule(rule_id) else {
+            return Vec::new();
+        };
+        let overrides = self.matching_overrides(filename);
+        if !self.should_run_rule_with(rule, &overrides) {
+            return Vec::new();
+        }
+
+        self.apply_severity_overrides(rule, &overrides, &mut diagnostics);
+        diagnostics
+    }
+
     fn should_run_rule(&self, rule: &dyn Rule) -> bool {
+        self.should_run_rule_with(rule, &[])
+    }
+
+    fn should_run_rule_with(&self, rule: &dyn Rule, overrides: &[&RuleOverride]) -> bool {
         let metadata = rule.metadata();
 
         if metadata.min_tier.level() > self.current_tier.level() {
@@ -137,24 +405,35 @@ impl RuleRegistry {
             return false;
         }
 
-        !self.is_rule_disabled(metadata)
-    }
+        let mut disabled = contains_rule(&self.disabled_rules, metadata);
+        for o in overrides {
+            if contains_rule(&o.disabled, metadata) {
+                disabled = true;
+            } else if contains_rule(&o.enabled, metadata) {
+                disabled = false;
+            }
+        }
 
-    fn is_rule_disabled(&self, metadata: &RuleMetadata) -> bool {
-        self.disabled_rules.contains(metadata.id) || self.disabled_rules.contains(metadata.name)
+        !disabled
     }
 
-    fn apply_severity_overrides(&self, rule: &dyn Rule, diagnostics: &mut [Diagnostic]) {
+    fn apply_severity_overrides(
+        &self,
+        rule: &dyn Rule,
+        overrides: &[&RuleOverride],
+        diagnostics: &mut [Diagnostic],
+    ) {
         let metadata = rule.metadata();
 
-        let override_severity = self
-            .severity_overrides
-            .get(metadata.id)
-            .or_else(|| self.severity_overrides.get(metadata.name));
+        let override_severity = overrides
+            .iter()
+            .rev()
+            .find_map(|o| rule_severity(&o.severity, metadata))
+            .or_else(|| rule_severity(&self.severity_overrides, metadata));
 
         if let Some(severity) = override_severity {
             for diag in diagnostics.iter_mut() {
-                diag.severity = *severity;
+                diag.severity = severity;
             }
         }
     }
@@ -201,6 +480,41 @@ impl Default for RuleRegistry {
 
 #[macro_export]
 macro_rules! declare_rule {
+    (
+        $name:ident,
+        id = $id:literal,
+        name = $rule_name:literal,
+        description = $desc:literal,
+        category = $cat:ident,
+        severity = $sev:ident
+        $(, min_tier = $tier:ident)?
+        $(, docs_url = $url:literal)?
+        $(, examples = $examples:literal)?
+        , options = $options:ty
+    ) => {
+        pub struct $name {
+            metadata: $crate::rules::RuleMetadata,
+            options: $options,
+        }
+
+        impl $name {
+            pub fn new() -> Self {
+                Self {
+                    metadata: declare_rule!(
+                        @metadata $id, $rule_name, $desc, $cat, $sev,
+                        [$($tier)?], [$($url)?], [$($examples)?]
+                    ),
+                    options: <$options>::default(),
+                }
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    };
     (
         $name:ident,
         id = $id:literal,
@@ -219,16 +533,10 @@ macro_rules! declare_rule {
         impl $name {
             pub fn new() -> Self {
                 Self {
-                    metadata: $crate::rules::RuleMetadata {
-                        id: $id,
-                        name: $rule_name,
-                        description: $desc,
-                        category: $crate::rules::RuleCategory::$cat,
-                        severity: $crate::rules::Severity::$sev,
-                        min_tier: declare_rule!(@min_tier $($tier)?),
-                        docs_url: declare_rule!(@docs_url $($url)?),
-                        examples: declare_rule!(@examples $($examples)?),
-                    },
+                    metadata: declare_rule!(
+                        @metadata $id, $rule_name, $desc, $cat, $sev,
+                        [$($tier)?], [$($url)?], [$($examples)?]
+                    ),
                 }
             }
         }
@@ -239,6 +547,21 @@ macro_rules! declare_rule {
             }
         }
     };
+    (
+        @metadata $id:literal, $rule_name:literal, $desc:literal, $cat:ident, $sev:ident,
+        [$($tier:ident)?], [$($url:literal)?], [$($examples:literal)?]
+    ) => {
+        $crate::rules::RuleMetadata {
+            id: $id,
+            name: $rule_name,
+            description: $desc,
+            category: $crate::rules::RuleCategory::$cat,
+            severity: $crate::rules::Severity::$sev,
+            min_tier: declare_rule!(@min_tier $($tier)?),
+            docs_url: declare_rule!(@docs_url $($url)?),
+            examples: declare_rule!(@examples $($examples)?),
+        }
+    };
     (@min_tier $tier:ident) => { $crate::licensing::PremiumTier::$tier };
     (@min_tier) => { $crate::licensing::PremiumTier::Free };
     (@docs_url $url:literal) => { Some($url) };
@@ -254,6 +577,7 @@ mod tests {
     struct TestRule {
         metadata: RuleMetadata,
         diagnostics_to_return: Vec<Diagnostic>,
+        scope: RuleScope,
     }
 
     impl TestRule {
@@ -270,6 +594,7 @@ mod tests {
                     examples: None,
                 },
                 diagnostics_to_return: Vec::new(),
+                scope: RuleScope::File,
             }
         }
 
@@ -292,6 +617,11 @@ mod tests {
             self.diagnostics_to_return.push(diagnostic);
             self
         }
+
+        fn with_scope(mut self, scope: RuleScope) -> Self {
+            self.scope = scope;
+            self
+        }
     }
 
     impl Rule for TestRule {
@@ -302,6 +632,14 @@ mod tests {
         fn check(&self, _file: &ParsedFile) -> Vec<Diagnostic> {
             self.diagnostics_to_return.clone()
         }
+
+        fn scope(&self) -> RuleScope {
+            self.scope
+        }
+
+        fn check_project(&self, _project: &Project) -> Vec<Diagnostic> {
+            self.diagnostics_to_return.clone()
+        }
     }
 
     #[test]
@@ -573,6 +911,273 @@ mod tests {
         );
     }
 
+    #[test]
+    fn configure_replaces_rule_with_configured_copy() {
+        use crate::config::RulesConfig;
+        use crate::rules::quality::MaxComplexity;
+
+        let mut registry = RuleRegistry::new();
+        registry.register(Box::new(MaxComplexity::new()));
+
+        let code = "function f() { if (a) {} if (b) {} }";
+        let file = ParsedFile::from_source("test.js", code);
+        assert!(registry.run_all(&file).is_empty());
+
+        let mut config = RulesConfig::default();
+        config
+            .options
+            .insert("Q010".to_string(), "threshold = 2".parse().unwrap());
+        let warnings = registry.configure(&config);
+
+        assert!(warnings.is_empty());
+        assert_eq!(registry.run_all(&file).len(), 1);
+    }
+
+    #[test]
+    fn options_for_rule_without_options_are_unknown() {
+        use crate::config::RulesConfig;
+
+        let mut registry = RuleRegistry::new();
+        registry.register(Box::new(TestRule::new("Q032").with_name("no-console")));
+
+        let mut config = RulesConfig::default();
+        config
+            .options
+            .insert("no-console".to_string(), "allow = []".parse().unwrap());
+        let warnings = registry.configure(&config);
+
+        assert_eq!(
+            warnings,
+            vec!["Unknown option 'allow' for rule 'no-console' in [rules.options]"]
+        );
+    }
+
+    fn override_config(toml_source: &str) -> Config {
+        toml::from_str(toml_source).unwrap()
+    }
+
+    fn console_rule() -> TestRule {
+        let diag = Diagnostic::new("Q032", Severity::Info, "console detected", "test.js", 1, 0);
+        TestRule::new("Q032")
+            .with_name("no-console")
+            .with_diagnostic(diag)
+    }
+
+    #[test]
+    fn override_disables_rule_for_matching_files() {
+        let mut registry = RuleRegistry::new();
+        registry.register(Box::new(console_rule()));
+
+        let config = override_config(
+            r#"
+[[overrides]]
+files = ["scripts/**"]
+disabled = ["no-console"]
+"#,
+        );
+        let warnings = registry.configure_overrides(&config);
+
+        assert!(warnings.is_empty());
+        let script = ParsedFile::from_source("scripts/build.js", "");
+        let source = ParsedFile::from_source("src/app.js", "");
+        assert!(registry.run_all(&script).is_empty());
+        assert_eq!(registry.run_all(&source).len(), 1);
+    }
+
+    #[test]
+    fn override_severity_applies_to_matching_files() {
+        let mut registry = RuleRegistry::new();
+        registry.register(Box::new(console_rule()));
+
+        let config = override_config(
+            r#"
+[rules.severity]
+no-console = "error"
+
+[[overrides]]
+files = ["tests/fixtures/**"]
+
+[overrides.severity]
+Q032 = "hint"
+"#,
+        );
+        registry.configure(&config.rules);
+        registry.configure_overrides(&config);
+
+        let fixture = ParsedFile::from_source("tests/fixtures/a.js", "");
+        let source = ParsedFile::from_source("src/app.js", "");
+        assert_eq!(registry.run_all(&fixture)[0].severity, Severity::Hint);
+        assert_eq!(registry.run_all(&source)[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn later_overrides_take_precedence() {
+        let mut registry = RuleRegistry::new();
+        registry.register(Box::new(console_rule()));
+
+        let config = override_config(
+            r#"
+[[overrides]]
+files = ["scripts/**"]
+disabled = ["no-console"]
+
+[[overrides]]
+files = ["scripts/release/**"]
+enabled = ["no-console"]
+"#,
+        );
+        registry.configure_overrides(&config);
+
+        let script = ParsedFile::from_source("scripts/build.js", "");
+        let release = ParsedFile::from_source("scripts/release/publish.js", "");
+        assert!(registry.run_all(&script).is_empty());
+        assert_eq!(registry.run_all(&release).len(), 1);
+    }
+
+    fn project_rule() -> TestRule {
+        TestRule::new("P001")
+            .with_name("project-rule")
+            .with_scope(RuleScope::Project)
+            .with_diagnostic(Diagnostic::new(
+                "P001",
+                Severity::Warning,
+                "a",
+                "src/a.js",
+                1,
+                1,
+            ))
+            .with_diagnostic(Diagnostic::new(
+                "P001",
+                Severity::Warning,
+                "b",
+                "legacy/b.js",
+                1,
+                1,
+            ))
+    }
+
+    #[test]
+    fn project_rules_only_run_over_the_project() {
+        let mut registry = RuleRegistry::new();
+        registry.register(Box::new(project_rule()));
+
+        let file = ParsedFile::from_source("src/a.js", "");
+        assert!(registry.has_project_rules());
+        assert!(registry.run_all(&file).is_empty());
+        assert_eq!(registry.run_project(&Project::new(Vec::new())).len(), 2);
+
+        registry.configure(&RulesConfig {
+            disabled: vec!["project-rule".to_string()],
+            ..Default::default()
+        });
+        assert!(!registry.has_project_rules());
+        assert!(registry.run_project(&Project::new(Vec::new())).is_empty());
+    }
+
+    #[test]
+    fn file_and_project_rules_run_in_both_passes() {
+        let mut registry = RuleRegistry::new();
+        registry.register(Box::new(
+            project_rule().with_scope(RuleScope::FileAndProject),
+        ));
+
+        let file = ParsedFile::from_source("src/a.js", "");
+        assert!(registry.has_project_rules());
+        assert_eq!(registry.run_all(&file).len(), 2);
+        assert_eq!(registry.run_project(&Project::new(Vec::new())).len(), 2);
+    }
+
+    #[test]
+    fn project_rule_diagnostics_follow_overrides_of_their_file() {
+        let mut registry = RuleRegistry::new();
+        registry.register(Box::new(project_rule()));
+
+        let config = override_config(
+            r#"
+[rules]
+disabled = ["P001"]
+
+[[overrides]]
+files = ["legacy/**"]
+enabled = ["P001"]
+
+[overrides.severity]
+P001 = "hint"
+"#,
+        );
+        registry.configure(&config.rules);
+        registry.configure_overrides(&config);
+
+        assert!(registry.has_project_rules());
+        let diagnostics = registry.run_project(&Project::new(Vec::new()));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file, "legacy/b.js");
+        assert_eq!(diagnostics[0].severity, Severity::Hint);
+    }
+
+    #[test]
+    fn override_options_layer_over_base_options() {
+        use crate::rules::quality::MaxComplexity;
+
+        let mut registry = RuleRegistry::new();
+        registry.register(Box::new(MaxComplexity::new()));
+
+        let config = override_config(
+            r#"
+[rules.options.max-complexity]
+threshold = 1
+
+[[overrides]]
+files = ["legacy/**"]
+
+[overrides.options.Q010]
+threshold = 5
+"#,
+        );
+        assert!(registry.configure(&config.rules).is_empty());
+        assert!(registry.configure_overrides(&config).is_empty());
+
+        let code = "function f() { if (a) {} if (b) {} }";
+        let legacy = ParsedFile::from_source("legacy/old.js", code);
+        let source = ParsedFile::from_source("src/new.js", code);
+        assert!(registry.run_all(&legacy).is_empty());
+        assert_eq!(registry.run_all(&source).len(), 1);
+    }
+
+    #[test]
+    fn override_warnings_are_reported() {
+        use crate::rules::quality::MaxComplexity;
+
+        let mut registry = RuleRegistry::new();
+        registry.register(Box::new(MaxComplexity::new()));
+
+        let config = override_config(
+            r#"
+[[overrides]]
+disabled = ["max-complexity"]
+
+[[overrides]]
+files = ["src/["]
+
+[[overrides]]
+files = ["legacy/**"]
+
+[overrides.options.max-complexity]
+limit = 5
+
+[overrides.options.unknown-rule]
+threshold = 5
+"#,
+        );
+        let warnings = registry.configure_overrides(&config);
+
+        assert_eq!(warnings.len(), 4);
+        assert!(warnings[0].contains("block 1 has no 'files'"));
+        assert!(warnings[1].starts_with("Invalid pattern in [[overrides]]"));
+        assert!(warnings[2].contains("Unknown option 'limit'"));
+        assert!(warnings[3].contains("'unknown-rule'"));
+    }
+
     #[test]
     fn all_rules_active_by_default() {
         use crate::config::RulesConfig;
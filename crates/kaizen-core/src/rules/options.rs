@@ -0,0 +1,184 @@
+//! Typed access to per-rule options from `[rules.options.<rule>]` tables
+
+use std::collections::HashSet;
+
+use crate::globs::GlobMatcher;
+
+/// Reads typed values from a rule's options table and records problems as warnings.
+///
+/// Every getter falls back to the given default when the key is missing or invalid,
+/// so a bad option never disables a rule.
+pub struct RuleOptions<'a> {
+    rule: &'a str,
+    table: &'a toml::Table,
+    read: HashSet<String>,
+    warnings: Vec<String>,
+}
+
+impl<'a> RuleOptions<'a> {
+    pub fn new(rule: &'a str, table: &'a toml::Table) -> Self {
+        Self {
+            rule,
+            table,
+            read: HashSet::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn positive_integer(&mut self, key: &str, default: usize) -> usize {
+        match self.get(key) {
+            Some(toml::Value::Integer(value)) if *value > 0 => *value as usize,
+            Some(_) => {
+                self.invalid(key, "a positive integer");
+                default
+            }
+            None => default,
+        }
+    }
+
+    pub fn boolean(&mut self, key: &str, default: bool) -> bool {
+        match self.get(key) {
+            Some(toml::Value::Boolean(value)) => *value,
+            Some(_) => {
+                self.invalid(key, "a boolean");
+                default
+            }
+            None => default,
+        }
+    }
+
+    pub fn string_list(&mut self, key: &str, default: &[&str]) -> Vec<String> {
+        let default = || default.iter().map(|s| s.to_string()).collect();
+        match self.get(key) {
+            Some(toml::Value::Array(items)) => {
+                let strings: Option<Vec<String>> = items
+                    .iter()
+                    .map(|item| item.as_str().map(str::to_string))
+                    .collect();
+                strings.unwrap_or_else(|| {
+                    self.invalid(key, "a list of strings");
+                    default()
+                })
+            }
+            Some(_) => {
+                self.invalid(key, "a list of strings");
+                default()
+            }
+            None => default(),
+        }
+    }
+
+    /// Like [`RuleOptions::string_list`], for lists of glob patterns that must all be valid.
+    pub fn glob_list(&mut self, key: &str, default: &[&str]) -> Vec<String> {
+        let patterns = self.string_list(key, default);
+        match GlobMatcher::new(&patterns, None) {
+            Ok(_) => patterns,
+            Err(e) => {
+                self.invalid(key, &format!("a list of glob patterns ({})", e));
+                default.iter().map(|s| s.to_string()).collect()
+            }
+        }
+    }
+
+    /// Consumes the reader, returning warnings for invalid values and for keys that were never read.
+    pub fn finish(mut self) -> Vec<String> {
+        let mut unknown: Vec<&String> = self
+            .table
+            .keys()
+            .filter(|key| !self.read.contains(key.as_str()))
+            .collect();
+        unknown.sort();
+
+        for key in unknown {
+            self.warnings.push(format!(
+                "Unknown option '{}' for rule '{}' in [rules.options]",
+                key, self.rule
+            ));
+        }
+
+        self.warnings
+    }
+
+    fn get(&mut self, key: &str) -> Option<&'a toml::Value> {
+        self.read.insert(key.to_string());
+        self.table.get(key)
+    }
+
+    fn invalid(&mut self, key: &str, expected: &str) {
+        self.warnings.push(format!(
+            "Invalid option '{}' for rule '{}': expected {}",
+            key, self.rule, expected
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(content: &str) -> toml::Table {
+        content.parse().unwrap()
+    }
+
+    #[test]
+    fn reads_typed_values() {
+        let table = table("threshold = 15\nenabled = false\nallow = [\"warn\", \"error\"]");
+        let mut options = RuleOptions::new("test-rule", &table);
+
+        assert_eq!(options.positive_integer("threshold", 10), 15);
+        assert!(!options.boolean("enabled", true));
+        assert_eq!(options.string_list("allow", &[]), vec!["warn", "error"]);
+        assert!(options.finish().is_empty());
+    }
+
+    #[test]
+    fn missing_keys_use_defaults() {
+        let table = table("");
+        let mut options = RuleOptions::new("test-rule", &table);
+
+        assert_eq!(options.positive_integer("threshold", 10), 10);
+        assert_eq!(options.string_list("allow", &["log"]), vec!["log"]);
+        assert!(options.finish().is_empty());
+    }
+
+    #[test]
+    fn invalid_values_warn_and_use_defaults() {
+        let table = table("threshold = -1\nallow = [1, 2]");
+        let mut options = RuleOptions::new("test-rule", &table);
+
+        assert_eq!(options.positive_integer("threshold", 10), 10);
+        assert!(options.string_list("allow", &[]).is_empty());
+
+        let warnings = options.finish();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("'threshold'"));
+        assert!(warnings[0].contains("positive integer"));
+        assert!(warnings[1].contains("list of strings"));
+    }
+
+    #[test]
+    fn invalid_glob_patterns_warn_and_use_defaults() {
+        let table = table("entry_points = [\"src/[main.ts\"]");
+        let mut options = RuleOptions::new("test-rule", &table);
+
+        assert_eq!(options.glob_list("entry_points", &["*.ts"]), vec!["*.ts"]);
+
+        let warnings = options.finish();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("glob patterns"));
+    }
+
+    #[test]
+    fn unread_keys_are_reported_as_unknown() {
+        let table = table("threshold = 15\nthreshhold = 20");
+        let mut options = RuleOptions::new("max-complexity", &table);
+
+        options.positive_integer("threshold", 10);
+
+        let warnings = options.finish();
+        assert_eq!(
+            warnings,
+            vec!["Unknown option 'threshhold' for rule 'max-complexity' in [rules.options]"]
+        );
+    }
+}
@@ -8,7 +8,7 @@ use swc_common::Spanned;
 use swc_ecma_ast::{BinExpr, BinaryOp, Expr, Lit};
 
 use crate::declare_rule;
-use crate::diagnostic::{Diagnostic, Fix};
+use crate::diagnostic::{Applicability, Diagnostic, Fix};
 use crate::parser::ParsedFile;
 use crate::rules::{Rule, RuleMetadata, Severity};
 use crate::visitor::{AstVisitor, VisitorContext, walk_ast};
@@ -108,7 +108,8 @@ impl AstVisitor for EqeqeqVisitor<'_> {
                     op_column,
                     op_line,
                     op_column + 1,
-                );
+                )
+                .with_applicability(Applicability::Unsafe);
 
                 let diagnostic = Diagnostic::new(
                     "Q033",
@@ -138,7 +139,8 @@ impl AstVisitor for EqeqeqVisitor<'_> {
                     op_column,
                     op_line,
                     op_column + 1,
-                );
+                )
+                .with_applicability(Applicability::Unsafe);
 
                 let diagnostic = Diagnostic::new(
                     "Q033",
@@ -298,9 +300,11 @@ function compare(a, b) {
         let fix = &diagnostics[0].fixes[0];
         assert_eq!(fix.title, "Replace '==' with '==='");
         assert!(matches!(
-            &fix.kind,
+            &fix.edits[0].kind,
             crate::diagnostic::FixKind::ReplaceWith { new_text } if new_text == "==="
         ));
+        // Strict equality skips type coercion, so the result can differ
+        assert_eq!(fix.applicability, Applicability::Unsafe);
     }
 
     #[test]
@@ -313,7 +317,7 @@ function compare(a, b) {
         let fix = &diagnostics[0].fixes[0];
         assert_eq!(fix.title, "Replace '!=' with '!=='");
         assert!(matches!(
-            &fix.kind,
+            &fix.edits[0].kind,
             crate::diagnostic::FixKind::ReplaceWith { new_text } if new_text == "!=="
         ));
     }
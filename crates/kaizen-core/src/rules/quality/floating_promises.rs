@@ -3,12 +3,13 @@
 //! This rule detects Promise-returning function calls that are not properly handled,
 //! which can lead to unhandled rejections and silent failures.
 
+use swc_common::Span;
 use swc_ecma_ast::{
     CallExpr, Callee, Decl, Expr, ExprStmt, MemberExpr, MemberProp, ModuleItem, Stmt, UnaryOp,
 };
 
 use crate::declare_rule;
-use crate::diagnostic::{Diagnostic, Fix};
+use crate::diagnostic::{Applicability, Diagnostic, Fix, TextEdit};
 use crate::parser::ParsedFile;
 use crate::rules::{Rule, RuleMetadata, Severity};
 use crate::visitor::VisitorContext;
@@ -39,6 +40,7 @@ impl Rule for FloatingPromises {
             diagnostics: Vec::new(),
             file_path: file.metadata().filename.clone(),
             ctx: &ctx,
+            function_stack: Vec::new(),
         };
 
         for item in &module.body {
@@ -53,6 +55,8 @@ struct FloatingPromisesVisitor<'a> {
     diagnostics: Vec<Diagnostic>,
     file_path: String,
     ctx: &'a VisitorContext<'a>,
+    /// Span and `async` flag of each enclosing function declaration.
+    function_stack: Vec<(Span, bool)>,
 }
 
 impl<'a> FloatingPromisesVisitor<'a> {
@@ -124,9 +128,12 @@ impl<'a> FloatingPromisesVisitor<'a> {
             }
             Stmt::Decl(Decl::Fn(fn_decl)) => {
                 if let Some(body) = &fn_decl.function.body {
+                    self.function_stack
+                        .push((fn_decl.function.span, fn_decl.function.is_async));
                     for s in &body.stmts {
                         self.check_stmt(s);
                     }
+                    self.function_stack.pop();
                 }
             }
             _ => {}
@@ -154,10 +161,19 @@ impl<'a> FloatingPromisesVisitor<'a> {
                 call_info.name
             );
 
-            let await_fix = Fix::insert_before("Add 'await'", "await ", line, column);
+            // Awaiting changes when the rest of the function runs, and a sync function
+            // has to become async, which changes its return type.
+            let mut await_fix = Fix::insert_before("Add 'await'", "await ", line, column)
+                .with_applicability(Applicability::Unsafe);
+            if let Some(&(fn_span, false)) = self.function_stack.last() {
+                let (fn_line, fn_column) = self.ctx.span_to_location(fn_span);
+                await_fix =
+                    await_fix.with_edit(TextEdit::insert_before("async ", fn_line, fn_column));
+            }
 
             let void_fix =
-                Fix::insert_before("Add 'void' (fire-and-forget)", "void ", line, column);
+                Fix::insert_before("Add 'void' (fire-and-forget)", "void ", line, column)
+                    .with_applicability(Applicability::Suggestion);
 
             let diagnostic = Diagnostic::new(
                 "Q021",
@@ -492,6 +508,29 @@ Object.keys(obj);
         assert!(suggestion.contains("void"));
     }
 
+    #[test]
+    fn await_fix_marks_enclosing_function_async() {
+        let code = "function test() {\n    fetchData();\n}";
+        let diagnostics = run_floating_promises(code);
+
+        let await_fix = &diagnostics[0].fixes[0];
+        assert_eq!(await_fix.applicability, Applicability::Unsafe);
+        assert_eq!(await_fix.edits.len(), 2);
+        assert_eq!(await_fix.edits[1], TextEdit::insert_before("async ", 1, 1));
+        assert_eq!(
+            diagnostics[0].fixes[1].applicability,
+            Applicability::Suggestion
+        );
+    }
+
+    #[test]
+    fn await_fix_in_async_function_is_single_edit() {
+        let code = "async function test() {\n    fetchData();\n}";
+        let diagnostics = run_floating_promises(code);
+
+        assert_eq!(diagnostics[0].fixes[0].edits.len(), 1);
+    }
+
     #[test]
     fn metadata_is_correct() {
         let rule = FloatingPromises::new();
@@ -11,11 +11,24 @@ use swc_ecma_ast::{
 use crate::declare_rule;
 use crate::diagnostic::Diagnostic;
 use crate::parser::ParsedFile;
-use crate::rules::{Rule, RuleMetadata, Severity};
+use crate::rules::{Rule, RuleMetadata, RuleOptions, Severity};
 use crate::visitor::{AstVisitor, VisitorContext, walk_ast};
 
 const DEFAULT_THRESHOLD: usize = 10;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaxComplexityOptions {
+    pub threshold: usize,
+}
+
+impl Default for MaxComplexityOptions {
+    fn default() -> Self {
+        Self {
+            threshold: DEFAULT_THRESHOLD,
+        }
+    }
+}
+
 declare_rule!(
     MaxComplexity,
     id = "Q010",
@@ -23,7 +36,8 @@ declare_rule!(
     description = "Enforce a maximum cyclomatic complexity threshold for functions",
     category = Quality,
     severity = Warning,
-    examples = "// Bad (complexity > 10)\nfunction complex(x) {\n  if (a) { if (b) { if (c) { if (d) { if (e) { if (f) { if (g) { if (h) { if (i) { if (j) { if (k) {} } } } } } } } } } }\n}\n\n// Good (complexity <= 10)\nfunction simple(x) {\n  if (x > 0) {\n    return x * 2;\n  }\n  return x;\n}"
+    examples = "// Bad (complexity > 10)\nfunction complex(x) {\n  if (a) { if (b) { if (c) { if (d) { if (e) { if (f) { if (g) { if (h) { if (i) { if (j) { if (k) {} } } } } } } } } } }\n}\n\n// Good (complexity <= 10)\nfunction simple(x) {\n  if (x > 0) {\n    return x * 2;\n  }\n  return x;\n}",
+    options = MaxComplexityOptions
 );
 
 impl Rule for MaxComplexity {
@@ -31,6 +45,15 @@ impl Rule for MaxComplexity {
         &self.metadata
     }
 
+    fn with_options(&self, options: &mut RuleOptions) -> Option<Box<dyn Rule>> {
+        Some(Box::new(Self {
+            metadata: self.metadata.clone(),
+            options: MaxComplexityOptions {
+                threshold: options.positive_integer("threshold", DEFAULT_THRESHOLD),
+            },
+        }))
+    }
+
     fn check(&self, file: &ParsedFile) -> Vec<Diagnostic> {
         let Some(module) = file.module() else {
             return Vec::new();
@@ -40,7 +63,7 @@ impl Rule for MaxComplexity {
         let mut visitor = MaxComplexityVisitor {
             diagnostics: Vec::new(),
             file_path: file.metadata().filename.clone(),
-            threshold: DEFAULT_THRESHOLD,
+            threshold: self.options.threshold,
         };
 
         walk_ast(module, &mut visitor, &ctx);
@@ -399,6 +422,49 @@ function tooComplex(x) {
         assert!(diagnostics[0].message.contains("11"));
     }
 
+    fn run_rule_with_options(code: &str, options: &str) -> (Vec<Diagnostic>, Vec<String>) {
+        let table: toml::Table = options.parse().unwrap();
+        let mut options = RuleOptions::new("max-complexity", &table);
+        let rule = MaxComplexity::new().with_options(&mut options).unwrap();
+        let file = ParsedFile::from_source("test.js", code);
+        (rule.check(&file), options.finish())
+    }
+
+    #[test]
+    fn configured_threshold_is_used() {
+        let code = r#"
+function tooComplex(x) {
+    if (a) {}
+    if (b) {}
+    if (c) {}
+    if (d) {}
+    if (e) {}
+    if (f) {}
+    if (g) {}
+    if (h) {}
+    if (i) {}
+    if (j) {}
+}
+"#;
+        let (diagnostics, warnings) = run_rule_with_options(code, "threshold = 15");
+        assert!(diagnostics.is_empty());
+        assert!(warnings.is_empty());
+
+        let (diagnostics, _) = run_rule_with_options(code, "threshold = 5");
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("5"));
+    }
+
+    #[test]
+    fn invalid_threshold_falls_back_to_default() {
+        let code = "function simple() { return 1; }";
+        let (diagnostics, warnings) = run_rule_with_options(code, "threshold = \"high\"");
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("'threshold'"));
+    }
+
     #[test]
     fn nested_ifs_add_complexity() {
         let code = r#"
@@ -11,11 +11,24 @@ use swc_ecma_ast::{
 use crate::declare_rule;
 use crate::diagnostic::Diagnostic;
 use crate::parser::ParsedFile;
-use crate::rules::{Rule, RuleMetadata, Severity};
+use crate::rules::{Rule, RuleMetadata, RuleOptions, Severity};
 use crate::visitor::{AstVisitor, VisitorContext, walk_ast};
 
 const DEFAULT_THRESHOLD: usize = 4;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaxDepthOptions {
+    pub threshold: usize,
+}
+
+impl Default for MaxDepthOptions {
+    fn default() -> Self {
+        Self {
+            threshold: DEFAULT_THRESHOLD,
+        }
+    }
+}
+
 declare_rule!(
     MaxDepth,
     id = "Q011",
@@ -23,7 +36,8 @@ declare_rule!(
     description = "Enforce a maximum nesting depth threshold",
     category = Quality,
     severity = Warning,
-    examples = "// Bad (depth > 4)\nfunction deep(x) {\n  if (a) {\n    if (b) {\n      if (c) {\n        if (d) {\n          if (e) {}\n        }\n      }\n    }\n  }\n}\n\n// Good (depth <= 4)\nfunction shallow(x) {\n  if (a) {\n    if (b) {\n      if (c) {\n        doSomething();\n      }\n    }\n  }\n}"
+    examples = "// Bad (depth > 4)\nfunction deep(x) {\n  if (a) {\n    if (b) {\n      if (c) {\n        if (d) {\n          if (e) {}\n        }\n      }\n    }\n  }\n}\n\n// Good (depth <= 4)\nfunction shallow(x) {\n  if (a) {\n    if (b) {\n      if (c) {\n        doSomething();\n      }\n    }\n  }\n}",
+    options = MaxDepthOptions
 );
 
 impl Rule for MaxDepth {
@@ -31,6 +45,15 @@ impl Rule for MaxDepth {
         &self.metadata
     }
 
+    fn with_options(&self, options: &mut RuleOptions) -> Option<Box<dyn Rule>> {
+        Some(Box::new(Self {
+            metadata: self.metadata.clone(),
+            options: MaxDepthOptions {
+                threshold: options.positive_integer("threshold", DEFAULT_THRESHOLD),
+            },
+        }))
+    }
+
     fn check(&self, file: &ParsedFile) -> Vec<Diagnostic> {
         let Some(module) = file.module() else {
             return Vec::new();
@@ -40,7 +63,7 @@ impl Rule for MaxDepth {
         let mut visitor = MaxDepthVisitor {
             diagnostics: Vec::new(),
             file_path: file.metadata().filename.clone(),
-            threshold: DEFAULT_THRESHOLD,
+            threshold: self.options.threshold,
         };
 
         walk_ast(module, &mut visitor, &ctx);
@@ -439,6 +462,29 @@ function tooDeep(x) {
         assert!(diagnostics[0].message.contains("5"));
     }
 
+    #[test]
+    fn configured_threshold_is_used() {
+        let code = r#"
+function nested() {
+    if (a) {
+        if (b) {
+            if (c) {}
+        }
+    }
+}
+"#;
+        let table: toml::Table = "threshold = 2".parse().unwrap();
+        let mut options = RuleOptions::new("max-depth", &table);
+        let rule = MaxDepth::new().with_options(&mut options).unwrap();
+        let file = ParsedFile::from_source("test.js", code);
+
+        let diagnostics = rule.check(&file);
+
+        assert!(options.finish().is_empty());
+        assert_eq!(diagnostics.len(), 1);
+        assert!(run_rule(code).is_empty());
+    }
+
     #[test]
     fn deeply_nested_reports_correct_depth() {
         let code = r#"
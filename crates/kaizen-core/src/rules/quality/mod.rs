@@ -6,7 +6,9 @@ pub mod max_complexity;
 pub mod max_depth;
 pub mod no_console;
 pub mod no_eval;
+pub mod no_import_cycles;
 pub mod no_unreachable;
+pub mod no_unused_exports;
 pub mod no_unused_imports;
 pub mod no_unused_vars;
 pub mod no_var;
@@ -14,6 +16,8 @@ pub mod prefer_const;
 pub mod prefer_nullish_coalescing;
 pub mod prefer_optional_chaining;
 pub mod prefer_using;
+pub mod unknown_disable_rule;
+pub mod unused_disable_directive;
 
 pub use eqeqeq::Eqeqeq;
 pub use floating_promises::FloatingPromises;
@@ -21,7 +25,9 @@ pub use max_complexity::MaxComplexity;
 pub use max_depth::MaxDepth;
 pub use no_console::NoConsole;
 pub use no_eval::NoEval;
+pub use no_import_cycles::NoImportCycles;
 pub use no_unreachable::NoUnreachable;
+pub use no_unused_exports::NoUnusedExports;
 pub use no_unused_imports::NoUnusedImports;
 pub use no_unused_vars::NoUnusedVars;
 pub use no_var::NoVar;
@@ -29,3 +35,5 @@ pub use prefer_const::PreferConst;
 pub use prefer_nullish_coalescing::PreferNullishCoalescing;
 pub use prefer_optional_chaining::PreferOptionalChaining;
 pub use prefer_using::PreferUsing;
+pub use unknown_disable_rule::UnknownDisableRule;
+pub use unused_disable_directive::UnusedDisableDirective;
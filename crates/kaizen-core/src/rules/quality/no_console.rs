@@ -13,7 +13,7 @@ use swc_ecma_ast::{CallExpr, Callee, Expr, MemberProp};
 use crate::declare_rule;
 use crate::diagnostic::Diagnostic;
 use crate::parser::ParsedFile;
-use crate::rules::{Rule, RuleMetadata, Severity};
+use crate::rules::{Rule, RuleMetadata, RuleOptions, Severity};
 use crate::visitor::{AstVisitor, VisitorContext, walk_ast};
 
 /// Check if the filename indicates a file where console.* is allowed
@@ -56,6 +56,23 @@ fn is_console_allowed_file(filename: &str) -> bool {
         || lower.starts_with("tests/")
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoConsoleOptions {
+    /// Console methods that may be called, such as `warn` or `error`.
+    pub allow: Vec<String>,
+    /// Skip example, CLI, script and test files.
+    pub skip_tooling_files: bool,
+}
+
+impl Default for NoConsoleOptions {
+    fn default() -> Self {
+        Self {
+            allow: Vec::new(),
+            skip_tooling_files: true,
+        }
+    }
+}
+
 declare_rule!(
     NoConsole,
     id = "Q032",
@@ -63,7 +80,8 @@ declare_rule!(
     description = "Disallow console.* calls in production code",
     category = Quality,
     severity = Info,
-    examples = "// Bad\nconsole.log('debug');\nconsole.error('error');\n\n// Good\n// Use a proper logging library\nlogger.info('message');"
+    examples = "// Bad\nconsole.log('debug');\nconsole.error('error');\n\n// Good\n// Use a proper logging library\nlogger.info('message');",
+    options = NoConsoleOptions
 );
 
 impl Rule for NoConsole {
@@ -71,9 +89,21 @@ impl Rule for NoConsole {
         &self.metadata
     }
 
+    fn with_options(&self, options: &mut RuleOptions) -> Option<Box<dyn Rule>> {
+        let defaults = NoConsoleOptions::default();
+        Some(Box::new(Self {
+            metadata: self.metadata.clone(),
+            options: NoConsoleOptions {
+                allow: options.string_list("allow", &[]),
+                skip_tooling_files: options
+                    .boolean("skip_tooling_files", defaults.skip_tooling_files),
+            },
+        }))
+    }
+
     fn check(&self, file: &ParsedFile) -> Vec<Diagnostic> {
         // Skip files where console.* is expected
-        if is_console_allowed_file(&file.metadata().filename) {
+        if self.options.skip_tooling_files && is_console_allowed_file(&file.metadata().filename) {
             return Vec::new();
         }
 
@@ -85,6 +115,7 @@ impl Rule for NoConsole {
         let mut visitor = NoConsoleVisitor {
             diagnostics: Vec::new(),
             file_path: file.metadata().filename.clone(),
+            allow: &self.options.allow,
         };
 
         walk_ast(module, &mut visitor, &ctx);
@@ -92,12 +123,13 @@ impl Rule for NoConsole {
     }
 }
 
-struct NoConsoleVisitor {
+struct NoConsoleVisitor<'a> {
     diagnostics: Vec<Diagnostic>,
     file_path: String,
+    allow: &'a [String],
 }
 
-impl AstVisitor for NoConsoleVisitor {
+impl AstVisitor for NoConsoleVisitor<'_> {
     fn visit_call_expr(&mut self, node: &CallExpr, ctx: &VisitorContext) -> ControlFlow<()> {
         if let Callee::Expr(callee_expr) = &node.callee {
             if let Expr::Member(member_expr) = callee_expr.as_ref() {
@@ -109,6 +141,10 @@ impl AstVisitor for NoConsoleVisitor {
                             MemberProp::PrivateName(_) => "method".to_string(),
                         };
 
+                        if self.allow.contains(&method_name) {
+                            return ControlFlow::Continue(());
+                        }
+
                         let (line, column, end_line, end_column) = ctx.span_to_range(node.span);
                         let diagnostic = Diagnostic::new(
                             "Q032",
@@ -188,6 +224,36 @@ myConsole.log("test");
         assert!(diagnostics.is_empty());
     }
 
+    fn run_no_console_with_options(filename: &str, code: &str, options: &str) -> Vec<Diagnostic> {
+        let table: toml::Table = options.parse().unwrap();
+        let mut options = RuleOptions::new("no-console", &table);
+        let rule = NoConsole::new().with_options(&mut options).unwrap();
+        assert!(options.finish().is_empty());
+        rule.check(&ParsedFile::from_source(filename, code))
+    }
+
+    #[test]
+    fn allowed_methods_are_not_reported() {
+        let code = "console.warn('w');\nconsole.error('e');\nconsole.log('l');";
+        let diagnostics =
+            run_no_console_with_options("src/app.js", code, "allow = [\"warn\", \"error\"]");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "Unexpected console.log call");
+    }
+
+    #[test]
+    fn tooling_files_can_be_checked() {
+        let code = "console.log('l');";
+
+        assert!(run_no_console_with_options("scripts/build.js", code, "").is_empty());
+        assert_eq!(
+            run_no_console_with_options("scripts/build.js", code, "skip_tooling_files = false")
+                .len(),
+            1
+        );
+    }
+
     #[test]
     fn severity_is_info() {
         let rule = NoConsole::new();
@@ -0,0 +1,387 @@
+//! no-import-cycles rule (Q040): Detects circular dependencies between modules
+//!
+//! Modules are walked depth-first; every import that leads back to a module still being
+//! visited closes a cycle and is reported, with the shortest path back to it listed as
+//! related locations. Dynamic `import()` never counts since it runs after initialization,
+//! and type-only imports only count with `include_type_imports`.
+
+use std::collections::{HashMap, VecDeque};
+
+use swc_common::{BytePos, Span};
+
+use crate::declare_rule;
+use crate::diagnostic::{Diagnostic, RelatedLocation};
+use crate::parser::ParsedFile;
+use crate::project::{Import, ImportKind, ModuleGraph, ModuleId, Project};
+use crate::rules::{Rule, RuleMetadata, RuleOptions, RuleScope, Severity};
+use crate::visitor::VisitorContext;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoImportCyclesOptions {
+    /// Longest cycle reported, in modules.
+    pub max_length: usize,
+    /// Count `import type` and other imports erased at runtime.
+    pub include_type_imports: bool,
+}
+
+impl Default for NoImportCyclesOptions {
+    fn default() -> Self {
+        Self {
+            max_length: usize::MAX,
+            include_type_imports: false,
+        }
+    }
+}
+
+declare_rule!(
+    NoImportCycles,
+    id = "Q040",
+    name = "no-import-cycles",
+    description = "Disallow circular imports between modules",
+    category = Quality,
+    severity = Warning,
+    examples = "// Bad\n// a.ts\nimport { b } from './b';\n// b.ts\nimport { a } from './a';\n\n// Good - move what both need into a third module\n// a.ts\nimport { shared } from './shared';\n// b.ts\nimport { shared } from './shared';",
+    options = NoImportCyclesOptions
+);
+
+/// An import of one analyzed module by another.
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    target: ModuleId,
+    /// Index of the import record in the importing module.
+    import: usize,
+}
+
+impl Rule for NoImportCycles {
+    fn metadata(&self) -> &RuleMetadata {
+        &self.metadata
+    }
+
+    fn with_options(&self, options: &mut RuleOptions) -> Option<Box<dyn Rule>> {
+        let defaults = NoImportCyclesOptions::default();
+        Some(Box::new(Self {
+            metadata: self.metadata.clone(),
+            options: NoImportCyclesOptions {
+                max_length: options.positive_integer("max_length", defaults.max_length),
+                include_type_imports: options
+                    .boolean("include_type_imports", defaults.include_type_imports),
+            },
+        }))
+    }
+
+    fn scope(&self) -> RuleScope {
+        RuleScope::Project
+    }
+
+    fn check(&self, _file: &ParsedFile) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn check_project(&self, project: &Project) -> Vec<Diagnostic> {
+        let graph = project.graph();
+        let edges = self.edges(graph);
+
+        let mut diagnostics = Vec::new();
+        for (from, edge) in closing_edges(graph, &edges) {
+            let Some(mut cycle) = shortest_path(&edges, edge.target, from) else {
+                continue;
+            };
+            cycle.push((from, edge));
+            if cycle.len() <= self.options.max_length {
+                diagnostics.push(self.diagnostic(project, &cycle));
+            }
+        }
+        diagnostics
+    }
+}
+
+impl NoImportCycles {
+    /// Imports between analyzed modules that run when the importer is loaded, one per
+    /// imported module.
+    fn edges(&self, graph: &ModuleGraph) -> Vec<Vec<Edge>> {
+        graph
+            .modules()
+            .map(|(_, module)| {
+                let mut edges: Vec<Edge> = Vec::new();
+                for (index, target) in module.dependencies() {
+                    let import = &module.info.imports[index];
+                    let counts = import.kind != ImportKind::Dynamic
+                        && (self.options.include_type_imports || !is_type_only(import));
+                    if counts && !edges.iter().any(|edge| edge.target == target) {
+                        edges.push(Edge {
+                            target,
+                            import: index,
+                        });
+                    }
+                }
+                edges
+            })
+            .collect()
+    }
+
+    /// Reports `cycle`, a list of imports that ends with the one closing it.
+    fn diagnostic(&self, project: &Project, cycle: &[(ModuleId, Edge)]) -> Diagnostic {
+        let graph = project.graph();
+        let name = |id: ModuleId| graph.module(id).filename.as_str();
+        let (from, closing) = cycle[cycle.len() - 1];
+
+        let mut path: Vec<&str> = cycle.iter().map(|(id, _)| name(*id)).collect();
+        path.push(name(closing.target));
+
+        let (line, column, end_line, end_column) = import_range(project, from, closing);
+        let mut diagnostic = Diagnostic::new(
+            "Q040",
+            Severity::Warning,
+            format!("Import cycle: {}", path.join(" -> ")),
+            name(from),
+            line,
+            column,
+        )
+        .with_end(end_line, end_column)
+        .with_suggestion(
+            "Move the code both modules need into a separate module, or import it lazily",
+        );
+
+        for (id, edge) in cycle {
+            let (line, column, end_line, end_column) = import_range(project, *id, *edge);
+            diagnostic = diagnostic.with_related(
+                RelatedLocation::new(
+                    format!("'{}' imports '{}'", name(*id), name(edge.target)),
+                    name(*id),
+                    line,
+                    column,
+                )
+                .with_end(end_line, end_column),
+            );
+        }
+        diagnostic
+    }
+}
+
+/// True for imports erased at runtime, including those whose every binding is a type.
+fn is_type_only(import: &Import) -> bool {
+    import.type_only || (!import.bindings.is_empty() && import.bindings.iter().all(|b| b.type_only))
+}
+
+fn import_range(project: &Project, id: ModuleId, edge: Edge) -> (usize, usize, usize, usize) {
+    let span = project.graph().module(id).info.imports[edge.import].span;
+    let ctx = VisitorContext::new(project.file(id));
+    ctx.span_to_range(Span::new(span.lo, span.hi - BytePos(1)))
+}
+
+/// Finds the imports that lead back to a module on the depth-first stack, visiting
+/// modules in filename order so that results do not depend on the order files were found.
+fn closing_edges(graph: &ModuleGraph, edges: &[Vec<Edge>]) -> Vec<(ModuleId, Edge)> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        New,
+        OnStack,
+        Done,
+    }
+
+    let mut roots: Vec<ModuleId> = graph.modules().map(|(id, _)| id).collect();
+    roots.sort_by(|a, b| graph.module(*a).filename.cmp(&graph.module(*b).filename));
+
+    let mut state = vec![State::New; edges.len()];
+    let mut closing = Vec::new();
+    for root in roots {
+        if state[root.index()] != State::New {
+            continue;
+        }
+        state[root.index()] = State::OnStack;
+        // Each frame holds a module and the position of its next edge to follow.
+        let mut stack = vec![(root, 0)];
+        while let Some((id, next)) = stack.last_mut() {
+            let id = *id;
+            let Some(edge) = edges[id.index()].get(*next).copied() else {
+                state[id.index()] = State::Done;
+                stack.pop();
+                continue;
+            };
+            *next += 1;
+            match state[edge.target.index()] {
+                State::New => {
+                    state[edge.target.index()] = State::OnStack;
+                    stack.push((edge.target, 0));
+                }
+                State::OnStack => closing.push((id, edge)),
+                State::Done => {}
+            }
+        }
+    }
+    closing
+}
+
+/// The imports of the shortest path from `start` to `end`, each with its importer. Empty
+/// when `start` is `end`.
+fn shortest_path(
+    edges: &[Vec<Edge>],
+    start: ModuleId,
+    end: ModuleId,
+) -> Option<Vec<(ModuleId, Edge)>> {
+    let mut previous: HashMap<ModuleId, (ModuleId, Edge)> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(id) = queue.pop_front() {
+        if id == end {
+            let mut path = Vec::new();
+            let mut current = end;
+            while current != start {
+                let step = previous[&current];
+                path.push(step);
+                current = step.0;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for edge in &edges[id.index()] {
+            if edge.target != start && !previous.contains_key(&edge.target) {
+                previous.insert(edge.target, (id, *edge));
+                queue.push_back(edge.target);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_with(rule: &dyn Rule, files: &[(&str, &str)]) -> Vec<Diagnostic> {
+        let dir = tempfile::tempdir().unwrap();
+        let parsed = files
+            .iter()
+            .map(|(name, source)| {
+                std::fs::write(dir.path().join(name), source).unwrap();
+                ParsedFile::from_source(&dir.path().join(name).to_string_lossy(), source)
+            })
+            .collect();
+        let prefix = format!("{}/", dir.path().to_string_lossy());
+        let mut diagnostics = rule.check_project(&Project::new(parsed));
+        for diagnostic in &mut diagnostics {
+            diagnostic.message = diagnostic.message.replace(&prefix, "");
+            diagnostic.file = diagnostic.file.replace(&prefix, "");
+            for related in &mut diagnostic.related {
+                related.message = related.message.replace(&prefix, "");
+                related.file = related.file.replace(&prefix, "");
+            }
+        }
+        diagnostics
+    }
+
+    fn run(files: &[(&str, &str)]) -> Vec<Diagnostic> {
+        run_with(&NoImportCycles::new(), files)
+    }
+
+    fn configured(options: &str) -> Box<dyn Rule> {
+        let table: toml::Table = toml::from_str(options).unwrap();
+        let mut options = RuleOptions::new("no-import-cycles", &table);
+        let rule = NoImportCycles::new().with_options(&mut options).unwrap();
+        assert!(options.finish().is_empty());
+        rule
+    }
+
+    #[test]
+    fn reports_the_import_closing_a_cycle() {
+        let diagnostics = run(&[
+            ("a.ts", "import { b } from './b';\nexport const a = b;"),
+            ("b.ts", "import { c } from './c';\nexport const b = c;"),
+            (
+                "c.ts",
+                "export const c = 1;\nimport { a } from './a';\nconsole.log(a);",
+            ),
+        ]);
+
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(
+            diagnostic.message,
+            "Import cycle: a.ts -> b.ts -> c.ts -> a.ts"
+        );
+        assert_eq!(diagnostic.file, "c.ts");
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 1));
+        assert_eq!((diagnostic.end_line, diagnostic.end_column), (2, 24));
+
+        let related: Vec<(&str, usize, &str)> = diagnostic
+            .related
+            .iter()
+            .map(|r| (r.file.as_str(), r.line, r.message.as_str()))
+            .collect();
+        assert_eq!(
+            related,
+            vec![
+                ("a.ts", 1, "'a.ts' imports 'b.ts'"),
+                ("b.ts", 1, "'b.ts' imports 'c.ts'"),
+                ("c.ts", 2, "'c.ts' imports 'a.ts'"),
+            ]
+        );
+    }
+
+    #[test]
+    fn acyclic_imports_are_not_reported() {
+        let diagnostics = run(&[
+            ("a.js", "import './b';\nimport './c';"),
+            ("b.js", "import './c';"),
+            ("c.js", "export const c = 1;"),
+        ]);
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn require_and_re_exports_form_cycles() {
+        let diagnostics = run(&[
+            ("a.js", "const b = require('./b');\nmodule.exports = b;"),
+            ("b.js", "export * from './a';"),
+        ]);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "Import cycle: a.js -> b.js -> a.js");
+    }
+
+    #[test]
+    fn dynamic_imports_do_not_count() {
+        let diagnostics = run(&[
+            ("a.js", "import './b';"),
+            ("b.js", "export const load = () => import('./a');"),
+        ]);
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn type_only_imports_count_only_when_configured() {
+        let files = [
+            (
+                "a.ts",
+                "import { b } from './b';\nexport type A = {};\nb();",
+            ),
+            (
+                "b.ts",
+                "import type { A } from './a';\nimport { type A as B } from './a';\nexport function b(): A {}",
+            ),
+        ];
+
+        assert!(run(&files).is_empty());
+
+        let diagnostics = run_with(configured("include_type_imports = true").as_ref(), &files);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file, "b.ts");
+    }
+
+    #[test]
+    fn cycles_longer_than_max_length_are_not_reported() {
+        let files = [
+            ("a.js", "import './b';\nimport './d';"),
+            ("b.js", "import './c';"),
+            ("c.js", "import './a';"),
+            ("d.js", "import './a';"),
+        ];
+
+        assert_eq!(run(&files).len(), 2);
+
+        let diagnostics = run_with(configured("max_length = 2").as_ref(), &files);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "Import cycle: a.js -> d.js -> a.js");
+    }
+}
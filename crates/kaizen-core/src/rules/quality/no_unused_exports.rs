@@ -0,0 +1,517 @@
+//! no-unused-exports rule (Q005): Detects exports that no other analyzed module imports
+//!
+//! Entry points are public API and keep all their exports: files named by `package.json`
+//! (`main`, `module`, `types`, `bin`, `exports`), files matching framework conventions such
+//! as Next.js pages or `*.config.*`, and files matching the `entry_points` option. Modules
+//! using CommonJS exports are skipped since their set of names is not known statically.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use swc_common::{BytePos, Span};
+
+use crate::declare_rule;
+use crate::diagnostic::{Applicability, Diagnostic, Fix};
+use crate::globs::GlobMatcher;
+use crate::parser::ParsedFile;
+use crate::project::{
+    DEFAULT_EXPORT, Export, ExportKind, ImportKind, ModuleGraph, ModuleId, PackageEntries, Project,
+    ResolvedImport, Resolver,
+};
+use crate::rules::{Rule, RuleMetadata, RuleOptions, RuleScope, Severity};
+use crate::visitor::VisitorContext;
+
+/// Files that frameworks and tools load by convention rather than through imports.
+const FRAMEWORK_ENTRY_POINTS: &[&str] = &[
+    // Next.js, Nuxt and Astro pages
+    "**/pages/**",
+    "**/app/**/{page,layout,template,loading,error,global-error,not-found,default,route}.*",
+    "**/app/{root,entry.client,entry.server}.*",
+    // Remix
+    "**/app/routes/**",
+    "middleware.*",
+    "instrumentation.*",
+    "*.config.*",
+    "*.test.*",
+    "*.spec.*",
+    "__tests__",
+    "*.stories.*",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoUnusedExportsOptions {
+    /// Globs of additional entry points, matched relative to the nearest `package.json`.
+    pub entry_points: Vec<String>,
+    /// Treat files loaded by framework conventions as entry points.
+    pub framework_entry_points: bool,
+}
+
+impl Default for NoUnusedExportsOptions {
+    fn default() -> Self {
+        Self {
+            entry_points: Vec::new(),
+            framework_entry_points: true,
+        }
+    }
+}
+
+declare_rule!(
+    NoUnusedExports,
+    id = "Q005",
+    name = "no-unused-exports",
+    description = "Disallow exports that no other module imports",
+    category = Quality,
+    severity = Warning,
+    examples = "// Bad - nothing imports `legacyFormat`\nexport function legacyFormat(date) {}\n\n// Good\nfunction legacyFormat(date) {}",
+    options = NoUnusedExportsOptions
+);
+
+impl Rule for NoUnusedExports {
+    fn metadata(&self) -> &RuleMetadata {
+        &self.metadata
+    }
+
+    fn with_options(&self, options: &mut RuleOptions) -> Option<Box<dyn Rule>> {
+        let defaults = NoUnusedExportsOptions::default();
+        Some(Box::new(Self {
+            metadata: self.metadata.clone(),
+            options: NoUnusedExportsOptions {
+                entry_points: options.glob_list("entry_points", &[]),
+                framework_entry_points: options
+                    .boolean("framework_entry_points", defaults.framework_entry_points),
+            },
+        }))
+    }
+
+    fn scope(&self) -> RuleScope {
+        RuleScope::Project
+    }
+
+    fn check(&self, _file: &ParsedFile) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    fn check_project(&self, project: &Project) -> Vec<Diagnostic> {
+        let graph = project.graph();
+        let mut entry_points = EntryPoints::new(&self.options);
+        let mut usage = Usage::new(graph);
+
+        for (id, module) in graph.modules() {
+            if entry_points.contains(&module.path, &module.filename) {
+                usage.use_all(id);
+            }
+            for (index, target) in module.dependencies() {
+                let import = &module.info.imports[index];
+                match import.kind {
+                    // Re-exported names count once something imports them in turn.
+                    ImportKind::ReExport => {}
+                    ImportKind::Dynamic => usage.use_all(target),
+                    _ if import.uses_whole_module() => usage.use_all(target),
+                    _ => {
+                        for name in import.bindings.iter().filter_map(|b| b.imported.as_ref()) {
+                            usage.use_name(target, name);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut diagnostics = Vec::new();
+        for (id, module) in graph.modules() {
+            if usage.whole.contains(&id) || module.info.is_commonjs() {
+                continue;
+            }
+            let file = project.file(id);
+            for export in &module.info.exports {
+                let reportable = matches!(export.kind, ExportKind::Declaration | ExportKind::Local)
+                    && export.name != DEFAULT_EXPORT;
+                if reportable && !usage.is_used(id, &export.name) {
+                    diagnostics.push(self.diagnostic(file, &module.info.exports, export));
+                }
+            }
+        }
+        diagnostics
+    }
+}
+
+impl NoUnusedExports {
+    fn diagnostic(&self, file: &ParsedFile, exports: &[Export], export: &Export) -> Diagnostic {
+        let ctx = VisitorContext::new(file);
+        let keyword = export_keyword(file.source(), export.span);
+        let (line, column, end_line, end_column) =
+            ctx.span_to_range(keyword.unwrap_or(export.span));
+
+        let mut diagnostic = Diagnostic::new(
+            "Q005",
+            Severity::Warning,
+            format!(
+                "'{}' is exported but never imported by another module",
+                export.name
+            ),
+            &file.metadata().filename,
+            line,
+            column,
+        )
+        .with_end(end_line, end_column)
+        .with_suggestion(format!(
+            "Remove the export of '{}', or add the file to `entry_points` if it is public API",
+            export.name
+        ));
+
+        // Dropping the keyword un-exports every name of the statement, so only offer it
+        // when the statement declares this one name.
+        let declares_one_name = exports.iter().filter(|e| e.span == export.span).count() == 1;
+        if export.kind == ExportKind::Declaration
+            && declares_one_name
+            && let Some(keyword) = keyword
+        {
+            let (line, column, end_line, end_column) = ctx.span_to_range(keyword);
+            diagnostic = diagnostic.with_fix(
+                Fix::delete(
+                    format!("Remove 'export' from '{}'", export.name),
+                    line,
+                    column,
+                    end_line,
+                    end_column,
+                )
+                // Code outside the analyzed files may still import it.
+                .with_applicability(Applicability::Suggestion),
+            );
+        }
+        diagnostic
+    }
+}
+
+/// The `export` keyword at the start of `span` with the whitespace after it, as an
+/// inclusive span.
+fn export_keyword(source: &str, span: Span) -> Option<Span> {
+    let start = span.lo.0.checked_sub(1)? as usize;
+    let rest = source.get(start..)?.strip_prefix("export")?;
+    let whitespace = rest.len() - rest.trim_start().len();
+    if whitespace == 0 {
+        return None;
+    }
+    let len = "export".len() + whitespace;
+    Some(Span::new(span.lo, span.lo + BytePos(len as u32 - 1)))
+}
+
+/// Tracks which exports are imported, following re-exports to the module that declares them.
+struct Usage<'a> {
+    graph: &'a ModuleGraph,
+    names: HashSet<(ModuleId, String)>,
+    /// Modules whose every export counts as used.
+    whole: HashSet<ModuleId>,
+}
+
+impl<'a> Usage<'a> {
+    fn new(graph: &'a ModuleGraph) -> Self {
+        Self {
+            graph,
+            names: HashSet::new(),
+            whole: HashSet::new(),
+        }
+    }
+
+    fn is_used(&self, id: ModuleId, name: &str) -> bool {
+        self.whole.contains(&id) || self.names.contains(&(id, name.to_string()))
+    }
+
+    fn use_name(&mut self, id: ModuleId, name: &str) {
+        if self.is_used(id, name) {
+            return;
+        }
+        self.names.insert((id, name.to_string()));
+
+        let module = self.graph.module(id);
+        match module.info.export(name) {
+            Some(export) => self.follow_reexport(id, export),
+            // Names not exported directly may come from `export * from`.
+            None => {
+                for &index in &module.info.star_reexports {
+                    if let ResolvedImport::Module(target) = module.resolved[index] {
+                        self.use_name(target, name);
+                    }
+                }
+            }
+        }
+    }
+
+    fn use_all(&mut self, id: ModuleId) {
+        if !self.whole.insert(id) {
+            return;
+        }
+        let module = self.graph.module(id);
+        for export in &module.info.exports {
+            self.follow_reexport(id, export);
+        }
+        for &index in &module.info.star_reexports {
+            if let ResolvedImport::Module(target) = module.resolved[index] {
+                self.use_all(target);
+            }
+        }
+    }
+
+    fn follow_reexport(&mut self, id: ModuleId, export: &Export) {
+        let ExportKind::ReExport { import, imported } = &export.kind else {
+            return;
+        };
+        if let ResolvedImport::Module(target) = self.graph.module(id).resolved[*import] {
+            match imported {
+                Some(name) => self.use_name(target, name),
+                None => self.use_all(target),
+            }
+        }
+    }
+}
+
+/// Decides which modules are entry points, reading each `package.json` once.
+struct EntryPoints {
+    resolver: Resolver,
+    patterns: Option<GlobMatcher>,
+    /// Entries of each package, with a matcher for its exposed directories.
+    packages: HashMap<PathBuf, (PackageEntries, Option<GlobMatcher>)>,
+}
+
+impl EntryPoints {
+    fn new(options: &NoUnusedExportsOptions) -> Self {
+        let mut patterns = options.entry_points.clone();
+        if options.framework_entry_points {
+            patterns.extend(FRAMEWORK_ENTRY_POINTS.iter().map(|p| p.to_string()));
+        }
+        Self {
+            resolver: Resolver::new(),
+            patterns: GlobMatcher::new(&patterns, None).ok(),
+            packages: HashMap::new(),
+        }
+    }
+
+    /// `path` is the canonical path of the module, `filename` the name it was analyzed under.
+    fn contains(&mut self, path: &Path, filename: &str) -> bool {
+        if filename.ends_with(".d.ts") {
+            return true;
+        }
+        if self.matches_pattern(Path::new(filename)) {
+            return true;
+        }
+
+        let Some(package_dir) = self.resolver.package_dir(path) else {
+            return false;
+        };
+        let Ok(relative) = path.strip_prefix(&package_dir) else {
+            return false;
+        };
+        if self.matches_pattern(relative) {
+            return true;
+        }
+
+        let resolver = &mut self.resolver;
+        let (entries, directories) =
+            self.packages.entry(package_dir.clone()).or_insert_with(|| {
+                let entries = resolver.package_entries(&package_dir);
+                let directories: Vec<String> = entries
+                    .directories
+                    .iter()
+                    .map(|dir| format!("/{}", dir.to_string_lossy()))
+                    .collect();
+                (entries, GlobMatcher::new(&directories, None).ok())
+            });
+        entries.files.iter().any(|file| file == path)
+            || directories.as_ref().is_some_and(|m| m.is_match(relative))
+    }
+
+    fn matches_pattern(&self, path: &Path) -> bool {
+        self.patterns.as_ref().is_some_and(|m| m.is_match(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(files: &[(&str, &str)]) -> Vec<Diagnostic> {
+        run_with(NoUnusedExports::new(), files)
+    }
+
+    fn run_with(rule: NoUnusedExports, files: &[(&str, &str)]) -> Vec<Diagnostic> {
+        let dir = tempfile::tempdir().unwrap();
+        let parsed = files
+            .iter()
+            .map(|(name, source)| {
+                let path = dir.path().join(name);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(&path, source).unwrap();
+                ParsedFile::from_source(&path.to_string_lossy(), source)
+            })
+            .collect();
+        let mut diagnostics = rule.check_project(&Project::new(parsed));
+        for diagnostic in &mut diagnostics {
+            let name = Path::new(&diagnostic.file)
+                .strip_prefix(dir.path())
+                .unwrap();
+            diagnostic.file = name.to_string_lossy().into_owned();
+        }
+        diagnostics
+    }
+
+    fn reported(diagnostics: &[Diagnostic]) -> Vec<(&str, &str)> {
+        diagnostics
+            .iter()
+            .map(|d| {
+                let name = d.message.split('\'').nth(1).unwrap();
+                (d.file.as_str(), name)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reports_exports_no_module_imports() {
+        let diagnostics = run(&[
+            ("main.ts", "import { used } from './lib';\nused();"),
+            (
+                "lib.ts",
+                "export function used() {}\nexport function unused() {}\nexport class Old {}\nexport type Shape = {};",
+            ),
+        ]);
+
+        assert_eq!(
+            reported(&diagnostics),
+            vec![("lib.ts", "unused"), ("lib.ts", "Old"), ("lib.ts", "Shape")]
+        );
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (2, 1));
+    }
+
+    #[test]
+    fn usage_in_the_same_file_does_not_count() {
+        let diagnostics = run(&[("lib.ts", "export const limit = 3;\nconsole.log(limit);")]);
+
+        assert_eq!(reported(&diagnostics), vec![("lib.ts", "limit")]);
+    }
+
+    #[test]
+    fn type_imports_count_as_usage() {
+        let diagnostics = run(&[
+            (
+                "main.ts",
+                "import type { Shape } from './types';\nlet s: Shape;",
+            ),
+            ("types.ts", "export interface Shape {}"),
+        ]);
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn follows_re_exports_to_the_declaring_module() {
+        let diagnostics = run(&[
+            ("main.ts", "import { a, c } from './index';\na(c);"),
+            (
+                "index.ts",
+                "export { a, b } from './ab';\nexport * from './cd';",
+            ),
+            ("ab.ts", "export const a = 1;\nexport const b = 2;"),
+            ("cd.ts", "export const c = 3;\nexport const d = 4;"),
+        ]);
+
+        // `b` is re-exported, but nothing imports it from the index either.
+        assert_eq!(reported(&diagnostics), vec![("ab.ts", "b"), ("cd.ts", "d")]);
+    }
+
+    #[test]
+    fn namespace_require_and_dynamic_imports_use_every_export() {
+        let diagnostics = run(&[
+            (
+                "main.js",
+                "import * as ns from './a';\nconst b = require('./b');\nimport('./c');",
+            ),
+            ("a.js", "export const one = 1;"),
+            ("b.js", "export const two = 2;"),
+            ("c.js", "export const three = 3;"),
+            ("d.js", "const { four } = require('./e');\nimport './f';"),
+            ("e.js", "export const four = 4;\nexport const five = 5;"),
+            ("f.js", "export const six = 6;"),
+        ]);
+
+        assert_eq!(
+            reported(&diagnostics),
+            vec![("e.js", "five"), ("f.js", "six")]
+        );
+    }
+
+    #[test]
+    fn entry_points_keep_their_exports() {
+        let diagnostics = run(&[
+            (
+                "package.json",
+                r#"{ "main": "./dist/index.js", "exports": { ".": "./src/index.js", "./cli": "./src/cli.ts" } }"#,
+            ),
+            ("src/index.ts", "export function api() {}"),
+            ("src/cli.ts", "export function run() {}"),
+            (
+                "src/pages/home.tsx",
+                "export function getServerSideProps() {}",
+            ),
+            ("vite.config.ts", "export const plugins = [];"),
+            ("src/internal.ts", "export function helper() {}"),
+        ]);
+
+        assert_eq!(reported(&diagnostics), vec![("src/internal.ts", "helper")]);
+    }
+
+    #[test]
+    fn configured_entry_points_are_matched_from_the_package() {
+        let table: toml::Table =
+            toml::from_str("entry_points = [\"src/public/**\"]\nframework_entry_points = false")
+                .unwrap();
+        let mut options = RuleOptions::new("no-unused-exports", &table);
+        let rule = NoUnusedExports::new().with_options(&mut options).unwrap();
+        assert!(options.finish().is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            ("package.json", "{}"),
+            ("src/public/api.ts", "export const api = 1;"),
+            ("src/pages/home.ts", "export const page = 1;"),
+        ];
+        let parsed = files
+            .iter()
+            .map(|(name, source)| {
+                let path = dir.path().join(name);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(&path, source).unwrap();
+                ParsedFile::from_source(&path.to_string_lossy(), source)
+            })
+            .collect();
+        let diagnostics = rule.check_project(&Project::new(parsed));
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("'page'"));
+    }
+
+    #[test]
+    fn commonjs_modules_and_default_exports_are_not_reported() {
+        let diagnostics = run(&[
+            ("a.js", "exports.helper = () => {};"),
+            ("b.ts", "export default function main() {}"),
+        ]);
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn fix_drops_the_export_keyword() {
+        let diagnostics = run(&[(
+            "lib.ts",
+            "export  async function load() {}\nexport const a = 1, b = 2;\nconst c = 3;\nexport { c };",
+        )]);
+
+        let fix = diagnostics[0].fixes.first().unwrap();
+        assert_eq!(fix.applicability, Applicability::Suggestion);
+        let edit = &fix.edits[0];
+        assert_eq!(
+            (edit.line, edit.column, edit.end_line, edit.end_column),
+            (1, 1, 1, 8)
+        );
+        assert!(diagnostics[1..].iter().all(|d| d.fixes.is_empty()));
+        assert_eq!(diagnostics.len(), 4);
+    }
+}
@@ -3,12 +3,13 @@
 //! Exception: In files containing JSX, the `React` import is allowed even if not explicitly
 //! used, as it was required for JSX transformation in React versions before 17.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use swc_ecma_ast::{ExportSpecifier, ModuleDecl, ModuleItem};
+use swc_common::{BytePos, Span, Spanned};
+use swc_ecma_ast::{ExportSpecifier, ImportDecl, ImportSpecifier, ModuleDecl, ModuleItem};
 
 use crate::declare_rule;
-use crate::diagnostic::{Diagnostic, Fix};
+use crate::diagnostic::{Applicability, Diagnostic, Fix};
 use crate::parser::ParsedFile;
 use crate::rules::helpers::file_contains_jsx;
 use crate::rules::{Rule, RuleMetadata, Severity};
@@ -40,6 +41,7 @@ impl Rule for NoUnusedImports {
         let semantic = ScopeBuilder::build(module);
 
         let re_exported_names = collect_re_exported_names(module);
+        let removals = collect_import_removals(module, file.source());
 
         // In files containing JSX, React import is allowed even if not explicitly used
         // (required for JSX transformation in React < 17)
@@ -73,16 +75,7 @@ impl Rule for NoUnusedImports {
                 let (line, column) = ctx.span_to_location(symbol.span);
                 let end_column = column + symbol.name.len() - 1;
 
-                let fix = Fix::replace(
-                    format!("Remove unused import '{}'", symbol.name),
-                    "",
-                    line,
-                    column,
-                    line,
-                    end_column,
-                );
-
-                let diagnostic = Diagnostic::new(
+                let mut diagnostic = Diagnostic::new(
                     "Q003",
                     Severity::Warning,
                     format!("'{}' is imported but never used", symbol.name),
@@ -94,8 +87,21 @@ impl Rule for NoUnusedImports {
                 .with_suggestion(format!(
                     "Remove unused import '{}' or prefix with underscore if intentionally unused",
                     symbol.name
-                ))
-                .with_fix(fix);
+                ));
+
+                if let Some(removal) = removals.get(&symbol.span) {
+                    let (start_line, start_column, end_line, end_column) =
+                        ctx.span_to_range(Span::new(removal.span.lo, removal.span.hi - BytePos(1)));
+                    let fix = Fix::delete(
+                        format!("Remove unused import '{}'", symbol.name),
+                        start_line,
+                        start_column,
+                        end_line,
+                        end_column,
+                    )
+                    .with_applicability(removal.applicability);
+                    diagnostic = diagnostic.with_fix(fix);
+                }
 
                 diagnostics.push(diagnostic);
             }
@@ -105,6 +111,96 @@ impl Rule for NoUnusedImports {
     }
 }
 
+/// The text to delete in order to remove one import specifier.
+struct ImportRemoval {
+    span: Span,
+    applicability: Applicability,
+}
+
+/// Maps the local binding span of every import specifier to the text that removes it,
+/// including the separating comma.
+fn collect_import_removals(
+    module: &swc_ecma_ast::Module,
+    source: &str,
+) -> HashMap<Span, ImportRemoval> {
+    let mut removals = HashMap::new();
+
+    for item in &module.body {
+        if let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item {
+            for (index, specifier) in import.specifiers.iter().enumerate() {
+                if let Some(span) = specifier_removal_span(import, index, source) {
+                    // Removing the whole declaration also drops the module's side effects.
+                    let applicability = if import.specifiers.len() == 1 {
+                        Applicability::Suggestion
+                    } else {
+                        Applicability::Safe
+                    };
+                    removals.insert(
+                        specifier_local_span(specifier),
+                        ImportRemoval {
+                            span,
+                            applicability,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    removals
+}
+
+fn specifier_removal_span(import: &ImportDecl, index: usize, source: &str) -> Option<Span> {
+    let specifiers = &import.specifiers;
+    let current = specifiers[index].span();
+
+    if specifiers.len() == 1 {
+        // Drop the whole declaration together with its line break.
+        let hi = import.span.hi;
+        let next_byte = source.as_bytes().get(hi.0.saturating_sub(1) as usize);
+        let hi = if next_byte == Some(&b'\n') {
+            hi + BytePos(1)
+        } else {
+            hi
+        };
+        return Some(Span::new(import.span.lo, hi));
+    }
+
+    if let Some(next) = specifiers.get(index + 1) {
+        // Remove up to the comma and the whitespace after it, so that an opening
+        // brace between a default and named specifiers is kept.
+        let between_lo = current.hi.0.saturating_sub(1) as usize;
+        let between_hi = next.span().lo.0.saturating_sub(1) as usize;
+        let between = source.get(between_lo..between_hi)?;
+        let comma = between.find(',')?;
+        let rest = &between[comma + 1..];
+        let skip = comma + 1 + (rest.len() - rest.trim_start().len());
+        return Some(Span::new(current.lo, current.hi + BytePos(skip as u32)));
+    }
+
+    let previous = &specifiers[index - 1];
+    if matches!(previous, ImportSpecifier::Default(_))
+        && matches!(specifiers[index], ImportSpecifier::Named(_))
+    {
+        // `import a, { b } from 'x'` - remove the braces along with the last named specifier
+        let lo = previous.span().hi;
+        let between_lo = lo.0.saturating_sub(1) as usize;
+        let between_hi = import.src.span.lo.0.saturating_sub(1) as usize;
+        let close = source.get(between_lo..between_hi)?.rfind('}')?;
+        return Some(Span::new(lo, lo + BytePos(close as u32 + 1)));
+    }
+
+    Some(Span::new(previous.span().hi, current.hi))
+}
+
+fn specifier_local_span(specifier: &ImportSpecifier) -> Span {
+    match specifier {
+        ImportSpecifier::Named(named) => named.local.span,
+        ImportSpecifier::Default(default) => default.local.span,
+        ImportSpecifier::Namespace(namespace) => namespace.local.span,
+    }
+}
+
 fn collect_re_exported_names(module: &swc_ecma_ast::Module) -> HashSet<String> {
     let mut re_exported = HashSet::new();
 
@@ -461,10 +557,63 @@ console.log(baz);
 
         let fix = &diagnostics[0].fixes[0];
         assert!(fix.title.contains("Remove unused import"));
-        assert!(matches!(
-            &fix.kind,
-            crate::diagnostic::FixKind::ReplaceWith { new_text } if new_text.is_empty()
-        ));
+        assert_eq!(fix.edits[0].kind, crate::diagnostic::FixKind::Delete);
+        assert_eq!(fix.applicability, Applicability::Suggestion);
+    }
+
+    fn apply_first_fix(code: &str) -> String {
+        let diagnostics = run_no_unused_imports(code);
+        let fix = &diagnostics[0].fixes[0];
+        crate::fixer::apply_fixes(code, &[fix]).0
+    }
+
+    #[test]
+    fn fix_removes_specifier_and_trailing_comma() {
+        let code = "import { unused, used } from 'module';\nused();\n";
+
+        assert_eq!(
+            apply_first_fix(code),
+            "import { used } from 'module';\nused();\n"
+        );
+        let diagnostics = run_no_unused_imports(code);
+        assert_eq!(diagnostics[0].fixes[0].applicability, Applicability::Safe);
+    }
+
+    #[test]
+    fn fix_removes_last_specifier_and_leading_comma() {
+        let code = "import { used, unused } from 'module';\nused();\n";
+
+        assert_eq!(
+            apply_first_fix(code),
+            "import { used } from 'module';\nused();\n"
+        );
+    }
+
+    #[test]
+    fn fix_removes_braces_after_default_import() {
+        let code = "import used, { unused } from 'module';\nused();\n";
+
+        assert_eq!(
+            apply_first_fix(code),
+            "import used from 'module';\nused();\n"
+        );
+    }
+
+    #[test]
+    fn fix_removes_default_before_named_imports() {
+        let code = "import unused, { used } from 'module';\nused();\n";
+
+        assert_eq!(
+            apply_first_fix(code),
+            "import { used } from 'module';\nused();\n"
+        );
+    }
+
+    #[test]
+    fn fix_removes_whole_declaration_with_line() {
+        let code = "import { unused } from 'module';\nrun();\n";
+
+        assert_eq!(apply_first_fix(code), "run();\n");
     }
 
     // === React import exception tests ===
@@ -5,7 +5,7 @@ use std::ops::ControlFlow;
 use swc_ecma_ast::{VarDecl, VarDeclKind};
 
 use crate::declare_rule;
-use crate::diagnostic::{Diagnostic, Fix};
+use crate::diagnostic::{Applicability, Diagnostic, Fix};
 use crate::parser::ParsedFile;
 use crate::rules::{Rule, RuleMetadata, Severity};
 use crate::visitor::{AstVisitor, VisitorContext, walk_ast};
@@ -54,6 +54,7 @@ impl AstVisitor for NoVarVisitor<'_> {
         if node.kind == VarDeclKind::Var {
             let (line, column) = self.ctx.span_to_location(node.span);
 
+            // `let` is block scoped and not hoisted, so the rewrite can change behavior
             let fix = Fix::replace(
                 "Replace 'var' with 'let'",
                 "let",
@@ -61,7 +62,8 @@ impl AstVisitor for NoVarVisitor<'_> {
                 column,
                 line,
                 column + 2,
-            );
+            )
+            .with_applicability(Applicability::Suggestion);
 
             let diagnostic = Diagnostic::new(
                 "Q030",
@@ -197,12 +199,13 @@ var c = 3;
         let fix = &diagnostics[0].fixes[0];
         assert_eq!(fix.title, "Replace 'var' with 'let'");
         assert!(matches!(
-            &fix.kind,
+            &fix.edits[0].kind,
             crate::diagnostic::FixKind::ReplaceWith { new_text } if new_text == "let"
         ));
-        assert_eq!(fix.line, 1);
-        assert_eq!(fix.column, diagnostics[0].column);
-        assert_eq!(fix.end_line, 1);
-        assert_eq!(fix.end_column, diagnostics[0].column + 2);
+        assert_eq!(fix.applicability, Applicability::Suggestion);
+        assert_eq!(fix.edits[0].line, 1);
+        assert_eq!(fix.edits[0].column, diagnostics[0].column);
+        assert_eq!(fix.edits[0].end_line, 1);
+        assert_eq!(fix.edits[0].end_column, diagnostics[0].column + 2);
     }
 }
@@ -679,9 +679,10 @@ console.log(x);
         let fix = &diagnostics[0].fixes[0];
         assert_eq!(fix.title, "Replace 'let' with 'const'");
         assert!(matches!(
-            &fix.kind,
+            &fix.edits[0].kind,
             crate::diagnostic::FixKind::ReplaceWith { new_text } if new_text == "const"
         ));
+        assert!(fix.is_safe());
     }
 
     #[test]
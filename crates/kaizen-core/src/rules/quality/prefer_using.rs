@@ -10,7 +10,7 @@ use swc_ecma_ast::{
 };
 
 use crate::declare_rule;
-use crate::diagnostic::{Diagnostic, Fix};
+use crate::diagnostic::{Applicability, Diagnostic, Fix};
 use crate::parser::ParsedFile;
 use crate::rules::{Confidence, Rule, RuleMetadata, Severity};
 use crate::semantic::types::DisposableTypesRegistry;
@@ -265,7 +265,8 @@ impl<'a> PreferUsingVisitor<'a> {
                     keyword_column,
                     keyword_line,
                     keyword_column + keyword_len - 1,
-                );
+                )
+                .with_applicability(Applicability::Suggestion);
 
                 let diagnostic =
                     Diagnostic::new("Q020", severity, message, &self.file_path, line, column)
@@ -0,0 +1,28 @@
+//! unknown-disable-rule rule (M005): Reports disable comments naming rules that do not exist
+
+use crate::declare_rule;
+use crate::diagnostic::Diagnostic;
+use crate::parser::ParsedFile;
+use crate::rules::{Rule, RuleMetadata};
+
+declare_rule!(
+    UnknownDisableRule,
+    id = "M005",
+    name = "unknown-disable-rule",
+    description = "Report disable comments that reference unknown rules",
+    category = Quality,
+    severity = Warning,
+    examples = "// Bad\n// kaizen-disable-next-line no-vars\nvar x = 1;\n\n// Good\n// kaizen-disable-next-line no-var\nvar x = 1;"
+);
+
+impl Rule for UnknownDisableRule {
+    fn metadata(&self) -> &RuleMetadata {
+        &self.metadata
+    }
+
+    /// Diagnostics need the full rule catalogue, so they are produced by
+    /// [`crate::suppressions`] rather than by walking the file.
+    fn check(&self, _file: &ParsedFile) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
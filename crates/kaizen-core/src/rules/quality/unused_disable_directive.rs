@@ -0,0 +1,28 @@
+//! unused-disable-directive rule (M004): Reports disable comments that suppress nothing
+
+use crate::declare_rule;
+use crate::diagnostic::Diagnostic;
+use crate::parser::ParsedFile;
+use crate::rules::{Rule, RuleMetadata};
+
+declare_rule!(
+    UnusedDisableDirective,
+    id = "M004",
+    name = "unused-disable-directive",
+    description = "Report disable comments that no longer suppress any diagnostic",
+    category = Quality,
+    severity = Warning,
+    examples = "// Bad\n// kaizen-disable-next-line no-var\nconst x = 1;\n\n// Good\nconst x = 1;"
+);
+
+impl Rule for UnusedDisableDirective {
+    fn metadata(&self) -> &RuleMetadata {
+        &self.metadata
+    }
+
+    /// Diagnostics depend on what every other rule reported, so they are produced by
+    /// [`crate::suppressions`] once the directives have been applied.
+    fn check(&self, _file: &ParsedFile) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
@@ -1,9 +1,13 @@
 //! no-command-injection rule (S003): Detects command injection vulnerabilities via taint analysis
 
+use std::sync::Arc;
+
 use crate::declare_rule;
-use crate::diagnostic::Diagnostic;
+use crate::diagnostic::{Diagnostic, RelatedLocation};
 use crate::parser::ParsedFile;
-use crate::rules::{Rule, RuleMetadata, Severity};
+use crate::project::Project;
+use crate::rules::security::{TaintOptions, cross_file_flows, trace_locations};
+use crate::rules::{Rule, RuleMetadata, RuleScope, Severity};
 use crate::taint::{TaintAnalyzer, TaintSinkCategory};
 use crate::visitor::VisitorContext;
 
@@ -14,7 +18,8 @@ declare_rule!(
     description = "Disallow shell commands constructed with untrusted data",
     category = Security,
     severity = Error,
-    examples = "// Bad\nconst cmd = req.body.command;\nexec(\"rm \" + cmd);\n\n// Good\nexecFile(\"rm\", [filename]);"
+    examples = "// Bad\nconst cmd = req.body.command;\nexec(\"rm \" + cmd);\n\n// Good\nexecFile(\"rm\", [filename]);",
+    options = TaintOptions
 );
 
 impl Rule for CommandInjection {
@@ -22,9 +27,17 @@ impl Rule for CommandInjection {
         &self.metadata
     }
 
+    fn with_taint_analyzer(&self, analyzer: &Arc<TaintAnalyzer>) -> Option<Box<dyn Rule>> {
+        Some(Box::new(Self {
+            metadata: self.metadata.clone(),
+            options: TaintOptions {
+                analyzer: Arc::clone(analyzer),
+            },
+        }))
+    }
+
     fn check(&self, file: &ParsedFile) -> Vec<Diagnostic> {
-        let analyzer = TaintAnalyzer::new();
-        let findings = analyzer.analyze(file);
+        let findings = self.options.analyzer.analyze(file);
         let ctx = VisitorContext::new(file);
 
         findings
@@ -34,25 +47,59 @@ impl Rule for CommandInjection {
                 let (sink_line, sink_column) = ctx.span_to_location(finding.sink_span);
                 let (source_line, _) = ctx.span_to_location(finding.source_span);
 
-                let message = format!(
-                    "Potential command injection: untrusted data from line {} flows to {}",
-                    source_line, finding.sink_description
-                );
-
-                Diagnostic::new(
-                    "S003",
-                    Severity::Error,
-                    message,
+                report(
+                    &format!("line {}", source_line),
+                    &finding.sink_description,
                     &file.metadata().filename,
                     sink_line,
                     sink_column,
-                )
-                .with_suggestion(
-                    "Use execFile with an arguments array or sanitize input with shell-escape",
+                    trace_locations(&finding.trace, file, None),
                 )
             })
             .collect()
     }
+
+    fn scope(&self) -> RuleScope {
+        RuleScope::FileAndProject
+    }
+
+    fn check_project(&self, project: &Project) -> Vec<Diagnostic> {
+        cross_file_flows(
+            &self.options.analyzer,
+            project,
+            &[TaintSinkCategory::CommandInjection],
+        )
+        .into_iter()
+        .map(|flow| {
+            report(
+                &flow.source,
+                &flow.sink_description,
+                &flow.file,
+                flow.line,
+                flow.column,
+                flow.trace,
+            )
+        })
+        .collect()
+    }
+}
+
+fn report(
+    source: &str,
+    sink_description: &str,
+    file: &str,
+    line: usize,
+    column: usize,
+    trace: Vec<RelatedLocation>,
+) -> Diagnostic {
+    let message = format!(
+        "Potential command injection: untrusted data from {} flows to {}",
+        source, sink_description
+    );
+
+    Diagnostic::new("S003", Severity::Error, message, file, line, column)
+        .with_trace(trace)
+        .with_suggestion("Use execFile with an arguments array or sanitize input with shell-escape")
 }
 
 #[cfg(test)]
@@ -215,6 +262,20 @@ mod tests {
         assert!(diagnostics.is_empty(), "should not flag static commands");
     }
 
+    #[test]
+    fn no_false_positive_for_axios_interceptor() {
+        let code = r#"
+            axios.interceptors.request.use((config) => {
+                exec('log ' + config.url);
+                return config;
+            });
+        "#;
+
+        let diagnostics = run_command_injection(code);
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
     #[test]
     fn no_false_positive_for_literal_only() {
         let code = r#"
@@ -315,4 +376,39 @@ mod tests {
             "should not flag shlex.quote sanitized input"
         );
     }
+
+    #[test]
+    fn detects_command_run_by_local_helper() {
+        let code = r#"
+            function checkout(branch) {
+                exec("git checkout " + branch);
+            }
+
+            function handler(req, res) {
+                checkout(req.query.branch);
+            }
+        "#;
+
+        let diagnostics = run_command_injection(code);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 3);
+    }
+
+    #[test]
+    fn no_false_positive_for_helper_that_escapes() {
+        let code = r#"
+            function quote(arg) {
+                return shellEscape(arg);
+            }
+
+            function handler(req, res) {
+                exec("ls " + quote(req.query.dir));
+            }
+        "#;
+
+        let diagnostics = run_command_injection(code);
+
+        assert!(diagnostics.is_empty(), "quote() escapes its argument");
+    }
 }
@@ -0,0 +1,687 @@
+//! no-insecure-jwt rule (S009): Detects JWT handling that lets forged or expired tokens through
+
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+use swc_common::Spanned;
+use swc_ecma_ast::{
+    BinaryOp, CallExpr, Callee, Expr, ExprOrSpread, Lit, MemberProp, ObjectLit, Pat, Prop,
+    PropName, PropOrSpread, VarDecl,
+};
+
+use crate::declare_rule;
+use crate::diagnostic::{Diagnostic, RelatedLocation};
+use crate::parser::ParsedFile;
+use crate::project::Project;
+use crate::rules::security::{TaintOptions, cross_file_flows, trace_locations};
+use crate::rules::{Confidence, Rule, RuleMetadata, RuleScope, Severity};
+use crate::taint::{TaintAnalyzer, TaintSinkCategory};
+use crate::visitor::{AstVisitor, VisitorContext, walk_ast};
+
+declare_rule!(
+    InsecureJwt,
+    id = "S009",
+    name = "no-insecure-jwt",
+    description = "Disallow JWT handling that accepts forged or expired tokens",
+    category = Security,
+    severity = Error,
+    examples = "// Bad\nconst user = jwt.decode(req.headers.authorization);\njwt.verify(token, 'secret');\n\n// Good\nconst user = jwt.verify(token, process.env.JWT_SECRET, { algorithms: ['HS256'] });",
+    options = TaintOptions
+);
+
+/// HS256 needs a key of 256 bits.
+const MIN_SECRET_LENGTH: usize = 32;
+
+impl Rule for InsecureJwt {
+    fn metadata(&self) -> &RuleMetadata {
+        &self.metadata
+    }
+
+    fn with_taint_analyzer(&self, analyzer: &Arc<TaintAnalyzer>) -> Option<Box<dyn Rule>> {
+        Some(Box::new(Self {
+            metadata: self.metadata.clone(),
+            options: TaintOptions {
+                analyzer: Arc::clone(analyzer),
+            },
+        }))
+    }
+
+    fn check(&self, file: &ParsedFile) -> Vec<Diagnostic> {
+        let Some(module) = file.module() else {
+            return Vec::new();
+        };
+
+        let ctx = VisitorContext::new(file);
+        let mut visitor = InsecureJwtVisitor {
+            diagnostics: Vec::new(),
+            file_path: &file.metadata().filename,
+            ctx: &ctx,
+            strings: HashMap::new(),
+            options: HashMap::new(),
+        };
+        walk_ast(module, &mut visitor, &ctx);
+
+        let findings = self.options.analyzer.analyze(file);
+        let decodes = findings
+            .into_iter()
+            .filter(|finding| finding.sink_category == TaintSinkCategory::JwtDecode)
+            .map(|finding| {
+                let (sink_line, sink_column) = ctx.span_to_location(finding.sink_span);
+                let (source_line, _) = ctx.span_to_location(finding.source_span);
+
+                report_decode(
+                    &format!("line {}", source_line),
+                    &finding.sink_description,
+                    finding.result_trusted,
+                    &file.metadata().filename,
+                    sink_line,
+                    sink_column,
+                    trace_locations(&finding.trace, file, None),
+                )
+            });
+
+        let mut diagnostics = visitor.diagnostics;
+        diagnostics.extend(decodes);
+        diagnostics
+    }
+
+    fn scope(&self) -> RuleScope {
+        RuleScope::FileAndProject
+    }
+
+    fn check_project(&self, project: &Project) -> Vec<Diagnostic> {
+        cross_file_flows(
+            &self.options.analyzer,
+            project,
+            &[TaintSinkCategory::JwtDecode],
+        )
+        .into_iter()
+        .map(|flow| {
+            report_decode(
+                &flow.source,
+                &flow.sink_description,
+                flow.result_trusted,
+                &flow.file,
+                flow.line,
+                flow.column,
+                flow.trace,
+            )
+        })
+        .collect()
+    }
+}
+
+/// Reports a decoded token, as an error when the claims are relied on and as
+/// a low-confidence warning when they may only be logged or displayed.
+fn report_decode(
+    source: &str,
+    sink_description: &str,
+    trusted: bool,
+    file: &str,
+    line: usize,
+    column: usize,
+    trace: Vec<RelatedLocation>,
+) -> Diagnostic {
+    let message = format!(
+        "Unverified JWT: untrusted token from {} is read with {}, which does not check its signature",
+        source, sink_description
+    );
+
+    let (severity, confidence) = if trusted {
+        (Severity::Error, Confidence::High)
+    } else {
+        (Severity::Warning, Confidence::Low)
+    };
+
+    Diagnostic::new("S009", severity, message, file, line, column)
+        .with_confidence(confidence)
+        .with_trace(trace)
+        .with_suggestion(
+            "Use jwt.verify() or jwtVerify() to read the claims of a token before trusting them",
+        )
+}
+
+/// The `algorithms` and `ignoreExpiration` options of a verification.
+#[derive(Debug, Clone, Default)]
+struct VerifyOptions {
+    /// The allowed algorithms, empty when they are not literals, or `None`
+    /// without an `algorithms` option.
+    algorithms: Option<Vec<String>>,
+    ignore_expiration: bool,
+}
+
+impl VerifyOptions {
+    fn from_object(object: &ObjectLit) -> Self {
+        let mut options = Self::default();
+        for prop in &object.props {
+            let PropOrSpread::Prop(prop) = prop else {
+                continue;
+            };
+            let Prop::KeyValue(kv) = prop.as_ref() else {
+                continue;
+            };
+            match prop_name(&kv.key).as_deref() {
+                Some("algorithms") => {
+                    options.algorithms = Some(match kv.value.as_ref() {
+                        Expr::Array(array) => array
+                            .elems
+                            .iter()
+                            .flatten()
+                            .filter_map(|element| match element.expr.as_ref() {
+                                Expr::Lit(Lit::Str(s)) => Some(s.value.to_string()),
+                                _ => None,
+                            })
+                            .collect(),
+                        _ => Vec::new(),
+                    });
+                }
+                Some("ignoreExpiration") => {
+                    options.ignore_expiration =
+                        matches!(kv.value.as_ref(), Expr::Lit(Lit::Bool(b)) if b.value);
+                }
+                _ => {}
+            }
+        }
+        options
+    }
+
+    fn allows_none(&self) -> bool {
+        self.algorithms
+            .iter()
+            .flatten()
+            .any(|algorithm| algorithm.eq_ignore_ascii_case("none"))
+    }
+}
+
+fn prop_name(key: &PropName) -> Option<String> {
+    match key {
+        PropName::Ident(ident) => Some(ident.sym.to_string()),
+        PropName::Str(s) => Some(s.value.to_string()),
+        _ => None,
+    }
+}
+
+fn has_property(object: &ObjectLit, name: &str) -> bool {
+    object.props.iter().any(|prop| match prop {
+        PropOrSpread::Prop(prop) => match prop.as_ref() {
+            Prop::KeyValue(kv) => prop_name(&kv.key).as_deref() == Some(name),
+            Prop::Shorthand(ident) => ident.sym.as_ref() == name,
+            _ => false,
+        },
+        PropOrSpread::Spread(_) => false,
+    })
+}
+
+fn property<'a>(object: &'a ObjectLit, name: &str) -> Option<&'a Expr> {
+    object.props.iter().find_map(|prop| match prop {
+        PropOrSpread::Prop(prop) => match prop.as_ref() {
+            Prop::KeyValue(kv) if prop_name(&kv.key).as_deref() == Some(name) => {
+                Some(kv.value.as_ref())
+            }
+            _ => None,
+        },
+        PropOrSpread::Spread(_) => None,
+    })
+}
+
+/// The object and the name of a called function or method.
+fn callee(call: &CallExpr) -> Option<(Option<&Expr>, &str)> {
+    let Callee::Expr(callee) = &call.callee else {
+        return None;
+    };
+    match callee.as_ref() {
+        Expr::Ident(ident) => Some((None, ident.sym.as_ref())),
+        Expr::Member(member) => match &member.prop {
+            MemberProp::Ident(prop) => Some((Some(member.obj.as_ref()), prop.sym.as_ref())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn ident_name(expr: Option<&Expr>) -> Option<&str> {
+    match expr {
+        Some(Expr::Ident(ident)) => Some(ident.sym.as_ref()),
+        _ => None,
+    }
+}
+
+/// Whether `expr` is a `new SignJWT(payload)` chain, such as
+/// `new SignJWT(payload).setProtectedHeader({ alg: 'HS256' })`.
+fn is_sign_jwt(expr: &Expr) -> bool {
+    match expr {
+        Expr::New(new) => {
+            matches!(new.callee.as_ref(), Expr::Ident(ident) if ident.sym.as_ref() == "SignJWT")
+        }
+        Expr::Call(call) => callee(call)
+            .and_then(|(object, _)| object)
+            .is_some_and(is_sign_jwt),
+        _ => false,
+    }
+}
+
+struct InsecureJwtVisitor<'a> {
+    diagnostics: Vec<Diagnostic>,
+    file_path: &'a str,
+    ctx: &'a VisitorContext<'a>,
+    /// Variables holding a string literal, for secrets kept in a constant.
+    strings: HashMap<String, String>,
+    /// Variables holding an object literal, for options kept in a constant.
+    options: HashMap<String, VerifyOptions>,
+}
+
+impl InsecureJwtVisitor<'_> {
+    /// The string literal a key is made of, including the fallback of
+    /// `process.env.JWT_SECRET || 'secret'` and literals passed to
+    /// `Buffer.from()` or `TextEncoder.encode()`.
+    fn literal_secret(&self, expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::Lit(Lit::Str(s)) => Some(s.value.to_string()),
+            Expr::Tpl(tpl) if tpl.exprs.is_empty() => {
+                Some(tpl.quasis.iter().map(|q| q.raw.to_string()).collect())
+            }
+            Expr::Ident(ident) => self.strings.get(ident.sym.as_ref()).cloned(),
+            Expr::Paren(paren) => self.literal_secret(&paren.expr),
+            Expr::Bin(bin)
+                if matches!(bin.op, BinaryOp::LogicalOr | BinaryOp::NullishCoalescing) =>
+            {
+                self.literal_secret(&bin.right)
+            }
+            Expr::Call(call) => match callee(call)? {
+                (Some(_), "encode") | (Some(Expr::Ident(_)), "from") => {
+                    self.literal_secret(&call.args.first()?.expr)
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn check_secret(&mut self, function: &str, key: Option<&Expr>) {
+        let Some(key) = key else {
+            return;
+        };
+        let Some(secret) = self.literal_secret(key) else {
+            return;
+        };
+        let length = secret.chars().count();
+        if length >= MIN_SECRET_LENGTH {
+            return;
+        }
+
+        self.report(
+            key,
+            Severity::Error,
+            format!(
+                "JWT secret passed to {}() is a {}-character string literal, which can be guessed",
+                function, length
+            ),
+            "Use a random secret of at least 32 characters, loaded from the environment or a secret manager",
+        );
+    }
+
+    /// Checks the options of a verification, which must list the allowed
+    /// algorithms if `require_algorithms` is set.
+    fn check_options(
+        &mut self,
+        function: &str,
+        call: &CallExpr,
+        options: Option<&Expr>,
+        require_algorithms: bool,
+    ) {
+        let options = match options {
+            None | Some(Expr::Fn(_) | Expr::Arrow(_)) => VerifyOptions::default(),
+            Some(Expr::Object(object)) => VerifyOptions::from_object(object),
+            Some(Expr::Ident(ident)) => match self.options.get(ident.sym.as_ref()) {
+                Some(options) => options.clone(),
+                None => return,
+            },
+            Some(_) => return,
+        };
+
+        if options.allows_none() {
+            self.report(
+                call,
+                Severity::Error,
+                format!(
+                    "{}() allows the 'none' algorithm, which accepts unsigned tokens",
+                    function
+                ),
+                "Remove 'none' from algorithms and list only the algorithms your tokens are signed with",
+            );
+        } else if options.algorithms.is_none() && require_algorithms {
+            self.report(
+                call,
+                Severity::Warning,
+                format!(
+                    "{}() without an algorithms allowlist accepts the algorithm named by the token",
+                    function
+                ),
+                "Pass the algorithms your tokens are signed with, such as { algorithms: ['HS256'] }",
+            );
+        }
+
+        if options.ignore_expiration {
+            self.report(
+                call,
+                Severity::Warning,
+                format!(
+                    "{}() with ignoreExpiration: true accepts expired tokens",
+                    function
+                ),
+                "Remove ignoreExpiration and let expired tokens be rejected",
+            );
+        }
+    }
+
+    fn report(
+        &mut self,
+        node: &impl Spanned,
+        severity: Severity,
+        message: String,
+        suggestion: &str,
+    ) {
+        let (line, column) = self.ctx.span_to_location(node.span());
+        self.diagnostics.push(
+            Diagnostic::new("S009", severity, message, self.file_path, line, column)
+                .with_suggestion(suggestion),
+        );
+    }
+}
+
+fn arg(args: &[ExprOrSpread], index: usize) -> Option<&Expr> {
+    args.get(index).map(|arg| arg.expr.as_ref())
+}
+
+impl AstVisitor for InsecureJwtVisitor<'_> {
+    fn visit_var_decl(&mut self, node: &VarDecl, _ctx: &VisitorContext) -> ControlFlow<()> {
+        for decl in &node.decls {
+            let (Pat::Ident(name), Some(init)) = (&decl.name, &decl.init) else {
+                continue;
+            };
+            let name = name.id.sym.to_string();
+            if let Expr::Object(object) = init.as_ref() {
+                self.options
+                    .insert(name, VerifyOptions::from_object(object));
+            } else if let Some(secret) = self.literal_secret(init) {
+                self.strings.insert(name, secret);
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn visit_call_expr(&mut self, node: &CallExpr, _ctx: &VisitorContext) -> ControlFlow<()> {
+        let Some((object, name)) = callee(node) else {
+            return ControlFlow::Continue(());
+        };
+        let args = &node.args;
+
+        match (ident_name(object), name) {
+            // jsonwebtoken
+            (Some("jwt" | "jsonwebtoken"), "verify") => {
+                self.check_secret("jwt.verify", arg(args, 1));
+                self.check_options("jwt.verify", node, arg(args, 2), true);
+            }
+            (Some("jwt" | "jsonwebtoken"), "sign") => {
+                self.check_secret("jwt.sign", arg(args, 1));
+            }
+            // jose, which matches the algorithm to the type of the key
+            (Some("jose") | None, "jwtVerify") => {
+                self.check_secret("jwtVerify", arg(args, 1));
+                self.check_options("jwtVerify", node, arg(args, 2), false);
+            }
+            (_, "sign") if object.is_some_and(is_sign_jwt) => {
+                self.check_secret("SignJWT.sign", arg(args, 0));
+            }
+            // express-jwt, whose options go to jsonwebtoken
+            (None, "expressjwt" | "expressJwt" | "jwt") => {
+                if let Some(Expr::Object(options)) = arg(args, 0)
+                    && has_property(options, "secret")
+                {
+                    self.check_secret(name, property(options, "secret"));
+                    self.check_options(name, node, arg(args, 0), true);
+                }
+            }
+            _ => {}
+        }
+
+        ControlFlow::Continue(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_insecure_jwt(code: &str) -> Vec<Diagnostic> {
+        let file = ParsedFile::from_source("test.js", code);
+        let rule = InsecureJwt::new();
+        rule.check(&file)
+    }
+
+    #[test]
+    fn detects_decode_of_request_token() {
+        let code = r#"
+            app.get('/admin', (req, res) => {
+                const token = req.headers.authorization.split(' ')[1];
+                const claims = jwt.decode(token);
+                if (claims.role !== 'admin') {
+                    return res.sendStatus(403);
+                }
+                res.json(report());
+            });
+        "#;
+
+        let diagnostics = run_insecure_jwt(code);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule_id, "S009");
+        assert_eq!(diagnostics[0].line, 4);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert!(diagnostics[0].message.contains("from line 2"));
+        assert!(
+            diagnostics[0]
+                .message
+                .contains("does not check its signature")
+        );
+    }
+
+    #[test]
+    fn detects_jose_decode_of_cookie() {
+        let code = r#"
+            export async function middleware(req, res) {
+                const { sub } = jose.decodeJwt(req.cookies.session);
+                req.user = await User.findById(sub);
+            }
+        "#;
+
+        let diagnostics = run_insecure_jwt(code);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("decodeJwt"));
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn decode_for_logging_is_a_low_confidence_warning() {
+        let code = r#"
+            function audit(req, res, next) {
+                const claims = jwt.decode(req.headers.authorization);
+                logger.info(claims && claims.sub);
+                next();
+            }
+        "#;
+
+        let diagnostics = run_insecure_jwt(code);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].confidence, Confidence::Low);
+    }
+
+    #[test]
+    fn no_false_positive_when_decoded_token_is_verified() {
+        let code = r#"
+            function authenticate(req, res, next) {
+                const token = req.headers.authorization;
+                const { header } = jwt.decode(token, { complete: true });
+                req.user = jwt.verify(token, keys[header.kid], { algorithms: ['RS256'] });
+                next();
+            }
+        "#;
+
+        let diagnostics = run_insecure_jwt(code);
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn detects_verify_without_algorithms() {
+        let code = r#"
+            jwt.verify(token, process.env.JWT_PUBLIC_KEY);
+            jwt.verify(token, process.env.JWT_PUBLIC_KEY, (err, claims) => done(err, claims));
+            jwt.verify(token, process.env.JWT_PUBLIC_KEY, { audience: 'api' });
+        "#;
+
+        let diagnostics = run_insecure_jwt(code);
+
+        assert_eq!(diagnostics.len(), 3);
+        assert!(diagnostics[0].message.contains("algorithms allowlist"));
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn allows_verify_with_algorithms() {
+        let code = r#"
+            const VERIFY_OPTIONS = { algorithms: ['RS256'], audience: 'api' };
+            jwt.verify(token, process.env.JWT_PUBLIC_KEY, { algorithms: ['HS256'] });
+            jwt.verify(token, process.env.JWT_PUBLIC_KEY, VERIFY_OPTIONS);
+            jwt.verify(token, process.env.JWT_PUBLIC_KEY, buildOptions());
+            await jwtVerify(token, publicKey);
+        "#;
+
+        let diagnostics = run_insecure_jwt(code);
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn detects_none_algorithm() {
+        let code = r#"
+            jwt.verify(token, publicKey, { algorithms: ['RS256', 'none'] });
+            await jose.jwtVerify(token, publicKey, { algorithms: ['none'] });
+        "#;
+
+        let diagnostics = run_insecure_jwt(code);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics[0].message.contains("'none'"));
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn detects_ignore_expiration() {
+        let code = r#"
+            jwt.verify(token, publicKey, { algorithms: ['RS256'], ignoreExpiration: true });
+            jwt.verify(token, publicKey, { algorithms: ['RS256'], ignoreExpiration: false });
+        "#;
+
+        let diagnostics = run_insecure_jwt(code);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("expired tokens"));
+    }
+
+    #[test]
+    fn detects_short_literal_secrets() {
+        let code = r#"
+            const SECRET = 'keyboard cat';
+            jwt.sign({ sub: user.id }, 'secret');
+            jwt.verify(token, SECRET, { algorithms: ['HS256'] });
+            jwt.sign(payload, process.env.JWT_SECRET || 'changeme');
+            await new SignJWT(payload)
+                .setProtectedHeader({ alg: 'HS256' })
+                .sign(new TextEncoder().encode('dev-secret'));
+        "#;
+
+        let diagnostics = run_insecure_jwt(code);
+
+        assert_eq!(diagnostics.len(), 4);
+        assert!(diagnostics[0].message.contains("6-character"));
+        assert!(diagnostics[1].message.contains("12-character"));
+        assert!(diagnostics[3].message.contains("SignJWT.sign"));
+    }
+
+    #[test]
+    fn allows_secrets_from_environment_or_long_enough() {
+        let code = r#"
+            jwt.sign(payload, process.env.JWT_SECRET);
+            jwt.sign(payload, config.jwt.secret);
+            jwt.sign(payload, privateKey, { algorithm: 'RS256' });
+            jwt.sign(payload, process.env.JWT_SECRET ?? 'f3b1c9e27a4d8e6f0b5c2a9d7e1f4b8c');
+        "#;
+
+        let diagnostics = run_insecure_jwt(code);
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn detects_express_jwt_misconfiguration() {
+        let code = r#"
+            app.use(expressjwt({ secret: 'shhhhhhared-secret', algorithms: ['HS256'] }));
+            app.use(jwt({ secret: process.env.JWT_SECRET }));
+            app.use(expressjwt({ secret: jwksSecret, algorithms: ['RS256'], ignoreExpiration: true }));
+            app.use(expressjwt({ secret: jwksSecret, algorithms: ['RS256'] }));
+        "#;
+
+        let diagnostics = run_insecure_jwt(code);
+
+        assert_eq!(diagnostics.len(), 3);
+        assert!(diagnostics[0].message.contains("18-character"));
+        assert!(diagnostics[1].message.contains("algorithms allowlist"));
+        assert!(diagnostics[2].message.contains("ignoreExpiration"));
+    }
+
+    #[test]
+    fn reports_decode_in_imported_module() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                "auth.js",
+                "exports.claims = (token) => jwt.decode(token);\n",
+            ),
+            (
+                "routes.js",
+                "const auth = require('./auth');\n\nrouter.get('/me', (q, s) => s.json(auth.claims(q.headers.authorization)));\n",
+            ),
+        ];
+        let parsed = files
+            .iter()
+            .map(|(name, source)| {
+                let path = dir.path().join(name);
+                std::fs::write(&path, source).unwrap();
+                ParsedFile::from_source(&path.to_string_lossy(), source)
+            })
+            .collect();
+
+        let diagnostics = InsecureJwt::new().check_project(&Project::new(parsed));
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].file.ends_with("auth.js"));
+        assert!(diagnostics[0].message.contains("routes.js:3"));
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn metadata_is_correct() {
+        let rule = InsecureJwt::new();
+        let metadata = rule.metadata();
+
+        assert_eq!(metadata.id, "S009");
+        assert_eq!(metadata.name, "no-insecure-jwt");
+        assert_eq!(metadata.category, crate::rules::RuleCategory::Security);
+        assert_eq!(metadata.severity, Severity::Error);
+    }
+}
@@ -3,10 +3,15 @@
 pub mod command_injection;
 pub mod eval_injection;
 pub mod hardcoded_secrets;
+pub mod insecure_jwt;
 pub mod insecure_random;
+pub mod nosql_injection;
+pub mod open_redirect;
+pub mod path_traversal;
 pub mod prototype_pollution;
 pub mod redos;
 pub mod sql_injection;
+pub mod ssrf;
 pub mod unsafe_deserialization;
 pub mod weak_hashing;
 pub mod xss;
@@ -14,10 +19,138 @@ pub mod xss;
 pub use command_injection::CommandInjection;
 pub use eval_injection::EvalInjection;
 pub use hardcoded_secrets::HardcodedSecrets;
+pub use insecure_jwt::InsecureJwt;
 pub use insecure_random::InsecureRandom;
+pub use nosql_injection::NoSqlInjection;
+pub use open_redirect::OpenRedirect;
+pub use path_traversal::PathTraversal;
 pub use prototype_pollution::PrototypePollution;
 pub use redos::ReDoS;
 pub use sql_injection::SqlInjection;
+pub use ssrf::Ssrf;
 pub use unsafe_deserialization::UnsafeDeserialization;
 pub use weak_hashing::WeakHashing;
 pub use xss::Xss;
+
+use std::sync::{Arc, LazyLock};
+
+use crate::diagnostic::RelatedLocation;
+use crate::parser::ParsedFile;
+use crate::project::Project;
+use crate::taint::{TaintAnalyzer, TaintSinkCategory, TraceStep};
+use crate::visitor::VisitorContext;
+
+/// Shared by the taint rules without a `[taint]` configuration, so that they
+/// also share its project findings.
+static DEFAULT_ANALYZER: LazyLock<Arc<TaintAnalyzer>> =
+    LazyLock::new(|| Arc::new(TaintAnalyzer::new()));
+
+/// The taint analyzer of a taint rule, built from the `[taint]` configuration.
+#[derive(Debug, Clone)]
+pub(crate) struct TaintOptions {
+    pub analyzer: Arc<TaintAnalyzer>,
+}
+
+impl Default for TaintOptions {
+    fn default() -> Self {
+        Self {
+            analyzer: Arc::clone(&DEFAULT_ANALYZER),
+        }
+    }
+}
+
+/// A taint flow from a source in one file to a sink in a module it imports.
+pub(crate) struct CrossFileFlow {
+    /// The file of the sink, where the flow is reported.
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    /// Where the untrusted data comes from, as `file:line`.
+    pub source: String,
+    pub sink_description: String,
+    /// Constant text the tainted sink argument starts with.
+    pub argument_prefix: Option<String>,
+    /// Whether the code relies on the value the sink returns.
+    pub result_trusted: bool,
+    /// Whether a check that may not hold for every value guards the sink argument.
+    pub uncertainly_guarded: bool,
+    pub trace: Vec<RelatedLocation>,
+}
+
+/// Flows into sinks of `categories` whose sink is in another file than their source.
+pub(crate) fn cross_file_flows(
+    analyzer: &TaintAnalyzer,
+    project: &Project,
+    categories: &[TaintSinkCategory],
+) -> Vec<CrossFileFlow> {
+    analyzer
+        .analyze_project(project)
+        .into_iter()
+        .filter(|flow| categories.contains(&flow.finding.sink_category))
+        .filter_map(|flow| {
+            let file = flow.finding.sink_file?;
+            let sink = project.file_named(&file)?;
+            let source = project.file_named(&flow.source_file)?;
+            let (line, column) = VisitorContext::new(sink).span_to_location(flow.finding.sink_span);
+            let (source_line, _) =
+                VisitorContext::new(source).span_to_location(flow.finding.source_span);
+            Some(CrossFileFlow {
+                file,
+                line,
+                column,
+                source: format!(
+                    "{}:{}",
+                    project.display_name(&flow.source_file),
+                    source_line
+                ),
+                sink_description: flow.finding.sink_description,
+                argument_prefix: flow.finding.argument_prefix,
+                result_trusted: flow.finding.result_trusted,
+                uncertainly_guarded: flow.finding.uncertainly_guarded,
+                trace: trace_locations(&flow.finding.trace, source, Some(project)),
+            })
+        })
+        .collect()
+}
+
+/// Whether a URL starting with `prefix` stays on one origin whatever follows,
+/// as `https://api.example.com/users/` or `/api/` do but `https://api.example.com`
+/// (which `.evil.com` extends) and `https://` do not.
+pub(crate) fn has_fixed_origin(prefix: &str) -> bool {
+    if let Some(path) = prefix.strip_prefix('/') {
+        return !path.is_empty() && !path.starts_with(['/', '\\']);
+    }
+    match prefix.split_once("://") {
+        Some((scheme, rest)) => {
+            !scheme.is_empty() && rest.find(['/', '?', '#']).is_some_and(|end| end > 0)
+        }
+        None => false,
+    }
+}
+
+/// A taint trace as locations, resolving steps in other files through `project`
+/// and naming files as it does.
+pub(crate) fn trace_locations(
+    trace: &[TraceStep],
+    source: &ParsedFile,
+    project: Option<&Project>,
+) -> Vec<RelatedLocation> {
+    trace
+        .iter()
+        .filter_map(|step| {
+            let file = match &step.file {
+                Some(name) => project?.file_named(name)?,
+                None => source,
+            };
+            let (line, column, end_line, end_column) =
+                VisitorContext::new(file).span_to_range(step.span);
+            let filename = &file.metadata().filename;
+            let filename =
+                project.map_or(filename.as_str(), |project| project.display_name(filename));
+            Some(
+                RelatedLocation::new(step.message.clone(), filename.to_string(), line, column)
+                    .with_end(end_line, end_column),
+            )
+        })
+        .collect()
+}
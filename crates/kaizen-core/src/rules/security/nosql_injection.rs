@@ -0,0 +1,304 @@
+//! no-nosql-injection rule (S008): Detects MongoDB queries built from untrusted objects via taint analysis
+
+use std::sync::Arc;
+
+use crate::declare_rule;
+use crate::diagnostic::{Diagnostic, RelatedLocation};
+use crate::parser::ParsedFile;
+use crate::project::Project;
+use crate::rules::security::{TaintOptions, cross_file_flows, trace_locations};
+use crate::rules::{Rule, RuleMetadata, RuleScope, Severity};
+use crate::taint::{TaintAnalyzer, TaintSinkCategory};
+use crate::visitor::VisitorContext;
+
+/// Query filters, and the JavaScript MongoDB runs for `$where` or `mapReduce`.
+const CATEGORIES: [TaintSinkCategory; 2] = [
+    TaintSinkCategory::NoSqlInjection,
+    TaintSinkCategory::NoSqlCodeExecution,
+];
+
+declare_rule!(
+    NoSqlInjection,
+    id = "S008",
+    name = "no-nosql-injection",
+    description = "Disallow MongoDB queries built from untrusted objects",
+    category = Security,
+    severity = Error,
+    examples = "// Bad\nconst user = await User.findOne({ email: req.body.email });\n\n// Good\nconst user = await User.findOne({ email: String(req.body.email) });",
+    options = TaintOptions
+);
+
+impl Rule for NoSqlInjection {
+    fn metadata(&self) -> &RuleMetadata {
+        &self.metadata
+    }
+
+    fn with_taint_analyzer(&self, analyzer: &Arc<TaintAnalyzer>) -> Option<Box<dyn Rule>> {
+        Some(Box::new(Self {
+            metadata: self.metadata.clone(),
+            options: TaintOptions {
+                analyzer: Arc::clone(analyzer),
+            },
+        }))
+    }
+
+    fn check(&self, file: &ParsedFile) -> Vec<Diagnostic> {
+        let findings = self.options.analyzer.analyze(file);
+        let ctx = VisitorContext::new(file);
+
+        findings
+            .into_iter()
+            .filter(|finding| CATEGORIES.contains(&finding.sink_category))
+            .map(|finding| {
+                let (sink_line, sink_column) = ctx.span_to_location(finding.sink_span);
+                let (source_line, _) = ctx.span_to_location(finding.source_span);
+
+                report(
+                    &format!("line {}", source_line),
+                    finding.sink_category,
+                    &finding.sink_description,
+                    &file.metadata().filename,
+                    sink_line,
+                    sink_column,
+                    trace_locations(&finding.trace, file, None),
+                )
+            })
+            .collect()
+    }
+
+    fn scope(&self) -> RuleScope {
+        RuleScope::FileAndProject
+    }
+
+    fn check_project(&self, project: &Project) -> Vec<Diagnostic> {
+        CATEGORIES
+            .into_iter()
+            .flat_map(|category| {
+                cross_file_flows(&self.options.analyzer, project, &[category])
+                    .into_iter()
+                    .map(move |flow| {
+                        report(
+                            &flow.source,
+                            category,
+                            &flow.sink_description,
+                            &flow.file,
+                            flow.line,
+                            flow.column,
+                            flow.trace,
+                        )
+                    })
+            })
+            .collect()
+    }
+}
+
+fn report(
+    source: &str,
+    category: TaintSinkCategory,
+    sink_description: &str,
+    file: &str,
+    line: usize,
+    column: usize,
+    trace: Vec<RelatedLocation>,
+) -> Diagnostic {
+    let message = format!(
+        "Potential NoSQL injection: untrusted data from {} flows to {}",
+        source, sink_description
+    );
+    let suggestion = if category == TaintSinkCategory::NoSqlCodeExecution {
+        "Use query operators instead of $where, $function or mapReduce code built from untrusted data"
+    } else {
+        "Convert the value with String() or validate the request against a schema, so that it cannot carry operators such as $ne"
+    };
+
+    Diagnostic::new("S008", Severity::Error, message, file, line, column)
+        .with_trace(trace)
+        .with_suggestion(suggestion)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_nosql_injection(code: &str) -> Vec<Diagnostic> {
+        let file = ParsedFile::from_source("test.js", code);
+        let rule = NoSqlInjection::new();
+        rule.check(&file)
+    }
+
+    #[test]
+    fn detects_request_value_in_mongoose_filter() {
+        let code = r#"
+            app.post('/login', async (req, res) => {
+                const user = await User.findOne({ email: req.body.email, password: req.body.password });
+                res.json(user);
+            });
+        "#;
+
+        let diagnostics = run_nosql_injection(code);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule_id, "S008");
+        assert_eq!(diagnostics[0].line, 3);
+        assert!(diagnostics[0].message.contains("NoSQL injection"));
+        assert!(
+            diagnostics[0]
+                .suggestion
+                .as_deref()
+                .unwrap()
+                .contains("String()")
+        );
+    }
+
+    #[test]
+    fn detects_whole_body_as_filter() {
+        let code = r#"
+            async function search(req, res) {
+                res.json(await Product.find(req.query));
+                await Order.deleteMany(req.body);
+            }
+        "#;
+
+        let diagnostics = run_nosql_injection(code);
+
+        assert_eq!(diagnostics.len(), 2);
+    }
+
+    #[test]
+    fn detects_driver_collections() {
+        let code = r#"
+            async function handler(req, res) {
+                await db.collection('users').find({ name: req.query.name }).toArray();
+                const sessions = db.collection('sessions');
+                await sessions.deleteOne({ token: req.body.token });
+            }
+        "#;
+
+        let diagnostics = run_nosql_injection(code);
+
+        assert_eq!(diagnostics.len(), 2);
+    }
+
+    #[test]
+    fn detects_aggregation_pipeline() {
+        let code = r#"
+            async function report(req, res) {
+                res.json(await Order.aggregate([{ $match: req.body.filter }]));
+            }
+        "#;
+
+        let diagnostics = run_nosql_injection(code);
+
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn detects_where_and_map_reduce_code() {
+        let code = r#"
+            async function search(req, res) {
+                await User.find({ $where: "this.name == '" + req.query.name + "'" });
+                await collection.mapReduce('function () { emit(' + req.body.key + ', 1); }', reduce);
+            }
+        "#;
+
+        let diagnostics = run_nosql_injection(code);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics[0].message.contains("server-side JavaScript"));
+        assert!(diagnostics[1].message.contains("mapReduce"));
+        assert!(
+            diagnostics[0]
+                .suggestion
+                .as_deref()
+                .unwrap()
+                .contains("$where")
+        );
+    }
+
+    #[test]
+    fn no_false_positive_for_converted_or_validated_values() {
+        let code = r#"
+            async function login(req, res) {
+                await User.findOne({ email: String(req.body.email) });
+                await User.findOne({ name: `${req.body.first} ${req.body.last}` });
+                await User.findOne({ name: `${req.body.name}` });
+                await User.findOne({ age: parseInt(req.query.age, 10) });
+                await User.findOne(sanitize(req.body));
+                const { value } = await schema.validateAsync(req.body);
+                await User.findOne(value);
+            }
+        "#;
+
+        let diagnostics = run_nosql_injection(code);
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn logical_operators_keep_objects() {
+        let code = r#"
+            async function list(req, res) {
+                await Product.find(req.query.filter || {});
+            }
+        "#;
+
+        let diagnostics = run_nosql_injection(code);
+
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn no_false_positive_for_arrays_and_constants() {
+        let code = r#"
+            function handler(req, res) {
+                const item = items.find((item) => item.id === req.params.id);
+                User.find({ active: true });
+                res.json(item);
+            }
+        "#;
+
+        let diagnostics = run_nosql_injection(code);
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn reports_query_in_imported_module() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                "users.js",
+                "exports.byEmail = (email) => User.findOne({ email });\n",
+            ),
+            (
+                "routes.js",
+                "const users = require('./users');\n\nrouter.post('/login', (q, s) => users.byEmail(q.body.email));\n",
+            ),
+        ];
+        let parsed = files
+            .iter()
+            .map(|(name, source)| {
+                let path = dir.path().join(name);
+                std::fs::write(&path, source).unwrap();
+                ParsedFile::from_source(&path.to_string_lossy(), source)
+            })
+            .collect();
+
+        let diagnostics = NoSqlInjection::new().check_project(&Project::new(parsed));
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].file.ends_with("users.js"));
+        assert!(diagnostics[0].message.contains("routes.js:3"));
+    }
+
+    #[test]
+    fn metadata_is_correct() {
+        let rule = NoSqlInjection::new();
+        let metadata = rule.metadata();
+
+        assert_eq!(metadata.id, "S008");
+        assert_eq!(metadata.name, "no-nosql-injection");
+        assert_eq!(metadata.category, crate::rules::RuleCategory::Security);
+        assert_eq!(metadata.severity, Severity::Error);
+    }
+}
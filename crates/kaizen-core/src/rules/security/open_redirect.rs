@@ -0,0 +1,335 @@
+//! no-open-redirect rule (S007): Detects redirects to targets built from untrusted data via taint analysis
+
+use std::sync::Arc;
+
+use crate::declare_rule;
+use crate::diagnostic::{Diagnostic, RelatedLocation};
+use crate::parser::ParsedFile;
+use crate::project::Project;
+use crate::rules::security::{TaintOptions, cross_file_flows, has_fixed_origin, trace_locations};
+use crate::rules::{Confidence, Rule, RuleMetadata, RuleScope, Severity};
+use crate::taint::{TaintAnalyzer, TaintSinkCategory};
+use crate::visitor::VisitorContext;
+
+declare_rule!(
+    OpenRedirect,
+    id = "S007",
+    name = "no-open-redirect",
+    description = "Disallow redirects to targets built from untrusted data",
+    category = Security,
+    severity = Warning,
+    examples = "// Bad\nres.redirect(req.query.next);\n\n// Good\nconst next = req.query.next;\nif (!next.startsWith('/') || next.startsWith('//')) return res.redirect('/');\nres.redirect(next);",
+    options = TaintOptions
+);
+
+impl Rule for OpenRedirect {
+    fn metadata(&self) -> &RuleMetadata {
+        &self.metadata
+    }
+
+    fn with_taint_analyzer(&self, analyzer: &Arc<TaintAnalyzer>) -> Option<Box<dyn Rule>> {
+        Some(Box::new(Self {
+            metadata: self.metadata.clone(),
+            options: TaintOptions {
+                analyzer: Arc::clone(analyzer),
+            },
+        }))
+    }
+
+    fn check(&self, file: &ParsedFile) -> Vec<Diagnostic> {
+        let findings = self.options.analyzer.analyze(file);
+        let ctx = VisitorContext::new(file);
+
+        findings
+            .into_iter()
+            .filter(|finding| finding.sink_category == TaintSinkCategory::OpenRedirect)
+            .map(|finding| {
+                let (sink_line, sink_column) = ctx.span_to_location(finding.sink_span);
+                let (source_line, _) = ctx.span_to_location(finding.source_span);
+
+                report(
+                    &format!("line {}", source_line),
+                    &finding.sink_description,
+                    finding.argument_prefix.as_deref(),
+                    &file.metadata().filename,
+                    sink_line,
+                    sink_column,
+                    trace_locations(&finding.trace, file, None),
+                )
+            })
+            .collect()
+    }
+
+    fn scope(&self) -> RuleScope {
+        RuleScope::FileAndProject
+    }
+
+    fn check_project(&self, project: &Project) -> Vec<Diagnostic> {
+        cross_file_flows(
+            &self.options.analyzer,
+            project,
+            &[TaintSinkCategory::OpenRedirect],
+        )
+        .into_iter()
+        .map(|flow| {
+            report(
+                &flow.source,
+                &flow.sink_description,
+                flow.argument_prefix.as_deref(),
+                &flow.file,
+                flow.line,
+                flow.column,
+                flow.trace,
+            )
+        })
+        .collect()
+    }
+}
+
+fn report(
+    source: &str,
+    sink_description: &str,
+    argument_prefix: Option<&str>,
+    file: &str,
+    line: usize,
+    column: usize,
+    trace: Vec<RelatedLocation>,
+) -> Diagnostic {
+    match argument_prefix.filter(|prefix| has_fixed_origin(prefix)) {
+        // The target stays on a known origin, such as `/profile/` + id
+        Some(prefix) => {
+            let message = format!(
+                "Potential open redirect: untrusted data from {} flows to {} under '{}'",
+                source, sink_description, prefix
+            );
+            Diagnostic::new("S007", Severity::Warning, message, file, line, column)
+                .with_confidence(Confidence::Low)
+                .with_trace(trace)
+                .with_suggestion(
+                    "Encode the untrusted segment with encodeURIComponent() so it cannot change the path",
+                )
+        }
+        None => {
+            let message = format!(
+                "Potential open redirect: untrusted data from {} flows to {}",
+                source, sink_description
+            );
+            Diagnostic::new("S007", Severity::Warning, message, file, line, column)
+                .with_trace(trace)
+                .with_suggestion(
+                    "Only redirect to relative paths (starting with '/' but not '//') or to URLs on an allowlist",
+                )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_open_redirect(code: &str) -> Vec<Diagnostic> {
+        let file = ParsedFile::from_source("test.js", code);
+        let rule = OpenRedirect::new();
+        rule.check(&file)
+    }
+
+    #[test]
+    fn detects_express_redirect_to_query_parameter() {
+        let code = r#"
+            app.post('/login', (req, res) => {
+                login(req.body);
+                res.redirect(req.query.next);
+            });
+        "#;
+
+        let diagnostics = run_open_redirect(code);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule_id, "S007");
+        assert_eq!(diagnostics[0].line, 4);
+        assert!(diagnostics[0].message.contains("open redirect"));
+    }
+
+    #[test]
+    fn detects_redirect_with_status_and_koa_redirect() {
+        let code = r#"
+            function logout(req, res) {
+                res.redirect(302, req.query.returnTo);
+            }
+            router.get('/callback', async (ctx) => {
+                ctx.redirect(ctx.query.state);
+            });
+        "#;
+
+        let diagnostics = run_open_redirect(code);
+
+        assert_eq!(diagnostics.len(), 2);
+    }
+
+    #[test]
+    fn detects_client_side_location_changes() {
+        let code = r#"
+            const next = new URLSearchParams(location.search).get('next');
+            window.location = next;
+            location.href = next;
+            location.assign(next);
+            window.location.replace(next);
+        "#;
+
+        let diagnostics = run_open_redirect(code);
+
+        assert_eq!(diagnostics.len(), 4);
+    }
+
+    #[test]
+    fn no_false_positive_after_relative_path_check() {
+        let code = r#"
+            function login(req, res) {
+                const next = req.query.next;
+                if (!next.startsWith('/') || next.startsWith('//')) {
+                    return res.redirect('/');
+                }
+                res.redirect(next);
+            }
+        "#;
+
+        let diagnostics = run_open_redirect(code);
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn half_of_relative_path_check_is_not_a_sanitizer() {
+        let code = r#"
+            function login(req, res) {
+                const next = req.query.next;
+                if (!next.startsWith('/')) {
+                    return res.redirect('/');
+                }
+                res.redirect(next);
+            }
+        "#;
+
+        let diagnostics = run_open_redirect(code);
+
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn relative_path_check_only_holds_where_it_is_checked() {
+        let code = r#"
+            function login(req, res) {
+                const next = req.query.next;
+                if (next.startsWith('/') && !next.startsWith('//')) return res.redirect(next);
+                res.redirect(next);
+            }
+        "#;
+
+        let diagnostics = run_open_redirect(code);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 5);
+    }
+
+    #[test]
+    fn path_under_a_relative_prefix_is_low_confidence() {
+        let code = r#"
+            function profile(req, res) {
+                res.redirect('/profile/' + req.query.id);
+                res.redirect(`/users/${req.params.name}/settings`);
+                res.redirect('/' + req.query.next);
+            }
+        "#;
+
+        let diagnostics = run_open_redirect(code);
+
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics[0].confidence, Confidence::Low);
+        assert_eq!(diagnostics[1].confidence, Confidence::Low);
+        assert_eq!(diagnostics[2].confidence, Confidence::High);
+    }
+
+    #[test]
+    fn no_false_positive_after_allowlist_checks() {
+        let code = r#"
+            const ALLOWED = ['/home', '/settings'];
+            const PARTNERS = new Set(['https://partner.example.com/']);
+            function back(req, res) {
+                const target = req.query.target;
+                if (!ALLOWED.includes(target)) {
+                    return res.redirect('/');
+                }
+                res.redirect(target);
+            }
+            function partner(req, res) {
+                const target = req.query.target;
+                if (PARTNERS.has(target)) res.redirect(target);
+            }
+            function external(req, res) {
+                const url = new URL(req.query.url, 'https://example.com');
+                if (url.origin !== 'https://example.com') {
+                    return res.redirect('/');
+                }
+                res.redirect(url.href);
+            }
+        "#;
+
+        let diagnostics = run_open_redirect(code);
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn no_false_positive_for_constant_targets() {
+        let code = r#"
+            function logout(req, res) {
+                req.session.destroy();
+                res.redirect('/login');
+            }
+        "#;
+
+        let diagnostics = run_open_redirect(code);
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn reports_redirect_in_imported_module() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                "auth.js",
+                "exports.finish = (res, target) => res.redirect(target || '/');\n",
+            ),
+            (
+                "routes.js",
+                "const auth = require('./auth');\n\nrouter.get('/done', (q, s) => auth.finish(s, q.query.next));\n",
+            ),
+        ];
+        let parsed = files
+            .iter()
+            .map(|(name, source)| {
+                let path = dir.path().join(name);
+                std::fs::write(&path, source).unwrap();
+                ParsedFile::from_source(&path.to_string_lossy(), source)
+            })
+            .collect();
+
+        let diagnostics = OpenRedirect::new().check_project(&Project::new(parsed));
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].file.ends_with("auth.js"));
+        assert!(diagnostics[0].message.contains("routes.js:3"));
+    }
+
+    #[test]
+    fn metadata_is_correct() {
+        let rule = OpenRedirect::new();
+        let metadata = rule.metadata();
+
+        assert_eq!(metadata.id, "S007");
+        assert_eq!(metadata.name, "no-open-redirect");
+        assert_eq!(metadata.category, crate::rules::RuleCategory::Security);
+        assert_eq!(metadata.severity, Severity::Warning);
+    }
+}
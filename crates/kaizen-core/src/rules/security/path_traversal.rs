@@ -0,0 +1,371 @@
+//! no-path-traversal rule (S004): Detects file paths built from untrusted data via taint analysis
+
+use std::sync::Arc;
+
+use crate::declare_rule;
+use crate::diagnostic::{Diagnostic, RelatedLocation};
+use crate::parser::ParsedFile;
+use crate::project::Project;
+use crate::rules::security::{TaintOptions, cross_file_flows, trace_locations};
+use crate::rules::{Confidence, Rule, RuleMetadata, RuleScope, Severity};
+use crate::taint::{TaintAnalyzer, TaintSinkCategory};
+use crate::visitor::VisitorContext;
+
+/// File operations and responses that serve a file by path.
+const CATEGORIES: [TaintSinkCategory; 2] = [
+    TaintSinkCategory::FileSystem,
+    TaintSinkCategory::PathTraversal,
+];
+
+declare_rule!(
+    PathTraversal,
+    id = "S004",
+    name = "no-path-traversal",
+    description = "Disallow file paths constructed with untrusted data",
+    category = Security,
+    severity = Error,
+    examples = "// Bad\nconst file = path.join(UPLOADS, req.params.name);\nres.sendFile(file);\n\n// Good\nconst file = path.resolve(UPLOADS, req.params.name);\nif (!file.startsWith(UPLOADS + path.sep)) throw new Error('Invalid path');\nres.sendFile(file);",
+    options = TaintOptions
+);
+
+impl Rule for PathTraversal {
+    fn metadata(&self) -> &RuleMetadata {
+        &self.metadata
+    }
+
+    fn with_taint_analyzer(&self, analyzer: &Arc<TaintAnalyzer>) -> Option<Box<dyn Rule>> {
+        Some(Box::new(Self {
+            metadata: self.metadata.clone(),
+            options: TaintOptions {
+                analyzer: Arc::clone(analyzer),
+            },
+        }))
+    }
+
+    fn check(&self, file: &ParsedFile) -> Vec<Diagnostic> {
+        let findings = self.options.analyzer.analyze(file);
+        let ctx = VisitorContext::new(file);
+
+        findings
+            .into_iter()
+            .filter(|finding| CATEGORIES.contains(&finding.sink_category))
+            .map(|finding| {
+                let (sink_line, sink_column) = ctx.span_to_location(finding.sink_span);
+                let (source_line, _) = ctx.span_to_location(finding.source_span);
+
+                report(
+                    &format!("line {}", source_line),
+                    &finding.sink_description,
+                    finding.uncertainly_guarded,
+                    &file.metadata().filename,
+                    sink_line,
+                    sink_column,
+                    trace_locations(&finding.trace, file, None),
+                )
+            })
+            .collect()
+    }
+
+    fn scope(&self) -> RuleScope {
+        RuleScope::FileAndProject
+    }
+
+    fn check_project(&self, project: &Project) -> Vec<Diagnostic> {
+        cross_file_flows(&self.options.analyzer, project, &CATEGORIES)
+            .into_iter()
+            .map(|flow| {
+                report(
+                    &flow.source,
+                    &flow.sink_description,
+                    flow.uncertainly_guarded,
+                    &flow.file,
+                    flow.line,
+                    flow.column,
+                    flow.trace,
+                )
+            })
+            .collect()
+    }
+}
+
+fn report(
+    source: &str,
+    sink_description: &str,
+    uncertainly_guarded: bool,
+    file: &str,
+    line: usize,
+    column: usize,
+    trace: Vec<RelatedLocation>,
+) -> Diagnostic {
+    if uncertainly_guarded {
+        // A sibling directory sharing the prefix, as `/srv/files2`, still passes the check
+        let message = format!(
+            "Potential path traversal: untrusted data from {} flows to {} after a check against a directory that may not end with a separator",
+            source, sink_description
+        );
+        return Diagnostic::new("S004", Severity::Warning, message, file, line, column)
+            .with_confidence(Confidence::Low)
+            .with_trace(trace)
+            .with_suggestion(
+                "Check that the path starts with the directory followed by path.sep, such as startsWith(root + path.sep)",
+            );
+    }
+
+    let message = format!(
+        "Potential path traversal: untrusted data from {} flows to {}",
+        source, sink_description
+    );
+
+    Diagnostic::new("S004", Severity::Error, message, file, line, column)
+        .with_trace(trace)
+        .with_suggestion(
+            "Keep only the file name with path.basename(), or resolve the path and check that it starts with the allowed directory",
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_path_traversal(code: &str) -> Vec<Diagnostic> {
+        let file = ParsedFile::from_source("test.js", code);
+        let rule = PathTraversal::new();
+        rule.check(&file)
+    }
+
+    #[test]
+    fn detects_read_file_with_user_input() {
+        let code = r#"
+            function handler(req, res) {
+                const name = req.params.name;
+                fs.readFile("/var/data/" + name, (err, data) => res.send(data));
+            }
+        "#;
+
+        let diagnostics = run_path_traversal(code);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule_id, "S004");
+        assert!(diagnostics[0].message.contains("path traversal"));
+    }
+
+    #[test]
+    fn detects_create_read_stream_with_path_join() {
+        let code = r#"
+            app.get('/files/:name', (request, response) => {
+                const file = path.join(__dirname, 'uploads', request.params.name);
+                fs.createReadStream(file).pipe(response);
+            });
+        "#;
+
+        let diagnostics = run_path_traversal(code);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 4);
+    }
+
+    #[test]
+    fn detects_send_file_and_download() {
+        let code = r#"
+            function show(req, res) {
+                res.sendFile(path.join(ROOT, req.query.file));
+            }
+            function download(req, res) {
+                res.download(req.query.file);
+            }
+        "#;
+
+        let diagnostics = run_path_traversal(code);
+
+        assert_eq!(diagnostics.len(), 2);
+    }
+
+    #[test]
+    fn detects_fs_promises_variants() {
+        let code = r#"
+            import { readFile } from 'fs/promises';
+            import fsPromises from 'fs/promises';
+
+            async function handler(req, res) {
+                await readFile(req.body.path);
+                await fsPromises.writeFile(req.body.target, 'data');
+                await fs.promises.readFile(req.body.other);
+            }
+        "#;
+
+        let diagnostics = run_path_traversal(code);
+
+        assert_eq!(diagnostics.len(), 3);
+    }
+
+    #[test]
+    fn path_resolve_alone_is_not_a_sanitizer() {
+        let code = r#"
+            function handler(req, res) {
+                const file = path.resolve(ROOT, req.params.name);
+                res.sendFile(file);
+            }
+        "#;
+
+        let diagnostics = run_path_traversal(code);
+
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn no_false_positive_after_starts_with_guard() {
+        let code = r#"
+            function handler(req, res) {
+                const file = path.resolve(ROOT, req.params.name);
+                if (!file.startsWith(ROOT + path.sep)) {
+                    return res.status(400).end();
+                }
+                res.sendFile(file);
+            }
+        "#;
+
+        let diagnostics = run_path_traversal(code);
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn no_false_positive_inside_guarded_branch() {
+        let code = r#"
+            function handler(req, res) {
+                const file = path.join(ROOT, req.params.name);
+                if (file.startsWith(ROOT + path.sep)) {
+                    res.sendFile(file);
+                }
+            }
+        "#;
+
+        let diagnostics = run_path_traversal(code);
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn starts_with_guard_only_holds_where_it_is_checked() {
+        let code = r#"
+            function handler(req, res) {
+                const file = path.resolve(ROOT, req.params.name);
+                if (file.startsWith(ROOT)) console.log('ok');
+                res.sendFile(file);
+            }
+        "#;
+
+        let diagnostics = run_path_traversal(code);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 5);
+    }
+
+    #[test]
+    fn starts_with_directory_without_separator_is_low_confidence() {
+        let code = r#"
+            const ROOT = '/srv/files';
+            const UPLOADS = '/srv/uploads/';
+            function download(req, res) {
+                const file = path.resolve(ROOT, req.params.name);
+                if (!file.startsWith(ROOT)) return res.status(400).end();
+                res.sendFile(file);
+            }
+            function upload(req, res) {
+                const file = path.resolve(UPLOADS, req.params.name);
+                if (!file.startsWith(UPLOADS)) return res.status(400).end();
+                res.sendFile(file);
+            }
+        "#;
+
+        let diagnostics = run_path_traversal(code);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 7);
+        assert_eq!(diagnostics[0].confidence, Confidence::Low);
+    }
+
+    #[test]
+    fn starts_with_file_system_root_is_not_a_guard() {
+        let code = r#"
+            function handler(req, res) {
+                const file = path.resolve(ROOT, req.params.name);
+                if (file.startsWith('/')) {
+                    res.sendFile(file);
+                }
+            }
+        "#;
+
+        let diagnostics = run_path_traversal(code);
+
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn no_false_positive_for_basename() {
+        let code = r#"
+            function handler(req, res) {
+                const name = path.basename(req.params.name);
+                fs.readFile(path.join(UPLOADS, name), (err, data) => res.send(data));
+            }
+        "#;
+
+        let diagnostics = run_path_traversal(code);
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn no_false_positive_for_constant_paths() {
+        let code = r#"
+            function handler(req, res) {
+                fs.readFile(path.join(__dirname, 'index.html'), (err, data) => res.send(data));
+                res.sendFile('/var/www/index.html');
+            }
+        "#;
+
+        let diagnostics = run_path_traversal(code);
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn reports_file_read_in_imported_module() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                "storage.js",
+                "const fs = require('fs');\nexports.load = (name) => fs.readFileSync('/data/' + name);\n",
+            ),
+            (
+                "routes.js",
+                "const storage = require('./storage');\n\nrouter.get('/doc', (r, s) => s.send(storage.load(r.query.name)));\n",
+            ),
+        ];
+        let parsed = files
+            .iter()
+            .map(|(name, source)| {
+                let path = dir.path().join(name);
+                std::fs::write(&path, source).unwrap();
+                ParsedFile::from_source(&path.to_string_lossy(), source)
+            })
+            .collect();
+
+        let diagnostics = PathTraversal::new().check_project(&Project::new(parsed));
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].file.ends_with("storage.js"));
+        assert!(diagnostics[0].message.contains("routes.js:3"));
+    }
+
+    #[test]
+    fn metadata_is_correct() {
+        let rule = PathTraversal::new();
+        let metadata = rule.metadata();
+
+        assert_eq!(metadata.id, "S004");
+        assert_eq!(metadata.name, "no-path-traversal");
+        assert_eq!(metadata.category, crate::rules::RuleCategory::Security);
+        assert_eq!(metadata.severity, Severity::Error);
+    }
+}
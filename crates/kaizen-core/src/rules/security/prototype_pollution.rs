@@ -1,9 +1,13 @@
 //! no-prototype-pollution rule (S020): Detects prototype pollution vulnerabilities via taint analysis
 
+use std::sync::Arc;
+
 use crate::declare_rule;
-use crate::diagnostic::Diagnostic;
+use crate::diagnostic::{Diagnostic, RelatedLocation};
 use crate::parser::ParsedFile;
-use crate::rules::{Rule, RuleMetadata, Severity};
+use crate::project::Project;
+use crate::rules::security::{TaintOptions, cross_file_flows, trace_locations};
+use crate::rules::{Rule, RuleMetadata, RuleScope, Severity};
 use crate::taint::{TaintAnalyzer, TaintSinkCategory};
 use crate::visitor::VisitorContext;
 
@@ -16,7 +20,8 @@ declare_rule!(
     category = Security,
     severity = Error,
     min_tier = Pro,
-    examples = "// Bad\nconst config = Object.assign({}, req.body);\n_.merge(options, userInput);\n\n// Good\nconst config = { ...defaults, ...sanitizedInput };"
+    examples = "// Bad\nconst config = Object.assign({}, req.body);\n_.merge(options, userInput);\n\n// Good\nconst config = { ...defaults, ...sanitizedInput };",
+    options = TaintOptions
 );
 
 impl Rule for PrototypePollution {
@@ -24,9 +29,17 @@ impl Rule for PrototypePollution {
         &self.metadata
     }
 
+    fn with_taint_analyzer(&self, analyzer: &Arc<TaintAnalyzer>) -> Option<Box<dyn Rule>> {
+        Some(Box::new(Self {
+            metadata: self.metadata.clone(),
+            options: TaintOptions {
+                analyzer: Arc::clone(analyzer),
+            },
+        }))
+    }
+
     fn check(&self, file: &ParsedFile) -> Vec<Diagnostic> {
-        let analyzer = TaintAnalyzer::new();
-        let findings = analyzer.analyze(file);
+        let findings = self.options.analyzer.analyze(file);
         let ctx = VisitorContext::new(file);
 
         findings
@@ -36,25 +49,59 @@ impl Rule for PrototypePollution {
                 let (sink_line, sink_column) = ctx.span_to_location(finding.sink_span);
                 let (source_line, _) = ctx.span_to_location(finding.source_span);
 
-                let message = format!(
-                    "Potential prototype pollution: untrusted data from line {} flows to {}",
-                    source_line, finding.sink_description
-                );
-
-                Diagnostic::new(
-                    "S020",
-                    Severity::Error,
-                    message,
+                report(
+                    &format!("line {}", source_line),
+                    &finding.sink_description,
                     &file.metadata().filename,
                     sink_line,
                     sink_column,
-                )
-                .with_suggestion(
-                    "Use object spread (...) instead, or validate keys against __proto__, constructor, and prototype before merging",
+                    trace_locations(&finding.trace, file, None),
                 )
             })
             .collect()
     }
+
+    fn scope(&self) -> RuleScope {
+        RuleScope::FileAndProject
+    }
+
+    fn check_project(&self, project: &Project) -> Vec<Diagnostic> {
+        cross_file_flows(
+            &self.options.analyzer,
+            project,
+            &[TaintSinkCategory::PrototypePollution],
+        )
+        .into_iter()
+        .map(|flow| {
+            report(
+                &flow.source,
+                &flow.sink_description,
+                &flow.file,
+                flow.line,
+                flow.column,
+                flow.trace,
+            )
+        })
+        .collect()
+    }
+}
+
+fn report(
+    source: &str,
+    sink_description: &str,
+    file: &str,
+    line: usize,
+    column: usize,
+    trace: Vec<RelatedLocation>,
+) -> Diagnostic {
+    let message = format!(
+        "Potential prototype pollution: untrusted data from {} flows to {}",
+        source, sink_description
+    );
+
+    Diagnostic::new("S020", Severity::Error, message, file, line, column).with_trace(trace).with_suggestion(
+        "Use object spread (...) instead, or validate keys against __proto__, constructor, and prototype before merging",
+    )
 }
 
 #[cfg(test)]
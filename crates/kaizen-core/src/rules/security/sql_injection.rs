@@ -1,9 +1,13 @@
 //! no-sql-injection rule (S001): Detects SQL injection vulnerabilities via taint analysis
 
+use std::sync::Arc;
+
 use crate::declare_rule;
-use crate::diagnostic::Diagnostic;
+use crate::diagnostic::{Diagnostic, RelatedLocation};
 use crate::parser::ParsedFile;
-use crate::rules::{Rule, RuleMetadata, Severity};
+use crate::project::Project;
+use crate::rules::security::{TaintOptions, cross_file_flows, trace_locations};
+use crate::rules::{Rule, RuleMetadata, RuleScope, Severity};
 use crate::taint::{TaintAnalyzer, TaintSinkCategory};
 use crate::visitor::VisitorContext;
 
@@ -14,7 +18,8 @@ declare_rule!(
     description = "Disallow SQL queries constructed with untrusted data",
     category = Security,
     severity = Error,
-    examples = "// Bad\nconst query = \"SELECT * FROM users WHERE id = \" + userId;\ndb.query(query);\n\n// Good\ndb.query(\"SELECT * FROM users WHERE id = ?\", [userId]);"
+    examples = "// Bad\nconst query = \"SELECT * FROM users WHERE id = \" + userId;\ndb.query(query);\n\n// Good\ndb.query(\"SELECT * FROM users WHERE id = ?\", [userId]);",
+    options = TaintOptions
 );
 
 impl Rule for SqlInjection {
@@ -22,9 +27,17 @@ impl Rule for SqlInjection {
         &self.metadata
     }
 
+    fn with_taint_analyzer(&self, analyzer: &Arc<TaintAnalyzer>) -> Option<Box<dyn Rule>> {
+        Some(Box::new(Self {
+            metadata: self.metadata.clone(),
+            options: TaintOptions {
+                analyzer: Arc::clone(analyzer),
+            },
+        }))
+    }
+
     fn check(&self, file: &ParsedFile) -> Vec<Diagnostic> {
-        let analyzer = TaintAnalyzer::new();
-        let findings = analyzer.analyze(file);
+        let findings = self.options.analyzer.analyze(file);
         let ctx = VisitorContext::new(file);
 
         findings
@@ -34,25 +47,61 @@ impl Rule for SqlInjection {
                 let (sink_line, sink_column) = ctx.span_to_location(finding.sink_span);
                 let (source_line, _) = ctx.span_to_location(finding.source_span);
 
-                let message = format!(
-                    "Potential SQL injection: untrusted data from line {} flows to {}",
-                    source_line, finding.sink_description
-                );
-
-                Diagnostic::new(
-                    "S001",
-                    Severity::Error,
-                    message,
+                report(
+                    &format!("line {}", source_line),
+                    &finding.sink_description,
                     &file.metadata().filename,
                     sink_line,
                     sink_column,
-                )
-                .with_suggestion(
-                    "Use parameterized queries or prepared statements instead of string concatenation",
+                    trace_locations(&finding.trace, file, None),
                 )
             })
             .collect()
     }
+
+    fn scope(&self) -> RuleScope {
+        RuleScope::FileAndProject
+    }
+
+    fn check_project(&self, project: &Project) -> Vec<Diagnostic> {
+        cross_file_flows(
+            &self.options.analyzer,
+            project,
+            &[TaintSinkCategory::SqlInjection],
+        )
+        .into_iter()
+        .map(|flow| {
+            report(
+                &flow.source,
+                &flow.sink_description,
+                &flow.file,
+                flow.line,
+                flow.column,
+                flow.trace,
+            )
+        })
+        .collect()
+    }
+}
+
+fn report(
+    source: &str,
+    sink_description: &str,
+    file: &str,
+    line: usize,
+    column: usize,
+    trace: Vec<RelatedLocation>,
+) -> Diagnostic {
+    let message = format!(
+        "Potential SQL injection: untrusted data from {} flows to {}",
+        source, sink_description
+    );
+
+    Diagnostic::new("S001", Severity::Error, message, file, line, column)
+        .with_trace(trace)
+        .with_suggestion(
+            "Use parameterized queries or prepared statements instead of string concatenation",
+        )
 }
 
 #[cfg(test)]
@@ -324,6 +373,139 @@ mod tests {
         assert!(diagnostics[0].message.contains("line"));
     }
 
+    #[test]
+    fn detects_query_built_by_local_helper() {
+        let code = r#"
+            function buildQuery(id) {
+                return "SELECT * FROM users WHERE id = " + id;
+            }
+
+            function runQuery(sql) {
+                return db.query(sql);
+            }
+
+            function handler(req, res) {
+                runQuery(buildQuery(req.params.id));
+            }
+        "#;
+
+        let diagnostics = run_sql_injection(code);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 7);
+        assert!(diagnostics[0].message.contains("line 10"));
+    }
+
+    #[test]
+    fn reports_query_in_imported_module_with_source_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                "repository.js",
+                "export function findUser(id) {\n  return db.query('SELECT * FROM users WHERE id = ' + id);\n}\n",
+            ),
+            (
+                "routes.js",
+                "import { findUser } from './repository';\n\nfunction show(req, res) {\n  findUser(req.params.id);\n}\n",
+            ),
+        ];
+        let parsed = files
+            .iter()
+            .map(|(name, source)| {
+                let path = dir.path().join(name);
+                std::fs::write(&path, source).unwrap();
+                ParsedFile::from_source(&path.to_string_lossy(), source)
+            })
+            .collect();
+
+        let diagnostics = SqlInjection::new().check_project(&Project::new(parsed));
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].file.ends_with("repository.js"));
+        assert_eq!(diagnostics[0].line, 2);
+        assert!(diagnostics[0].message.contains("routes.js:3"));
+
+        let trace = &diagnostics[0].trace;
+        assert!(trace[0].file.ends_with("routes.js"));
+        assert_eq!(trace[0].line, 3);
+        let sink = trace.last().unwrap();
+        assert!(sink.file.ends_with("repository.js"));
+        assert_eq!(sink.line, 2);
+    }
+
+    #[test]
+    fn cross_file_trace_shows_every_hop_relative_to_the_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                "db.js",
+                "function buildQuery(id) {\n  return 'SELECT * FROM users WHERE id = ' + id;\n}\nexport function findUser(id) {\n  const sql = buildQuery(id);\n  return db.query(sql);\n}\n",
+            ),
+            (
+                "service.js",
+                "import { findUser } from './db';\nexport function getUser(userId) {\n  return findUser(userId);\n}\n",
+            ),
+            (
+                "routes/users.js",
+                "import { getUser } from '../service';\nfunction show(req, res) {\n  getUser(req.params.id);\n}\n",
+            ),
+        ];
+        let parsed = files
+            .iter()
+            .map(|(name, source)| {
+                let path = dir.path().join(name);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(&path, source).unwrap();
+                ParsedFile::from_source(&path.to_string_lossy(), source)
+            })
+            .collect();
+
+        let diagnostics =
+            SqlInjection::new().check_project(&Project::new(parsed).with_root(dir.path()));
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("from routes/users.js:2"));
+        let trace: Vec<_> = diagnostics[0]
+            .trace
+            .iter()
+            .map(|step| format!("{}:{}: {}", step.file, step.line, step.message))
+            .collect();
+        assert_eq!(
+            trace,
+            [
+                "routes/users.js:2: Untrusted data enters from 'req'",
+                "routes/users.js:3: Read from 'req.params.id'",
+                "routes/users.js:3: Passed to 'getUser()'",
+                "service.js:2: Received as parameter 'userId'",
+                "service.js:3: Passed to 'findUser()'",
+                "db.js:4: Received as parameter 'id'",
+                "db.js:5: Passed to 'buildQuery()'",
+                "db.js:5: Assigned to 'sql'",
+                "db.js:6: Reaches the sink: Database query execution",
+            ]
+        );
+    }
+
+    #[test]
+    fn diagnostic_carries_trace_from_source_to_sink() {
+        let code = r#"
+function handler(req, res) {
+    const id = req.params.id;
+    const query = "SELECT * FROM users WHERE id = " + id;
+    db.query(query);
+}
+"#;
+
+        let diagnostics = run_sql_injection(code);
+
+        assert_eq!(diagnostics.len(), 1);
+        let lines: Vec<_> = diagnostics[0].trace.iter().map(|step| step.line).collect();
+        assert_eq!(lines.first(), Some(&2));
+        assert!(lines.contains(&3) && lines.contains(&4));
+        assert_eq!(lines.last(), Some(&5));
+        assert!(diagnostics[0].trace[2].message.contains("'id'"));
+    }
+
     #[test]
     fn metadata_is_correct() {
         let rule = SqlInjection::new();
@@ -0,0 +1,394 @@
+//! no-ssrf rule (S006): Detects request URLs built from untrusted data via taint analysis
+
+use std::sync::Arc;
+
+use crate::declare_rule;
+use crate::diagnostic::{Diagnostic, RelatedLocation};
+use crate::parser::ParsedFile;
+use crate::project::Project;
+use crate::rules::security::{TaintOptions, cross_file_flows, has_fixed_origin, trace_locations};
+use crate::rules::{Confidence, Rule, RuleMetadata, RuleScope, Severity};
+use crate::taint::{TaintAnalyzer, TaintSinkCategory};
+use crate::visitor::VisitorContext;
+
+declare_rule!(
+    Ssrf,
+    id = "S006",
+    name = "no-ssrf",
+    description = "Disallow outgoing requests to URLs built from untrusted data",
+    category = Security,
+    severity = Error,
+    examples = "// Bad\nconst response = await fetch(req.query.url);\n\n// Good\nconst url = new URL(req.query.url);\nif (!ALLOWED_HOSTS.includes(url.hostname)) throw new Error('Host not allowed');\nconst response = await fetch(url);",
+    options = TaintOptions
+);
+
+impl Rule for Ssrf {
+    fn metadata(&self) -> &RuleMetadata {
+        &self.metadata
+    }
+
+    fn with_taint_analyzer(&self, analyzer: &Arc<TaintAnalyzer>) -> Option<Box<dyn Rule>> {
+        Some(Box::new(Self {
+            metadata: self.metadata.clone(),
+            options: TaintOptions {
+                analyzer: Arc::clone(analyzer),
+            },
+        }))
+    }
+
+    fn check(&self, file: &ParsedFile) -> Vec<Diagnostic> {
+        let findings = self.options.analyzer.analyze(file);
+        let ctx = VisitorContext::new(file);
+
+        findings
+            .into_iter()
+            .filter(|finding| finding.sink_category == TaintSinkCategory::NetworkRequest)
+            .map(|finding| {
+                let (sink_line, sink_column) = ctx.span_to_location(finding.sink_span);
+                let (source_line, _) = ctx.span_to_location(finding.source_span);
+
+                report(
+                    &format!("line {}", source_line),
+                    &finding.sink_description,
+                    finding.argument_prefix.as_deref(),
+                    &file.metadata().filename,
+                    sink_line,
+                    sink_column,
+                    trace_locations(&finding.trace, file, None),
+                )
+            })
+            .collect()
+    }
+
+    fn scope(&self) -> RuleScope {
+        RuleScope::FileAndProject
+    }
+
+    fn check_project(&self, project: &Project) -> Vec<Diagnostic> {
+        cross_file_flows(
+            &self.options.analyzer,
+            project,
+            &[TaintSinkCategory::NetworkRequest],
+        )
+        .into_iter()
+        .map(|flow| {
+            report(
+                &flow.source,
+                &flow.sink_description,
+                flow.argument_prefix.as_deref(),
+                &flow.file,
+                flow.line,
+                flow.column,
+                flow.trace,
+            )
+        })
+        .collect()
+    }
+}
+
+fn report(
+    source: &str,
+    sink_description: &str,
+    argument_prefix: Option<&str>,
+    file: &str,
+    line: usize,
+    column: usize,
+    trace: Vec<RelatedLocation>,
+) -> Diagnostic {
+    match argument_prefix.filter(|prefix| has_fixed_origin(prefix)) {
+        // Only the path or query is attacker-controlled
+        Some(prefix) => {
+            let message = format!(
+                "Potential server-side request forgery: untrusted data from {} flows to {} under '{}'",
+                source, sink_description, prefix
+            );
+            Diagnostic::new("S006", Severity::Warning, message, file, line, column)
+                .with_confidence(Confidence::Low)
+                .with_trace(trace)
+                .with_suggestion(
+                    "Encode the untrusted segment with encodeURIComponent() so it cannot change the path",
+                )
+        }
+        None => {
+            let message = format!(
+                "Potential server-side request forgery: untrusted data from {} flows to the URL of {}",
+                source, sink_description
+            );
+            Diagnostic::new("S006", Severity::Error, message, file, line, column)
+                .with_trace(trace)
+                .with_suggestion(
+                    "Parse the URL with new URL() and check its hostname against an allowlist before making the request",
+                )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_ssrf(code: &str) -> Vec<Diagnostic> {
+        let file = ParsedFile::from_source("test.js", code);
+        let rule = Ssrf::new();
+        rule.check(&file)
+    }
+
+    #[test]
+    fn detects_fetch_of_user_url() {
+        let code = r#"
+            app.get('/proxy', async (req, res) => {
+                const response = await fetch(req.query.url);
+                res.send(await response.text());
+            });
+        "#;
+
+        let diagnostics = run_ssrf(code);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule_id, "S006");
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].confidence, Confidence::High);
+        assert!(
+            diagnostics[0]
+                .message
+                .contains("server-side request forgery")
+        );
+    }
+
+    #[test]
+    fn detects_http_client_libraries() {
+        let code = r#"
+            async function handler(req, res) {
+                await axios.get(req.body.webhook);
+                await got(req.body.webhook);
+                await undici.request(req.body.webhook);
+                http.request(req.body.webhook, (response) => {});
+            }
+        "#;
+
+        let diagnostics = run_ssrf(code);
+
+        assert_eq!(diagnostics.len(), 4);
+    }
+
+    #[test]
+    fn user_controlled_host_is_high_confidence() {
+        let code = r#"
+            function handler(req, res) {
+                fetch(`https://${req.query.host}/status`);
+                fetch('https://api.example.com' + req.query.domain);
+            }
+        "#;
+
+        let diagnostics = run_ssrf(code);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics.iter().all(|d| d.confidence == Confidence::High));
+    }
+
+    #[test]
+    fn path_under_constant_origin_is_low_confidence() {
+        let code = r#"
+            const API = 'https://api.example.com/';
+            function handler(req, res) {
+                const url = `${API}users/${req.params.id}`;
+                fetch(url);
+            }
+        "#;
+
+        let diagnostics = run_ssrf(code);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].confidence, Confidence::Low);
+        assert!(
+            diagnostics[0]
+                .message
+                .contains("'https://api.example.com/users/'")
+        );
+    }
+
+    #[test]
+    fn no_false_positive_after_hostname_allowlist() {
+        let code = r#"
+            const ALLOWED_HOSTS = ['api.example.com'];
+            async function handler(req, res) {
+                const url = new URL(req.query.url);
+                if (!ALLOWED_HOSTS.includes(url.hostname)) {
+                    return res.status(400).end();
+                }
+                res.send(await fetch(url));
+            }
+        "#;
+
+        let diagnostics = run_ssrf(code);
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn no_false_positive_after_inline_hostname_checks() {
+        let code = r#"
+            function proxy(req, res) {
+                const target = req.query.url;
+                if (new URL(target).hostname !== 'api.example.com') {
+                    throw new Error('Host not allowed');
+                }
+                return fetch(target);
+            }
+            function webhook(req, res) {
+                const target = req.body.url;
+                const { hostname } = new URL(target);
+                if (!allowedHosts.has(hostname)) {
+                    throw new Error('Host not allowed');
+                }
+                return axios.post(target, {});
+            }
+        "#;
+
+        let diagnostics = run_ssrf(code);
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn no_false_positive_after_domain_suffix_check() {
+        let code = r#"
+            async function handler(req, res) {
+                const url = new URL(req.query.url);
+                if (url.hostname === 'example.com' || url.hostname.endsWith('.example.com')) {
+                    return fetch(url);
+                }
+            }
+        "#;
+
+        let diagnostics = run_ssrf(code);
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn suffix_check_without_dot_is_not_a_sanitizer() {
+        let code = r#"
+            async function handler(req, res) {
+                const url = new URL(req.query.url);
+                // Also true for evilexample.com
+                if (!url.hostname.endsWith('example.com')) {
+                    throw new Error('Host not allowed');
+                }
+                return fetch(url);
+            }
+            async function either(req, res) {
+                const url = new URL(req.query.url);
+                if (url.hostname === 'example.com' || url.hostname.endsWith('example.com')) {
+                    return fetch(url);
+                }
+            }
+        "#;
+
+        let diagnostics = run_ssrf(code);
+
+        assert_eq!(diagnostics.len(), 2);
+    }
+
+    #[test]
+    fn lookup_in_a_cache_is_not_an_allowlist() {
+        let code = r#"
+            const seen = new Set();
+            async function crawl(req, res) {
+                const url = req.query.url;
+                if (seen.has(url)) return;
+                seen.add(url);
+                await fetch(url);
+            }
+        "#;
+
+        let diagnostics = run_ssrf(code);
+
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn pathname_check_is_not_a_sanitizer() {
+        let code = r#"
+            function handler(req, res) {
+                const url = new URL(req.query.url);
+                if (url.pathname !== '/status') {
+                    throw new Error('Invalid path');
+                }
+                fetch(url);
+            }
+        "#;
+
+        let diagnostics = run_ssrf(code);
+
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn no_false_positive_for_constant_urls() {
+        let code = r#"
+            function handler(req, res) {
+                fetch('https://api.example.com/status');
+                axios.post(WEBHOOK_URL, req.body);
+            }
+        "#;
+
+        let diagnostics = run_ssrf(code);
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn fixed_origin_prefixes() {
+        assert!(has_fixed_origin("https://api.example.com/"));
+        assert!(has_fixed_origin("https://api.example.com/users?id="));
+        assert!(has_fixed_origin("/api/users/"));
+        assert!(!has_fixed_origin("https://api.example.com"));
+        assert!(!has_fixed_origin("https://"));
+        assert!(!has_fixed_origin("/"));
+        assert!(!has_fixed_origin("//"));
+        assert!(!has_fixed_origin("api/"));
+    }
+
+    #[test]
+    fn reports_request_in_imported_module() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                "client.js",
+                "exports.ping = (host) => fetch('https://' + host + '/health');\n",
+            ),
+            (
+                "routes.js",
+                "const client = require('./client');\n\nrouter.get('/ping', (r, s) => client.ping(r.query.host));\n",
+            ),
+        ];
+        let parsed = files
+            .iter()
+            .map(|(name, source)| {
+                let path = dir.path().join(name);
+                std::fs::write(&path, source).unwrap();
+                ParsedFile::from_source(&path.to_string_lossy(), source)
+            })
+            .collect();
+
+        let diagnostics = Ssrf::new().check_project(&Project::new(parsed));
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].file.ends_with("client.js"));
+        assert!(diagnostics[0].message.contains("routes.js:3"));
+        assert_eq!(diagnostics[0].confidence, Confidence::High);
+    }
+
+    #[test]
+    fn metadata_is_correct() {
+        let rule = Ssrf::new();
+        let metadata = rule.metadata();
+
+        assert_eq!(metadata.id, "S006");
+        assert_eq!(metadata.name, "no-ssrf");
+        assert_eq!(metadata.category, crate::rules::RuleCategory::Security);
+        assert_eq!(metadata.severity, Severity::Error);
+    }
+}
@@ -1,9 +1,13 @@
 //! no-xss rule (S002): Detects XSS vulnerabilities via taint analysis
 
+use std::sync::Arc;
+
 use crate::declare_rule;
-use crate::diagnostic::Diagnostic;
+use crate::diagnostic::{Diagnostic, RelatedLocation};
 use crate::parser::ParsedFile;
-use crate::rules::{Rule, RuleMetadata, Severity};
+use crate::project::Project;
+use crate::rules::security::{TaintOptions, cross_file_flows, trace_locations};
+use crate::rules::{Rule, RuleMetadata, RuleScope, Severity};
 use crate::taint::{TaintAnalyzer, TaintSinkCategory};
 use crate::visitor::VisitorContext;
 
@@ -14,7 +18,8 @@ declare_rule!(
     description = "Disallow untrusted HTML from being inserted into the DOM",
     category = Security,
     severity = Error,
-    examples = "// Bad\nconst html = req.query.html;\nelement.innerHTML = html;\n\n// Good\nconst safe = DOMPurify.sanitize(html);\nelement.innerHTML = safe;"
+    examples = "// Bad\nconst html = req.query.html;\nelement.innerHTML = html;\n\n// Good\nconst safe = DOMPurify.sanitize(html);\nelement.innerHTML = safe;",
+    options = TaintOptions
 );
 
 impl Rule for Xss {
@@ -22,9 +27,17 @@ impl Rule for Xss {
         &self.metadata
     }
 
+    fn with_taint_analyzer(&self, analyzer: &Arc<TaintAnalyzer>) -> Option<Box<dyn Rule>> {
+        Some(Box::new(Self {
+            metadata: self.metadata.clone(),
+            options: TaintOptions {
+                analyzer: Arc::clone(analyzer),
+            },
+        }))
+    }
+
     fn check(&self, file: &ParsedFile) -> Vec<Diagnostic> {
-        let analyzer = TaintAnalyzer::new();
-        let findings = analyzer.analyze(file);
+        let findings = self.options.analyzer.analyze(file);
         let ctx = VisitorContext::new(file);
 
         findings
@@ -34,25 +47,59 @@ impl Rule for Xss {
                 let (sink_line, sink_column) = ctx.span_to_location(finding.sink_span);
                 let (source_line, _) = ctx.span_to_location(finding.source_span);
 
-                let message = format!(
-                    "Potential XSS: untrusted data from line {} flows to {}",
-                    source_line, finding.sink_description
-                );
-
-                Diagnostic::new(
-                    "S002",
-                    Severity::Error,
-                    message,
+                report(
+                    &format!("line {}", source_line),
+                    &finding.sink_description,
                     &file.metadata().filename,
                     sink_line,
                     sink_column,
-                )
-                .with_suggestion(
-                    "Use DOMPurify.sanitize() or escapeHtml() to sanitize HTML content",
+                    trace_locations(&finding.trace, file, None),
                 )
             })
             .collect()
     }
+
+    fn scope(&self) -> RuleScope {
+        RuleScope::FileAndProject
+    }
+
+    fn check_project(&self, project: &Project) -> Vec<Diagnostic> {
+        cross_file_flows(
+            &self.options.analyzer,
+            project,
+            &[TaintSinkCategory::XssSink],
+        )
+        .into_iter()
+        .map(|flow| {
+            report(
+                &flow.source,
+                &flow.sink_description,
+                &flow.file,
+                flow.line,
+                flow.column,
+                flow.trace,
+            )
+        })
+        .collect()
+    }
+}
+
+fn report(
+    source: &str,
+    sink_description: &str,
+    file: &str,
+    line: usize,
+    column: usize,
+    trace: Vec<RelatedLocation>,
+) -> Diagnostic {
+    let message = format!(
+        "Potential XSS: untrusted data from {} flows to {}",
+        source, sink_description
+    );
+
+    Diagnostic::new("S002", Severity::Error, message, file, line, column)
+        .with_trace(trace)
+        .with_suggestion("Use DOMPurify.sanitize() or escapeHtml() to sanitize HTML content")
 }
 
 #[cfg(test)]
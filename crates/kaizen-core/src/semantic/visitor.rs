@@ -175,17 +175,15 @@ impl ScopeBuilder {
                 // Handle `export default expr` (e.g., `export default opts => new Foo(opts)`)
                 self.visit_expr(&export_expr.expr);
             }
-            ModuleDecl::ExportNamed(named_export) => {
-                // Handle `export { foo }` and `export { foo as bar }`
-                // Only visit specifiers when there's no source module (local re-exports)
-                // For `export { foo } from 'module'`, we don't need to visit local references
-                if named_export.src.is_none() {
-                    for specifier in &named_export.specifiers {
-                        if let swc_ecma_ast::ExportSpecifier::Named(named) = specifier {
-                            // The `orig` is the local variable being exported
-                            if let swc_ecma_ast::ModuleExportName::Ident(ident) = &named.orig {
-                                self.visit_ident_reference(ident);
-                            }
+            // Handle `export { foo }` and `export { foo as bar }`
+            // Only visit specifiers when there's no source module (local re-exports)
+            // For `export { foo } from 'module'`, we don't need to visit local references
+            ModuleDecl::ExportNamed(named_export) if named_export.src.is_none() => {
+                for specifier in &named_export.specifiers {
+                    if let swc_ecma_ast::ExportSpecifier::Named(named) = specifier {
+                        // The `orig` is the local variable being exported
+                        if let swc_ecma_ast::ModuleExportName::Ident(ident) = &named.orig {
+                            self.visit_ident_reference(ident);
                         }
                     }
                 }
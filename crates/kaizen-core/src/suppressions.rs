@@ -0,0 +1,412 @@
+//! Applying disable directives to diagnostics and reporting on the directives themselves
+//!
+//! Directives that suppress nothing are reported as `unused-disable-directive` (M004)
+//! with a fix that removes them, and rule references that match no known rule as
+//! `unknown-disable-rule` (M005).
+
+use crate::config::DisableReasonPolicy;
+use crate::diagnostic::{Diagnostic, Fix, TextEdit};
+use crate::disable_comments::{CommentPosition, DisableDirective, wildcard_match};
+use crate::parser::ParsedFile;
+use crate::rules::{RuleRegistry, RuleScope, Severity};
+
+pub const UNUSED_DIRECTIVE_ID: &str = "M004";
+pub const UNKNOWN_RULE_ID: &str = "M005";
+
+/// Diagnostic ID for parse errors, which directives may also disable.
+const PARSE_RULE_ID: &str = "PARSE";
+
+/// Drops the diagnostics suppressed by disable directives in `file`.
+///
+/// Directives match rule IDs and names. When `policy` requires a reason for a rule,
+/// a directive without `-- reason` leaves the diagnostic in place and says so.
+pub fn filter_disabled(
+    file: &ParsedFile,
+    registry: &RuleRegistry,
+    policy: DisableReasonPolicy,
+    diagnostics: Vec<Diagnostic>,
+) -> Vec<Diagnostic> {
+    apply_directives(file, registry, policy, diagnostics).0
+}
+
+/// Like [`filter_disabled`], also reporting unused directives and unknown rule references.
+pub fn filter_disabled_and_report(
+    file: &ParsedFile,
+    registry: &RuleRegistry,
+    policy: DisableReasonPolicy,
+    diagnostics: Vec<Diagnostic>,
+) -> Vec<Diagnostic> {
+    let (mut kept, used) = apply_directives(file, registry, policy, diagnostics);
+    kept.extend(directive_diagnostics(file, registry, &used));
+    kept
+}
+
+/// Returns the diagnostics left after suppression, and for each directive whether it
+/// matched any diagnostic.
+fn apply_directives(
+    file: &ParsedFile,
+    registry: &RuleRegistry,
+    policy: DisableReasonPolicy,
+    diagnostics: Vec<Diagnostic>,
+) -> (Vec<Diagnostic>, Vec<bool>) {
+    let directives: Vec<&DisableDirective> = file.disable_directives().directives().collect();
+    let mut used = vec![false; directives.len()];
+    if directives.is_empty() {
+        return (diagnostics, used);
+    }
+
+    let kept = diagnostics
+        .into_iter()
+        .filter_map(|mut diagnostic| {
+            let metadata = registry
+                .get_rule(&diagnostic.rule_id)
+                .map(|rule| rule.metadata());
+            let name = metadata.map(|m| m.name);
+            let reason_required = metadata.is_some_and(|m| policy.requires_reason(m.category));
+
+            let matching: Vec<usize> = directives
+                .iter()
+                .enumerate()
+                .filter(|(_, d)| {
+                    d.covers_line(diagnostic.line) && d.disables(&diagnostic.rule_id, name)
+                })
+                .map(|(index, _)| index)
+                .collect();
+            if matching.is_empty() {
+                return Some(diagnostic);
+            }
+            for &index in &matching {
+                used[index] = true;
+            }
+
+            if !reason_required || matching.iter().any(|&i| directives[i].reason.is_some()) {
+                return None;
+            }
+
+            diagnostic
+                .message
+                .push_str(" (disable directive ignored: a `-- reason` is required for this rule)");
+            Some(diagnostic)
+        })
+        .collect();
+
+    (kept, used)
+}
+
+fn directive_diagnostics(
+    file: &ParsedFile,
+    registry: &RuleRegistry,
+    used: &[bool],
+) -> Vec<Diagnostic> {
+    let filename = &file.metadata().filename;
+    let mut unused = Vec::new();
+    let mut unknown = Vec::new();
+
+    let directives = file.disable_directives();
+    for (directive, &was_used) in directives.directives().zip(used) {
+        let position = directive.comment;
+        let unknown_refs: Vec<&String> = directive
+            .rule_ids
+            .iter()
+            .filter(|pattern| !is_known_rule(registry, pattern))
+            .collect();
+
+        for pattern in &unknown_refs {
+            unknown.push(
+                at_comment(
+                    Diagnostic::new(
+                        UNKNOWN_RULE_ID,
+                        Severity::Warning,
+                        format!("Unknown rule '{}' in disable directive", pattern),
+                        filename,
+                        position.line,
+                        position.column,
+                    ),
+                    position,
+                )
+                .with_suggestion("Use a rule ID or name listed by `kaizen explain --list`"),
+            );
+        }
+
+        if was_used
+            || !unknown_refs.is_empty()
+            || !targets_active_rules(registry, directive, filename)
+        {
+            continue;
+        }
+
+        let message = if directive.disables_all() {
+            "Unused disable directive: no diagnostics were suppressed".to_string()
+        } else {
+            format!(
+                "Unused disable directive for '{}': no diagnostics were suppressed",
+                directive.rule_ids.join(", ")
+            )
+        };
+        let mut diagnostic = at_comment(
+            Diagnostic::new(
+                UNUSED_DIRECTIVE_ID,
+                Severity::Warning,
+                message,
+                filename,
+                position.line,
+                position.column,
+            ),
+            position,
+        );
+        // An enable comment closing several ranges stays while another range needs it.
+        let shares_enable = directive.enable_comment.is_some()
+            && directives
+                .directives()
+                .filter(|other| other.enable_comment == directive.enable_comment)
+                .count()
+                > 1;
+        if let Some(fix) = removal_fix(file.source(), directive, shares_enable) {
+            diagnostic = diagnostic.with_fix(fix);
+        }
+        unused.push(diagnostic);
+    }
+
+    let mut diagnostics = registry.report(UNUSED_DIRECTIVE_ID, filename, unused);
+    diagnostics.extend(registry.report(UNKNOWN_RULE_ID, filename, unknown));
+    diagnostics
+}
+
+fn at_comment(diagnostic: Diagnostic, position: CommentPosition) -> Diagnostic {
+    match position.end_column {
+        Some(end_column) => diagnostic.with_end(position.line, end_column),
+        None => diagnostic,
+    }
+}
+
+fn is_known_rule(registry: &RuleRegistry, pattern: &str) -> bool {
+    wildcard_match(pattern, PARSE_RULE_ID)
+        || registry.rules().any(|rule| {
+            let metadata = rule.metadata();
+            wildcard_match(pattern, metadata.id) || wildcard_match(pattern, metadata.name)
+        })
+}
+
+/// A directive only counts as unused when every rule it names ran on the file; a rule
+/// that is disabled or needs a higher tier may still need the directive elsewhere, and
+/// rules that run over the project are suppressed in a separate pass that does not track
+/// directive use.
+fn targets_active_rules(
+    registry: &RuleRegistry,
+    directive: &DisableDirective,
+    filename: &str,
+) -> bool {
+    if directive.disables_all() {
+        return true;
+    }
+
+    registry
+        .rules()
+        .filter(|rule| directive.disables(rule.metadata().id, Some(rule.metadata().name)))
+        .all(|rule| rule.scope() == RuleScope::File && registry.is_rule_active_for(rule, filename))
+}
+
+fn removal_fix(source: &str, directive: &DisableDirective, keep_enable: bool) -> Option<Fix> {
+    let mut edits = vec![comment_removal(source, directive.comment)?];
+    if let Some(enable) = directive.enable_comment.filter(|_| !keep_enable) {
+        edits.push(comment_removal(source, enable)?);
+    }
+
+    Some(Fix::new("Remove unused disable directive", edits))
+}
+
+/// Deletes a directive comment, together with its line when nothing else is on it.
+fn comment_removal(source: &str, position: CommentPosition) -> Option<TextEdit> {
+    let end_column = position.end_column?;
+    let raw_lines: Vec<&str> = source.split('\n').collect();
+    let raw_line = raw_lines.get(position.line - 1)?;
+    let line = raw_line.strip_suffix('\r').unwrap_or(raw_line);
+
+    let before = line.get(..position.column - 1)?;
+    let after = line.get(end_column..)?;
+    let (before_trimmed, after_trimmed) = (before.trim(), after.trim());
+    let own_line = (before_trimmed.is_empty() && after_trimmed.is_empty())
+        || (before_trimmed == "{" && after_trimmed == "}");
+
+    if !own_line {
+        let start = if after_trimmed.is_empty() {
+            before.trim_end().len() + 1
+        } else {
+            position.column
+        };
+        return Some(TextEdit::delete(
+            position.line,
+            start,
+            position.line,
+            end_column,
+        ));
+    }
+
+    if position.line < raw_lines.len() {
+        // Column one past the line's text is its newline, which the deletion includes.
+        Some(TextEdit::delete(
+            position.line,
+            1,
+            position.line,
+            raw_line.len() + 1,
+        ))
+    } else if position.line > 1 {
+        let previous = raw_lines[position.line - 2];
+        Some(TextEdit::delete(
+            position.line - 1,
+            previous.len() + 1,
+            position.line,
+            raw_line.len(),
+        ))
+    } else {
+        Some(TextEdit::delete(
+            position.line,
+            1,
+            position.line,
+            raw_line.len(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::AnalysisEngine;
+    use crate::config::Config;
+    use crate::diagnostic::Applicability;
+    use crate::fixer::fix_source;
+
+    fn analyze(source: &str) -> Vec<Diagnostic> {
+        AnalysisEngine::new().analyze(&ParsedFile::from_source("test.js", source))
+    }
+
+    fn ids(diagnostics: &[Diagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|d| d.rule_id.as_str()).collect()
+    }
+
+    fn fixed(source: &str) -> String {
+        fix_source(
+            &AnalysisEngine::new(),
+            "test.jsx",
+            source,
+            Applicability::Safe,
+        )
+        .source
+    }
+
+    #[test]
+    fn used_directive_is_not_reported() {
+        let diagnostics =
+            analyze("// kaizen-disable-next-line no-var\nvar x = 1;\nexport { x };\n");
+
+        assert!(ids(&diagnostics).is_empty(), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn unused_directive_is_reported_at_comment() {
+        let source = "const y = 2;\n  // kaizen-disable-next-line no-var\nexport const x = y;\n";
+        let diagnostics = analyze(source);
+
+        assert_eq!(ids(&diagnostics), vec![UNUSED_DIRECTIVE_ID]);
+        let diagnostic = &diagnostics[0];
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 3));
+        assert_eq!(diagnostic.end_column, 36);
+        assert!(diagnostic.message.contains("'no-var'"));
+        assert!(diagnostic.fixes[0].is_safe());
+    }
+
+    #[test]
+    fn unknown_rule_is_reported_instead_of_unused() {
+        let diagnostics = analyze("// kaizen-disable-next-line no-vars, X*\nexport const x = 1;\n");
+
+        assert_eq!(ids(&diagnostics), vec![UNKNOWN_RULE_ID, UNKNOWN_RULE_ID]);
+        assert!(diagnostics[0].message.contains("'no-vars'"));
+        assert!(diagnostics[1].message.contains("'X*'"));
+        assert!(diagnostics[0].fixes.is_empty());
+    }
+
+    #[test]
+    fn known_wildcards_and_parse_are_accepted() {
+        let source = "// kaizen-disable-next-line S*, prefer-*, PARSE\nvar x = 1;\nexport { x };\n";
+        let diagnostics = analyze(source);
+
+        assert!(!ids(&diagnostics).contains(&UNKNOWN_RULE_ID));
+    }
+
+    #[test]
+    fn directive_for_inactive_rule_is_not_reported() {
+        let config: Config = toml::from_str("[rules]\ndisabled = [\"no-console\"]").unwrap();
+        let engine = AnalysisEngine::with_config(&config);
+        let file = ParsedFile::from_source(
+            "test.js",
+            "// kaizen-disable-next-line no-console\nexport const x = 1;\n",
+        );
+
+        assert!(engine.analyze(&file).is_empty());
+    }
+
+    #[test]
+    fn meta_rules_follow_configuration() {
+        let config: Config =
+            toml::from_str("[rules]\ndisabled = [\"unused-disable-directive\"]\n[rules.severity]\nM005 = \"error\"")
+                .unwrap();
+        let engine = AnalysisEngine::with_config(&config);
+        let file = ParsedFile::from_source(
+            "test.js",
+            "// kaizen-disable-next-line no-var\nexport const x = 1;\n// kaizen-disable-line nope\n",
+        );
+
+        let diagnostics = engine.analyze(&file);
+
+        assert_eq!(ids(&diagnostics), vec![UNKNOWN_RULE_ID]);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn fix_removes_directive_line() {
+        let source = "const y = 2;\n// kaizen-disable-next-line no-var\nexport const x = y;\n";
+
+        assert_eq!(fixed(source), "const y = 2;\nexport const x = y;\n");
+    }
+
+    #[test]
+    fn fix_removes_trailing_comment_only() {
+        let source = "export const x = 1; // kaizen-disable-line no-var -- legacy\n";
+
+        assert_eq!(fixed(source), "export const x = 1;\n");
+    }
+
+    #[test]
+    fn fix_removes_directive_on_last_line() {
+        let source = "export const x = 1;\n// kaizen-disable-file no-var";
+
+        assert_eq!(fixed(source), "export const x = 1;");
+    }
+
+    #[test]
+    fn fix_removes_both_comments_of_unused_range() {
+        let source = "/* kaizen-disable no-var */\nexport const x = 1;\n/* kaizen-enable */\n";
+
+        assert_eq!(fixed(source), "export const x = 1;\n");
+    }
+
+    #[test]
+    fn shared_enable_comment_is_kept() {
+        let source = "/* kaizen-disable no-var */\n/* kaizen-disable no-console */\nvar x = 1;\n/* kaizen-enable */\nexport { x };\n";
+
+        assert_eq!(
+            fixed(source),
+            "/* kaizen-disable no-var */\nvar x = 1;\n/* kaizen-enable */\nexport { x };\n"
+        );
+    }
+
+    #[test]
+    fn fix_removes_jsx_directive_line() {
+        let source = "export const App = () => (\n  <div>\n    {/* kaizen-disable-next-line no-console */}\n    <span />\n  </div>\n);\n";
+
+        assert_eq!(
+            fixed(source),
+            "export const App = () => (\n  <div>\n    <span />\n  </div>\n);\n"
+        );
+    }
+}
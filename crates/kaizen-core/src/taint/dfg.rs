@@ -8,11 +8,13 @@ use id_arena::{Arena, Id};
 use std::collections::{HashMap, HashSet};
 use swc_common::{Span, Spanned};
 use swc_ecma_ast::{
-    ArrowExpr, AssignExpr, BinExpr, BlockStmt, CallExpr, Callee, CondExpr, Decl, Expr, FnDecl,
-    FnExpr, ForInStmt, ForOfStmt, ForStmt, Ident, MemberExpr, MemberProp, Module, ModuleItem,
-    NewExpr, ObjectLit, OptChainExpr, Pat, Prop, PropOrSpread, Stmt, VarDecl, VarDeclarator,
+    ArrowExpr, AssignExpr, BinExpr, BinaryOp, BlockStmt, CallExpr, Callee, Class, ClassMember,
+    CondExpr, Decl, Expr, FnDecl, FnExpr, ForInStmt, ForOfStmt, ForStmt, Function, Ident, Lit,
+    MemberExpr, MemberProp, Module, ModuleItem, NewExpr, ObjectLit, OptChainExpr,
+    ParamOrTsParamProp, Pat, Prop, PropName, PropOrSpread, Stmt, UnaryOp, VarDecl, VarDeclarator,
 };
 
+use crate::project::DEFAULT_EXPORT;
 use crate::semantic::{ScopeId, SemanticModel};
 
 pub type DfgNodeId = Id<DfgNode>;
@@ -46,6 +48,9 @@ pub enum DfgNodeKind {
         name: String,
         index: usize,
     },
+    /// An object literal, which its members flow into through a
+    /// `PropertyAssignment` each.
+    Object,
     Unknown,
 }
 
@@ -58,10 +63,59 @@ pub struct DfgNode {
     pub flows_from: Vec<DfgNodeId>,
 }
 
+/// A function or method body, as seen by interprocedural analysis.
+#[derive(Debug)]
+pub struct DfgFunction {
+    /// Declared name, or the variable an anonymous function is assigned to.
+    pub name: Option<String>,
+    pub is_method: bool,
+    pub span: Span,
+    /// Parameter nodes by position; `None` for destructured parameters.
+    pub params: Vec<Option<DfgNodeId>>,
+    pub returns: Vec<DfgNodeId>,
+    /// Assignments to properties of `this`.
+    pub this_writes: Vec<DfgNodeId>,
+}
+
+/// Arguments of a call node, by position.
+#[derive(Debug)]
+pub struct DfgCallSite {
+    pub is_method: bool,
+    pub args: Vec<Option<DfgNodeId>>,
+    pub receiver: Option<DfgNodeId>,
+    /// Whether an argument is spread, so positions do not map to parameters.
+    pub has_spread: bool,
+}
+
+/// Code that only runs when a call or comparison has a known result, such as
+/// the statements after `if (!file.startsWith(root)) return;`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DfgCondition {
+    /// The span of the call or comparison.
+    pub check: Span,
+    /// Whether the call returns a truthy value, or the values are equal.
+    pub holds: bool,
+    pub region: Span,
+    /// Checks that may have their result in `region` instead, as `b` for `a`
+    /// in `if (a || b)`. Each has a condition of its own.
+    pub alternatives: Vec<Span>,
+}
+
 #[derive(Debug)]
 pub struct DataFlowGraph {
     arena: Arena<DfgNode>,
     var_to_node: HashMap<(Option<ScopeId>, String), DfgNodeId>,
+    functions: Vec<DfgFunction>,
+    call_sites: HashMap<DfgNodeId, DfgCallSite>,
+    /// Values of string literal nodes.
+    strings: HashMap<DfgNodeId, String>,
+    /// Operators of binary nodes; templates are concatenations.
+    operators: HashMap<DfgNodeId, BinaryOp>,
+    conditions: Vec<DfgCondition>,
+    /// Array literal nodes, which their elements flow into.
+    arrays: HashSet<DfgNodeId>,
+    /// Values tested by `if`, loops and `?:`.
+    tests: HashSet<DfgNodeId>,
 }
 
 impl Default for DataFlowGraph {
@@ -75,6 +129,13 @@ impl DataFlowGraph {
         Self {
             arena: Arena::new(),
             var_to_node: HashMap::new(),
+            functions: Vec::new(),
+            call_sites: HashMap::new(),
+            strings: HashMap::new(),
+            operators: HashMap::new(),
+            conditions: Vec::new(),
+            arrays: HashSet::new(),
+            tests: HashSet::new(),
         }
     }
 
@@ -118,6 +179,130 @@ impl DataFlowGraph {
         self.arena.len()
     }
 
+    pub fn functions(&self) -> &[DfgFunction] {
+        &self.functions
+    }
+
+    pub fn call_site(&self, call: DfgNodeId) -> Option<&DfgCallSite> {
+        self.call_sites.get(&call)
+    }
+
+    pub fn operator(&self, node: DfgNodeId) -> Option<BinaryOp> {
+        self.operators.get(&node).copied()
+    }
+
+    /// Where the call or comparison at `check` is known to be true or false.
+    pub fn conditions(&self, check: Span) -> impl Iterator<Item = &DfgCondition> {
+        self.conditions
+            .iter()
+            .filter(move |condition| condition.check == check)
+    }
+
+    /// Whether `node` is the test of an `if`, a loop or `?:`.
+    pub fn is_tested(&self, node: DfgNodeId) -> bool {
+        self.tests.contains(&node)
+    }
+
+    /// Whether `node` compares two values with `===`, `==`, `!==` or `!=`.
+    pub fn is_comparison(&self, node: DfgNodeId) -> bool {
+        matches!(
+            self.operator(node),
+            Some(BinaryOp::EqEq | BinaryOp::EqEqEq | BinaryOp::NotEq | BinaryOp::NotEqEq)
+        )
+    }
+
+    /// The text a string is known to start with, such as the origin of
+    /// `` `https://api.example.com/users/${id}` ``, following constants that are
+    /// assigned once.
+    pub fn leading_text(&self, node: DfgNodeId) -> Option<String> {
+        self.text(node, 0).map(|(text, _)| text)
+    }
+
+    /// The value of a string built only from constants.
+    pub fn constant_text(&self, node: DfgNodeId) -> Option<String> {
+        self.text(node, 0)
+            .and_then(|(text, whole)| whole.then_some(text))
+    }
+
+    /// Whether the string `node` ends with a path separator, as `'/srv/files/'`
+    /// and `root + path.sep` do.
+    pub fn ends_with_separator(&self, node: DfgNodeId) -> bool {
+        let mut node = &self.arena[node];
+        for _ in 0..16 {
+            match &node.kind {
+                DfgNodeKind::BinaryOp { right, .. }
+                    if self.operator(node.id) == Some(BinaryOp::Add) =>
+                {
+                    node = &self.arena[*right]
+                }
+                DfgNodeKind::Variable { .. } if node.flows_from.len() == 1 => {
+                    node = &self.arena[node.flows_from[0]]
+                }
+                DfgNodeKind::PropertyAccess { object, property } => {
+                    return property == "sep"
+                        && matches!(
+                            &self.arena[*object].kind,
+                            DfgNodeKind::Variable { name, .. } if name == "path"
+                        );
+                }
+                _ => {
+                    return self
+                        .strings
+                        .get(&node.id)
+                        .is_some_and(|text| text.ends_with(['/', '\\']));
+                }
+            }
+        }
+        false
+    }
+
+    /// Whether `node` is an array or `Set` of constant strings, or a variable
+    /// assigned one once, as `ALLOWED` in `const ALLOWED = ['a', 'b']`.
+    pub fn is_constant_list(&self, node: DfgNodeId) -> bool {
+        let mut node = &self.arena[node];
+        for _ in 0..16 {
+            match (&node.kind, &node.flows_from[..]) {
+                (DfgNodeKind::Variable { .. }, &[from]) => node = &self.arena[from],
+                (DfgNodeKind::NewExpr { callee_name }, &[from]) if callee_name == "Set" => {
+                    node = &self.arena[from]
+                }
+                (_, items) => {
+                    return self.arrays.contains(&node.id)
+                        && !items.is_empty()
+                        && items.iter().all(|&item| self.constant_text(item).is_some());
+                }
+            }
+        }
+        false
+    }
+
+    /// The leading text of `node`, and whether it is the whole value.
+    fn text(&self, node: DfgNodeId, depth: usize) -> Option<(String, bool)> {
+        if depth > 16 {
+            return None;
+        }
+        let n = &self.arena[node];
+        match &n.kind {
+            DfgNodeKind::Literal => self.strings.get(&node).map(|text| (text.clone(), true)),
+            DfgNodeKind::BinaryOp { left, right } if self.operator(node) == Some(BinaryOp::Add) => {
+                match self.text(*left, depth + 1)? {
+                    (mut text, true) => match self.text(*right, depth + 1) {
+                        Some((rest, whole)) => {
+                            text.push_str(&rest);
+                            Some((text, whole))
+                        }
+                        None => Some((text, false)),
+                    },
+                    partial => Some(partial),
+                }
+            }
+            DfgNodeKind::Variable { .. } if n.flows_from.len() == 1 => {
+                self.text(n.flows_from[0], depth + 1)
+            }
+            _ => None,
+        }
+    }
+
     pub fn get_variable_node(&self, scope_id: Option<ScopeId>, name: &str) -> Option<DfgNodeId> {
         self.var_to_node.get(&(scope_id, name.to_string())).copied()
     }
@@ -186,6 +371,8 @@ struct DfgBuilder<'a> {
     graph: DataFlowGraph,
     semantic: &'a SemanticModel,
     current_scope: Option<ScopeId>,
+    function_stack: Vec<usize>,
+    fn_name_hint: Option<String>,
 }
 
 impl<'a> DfgBuilder<'a> {
@@ -194,6 +381,8 @@ impl<'a> DfgBuilder<'a> {
             graph: DataFlowGraph::new(),
             semantic,
             current_scope: semantic.scope_tree.root(),
+            function_stack: Vec::new(),
+            fn_name_hint: None,
         }
     }
 
@@ -206,11 +395,26 @@ impl<'a> DfgBuilder<'a> {
     fn visit_module_item(&mut self, item: &ModuleItem) {
         match item {
             ModuleItem::Stmt(stmt) => self.visit_stmt(stmt),
-            ModuleItem::ModuleDecl(decl) => {
-                if let swc_ecma_ast::ModuleDecl::ExportDecl(export) = decl {
-                    self.visit_decl(&export.decl);
+            ModuleItem::ModuleDecl(decl) => match decl {
+                swc_ecma_ast::ModuleDecl::ExportDecl(export) => self.visit_decl(&export.decl),
+                swc_ecma_ast::ModuleDecl::ExportDefaultDecl(export) => match &export.decl {
+                    swc_ecma_ast::DefaultDecl::Fn(fn_expr) => {
+                        let name = fn_expr
+                            .ident
+                            .as_ref()
+                            .map_or(DEFAULT_EXPORT, |ident| ident.sym.as_ref());
+                        self.visit_function(Some(name.to_string()), false, &fn_expr.function);
+                    }
+                    swc_ecma_ast::DefaultDecl::Class(class_expr) => {
+                        self.visit_class(&class_expr.class)
+                    }
+                    swc_ecma_ast::DefaultDecl::TsInterfaceDecl(_) => {}
+                },
+                swc_ecma_ast::ModuleDecl::ExportDefaultExpr(export) => {
+                    self.visit_named_expr(Some(DEFAULT_EXPORT.to_string()), &export.expr);
                 }
-            }
+                _ => {}
+            },
         }
     }
 
@@ -222,26 +426,30 @@ impl<'a> DfgBuilder<'a> {
             }
             Stmt::Block(block) => self.visit_block(block),
             Stmt::If(if_stmt) => {
-                self.visit_expr(&if_stmt.test);
+                self.visit_test(&if_stmt.test);
                 self.visit_stmt(&if_stmt.cons);
+                self.record_conditions(&if_stmt.test, true, if_stmt.cons.span());
                 if let Some(alt) = &if_stmt.alt {
                     self.visit_stmt(alt);
+                    self.record_conditions(&if_stmt.test, false, alt.span());
                 }
             }
             Stmt::For(for_stmt) => self.visit_for_stmt(for_stmt),
             Stmt::ForIn(for_in) => self.visit_for_in_stmt(for_in),
             Stmt::ForOf(for_of) => self.visit_for_of_stmt(for_of),
             Stmt::While(while_stmt) => {
-                self.visit_expr(&while_stmt.test);
+                self.visit_test(&while_stmt.test);
                 self.visit_stmt(&while_stmt.body);
             }
             Stmt::DoWhile(do_while) => {
                 self.visit_stmt(&do_while.body);
-                self.visit_expr(&do_while.test);
+                self.visit_test(&do_while.test);
             }
             Stmt::Return(ret) => {
-                if let Some(arg) = &ret.arg {
-                    self.visit_expr(arg);
+                if let Some(arg) = &ret.arg
+                    && let Some(node) = self.visit_expr(arg)
+                {
+                    self.record_return(node);
                 }
             }
             Stmt::Switch(switch_stmt) => {
@@ -272,15 +480,48 @@ impl<'a> DfgBuilder<'a> {
     }
 
     fn visit_block(&mut self, block: &BlockStmt) {
-        for stmt in &block.stmts {
+        for (index, stmt) in block.stmts.iter().enumerate() {
             self.visit_stmt(stmt);
+
+            // `if (!check) return;` leaves the check true for the rest of the block
+            if let Stmt::If(if_stmt) = stmt
+                && let Some(next) = block.stmts.get(index + 1)
+            {
+                let rest = Span::new(next.span().lo, block.span.hi);
+                if always_exits(&if_stmt.cons) {
+                    self.record_conditions(&if_stmt.test, false, rest);
+                }
+                if if_stmt.alt.as_deref().is_some_and(always_exits) {
+                    self.record_conditions(&if_stmt.test, true, rest);
+                }
+            }
         }
     }
 
+    /// Records the checks of `test` that are known in `region`, which only
+    /// runs when `test` is `outcome`.
+    fn record_conditions(&mut self, test: &Expr, outcome: bool, region: Span) {
+        let mut known = Vec::new();
+        known_checks(test, outcome, &mut known);
+        self.graph
+            .conditions
+            .extend(
+                known
+                    .into_iter()
+                    .map(|(check, holds, alternatives)| DfgCondition {
+                        check,
+                        holds,
+                        region,
+                        alternatives,
+                    }),
+            );
+    }
+
     fn visit_decl(&mut self, decl: &Decl) {
         match decl {
             Decl::Var(var_decl) => self.visit_var_decl(var_decl),
             Decl::Fn(fn_decl) => self.visit_fn_decl(fn_decl),
+            Decl::Class(class_decl) => self.visit_class(&class_decl.class),
             _ => {}
         }
     }
@@ -295,13 +536,71 @@ impl<'a> DfgBuilder<'a> {
         let var_node = self.create_node_for_pattern(&declarator.name);
 
         if let Some(init) = &declarator.init {
-            let init_node = self.visit_expr(init);
-            if let (Some(var_id), Some(init_id)) = (var_node, init_node) {
-                self.graph.add_edge(init_id, var_id);
+            let name = match &declarator.name {
+                Pat::Ident(ident) => Some(ident.sym.to_string()),
+                _ => None,
+            };
+            let init_node = self.visit_named_expr(name, init);
+            match (var_node, init_node) {
+                (Some(var_id), Some(init_id)) => self.graph.add_edge(init_id, var_id),
+                (None, Some(init_id)) => self.bind_pattern(&declarator.name, init_id),
+                _ => {}
+            }
+        }
+    }
+
+    /// Connects `value` to the bindings of a destructuring pattern, reading
+    /// `{ a: { b } }` as `value.a.b`.
+    fn bind_pattern(&mut self, pat: &Pat, value: DfgNodeId) {
+        match pat {
+            Pat::Ident(ident) => {
+                if let Some(node) = self.graph.get_variable_node(self.current_scope, &ident.sym) {
+                    self.graph.add_edge(value, node);
+                }
+            }
+            Pat::Array(array_pat) => {
+                for elem in array_pat.elems.iter().flatten() {
+                    self.bind_pattern(elem, value);
+                }
+            }
+            Pat::Object(object_pat) => {
+                for prop in &object_pat.props {
+                    match prop {
+                        swc_ecma_ast::ObjectPatProp::KeyValue(kv) => {
+                            let property =
+                                prop_name(&kv.key).unwrap_or_else(|| "[computed]".to_string());
+                            let read = self.read_property(value, property, kv.key.span());
+                            self.bind_pattern(&kv.value, read);
+                        }
+                        swc_ecma_ast::ObjectPatProp::Assign(assign) => {
+                            let name = assign.key.sym.to_string();
+                            let read = self.read_property(value, name.clone(), assign.span);
+                            if let Some(node) =
+                                self.graph.get_variable_node(self.current_scope, &name)
+                            {
+                                self.graph.add_edge(read, node);
+                            }
+                        }
+                        swc_ecma_ast::ObjectPatProp::Rest(rest) => {
+                            self.bind_pattern(&rest.arg, value);
+                        }
+                    }
+                }
             }
+            Pat::Rest(rest) => self.bind_pattern(&rest.arg, value),
+            Pat::Assign(assign) => self.bind_pattern(&assign.left, value),
+            _ => {}
         }
     }
 
+    fn read_property(&mut self, object: DfgNodeId, property: String, span: Span) -> DfgNodeId {
+        let node = self
+            .graph
+            .create_node(DfgNodeKind::PropertyAccess { object, property }, span);
+        self.graph.add_edge(object, node);
+        node
+    }
+
     fn create_node_for_pattern(&mut self, pat: &Pat) -> Option<DfgNodeId> {
         match pat {
             Pat::Ident(ident) => {
@@ -357,19 +656,95 @@ impl<'a> DfgBuilder<'a> {
     }
 
     fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
+        self.visit_function(
+            Some(fn_decl.ident.sym.to_string()),
+            false,
+            &fn_decl.function,
+        );
+    }
+
+    fn visit_function(&mut self, name: Option<String>, is_method: bool, function: &Function) {
         let saved_scope = self.current_scope;
 
-        for (index, param) in fn_decl.function.params.iter().enumerate() {
-            self.create_parameter_node(&param.pat, index);
+        self.begin_function(name, is_method, function.span);
+        for (index, param) in function.params.iter().enumerate() {
+            let node = self.create_parameter_node(&param.pat, index);
+            self.record_param(node);
         }
 
-        if let Some(body) = &fn_decl.function.body {
+        if let Some(body) = &function.body {
             self.visit_block(body);
         }
+        self.function_stack.pop();
 
         self.current_scope = saved_scope;
     }
 
+    fn visit_class(&mut self, class: &Class) {
+        for member in &class.body {
+            match member {
+                ClassMember::Method(method) => {
+                    self.visit_function(prop_name(&method.key), true, &method.function);
+                }
+                ClassMember::Constructor(constructor) => {
+                    let saved_scope = self.current_scope;
+
+                    self.begin_function(Some("constructor".to_string()), true, constructor.span);
+                    for (index, param) in constructor.params.iter().enumerate() {
+                        let node = match param {
+                            ParamOrTsParamProp::Param(param) => {
+                                self.create_parameter_node(&param.pat, index)
+                            }
+                            ParamOrTsParamProp::TsParamProp(_) => None,
+                        };
+                        self.record_param(node);
+                    }
+                    if let Some(body) = &constructor.body {
+                        self.visit_block(body);
+                    }
+                    self.function_stack.pop();
+
+                    self.current_scope = saved_scope;
+                }
+                ClassMember::ClassProp(prop) => {
+                    if let Some(value) = &prop.value {
+                        self.visit_expr(value);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn begin_function(&mut self, name: Option<String>, is_method: bool, span: Span) {
+        self.graph.functions.push(DfgFunction {
+            name,
+            is_method,
+            span,
+            params: Vec::new(),
+            returns: Vec::new(),
+            this_writes: Vec::new(),
+        });
+        self.function_stack.push(self.graph.functions.len() - 1);
+    }
+
+    fn current_function(&mut self) -> Option<&mut DfgFunction> {
+        let index = *self.function_stack.last()?;
+        self.graph.functions.get_mut(index)
+    }
+
+    fn record_param(&mut self, node: Option<DfgNodeId>) {
+        if let Some(function) = self.current_function() {
+            function.params.push(node);
+        }
+    }
+
+    fn record_return(&mut self, node: DfgNodeId) {
+        if let Some(function) = self.current_function() {
+            function.returns.push(node);
+        }
+    }
+
     fn create_parameter_node(&mut self, pat: &Pat, index: usize) -> Option<DfgNodeId> {
         match pat {
             Pat::Ident(ident) => {
@@ -390,9 +765,29 @@ impl<'a> DfgBuilder<'a> {
         }
     }
 
+    /// Visits the condition of an `if`, loop or `?:`, marking its value as tested.
+    fn visit_test(&mut self, test: &Expr) {
+        if let Some(node) = self.visit_expr(test) {
+            self.graph.tests.insert(node);
+        }
+    }
+
+    /// Visits `expr`, naming it `name` if it is an anonymous function.
+    fn visit_named_expr(&mut self, name: Option<String>, expr: &Expr) -> Option<DfgNodeId> {
+        if matches!(expr, Expr::Arrow(_) | Expr::Fn(_)) {
+            self.fn_name_hint = name;
+        }
+        let node = self.visit_expr(expr);
+        self.fn_name_hint = None;
+        node
+    }
+
     fn visit_expr(&mut self, expr: &Expr) -> Option<DfgNodeId> {
         match expr {
             Expr::Ident(ident) => self.visit_ident(ident),
+            Expr::Lit(Lit::Str(string)) => {
+                Some(self.string_literal(string.value.to_string(), string.span))
+            }
             Expr::Lit(_) => Some(self.graph.create_node(DfgNodeKind::Literal, expr.span())),
             Expr::Call(call) => self.visit_call_expr(call),
             Expr::Member(member) => self.visit_member_expr(member),
@@ -413,10 +808,14 @@ impl<'a> DfgBuilder<'a> {
             Expr::Unary(unary) => self.visit_expr(&unary.arg),
             Expr::Update(update) => self.visit_expr(&update.arg),
             Expr::Array(array) => {
+                let array_node = self.graph.create_node(DfgNodeKind::Unknown, array.span);
+                self.graph.arrays.insert(array_node);
                 for elem in array.elems.iter().flatten() {
-                    self.visit_expr(&elem.expr);
+                    if let Some(elem_node) = self.visit_expr(&elem.expr) {
+                        self.graph.add_edge(elem_node, array_node);
+                    }
                 }
-                Some(self.graph.create_node(DfgNodeKind::Unknown, array.span))
+                Some(array_node)
             }
             Expr::Object(obj) => self.visit_object_lit(obj),
             Expr::Tpl(tpl) => self.visit_template_literal(tpl),
@@ -436,6 +835,20 @@ impl<'a> DfgBuilder<'a> {
                 }
             }
             Expr::OptChain(opt_chain) => self.visit_opt_chain_expr(opt_chain),
+            Expr::This(this) => Some(self.graph.create_node(
+                DfgNodeKind::Variable {
+                    name: "this".to_string(),
+                    scope_id: self.current_scope,
+                },
+                this.span,
+            )),
+            Expr::Class(class_expr) => {
+                self.visit_class(&class_expr.class);
+                Some(
+                    self.graph
+                        .create_node(DfgNodeKind::Unknown, class_expr.class.span),
+                )
+            }
             _ => None,
         }
     }
@@ -471,9 +884,11 @@ impl<'a> DfgBuilder<'a> {
     fn visit_call_expr(&mut self, call: &CallExpr) -> Option<DfgNodeId> {
         let callee_name = self.extract_callee_name(&call.callee);
 
-        for arg in &call.args {
-            self.visit_expr(&arg.expr);
-        }
+        let args: Vec<_> = call
+            .args
+            .iter()
+            .map(|arg| self.visit_expr(&arg.expr))
+            .collect();
 
         let call_node = self.graph.create_node(
             DfgNodeKind::Call {
@@ -483,21 +898,33 @@ impl<'a> DfgBuilder<'a> {
         );
 
         // Connect arguments to call node
-        for arg in &call.args {
-            if let Some(arg_node) = self.visit_expr(&arg.expr) {
-                self.graph.add_edge(arg_node, call_node);
-            }
+        for &arg_node in args.iter().flatten() {
+            self.graph.add_edge(arg_node, call_node);
         }
 
         // Connect callee object to call node for method calls (e.g., db.query())
-        if let Callee::Expr(callee_expr) = &call.callee {
-            if let Expr::Member(member) = callee_expr.as_ref() {
-                if let Some(obj_node) = self.visit_expr(&member.obj) {
-                    self.graph.add_edge(obj_node, call_node);
-                }
+        let mut receiver = None;
+        let mut is_method = false;
+        if let Callee::Expr(callee_expr) = &call.callee
+            && let Expr::Member(member) = callee_expr.as_ref()
+        {
+            is_method = true;
+            receiver = self.visit_expr(&member.obj);
+            if let Some(obj_node) = receiver {
+                self.graph.add_edge(obj_node, call_node);
             }
         }
 
+        self.graph.call_sites.insert(
+            call_node,
+            DfgCallSite {
+                is_method,
+                args,
+                receiver,
+                has_spread: call.args.iter().any(|arg| arg.spread.is_some()),
+            },
+        );
+
         Some(call_node)
     }
 
@@ -530,27 +957,24 @@ impl<'a> DfgBuilder<'a> {
             MemberProp::PrivateName(private) => format!("#{}", private.name),
         };
 
-        let prop_node = self.graph.create_node(
-            DfgNodeKind::PropertyAccess {
-                object: object_node,
-                property,
-            },
-            member.span,
-        );
-
-        self.graph.add_edge(object_node, prop_node);
-        Some(prop_node)
+        Some(self.read_property(object_node, property, member.span))
     }
 
     fn visit_bin_expr(&mut self, bin: &BinExpr) -> Option<DfgNodeId> {
         let left_node = self.visit_expr(&bin.left);
         let right_node = self.visit_expr(&bin.right);
+        match bin.op {
+            BinaryOp::LogicalAnd => self.record_conditions(&bin.left, true, bin.right.span()),
+            BinaryOp::LogicalOr => self.record_conditions(&bin.left, false, bin.right.span()),
+            _ => {}
+        }
 
         match (left_node, right_node) {
             (Some(left), Some(right)) => {
                 let bin_node = self
                     .graph
                     .create_node(DfgNodeKind::BinaryOp { left, right }, bin.span);
+                self.graph.operators.insert(bin_node, bin.op);
                 self.graph.add_edge(left, bin_node);
                 self.graph.add_edge(right, bin_node);
                 Some(bin_node)
@@ -562,7 +986,17 @@ impl<'a> DfgBuilder<'a> {
     }
 
     fn visit_assign_expr(&mut self, assign: &AssignExpr) -> Option<DfgNodeId> {
-        let value_node = self.visit_expr(&assign.right);
+        // `exports.find = function () {}` names the function after the property
+        let name = match &assign.left {
+            swc_ecma_ast::AssignTarget::Simple(swc_ecma_ast::SimpleAssignTarget::Member(
+                member,
+            )) => match &member.prop {
+                MemberProp::Ident(ident) => Some(ident.sym.to_string()),
+                _ => None,
+            },
+            _ => None,
+        };
+        let value_node = self.visit_named_expr(name, &assign.right);
 
         match &assign.left {
             swc_ecma_ast::AssignTarget::Simple(simple) => match simple {
@@ -614,6 +1048,11 @@ impl<'a> DfgBuilder<'a> {
                         if let Some(value_id) = value_node {
                             self.graph.add_edge(value_id, assign_node);
                         }
+                        if matches!(*member.obj, Expr::This(_))
+                            && let Some(function) = self.current_function()
+                        {
+                            function.this_writes.push(assign_node);
+                        }
                         Some(assign_node)
                     } else {
                         value_node
@@ -663,8 +1102,11 @@ impl<'a> DfgBuilder<'a> {
     fn visit_arrow_expr(&mut self, arrow: &ArrowExpr) -> Option<DfgNodeId> {
         let saved_scope = self.current_scope;
 
+        let name = self.fn_name_hint.take();
+        self.begin_function(name, false, arrow.span);
         for (index, param) in arrow.params.iter().enumerate() {
-            self.create_parameter_node(param, index);
+            let node = self.create_parameter_node(param, index);
+            self.record_param(node);
         }
 
         match arrow.body.as_ref() {
@@ -672,26 +1114,26 @@ impl<'a> DfgBuilder<'a> {
                 self.visit_block(block);
             }
             swc_ecma_ast::BlockStmtOrExpr::Expr(expr) => {
-                self.visit_expr(expr);
+                if let Some(node) = self.visit_expr(expr) {
+                    self.record_return(node);
+                }
             }
         }
+        self.function_stack.pop();
 
         self.current_scope = saved_scope;
         Some(self.graph.create_node(DfgNodeKind::Unknown, arrow.span))
     }
 
     fn visit_fn_expr(&mut self, fn_expr: &FnExpr) -> Option<DfgNodeId> {
-        let saved_scope = self.current_scope;
+        let hint = self.fn_name_hint.take();
+        let name = fn_expr
+            .ident
+            .as_ref()
+            .map(|ident| ident.sym.to_string())
+            .or(hint);
+        self.visit_function(name, false, &fn_expr.function);
 
-        for (index, param) in fn_expr.function.params.iter().enumerate() {
-            self.create_parameter_node(&param.pat, index);
-        }
-
-        if let Some(body) = &fn_expr.function.body {
-            self.visit_block(body);
-        }
-
-        self.current_scope = saved_scope;
         Some(
             self.graph
                 .create_node(DfgNodeKind::Unknown, fn_expr.function.span),
@@ -727,9 +1169,11 @@ impl<'a> DfgBuilder<'a> {
     }
 
     fn visit_cond_expr(&mut self, cond: &CondExpr) -> Option<DfgNodeId> {
-        self.visit_expr(&cond.test);
+        self.visit_test(&cond.test);
         let cons = self.visit_expr(&cond.cons);
         let alt = self.visit_expr(&cond.alt);
+        self.record_conditions(&cond.test, true, cond.cons.span());
+        self.record_conditions(&cond.test, false, cond.alt.span());
 
         let cond_node = self.graph.create_node(DfgNodeKind::Unknown, cond.span);
         if let Some(c) = cons {
@@ -742,38 +1186,93 @@ impl<'a> DfgBuilder<'a> {
     }
 
     fn visit_object_lit(&mut self, obj: &ObjectLit) -> Option<DfgNodeId> {
+        let object = self.graph.create_node(DfgNodeKind::Object, obj.span);
         for prop in &obj.props {
             match prop {
-                PropOrSpread::Prop(prop) => {
-                    if let Prop::KeyValue(kv) = prop.as_ref() {
-                        self.visit_expr(&kv.value);
+                PropOrSpread::Prop(prop) => match prop.as_ref() {
+                    Prop::KeyValue(kv) => {
+                        let name = prop_name(&kv.key);
+                        if let Some(value) = self.visit_named_expr(name.clone(), &kv.value) {
+                            self.add_member(object, name, value, prop.span());
+                        }
                     }
-                }
+                    Prop::Shorthand(ident) => {
+                        if let Some(value) = self.visit_ident(ident) {
+                            self.add_member(object, Some(ident.sym.to_string()), value, ident.span);
+                        }
+                    }
+                    Prop::Method(method) => {
+                        self.visit_function(prop_name(&method.key), true, &method.function);
+                    }
+                    _ => {}
+                },
                 PropOrSpread::Spread(spread) => {
-                    self.visit_expr(&spread.expr);
+                    if let Some(value) = self.visit_expr(&spread.expr) {
+                        self.graph.add_edge(value, object);
+                    }
                 }
             }
         }
-        Some(self.graph.create_node(DfgNodeKind::Unknown, obj.span))
+        Some(object)
+    }
+
+    /// Stores `value` in the member `name` of an object literal, or in a computed
+    /// member when the name is not known.
+    fn add_member(
+        &mut self,
+        object: DfgNodeId,
+        name: Option<String>,
+        value: DfgNodeId,
+        span: Span,
+    ) {
+        let Some(property) = name else {
+            self.graph.add_edge(value, object);
+            return;
+        };
+        let member = self
+            .graph
+            .create_node(DfgNodeKind::PropertyAssignment { object, property }, span);
+        self.graph.add_edge(value, member);
+        self.graph.add_edge(member, object);
     }
 
+    /// Builds `` `a${b}c` `` like `"a" + b + "c"`.
     fn visit_template_literal(&mut self, tpl: &swc_ecma_ast::Tpl) -> Option<DfgNodeId> {
-        let mut expr_nodes = Vec::new();
-        for expr in &tpl.exprs {
-            if let Some(node) = self.visit_expr(expr) {
-                expr_nodes.push(node);
+        let mut parts = Vec::new();
+        for (index, quasi) in tpl.quasis.iter().enumerate() {
+            let text = match &quasi.cooked {
+                Some(cooked) => cooked.to_string(),
+                None => quasi.raw.to_string(),
+            };
+            if !text.is_empty() || tpl.exprs.is_empty() {
+                parts.push(self.string_literal(text, quasi.span));
+            }
+            if let Some(expr) = tpl.exprs.get(index)
+                && let Some(node) = self.visit_expr(expr)
+            {
+                parts.push(node);
             }
         }
-
-        if expr_nodes.is_empty() {
-            return Some(self.graph.create_node(DfgNodeKind::Literal, tpl.span));
+        // `${value}` alone still converts the value to a string, like `"" + value`
+        if parts.len() == 1 && !tpl.exprs.is_empty() {
+            parts.insert(0, self.string_literal(String::new(), tpl.span));
         }
 
-        let tpl_node = self.graph.create_node(DfgNodeKind::Unknown, tpl.span);
-        for node in expr_nodes {
-            self.graph.add_edge(node, tpl_node);
-        }
-        Some(tpl_node)
+        parts.into_iter().reduce(|left, right| {
+            let node = self
+                .graph
+                .create_node(DfgNodeKind::BinaryOp { left, right }, tpl.span);
+            self.graph.operators.insert(node, BinaryOp::Add);
+            self.graph.add_edge(left, node);
+            self.graph.add_edge(right, node);
+            node
+        })
+    }
+
+    fn string_literal(&mut self, value: String, span: Span) -> DfgNodeId {
+        let node = self.graph.create_node(DfgNodeKind::Literal, span);
+        self.graph.strings.insert(node, value);
+        node
     }
 
     fn visit_opt_chain_expr(&mut self, opt_chain: &OptChainExpr) -> Option<DfgNodeId> {
@@ -856,6 +1355,115 @@ impl<'a> DfgBuilder<'a> {
     }
 }
 
+/// Collects the calls and comparisons of `test` whose results are known when
+/// `test` is `outcome`, with the checks that may hold instead of each.
+fn known_checks(test: &Expr, outcome: bool, known: &mut Vec<(Span, bool, Vec<Span>)>) {
+    match test {
+        Expr::Paren(paren) => known_checks(&paren.expr, outcome, known),
+        Expr::Await(await_expr) => known_checks(&await_expr.arg, outcome, known),
+        Expr::Unary(unary) if unary.op == UnaryOp::Bang => {
+            known_checks(&unary.arg, !outcome, known)
+        }
+        // `a && b` is only true, and `a || b` only false, when both are
+        Expr::Bin(bin)
+            if matches!(bin.op, BinaryOp::LogicalAnd | BinaryOp::LogicalOr)
+                && outcome == (bin.op == BinaryOp::LogicalAnd) =>
+        {
+            known_checks(&bin.left, outcome, known);
+            known_checks(&bin.right, outcome, known);
+        }
+        _ => {
+            let Some(options) = alternatives(test, outcome) else {
+                return;
+            };
+            for &(check, holds) in &options {
+                let others = options
+                    .iter()
+                    .map(|&(other, _)| other)
+                    .filter(|&other| other != check)
+                    .collect();
+                known.push((check, holds, others));
+            }
+        }
+    }
+}
+
+/// The checks of `test` one of which has its result when `test` is
+/// `outcome`, as both calls of `a() || b()` when it is true.
+fn alternatives(test: &Expr, outcome: bool) -> Option<Vec<(Span, bool)>> {
+    match test {
+        Expr::Paren(paren) => alternatives(&paren.expr, outcome),
+        Expr::Await(await_expr) => alternatives(&await_expr.arg, outcome),
+        Expr::Unary(unary) if unary.op == UnaryOp::Bang => alternatives(&unary.arg, !outcome),
+        Expr::Call(call) => Some(vec![(call.span, outcome)]),
+        Expr::Bin(bin) => match bin.op {
+            BinaryOp::LogicalAnd | BinaryOp::LogicalOr => {
+                if outcome == (bin.op == BinaryOp::LogicalAnd) {
+                    return None;
+                }
+                let mut options = alternatives(&bin.left, outcome)?;
+                options.extend(alternatives(&bin.right, outcome)?);
+                Some(options)
+            }
+            _ => {
+                if let Some((call, found)) = index_check(bin) {
+                    return Some(vec![(call, outcome == found)]);
+                }
+                match bin.op {
+                    BinaryOp::EqEq | BinaryOp::EqEqEq => Some(vec![(bin.span, outcome)]),
+                    BinaryOp::NotEq | BinaryOp::NotEqEq => Some(vec![(bin.span, !outcome)]),
+                    _ => None,
+                }
+            }
+        },
+        _ => None,
+    }
+}
+
+/// The call of `list.indexOf(value) !== -1` or `>= 0`, and whether the
+/// comparison is true when the value is found.
+fn index_check(bin: &BinExpr) -> Option<(Span, bool)> {
+    let Expr::Call(call) = bin.left.as_ref() else {
+        return None;
+    };
+    let number = match bin.right.as_ref() {
+        Expr::Lit(Lit::Num(number)) => number.value,
+        Expr::Unary(unary) if unary.op == UnaryOp::Minus => match unary.arg.as_ref() {
+            Expr::Lit(Lit::Num(number)) => -number.value,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let found = match bin.op {
+        BinaryOp::NotEq | BinaryOp::NotEqEq | BinaryOp::Gt if number == -1.0 => true,
+        BinaryOp::GtEq if number == 0.0 => true,
+        BinaryOp::EqEq | BinaryOp::EqEqEq if number == -1.0 => false,
+        BinaryOp::Lt if number == 0.0 => false,
+        _ => return None,
+    };
+    Some((call.span, found))
+}
+
+/// Whether `stmt` always leaves the code that follows it, as `return` or `throw`.
+fn always_exits(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Return(_) | Stmt::Throw(_) | Stmt::Continue(_) | Stmt::Break(_) => true,
+        Stmt::Block(block) => block.stmts.last().is_some_and(always_exits),
+        Stmt::If(if_stmt) => {
+            always_exits(&if_stmt.cons) && if_stmt.alt.as_deref().is_some_and(always_exits)
+        }
+        _ => false,
+    }
+}
+
+fn prop_name(key: &PropName) -> Option<String> {
+    match key {
+        PropName::Ident(ident) => Some(ident.sym.to_string()),
+        PropName::Str(s) => Some(s.value.to_string()),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1121,6 +1729,57 @@ mod tests {
         assert!(b_node.is_some(), "b node should exist from destructuring");
     }
 
+    #[test]
+    fn dfg_destructuring_reads_properties_of_the_initializer() {
+        let dfg = build_dfg("const obj = {}; const { a: { b }, ...rest } = obj;");
+
+        let variable = |wanted: &str| {
+            dfg.nodes()
+                .find(|n| matches!(&n.kind, DfgNodeKind::Variable { name, .. } if name == wanted))
+                .unwrap()
+        };
+        let obj = variable("obj").id;
+        let b = variable("b");
+
+        // `b` reads `obj.a.b`
+        let read = dfg.get(b.flows_from[0]);
+        let DfgNodeKind::PropertyAccess { object, property } = &read.kind else {
+            panic!("expected a property read, got {:?}", read.kind);
+        };
+        assert_eq!(property, "b");
+        assert!(matches!(
+            &dfg.get(*object).kind,
+            DfgNodeKind::PropertyAccess { property, .. } if property == "a"
+        ));
+        let dependents = dfg.get_dependents(obj);
+        assert!(dependents.contains(&b.id));
+        assert!(dependents.contains(&variable("rest").id));
+    }
+
+    #[test]
+    fn dfg_leading_text_of_concatenations_and_templates() {
+        let dfg = build_dfg(
+            "const base = 'https://api.example.com/';\n\
+             const a = `${base}users/${id}/posts`;\n\
+             const b = 'https://' + host + '/health';\n\
+             const c = id + '/x';\n\
+             const d = 'a' === id;",
+        );
+
+        let text = |wanted: &str| {
+            let node = dfg
+                .nodes()
+                .find(|n| matches!(&n.kind, DfgNodeKind::Variable { name, .. } if name == wanted))
+                .unwrap();
+            dfg.leading_text(node.id)
+        };
+        assert_eq!(text("a").as_deref(), Some("https://api.example.com/users/"));
+        assert_eq!(text("b").as_deref(), Some("https://"));
+        assert_eq!(text("c"), None);
+        assert_eq!(text("d"), None);
+        assert!(dfg.nodes().any(|n| dfg.is_comparison(n.id)));
+    }
+
     #[test]
     fn dfg_handles_conditional_expression() {
         let dfg = build_dfg("const a = 1; const b = 2; const c = true ? a : b;");
@@ -1143,6 +1802,54 @@ mod tests {
         assert_eq!(dfg.node_count(), 0);
     }
 
+    #[test]
+    fn dfg_records_where_checks_hold() {
+        let dfg = build_dfg(
+            "function f(a) { if (!a.ok() || a.bad()) return; use(a); }
+             if (list.indexOf(a) === -1) { other(a); }",
+        );
+        let call = |name: &str| {
+            dfg.nodes()
+                .find(|node| {
+                    matches!(&node.kind, DfgNodeKind::Call { callee_name } if callee_name == name)
+                })
+                .unwrap()
+                .span
+        };
+        let known = |check: &str, at: &str| -> Vec<bool> {
+            dfg.conditions(call(check))
+                .filter(|condition| condition.region.contains(call(at)))
+                .map(|condition| condition.holds)
+                .collect()
+        };
+
+        assert_eq!(known("ok", "use"), vec![true]);
+        assert_eq!(known("bad", "use"), vec![false]);
+        assert!(known("ok", "other").is_empty());
+        assert_eq!(known("indexOf", "other"), vec![false]);
+    }
+
+    #[test]
+    fn dfg_finds_strings_ending_with_separator() {
+        let dfg = build_dfg(
+            "const a = '/srv/files/'; const b = '/srv/files'; const c = b + path.sep;
+             const d = b + '\\\\'; const e = b + sep;",
+        );
+        let ends = |variable: &str| {
+            let node = dfg
+                .nodes()
+                .find(|n| matches!(&n.kind, DfgNodeKind::Variable { name, .. } if name == variable))
+                .unwrap();
+            dfg.ends_with_separator(node.id)
+        };
+
+        assert!(ends("a"));
+        assert!(!ends("b"));
+        assert!(ends("c"));
+        assert!(ends("d"));
+        assert!(!ends("e"));
+    }
+
     #[test]
     fn dfg_node_count_grows_with_declarations() {
         let dfg1 = build_dfg("const a = 1;");
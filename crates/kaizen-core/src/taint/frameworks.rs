@@ -0,0 +1,564 @@
+//! Request parameters of web framework handlers
+//!
+//! Frameworks hand request data to handlers through parameters whose names
+//! are up to the author, so they cannot be matched by name. This module finds
+//! the handlers instead, from route registrations (Express, Koa, Fastify),
+//! NestJS parameter decorators and Next.js file conventions, and reports the
+//! bindings of their request parameters.
+
+use std::collections::{HashMap, HashSet};
+
+use swc_common::Span;
+use swc_ecma_ast::{
+    ArrowExpr, CallExpr, Callee, Decl, DefaultDecl, ExportDecl, ExportDefaultDecl,
+    ExportDefaultExpr, Expr, FnDecl, Function, Lit, MemberProp, Module, ObjectLit, ObjectPatProp,
+    Pat, Prop, PropName, PropOrSpread, VarDeclarator,
+};
+use swc_ecma_visit::{Visit, VisitWith};
+
+/// Spans of the bindings that hold request data, as used by DFG parameter and
+/// variable nodes.
+pub type RequestParameters = HashSet<Span>;
+
+/// Methods that register route handlers on an Express, Koa or Fastify app or
+/// router.
+const ROUTE_METHODS: &[&str] = &[
+    "get", "post", "put", "patch", "delete", "del", "all", "options", "head", "use",
+];
+
+/// Names under which apps and routers are usually kept, whose `use()` takes
+/// middleware without a path.
+const APP_NAMES: &[&str] = &["app", "router", "server"];
+
+/// Functions and classes that create an Express, Koa or Fastify app or router.
+const APP_FACTORIES: &[&str] = &[
+    "express",
+    "Router",
+    "Koa",
+    "KoaRouter",
+    "fastify",
+    "Fastify",
+];
+
+/// Properties of a Fastify route object that hold request handlers.
+const FASTIFY_HANDLER_KEYS: &[&str] = &["handler", "preHandler", "preValidation", "onRequest"];
+
+/// NestJS parameter decorators that inject request data.
+const NEST_DECORATORS: &[&str] = &[
+    "Body",
+    "Query",
+    "Param",
+    "Headers",
+    "Req",
+    "Request",
+    "HostParam",
+    "UploadedFile",
+    "UploadedFiles",
+];
+
+/// Next.js route handler exports, named after the HTTP method they serve.
+const NEXT_ROUTE_HANDLERS: &[&str] = &["GET", "HEAD", "POST", "PUT", "DELETE", "PATCH", "OPTIONS"];
+
+/// Next.js page props that come from the URL.
+const NEXT_PAGE_PROPS: &[&str] = &["searchParams", "params"];
+
+/// How a handler receives request data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Handler {
+    /// `(req, res, next)`, `(ctx, next)` or `(request, reply)`; error middleware
+    /// `(err, req, res, next)` receives the request second.
+    Route,
+    /// Next.js `GET(request, { params })`.
+    NextRoute,
+    /// Next.js `middleware(request)` and `getServerSideProps(context)`.
+    NextRequest,
+    /// Next.js pages and `generateMetadata`, whose `searchParams` and `params`
+    /// props come from the URL.
+    NextPage,
+}
+
+/// Finds the request parameters of the handlers in `module`.
+pub fn request_parameters(module: &Module) -> RequestParameters {
+    let mut finder = HandlerFinder::default();
+    module.visit_with(&mut finder);
+
+    let mut parameters = finder.parameters;
+    for (name, handler) in finder.named_handlers {
+        if let Some(params) = finder.functions.get(&name) {
+            mark(&mut parameters, params.iter(), handler);
+        }
+    }
+    parameters
+}
+
+#[derive(Default)]
+struct HandlerFinder {
+    parameters: RequestParameters,
+    /// Parameters of named functions, for handlers passed by name.
+    functions: HashMap<String, Vec<Pat>>,
+    named_handlers: Vec<(String, Handler)>,
+    /// Variables holding an app or router, such as `const api = express.Router()`.
+    apps: HashSet<String>,
+}
+
+impl HandlerFinder {
+    fn route_call(&mut self, call: &CallExpr) {
+        let Callee::Expr(callee) = &call.callee else {
+            return;
+        };
+        let Expr::Member(member) = &**callee else {
+            return;
+        };
+        let MemberProp::Ident(method) = &member.prop else {
+            return;
+        };
+        let method = method.sym.as_ref();
+
+        if method == "route" {
+            // Fastify `fastify.route({ url, handler })`
+            if let Some(arg) = call.args.first()
+                && let Expr::Object(object) = &*arg.expr
+            {
+                self.route_object(object);
+            }
+            return;
+        }
+        if !ROUTE_METHODS.contains(&method) {
+            return;
+        }
+
+        // `app.route('/users').get(handler)` has its path on the receiver
+        let path_on_receiver = matches!(
+            &*member.obj,
+            Expr::Call(CallExpr { callee: Callee::Expr(inner), .. })
+                if matches!(&**inner, Expr::Member(m)
+                    if matches!(&m.prop, MemberProp::Ident(p) if p.sym == *"route"))
+        );
+        let has_path = call
+            .args
+            .first()
+            .is_some_and(|arg| is_route_path(&arg.expr));
+        // Other libraries have a `use()` too, such as `axios.interceptors.request.use()`
+        let middleware = method == "use" && self.is_app(&member.obj);
+        if !(has_path || path_on_receiver || middleware) {
+            return;
+        }
+
+        for arg in call.args.iter().skip(usize::from(has_path)) {
+            match &*arg.expr {
+                Expr::Object(object) => self.route_object(object),
+                expr => self.handler(expr, Handler::Route),
+            }
+        }
+    }
+
+    /// Whether `expr` is an app or router: a variable named or created as one,
+    /// or a call such as `express()`.
+    fn is_app(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Ident(ident) => {
+                APP_NAMES.contains(&ident.sym.as_ref()) || self.apps.contains(ident.sym.as_ref())
+            }
+            expr => is_app_factory(expr),
+        }
+    }
+
+    fn route_object(&mut self, object: &ObjectLit) {
+        for prop in &object.props {
+            if let PropOrSpread::Prop(prop) = prop
+                && let Prop::KeyValue(kv) = &**prop
+                && prop_name(&kv.key).is_some_and(|key| FASTIFY_HANDLER_KEYS.contains(&key))
+            {
+                self.handler(&kv.value, Handler::Route);
+            }
+        }
+    }
+
+    /// Marks the request parameters of a handler argument: a function, a
+    /// reference to one, or a wrapper such as `asyncHandler(fn)`.
+    fn handler(&mut self, expr: &Expr, handler: Handler) {
+        match expr {
+            Expr::Arrow(arrow) => mark(&mut self.parameters, arrow.params.iter(), handler),
+            Expr::Fn(fn_expr) => mark(
+                &mut self.parameters,
+                fn_expr.function.params.iter().map(|param| &param.pat),
+                handler,
+            ),
+            Expr::Ident(ident) => self.named_handlers.push((ident.sym.to_string(), handler)),
+            Expr::Paren(paren) => self.handler(&paren.expr, handler),
+            Expr::Call(call) => {
+                for arg in &call.args {
+                    if matches!(&*arg.expr, Expr::Arrow(_) | Expr::Fn(_)) {
+                        self.handler(&arg.expr, handler);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Parameters injected by NestJS decorators such as `@Body() dto`.
+    fn decorated_params(&mut self, function: &Function) {
+        for param in &function.params {
+            let injected = param.decorators.iter().any(|decorator| {
+                let name = match &*decorator.expr {
+                    Expr::Call(CallExpr {
+                        callee: Callee::Expr(callee),
+                        ..
+                    }) => callee.as_ident(),
+                    Expr::Ident(ident) => Some(ident),
+                    _ => None,
+                };
+                name.is_some_and(|name| NEST_DECORATORS.contains(&name.sym.as_ref()))
+            });
+            if injected {
+                bindings(&param.pat, &mut self.parameters);
+            }
+        }
+    }
+
+    fn export_decl(&mut self, export: &ExportDecl) {
+        match &export.decl {
+            Decl::Fn(FnDecl {
+                ident, function, ..
+            }) => {
+                if let Some(handler) = next_export(ident.sym.as_ref()) {
+                    mark(
+                        &mut self.parameters,
+                        function.params.iter().map(|param| &param.pat),
+                        handler,
+                    );
+                }
+            }
+            Decl::Var(var) => {
+                for declarator in &var.decls {
+                    if let (Pat::Ident(name), Some(init)) = (&declarator.name, &declarator.init)
+                        && let Some(handler) = next_export(name.id.sym.as_ref())
+                    {
+                        self.handler(init, handler);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn remember_function(&mut self, name: &str, params: Vec<Pat>) {
+        self.functions.insert(name.to_string(), params);
+    }
+}
+
+impl Visit for HandlerFinder {
+    fn visit_call_expr(&mut self, node: &CallExpr) {
+        self.route_call(node);
+        node.visit_children_with(self);
+    }
+
+    fn visit_function(&mut self, node: &Function) {
+        self.decorated_params(node);
+        node.visit_children_with(self);
+    }
+
+    fn visit_fn_decl(&mut self, node: &FnDecl) {
+        let params = node.function.params.iter().map(|p| p.pat.clone()).collect();
+        self.remember_function(node.ident.sym.as_ref(), params);
+        node.visit_children_with(self);
+    }
+
+    fn visit_var_declarator(&mut self, node: &VarDeclarator) {
+        if let (Pat::Ident(name), Some(init)) = (&node.name, &node.init) {
+            if is_app_factory(init) {
+                self.apps.insert(name.id.sym.to_string());
+            }
+            let params = match &**init {
+                Expr::Arrow(ArrowExpr { params, .. }) => Some(params.clone()),
+                Expr::Fn(fn_expr) => Some(
+                    fn_expr
+                        .function
+                        .params
+                        .iter()
+                        .map(|p| p.pat.clone())
+                        .collect(),
+                ),
+                _ => None,
+            };
+            if let Some(params) = params {
+                self.remember_function(name.id.sym.as_ref(), params);
+            }
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_export_decl(&mut self, node: &ExportDecl) {
+        self.export_decl(node);
+        node.visit_children_with(self);
+    }
+
+    fn visit_export_default_decl(&mut self, node: &ExportDefaultDecl) {
+        if let DefaultDecl::Fn(fn_expr) = &node.decl {
+            mark(
+                &mut self.parameters,
+                fn_expr.function.params.iter().map(|param| &param.pat),
+                Handler::NextPage,
+            );
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_export_default_expr(&mut self, node: &ExportDefaultExpr) {
+        self.handler(&node.expr, Handler::NextPage);
+        node.visit_children_with(self);
+    }
+}
+
+/// Whether `expr` creates an app or router, as `express()`, `express.Router()`
+/// or `new Koa()` do.
+fn is_app_factory(expr: &Expr) -> bool {
+    let callee = match expr {
+        Expr::Call(CallExpr {
+            callee: Callee::Expr(callee),
+            ..
+        }) => &**callee,
+        Expr::New(new) => &*new.callee,
+        Expr::Await(await_expr) => return is_app_factory(&await_expr.arg),
+        _ => return false,
+    };
+    let name = match callee {
+        Expr::Ident(ident) => ident.sym.as_ref(),
+        Expr::Member(member) => match &member.prop {
+            MemberProp::Ident(prop) => prop.sym.as_ref(),
+            _ => return false,
+        },
+        _ => return false,
+    };
+    APP_FACTORIES.contains(&name)
+}
+
+/// The kind of Next.js handler an export named `name` is.
+fn next_export(name: &str) -> Option<Handler> {
+    if NEXT_ROUTE_HANDLERS.contains(&name) {
+        Some(Handler::NextRoute)
+    } else if matches!(name, "middleware" | "getServerSideProps") {
+        Some(Handler::NextRequest)
+    } else if name == "generateMetadata" {
+        Some(Handler::NextPage)
+    } else {
+        None
+    }
+}
+
+fn mark<'a>(
+    parameters: &mut RequestParameters,
+    params: impl Iterator<Item = &'a Pat>,
+    handler: Handler,
+) {
+    let params: Vec<&Pat> = params.collect();
+    match handler {
+        Handler::Route => {
+            let index = if params.len() == 4 { 1 } else { 0 };
+            if let Some(param) = params.get(index) {
+                bindings(param, parameters);
+            }
+        }
+        Handler::NextRoute => {
+            for param in params.iter().take(2) {
+                bindings(param, parameters);
+            }
+        }
+        Handler::NextRequest => {
+            if let Some(param) = params.first() {
+                bindings(param, parameters);
+            }
+        }
+        Handler::NextPage => {
+            if let Some(Pat::Object(props)) = params.first().map(|param| strip_default(param)) {
+                for prop in &props.props {
+                    match prop {
+                        ObjectPatProp::KeyValue(kv)
+                            if prop_name(&kv.key)
+                                .is_some_and(|key| NEXT_PAGE_PROPS.contains(&key)) =>
+                        {
+                            bindings(&kv.value, parameters);
+                        }
+                        ObjectPatProp::Assign(assign)
+                            if NEXT_PAGE_PROPS.contains(&assign.key.sym.as_ref()) =>
+                        {
+                            parameters.insert(assign.key.span);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Collects the spans of the names bound by `pat`.
+fn bindings(pat: &Pat, spans: &mut RequestParameters) {
+    match pat {
+        Pat::Ident(ident) => {
+            spans.insert(ident.span);
+        }
+        Pat::Array(array) => {
+            for elem in array.elems.iter().flatten() {
+                bindings(elem, spans);
+            }
+        }
+        Pat::Object(object) => {
+            for prop in &object.props {
+                match prop {
+                    ObjectPatProp::KeyValue(kv) => bindings(&kv.value, spans),
+                    ObjectPatProp::Assign(assign) => {
+                        spans.insert(assign.key.span);
+                    }
+                    ObjectPatProp::Rest(rest) => bindings(&rest.arg, spans),
+                }
+            }
+        }
+        Pat::Rest(rest) => bindings(&rest.arg, spans),
+        Pat::Assign(assign) => bindings(&assign.left, spans),
+        _ => {}
+    }
+}
+
+fn strip_default(pat: &Pat) -> &Pat {
+    match pat {
+        Pat::Assign(assign) => &assign.left,
+        _ => pat,
+    }
+}
+
+fn is_route_path(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(Lit::Str(path)) => path.value.starts_with('/') || path.value == *"*",
+        Expr::Lit(Lit::Regex(_)) | Expr::Tpl(_) => true,
+        Expr::Array(array) => array
+            .elems
+            .iter()
+            .flatten()
+            .all(|elem| is_route_path(&elem.expr)),
+        _ => false,
+    }
+}
+
+fn prop_name(key: &PropName) -> Option<&str> {
+    match key {
+        PropName::Ident(ident) => Some(ident.sym.as_ref()),
+        PropName::Str(s) => Some(s.value.as_str()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ParsedFile;
+
+    /// Names of the bindings marked as request parameters, in source order.
+    fn request_names(code: &str) -> Vec<String> {
+        let parsed = ParsedFile::from_source("test.ts", code);
+        let module = parsed.module().expect("parse failed");
+        let mut spans: Vec<Span> = request_parameters(module).into_iter().collect();
+        spans.sort_by_key(|span| span.lo);
+        spans
+            .iter()
+            .map(|span| {
+                // Arrow parameter spans include their type annotation
+                let text = &parsed.source()[span.lo.0 as usize - 1..span.hi.0 as usize - 1];
+                text.split(':').next().unwrap_or(text).trim().to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn marks_route_handler_requests_whatever_their_name() {
+        let code = r#"
+            app.get('/users/:id', (r, s) => s.send(r.params.id));
+            router.post('/items', auth, async function (input, output, next) {});
+            app.use((err, request, response, next) => {});
+            app.route('/books').put(({ body }, res) => {});
+            fastify.route({ method: 'GET', url: '/', handler: async (q, reply) => {} });
+        "#;
+
+        assert_eq!(
+            request_names(code),
+            vec!["r", "input", "request", "body", "q"]
+        );
+    }
+
+    #[test]
+    fn marks_handlers_passed_by_name_or_wrapped() {
+        let code = r#"
+            async function show(incoming, res) {}
+            const update = (c, next) => {};
+            app.get('/show', show);
+            router.patch('/update', asyncHandler(update), catchAsync((x, y) => {}));
+        "#;
+
+        assert_eq!(request_names(code), vec!["incoming", "x"]);
+    }
+
+    #[test]
+    fn ignores_calls_that_do_not_register_routes() {
+        let code = r#"
+            cache.get('key', (err, value) => {});
+            map.delete(key);
+            items.all((item) => item.ok);
+        "#;
+
+        assert!(request_names(code).is_empty());
+    }
+
+    #[test]
+    fn marks_middleware_of_apps_and_routers_only() {
+        let code = r#"
+            const api = express.Router();
+            const koa = new Koa();
+            api.use((first, res, next) => {});
+            koa.use(async (second, next) => {});
+            express().use((third, res, next) => {});
+            axios.interceptors.request.use((config) => config);
+            chai.use((chai, utils) => {});
+            i18next.use(backend);
+            vue.use((instance) => {});
+        "#;
+
+        assert_eq!(request_names(code), vec!["first", "second", "third"]);
+    }
+
+    #[test]
+    fn marks_nest_decorated_parameters() {
+        let code = r#"
+            @Controller('users')
+            class UsersController {
+                @Post()
+                create(@Body() dto: CreateUserDto, @Query('page') page: string, service: Service) {}
+            }
+        "#;
+
+        assert_eq!(request_names(code), vec!["dto", "page"]);
+    }
+
+    #[test]
+    fn marks_next_route_handlers_and_page_props() {
+        let code = r#"
+            export async function GET(req: Request, { params }) {}
+            export const POST = async (incoming: NextRequest) => {};
+            export function middleware(request) {}
+            export default function Page({ searchParams, params: route, title }) {}
+            export function helper(req) {}
+        "#;
+
+        assert_eq!(
+            request_names(code),
+            vec![
+                "req",
+                "params",
+                "incoming",
+                "request",
+                "searchParams",
+                "route"
+            ]
+        );
+    }
+}
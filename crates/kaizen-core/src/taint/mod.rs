@@ -3,15 +3,25 @@
 //! Used for detecting security vulnerabilities by tracking untrusted data.
 
 pub mod dfg;
+pub mod frameworks;
+pub mod project;
 pub mod propagation;
 pub mod sanitizers;
 pub mod sinks;
 pub mod sources;
+pub mod summaries;
 
-pub use dfg::{DataFlowGraph, DfgNode, DfgNodeId, DfgNodeKind};
-pub use propagation::{TaintFinding, TaintPropagator, TaintState, TaintedNode};
+pub use dfg::{
+    DataFlowGraph, DfgCallSite, DfgCondition, DfgFunction, DfgNode, DfgNodeId, DfgNodeKind,
+};
+pub use frameworks::RequestParameters;
+pub use project::ProjectTaintFinding;
+pub use propagation::{
+    TaintFinding, TaintPropagator, TaintState, TaintedNode, TraceStep, TraceStepKind,
+};
 pub use sanitizers::{
-    SanitizerCategory, SanitizerKind, SanitizerMatch, SanitizerPattern, SanitizersRegistry,
+    CustomSanitizerConfig, GuardCheck, GuardPattern, SanitizerCategory, SanitizerKind,
+    SanitizerMatch, SanitizerPattern, SanitizersRegistry,
 };
 pub use sinks::{
     TaintSinkCategory, TaintSinkKind, TaintSinkMatch, TaintSinkPattern, TaintSinksRegistry,
@@ -20,8 +30,15 @@ pub use sources::{
     PropertyMatcher, TaintCategory, TaintSourceKind, TaintSourceMatch, TaintSourcePattern,
     TaintSourcesRegistry,
 };
+pub use summaries::{
+    FunctionSummaries, FunctionSummary, ImportedSink, ImportedSummaries, Sanitized, SummarySink,
+};
 
+use std::sync::{Mutex, PoisonError};
+
+use crate::config::TaintConfig;
 use crate::parser::ParsedFile;
+use crate::project::Project;
 use crate::semantic::ScopeBuilder;
 
 #[derive(Debug)]
@@ -29,6 +46,9 @@ pub struct TaintAnalyzer {
     sources_registry: TaintSourcesRegistry,
     sinks_registry: TaintSinksRegistry,
     sanitizers_registry: SanitizersRegistry,
+    /// Findings of the last project analyzed, by [`Project::id`], which every
+    /// taint rule asks for in turn.
+    project_findings: Mutex<Option<(u64, Vec<ProjectTaintFinding>)>>,
 }
 
 impl Default for TaintAnalyzer {
@@ -39,11 +59,11 @@ impl Default for TaintAnalyzer {
 
 impl TaintAnalyzer {
     pub fn new() -> Self {
-        Self {
-            sources_registry: TaintSourcesRegistry::with_defaults(),
-            sinks_registry: TaintSinksRegistry::with_defaults(),
-            sanitizers_registry: SanitizersRegistry::with_defaults(),
-        }
+        Self::with_registries(
+            TaintSourcesRegistry::with_defaults(),
+            TaintSinksRegistry::with_defaults(),
+            SanitizersRegistry::with_defaults(),
+        )
     }
 
     pub fn with_registries(
@@ -55,7 +75,69 @@ impl TaintAnalyzer {
             sources_registry,
             sinks_registry,
             sanitizers_registry,
+            project_findings: Mutex::new(None),
+        }
+    }
+
+    /// The built-in registries extended with the project's `[taint]` configuration.
+    pub fn with_config(config: &TaintConfig) -> Self {
+        let mut sources_registry = TaintSourcesRegistry::with_defaults();
+        for source in &config.sources {
+            let description = source.description.as_deref().unwrap_or("Configured source");
+            match source.path.rsplit_once('.') {
+                Some((object, property)) => {
+                    let property = match property {
+                        "*" => PropertyMatcher::Any,
+                        property => PropertyMatcher::Exact(property.to_string()),
+                    };
+                    sources_registry.register_pattern(TaintSourcePattern::custom(
+                        object.split('.').collect(),
+                        property,
+                        source.category,
+                        description,
+                    ));
+                }
+                None => {
+                    sources_registry.register_parameter_name(source.path.clone(), source.category)
+                }
+            }
+        }
+
+        // Calls are matched on their callee, or on a method and the object it is called on.
+        let mut sinks_registry = TaintSinksRegistry::with_defaults();
+        for sink in &config.sinks {
+            let (callee, method) = callee_and_method(&sink.path);
+            sinks_registry.register_pattern(TaintSinkPattern::custom(
+                vec![callee],
+                method,
+                sink.category,
+                sink.description.as_deref().unwrap_or("Configured sink"),
+                sink.args.clone(),
+            ));
         }
+
+        let sanitizers: Vec<CustomSanitizerConfig> = config
+            .sanitizers
+            .iter()
+            .map(|sanitizer| {
+                let (callee, method) = callee_and_method(&sanitizer.path);
+                CustomSanitizerConfig {
+                    callee_path: vec![callee.to_string()],
+                    method: method.map(str::to_string),
+                    category: sanitizer.category,
+                    description: sanitizer
+                        .description
+                        .clone()
+                        .unwrap_or_else(|| "Configured sanitizer".to_string()),
+                }
+            })
+            .collect();
+
+        Self::with_registries(
+            sources_registry,
+            sinks_registry,
+            SanitizersRegistry::with_custom_sanitizers(&sanitizers),
+        )
     }
 
     pub fn analyze(&self, parsed: &ParsedFile) -> Vec<TaintFinding> {
@@ -66,13 +148,38 @@ impl TaintAnalyzer {
 
         let semantic = ScopeBuilder::build(module);
         let dfg = DataFlowGraph::build(module, &semantic);
-        let mut propagator = TaintPropagator::new(
-            &dfg,
+        self.propagator(&dfg)
+            .with_request_parameters(frameworks::request_parameters(module))
+            .analyze()
+    }
+
+    /// Finds flows from a source in one file to a sink in another, through
+    /// functions that the source file imports. Flows within a single file are
+    /// left to [`TaintAnalyzer::analyze`]. The findings are kept until another
+    /// project is analyzed.
+    pub fn analyze_project(&self, project: &Project) -> Vec<ProjectTaintFinding> {
+        let mut cached = self
+            .project_findings
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some((id, findings)) = cached.as_ref()
+            && *id == project.id()
+        {
+            return findings.clone();
+        }
+
+        let findings = project::analyze_project(self, project);
+        *cached = Some((project.id(), findings.clone()));
+        findings
+    }
+
+    fn propagator<'a>(&'a self, dfg: &'a DataFlowGraph) -> TaintPropagator<'a> {
+        TaintPropagator::new(
+            dfg,
             &self.sources_registry,
             &self.sinks_registry,
             &self.sanitizers_registry,
-        );
-        propagator.analyze()
+        )
     }
 
     pub fn sources_registry(&self) -> &TaintSourcesRegistry {
@@ -88,6 +195,16 @@ impl TaintAnalyzer {
     }
 }
 
+/// Splits `a.b.run` into the object a method is called on, `b`, and the method.
+fn callee_and_method(path: &str) -> (&str, Option<&str>) {
+    let mut segments = path.rsplit('.');
+    let last = segments.next().unwrap_or(path);
+    match segments.next() {
+        Some(object) => (object, Some(last)),
+        None => (last, None),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -121,4 +238,48 @@ mod tests {
 
         assert!(findings.is_empty(), "should return empty for parse errors");
     }
+
+    #[test]
+    fn analyzer_follows_framework_handler_parameters() {
+        let cases = [
+            (
+                "express.js",
+                "app.get('/run', (r, s) => { exec(r.query.cmd); });",
+            ),
+            (
+                "koa.js",
+                "router.post('/users', async (c) => { db.query('SELECT ' + c.request.body.id); });",
+            ),
+            (
+                "fastify.js",
+                "fastify.get('/files', async (incoming, reply) => { eval(incoming.params.code); });",
+            ),
+            (
+                "users.controller.ts",
+                "class UsersController {\n  create(@Body() dto: CreateUserDto) { db.query(`INSERT ${dto.name}`); }\n}",
+            ),
+            (
+                "app/search/page.tsx",
+                "export default async function Page({ searchParams }) {\n  const { q } = await searchParams;\n  eval(q);\n}",
+            ),
+            (
+                "app/api/route.ts",
+                "export async function POST(incoming: Request) {\n  const body = await incoming.json();\n  exec(body.cmd);\n}",
+            ),
+        ];
+
+        let analyzer = TaintAnalyzer::new();
+        for (filename, code) in cases {
+            let parsed = ParsedFile::from_source(filename, code);
+            let findings = analyzer.analyze(&parsed);
+            assert_eq!(findings.len(), 1, "{filename}: {findings:?}");
+        }
+    }
+
+    #[test]
+    fn analyzer_ignores_parameters_of_other_callbacks() {
+        let code = "cache.get('key', (err, value) => { eval(value); });";
+        let parsed = ParsedFile::from_source("test.js", code);
+        assert!(TaintAnalyzer::new().analyze(&parsed).is_empty());
+    }
 }
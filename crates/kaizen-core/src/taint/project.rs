@@ -0,0 +1,350 @@
+//! Cross-module taint analysis
+//!
+//! Exported functions are summarized per module and handed to the modules that
+//! import them, so that untrusted data passed to an imported function is
+//! followed to the sinks inside it. Summaries are recomputed for importers
+//! until they stop changing, which also settles import cycles.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::project::{DEFAULT_EXPORT, ExportKind, ImportKind, ModuleId, Project, ResolvedImport};
+use crate::semantic::ScopeBuilder;
+
+use super::{
+    DataFlowGraph, FunctionSummary, ImportedSummaries, RequestParameters, TaintAnalyzer,
+    TaintFinding, frameworks,
+};
+
+/// A finding whose sink is in another file than its source.
+#[derive(Debug, Clone)]
+pub struct ProjectTaintFinding {
+    /// The file `finding.source_span` is relative to.
+    pub source_file: String,
+    /// The finding, with `sink_file` set.
+    pub finding: TaintFinding,
+}
+
+type ExportedSummaries = HashMap<String, FunctionSummary>;
+
+pub(super) fn analyze_project(
+    analyzer: &TaintAnalyzer,
+    project: &Project,
+) -> Vec<ProjectTaintFinding> {
+    let graph = project.graph();
+    let analyzed: Vec<Option<(DataFlowGraph, RequestParameters)>> = project
+        .files()
+        .map(|(_, file)| {
+            file.module().map(|module| {
+                let semantic = ScopeBuilder::build(module);
+                (
+                    DataFlowGraph::build(module, &semantic),
+                    frameworks::request_parameters(module),
+                )
+            })
+        })
+        .collect();
+
+    let mut exported: Vec<ExportedSummaries> = vec![HashMap::new(); analyzed.len()];
+    let mut worklist: VecDeque<ModuleId> = graph.modules().map(|(id, _)| id).collect();
+    let mut queued: HashSet<ModuleId> = worklist.iter().copied().collect();
+
+    while let Some(id) = worklist.pop_front() {
+        queued.remove(&id);
+        let module = graph.module(id);
+        let Some((dfg, _)) = &analyzed[id.index()] else {
+            continue;
+        };
+        if module.info.exports.is_empty() {
+            continue;
+        }
+
+        let mut propagator = analyzer
+            .propagator(dfg)
+            .with_imports(imported_summaries(project, id, &exported));
+        propagator.summarize();
+
+        let mut summaries = ExportedSummaries::new();
+        for export in &module.info.exports {
+            let summary = match &export.kind {
+                ExportKind::ReExport {
+                    import,
+                    imported: Some(name),
+                } => match module.resolved.get(*import) {
+                    Some(ResolvedImport::Module(target)) => {
+                        exported[target.index()].get(name).cloned()
+                    }
+                    _ => None,
+                },
+                ExportKind::ReExport { imported: None, .. } => None,
+                _ => propagator.exported_summary(
+                    export.local.as_deref().unwrap_or(&export.name),
+                    &module.filename,
+                ),
+            };
+            if let Some(summary) = summary {
+                summaries.insert(export.name.clone(), summary);
+            }
+        }
+        for &import in &module.info.star_reexports {
+            if let Some(ResolvedImport::Module(target)) = module.resolved.get(import) {
+                for (name, summary) in &exported[target.index()] {
+                    if name != DEFAULT_EXPORT {
+                        summaries
+                            .entry(name.clone())
+                            .or_insert_with(|| summary.clone());
+                    }
+                }
+            }
+        }
+
+        if summaries != exported[id.index()] {
+            exported[id.index()] = summaries;
+            for &dependent in graph.dependents(id) {
+                if queued.insert(dependent) {
+                    worklist.push_back(dependent);
+                }
+            }
+        }
+    }
+
+    let mut findings = Vec::new();
+    for (id, module) in graph.modules() {
+        let Some((dfg, request_parameters)) = &analyzed[id.index()] else {
+            continue;
+        };
+        let imports = imported_summaries(project, id, &exported);
+        if imports.is_empty() {
+            continue;
+        }
+        let mut propagator = analyzer
+            .propagator(dfg)
+            .with_imports(imports)
+            .with_request_parameters(request_parameters.clone());
+        findings.extend(
+            propagator
+                .analyze()
+                .into_iter()
+                .filter(|finding| finding.sink_file.is_some())
+                .map(|finding| ProjectTaintFinding {
+                    source_file: module.filename.clone(),
+                    finding,
+                }),
+        );
+    }
+    findings
+}
+
+/// Summaries of the functions module `id` imports from other analyzed modules.
+fn imported_summaries(
+    project: &Project,
+    id: ModuleId,
+    exported: &[ExportedSummaries],
+) -> ImportedSummaries {
+    let module = project.graph().module(id);
+    let mut imports = ImportedSummaries::default();
+
+    for (index, import) in module.info.imports.iter().enumerate() {
+        if import.type_only || matches!(import.kind, ImportKind::ReExport | ImportKind::Dynamic) {
+            continue;
+        }
+        let Some(ResolvedImport::Module(target)) = module.resolved.get(index) else {
+            continue;
+        };
+        let target_exports = &exported[target.index()];
+        if target_exports.is_empty() {
+            continue;
+        }
+
+        for binding in &import.bindings {
+            if binding.local.is_empty() || binding.type_only {
+                continue;
+            }
+            match binding.imported.as_deref() {
+                Some(name) => {
+                    if let Some(summary) = target_exports.get(name) {
+                        imports
+                            .functions
+                            .insert(binding.local.clone(), summary.clone());
+                    }
+                    // A default import of a CommonJS module is its `module.exports` object
+                    if name == DEFAULT_EXPORT {
+                        imports
+                            .namespaces
+                            .insert(binding.local.clone(), target_exports.clone());
+                    }
+                }
+                None => {
+                    imports
+                        .namespaces
+                        .insert(binding.local.clone(), target_exports.clone());
+                }
+            }
+        }
+    }
+
+    imports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ParsedFile;
+    use crate::taint::TaintSinkCategory;
+    use std::fs;
+    use std::path::Path;
+
+    fn analyze(dir: &Path, files: &[(&str, &str)]) -> Vec<ProjectTaintFinding> {
+        let parsed = files
+            .iter()
+            .map(|(name, source)| {
+                let path = dir.join(name);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(&path, source).unwrap();
+                ParsedFile::from_source(&path.to_string_lossy(), source)
+            })
+            .collect();
+        TaintAnalyzer::new().analyze_project(&Project::new(parsed))
+    }
+
+    #[test]
+    fn findings_are_reused_for_the_same_project_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            ("db.js", "exports.run = (sql) => db.query(sql);\n"),
+            (
+                "app.js",
+                "const db = require('./db');\nfunction show(req) {\n  db.run(req.query.sql);\n}\n",
+            ),
+        ];
+        let parse = |files: &[(&str, &str)]| {
+            files
+                .iter()
+                .map(|(name, source)| {
+                    let path = dir.path().join(name);
+                    fs::write(&path, source).unwrap();
+                    ParsedFile::from_source(&path.to_string_lossy(), source)
+                })
+                .collect()
+        };
+        let analyzer = TaintAnalyzer::new();
+        let project = Project::new(parse(&files));
+
+        assert_eq!(analyzer.analyze_project(&project).len(), 1);
+        assert_eq!(analyzer.analyze_project(&project).len(), 1);
+
+        let fixed = Project::new(parse(&[
+            files[0],
+            ("app.js", "require('./db').run('SELECT 1');\n"),
+        ]));
+        assert!(analyzer.analyze_project(&fixed).is_empty());
+    }
+
+    #[test]
+    fn follows_tainted_arguments_into_imported_functions() {
+        let dir = tempfile::tempdir().unwrap();
+        let findings = analyze(
+            dir.path(),
+            &[
+                (
+                    "repository.js",
+                    "export function findUser(id) {\n  return db.query('SELECT * FROM users WHERE id = ' + id);\n}\n",
+                ),
+                (
+                    "routes/users.js",
+                    "import { findUser } from '../repository';\nfunction show(req, res) {\n  findUser(req.params.id);\n}\n",
+                ),
+            ],
+        );
+
+        assert_eq!(findings.len(), 1);
+        let finding = &findings[0];
+        assert!(finding.source_file.ends_with("users.js"));
+        assert!(
+            finding
+                .finding
+                .sink_file
+                .as_deref()
+                .is_some_and(|file| file.ends_with("repository.js"))
+        );
+        assert_eq!(
+            finding.finding.sink_category,
+            TaintSinkCategory::SqlInjection
+        );
+    }
+
+    #[test]
+    fn follows_commonjs_modules_and_reexports() {
+        let dir = tempfile::tempdir().unwrap();
+        let findings = analyze(
+            dir.path(),
+            &[
+                (
+                    "lib/shell.js",
+                    "function run(command) {\n  exec(command);\n}\nmodule.exports = { run };\n",
+                ),
+                ("lib/index.js", "export { run } from './shell';\n"),
+                (
+                    "tasks.js",
+                    "const lib = require('./lib');\nfunction deploy(req, res) {\n  lib.run('deploy ' + req.body.target);\n}\n",
+                ),
+            ],
+        );
+
+        // `lib/index.js` re-exports `run`, which is reached through `lib.run`
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].source_file.ends_with("tasks.js"));
+        assert_eq!(
+            findings[0].finding.sink_category,
+            TaintSinkCategory::CommandInjection
+        );
+    }
+
+    #[test]
+    fn follows_calls_through_intermediate_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        let findings = analyze(
+            dir.path(),
+            &[
+                ("db.js", "export const raw = (sql) => pool.query(sql);\n"),
+                (
+                    "service.js",
+                    "import { raw } from './db';\nexport function search(term) {\n  return raw(`SELECT * FROM items WHERE name = '${term}'`);\n}\n",
+                ),
+                (
+                    "handler.js",
+                    "import { search } from './service';\nexport function handle(req, res) {\n  search(req.query.q);\n}\n",
+                ),
+            ],
+        );
+
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].source_file.ends_with("handler.js"));
+        assert!(
+            findings[0]
+                .finding
+                .sink_file
+                .as_deref()
+                .is_some_and(|file| file.ends_with("db.js"))
+        );
+    }
+
+    #[test]
+    fn ignores_untainted_arguments_and_local_sinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let findings = analyze(
+            dir.path(),
+            &[
+                (
+                    "repository.js",
+                    "export function findUser(id) {\n  return db.query('SELECT * FROM users WHERE id = ' + id);\n}\n",
+                ),
+                (
+                    "routes/users.js",
+                    "import { findUser } from '../repository';\nfunction show(req, res) {\n  findUser(42);\n  eval(req.body.code);\n}\n",
+                ),
+            ],
+        );
+
+        assert!(findings.is_empty());
+    }
+}
@@ -2,23 +2,50 @@
 //!
 //! This module implements the core taint propagation that tracks how
 //! tainted values flow through assignments, string concatenation,
-//! and function returns using the Data Flow Graph.
+//! and function returns using the Data Flow Graph. Calls of functions
+//! defined in the same file follow their [`FunctionSummaries`].
+//!
+//! Each finding carries a [`TraceStep`] list from its source to its sink.
 
 use std::collections::{HashMap, HashSet, VecDeque};
 
 use swc_common::Span;
+use swc_ecma_ast::BinaryOp;
 
 use super::{
-    DataFlowGraph, DfgNode, DfgNodeId, DfgNodeKind, TaintCategory, TaintSinkCategory,
-    TaintSinkMatch, TaintSinksRegistry, TaintSourceMatch, TaintSourcesRegistry,
-    sanitizers::SanitizersRegistry,
+    DataFlowGraph, DfgCondition, DfgNode, DfgNodeId, DfgNodeKind, RequestParameters, TaintCategory,
+    TaintSinkCategory, TaintSinkMatch, TaintSinkPattern, TaintSinksRegistry, TaintSourceMatch,
+    TaintSourcesRegistry,
+    sanitizers::{GuardCheck, GuardPattern, SanitizerCategory, SanitizersRegistry},
+    sinks::OBJECT_LITERAL,
+    summaries::{
+        FunctionSummaries, FunctionSummary, Guards, ImportedSink, ImportedSummaries, Sanitized,
+        SummarySink, is_guarded, is_protected, is_sink_argument,
+    },
 };
 
+/// A check made on a value, which may guard it.
+struct ValueCheck {
+    /// The checked node, such as `target` in `target.startsWith('/')`.
+    node: DfgNodeId,
+    kind: GuardCheck,
+    /// The constant string the value is checked with.
+    argument: Option<String>,
+    /// Whether the string the value is checked with ends with a path separator.
+    separated: bool,
+    /// The call or comparison making the check.
+    span: Span,
+    /// The value an argument is looked up in, as `allowed` in `allowed.includes(host)`.
+    list: Option<DfgNodeId>,
+}
+
 #[derive(Debug, Clone)]
 pub struct TaintedNode {
     pub node_id: DfgNodeId,
     pub categories: HashSet<TaintCategory>,
     pub source_spans: Vec<Span>,
+    /// Sanitizers the value has passed through on every path from its sources.
+    pub sanitized_for: Sanitized,
 }
 
 #[derive(Debug)]
@@ -47,6 +74,7 @@ impl TaintState {
                 if !t.source_spans.contains(&source_span) {
                     t.source_spans.push(source_span);
                 }
+                t.sanitized_for.clear();
             })
             .or_insert_with(|| {
                 let mut categories = HashSet::new();
@@ -55,28 +83,56 @@ impl TaintState {
                     node_id,
                     categories,
                     source_spans: vec![source_span],
+                    sanitized_for: Sanitized::new(),
                 }
             });
     }
 
     pub fn merge_taint(&mut self, target: DfgNodeId, source: DfgNodeId) {
-        if let Some(source_taint) = self.tainted.get(&source).cloned() {
-            self.tainted
-                .entry(target)
-                .and_modify(|t| {
-                    t.categories.extend(source_taint.categories.iter().copied());
-                    for span in &source_taint.source_spans {
-                        if !t.source_spans.contains(span) {
-                            t.source_spans.push(*span);
-                        }
-                    }
-                })
-                .or_insert_with(|| TaintedNode {
+        self.flow(target, source, &Sanitized::new());
+    }
+
+    /// Merges the taint of `source` into `target`, after passing through the
+    /// `sanitized` categories. Returns whether the taint of `target` changed.
+    pub fn flow(&mut self, target: DfgNodeId, source: DfgNodeId, sanitized: &Sanitized) -> bool {
+        let Some(source_taint) = self.tainted.get(&source).cloned() else {
+            return false;
+        };
+        let mut sanitized_for = source_taint.sanitized_for;
+        sanitized_for.extend(sanitized.iter().copied());
+
+        let Some(t) = self.tainted.get_mut(&target) else {
+            self.tainted.insert(
+                target,
+                TaintedNode {
                     node_id: target,
-                    categories: source_taint.categories.clone(),
-                    source_spans: source_taint.source_spans.clone(),
-                });
+                    categories: source_taint.categories,
+                    source_spans: source_taint.source_spans,
+                    sanitized_for,
+                },
+            );
+            return true;
+        };
+
+        let before = (
+            t.categories.len(),
+            t.source_spans.len(),
+            t.sanitized_for.len(),
+        );
+        t.categories.extend(source_taint.categories);
+        for span in source_taint.source_spans {
+            if !t.source_spans.contains(&span) {
+                t.source_spans.push(span);
+            }
         }
+        t.sanitized_for
+            .retain(|category| sanitized_for.contains(category));
+        before
+            != (
+                t.categories.len(),
+                t.source_spans.len(),
+                t.sanitized_for.len(),
+            )
     }
 
     pub fn is_tainted(&self, node_id: DfgNodeId) -> bool {
@@ -100,6 +156,54 @@ pub struct TaintFinding {
     pub sink_category: TaintSinkCategory,
     pub sink_description: String,
     pub path: Vec<DfgNodeId>,
+    /// File of the sink when it is in an imported module; `sink_span` is then
+    /// relative to that file.
+    pub sink_file: Option<String>,
+    /// How the data gets from the source to the sink, starting at the source.
+    pub trace: Vec<TraceStep>,
+    /// Constant text the tainted sink argument starts with, such as the origin
+    /// of a URL.
+    pub argument_prefix: Option<String>,
+    /// Whether the code relies on the value the sink returns: compares it,
+    /// tests it, stores it on another object or returns it.
+    pub result_trusted: bool,
+    /// Whether a check that may not hold for every value guards the sink
+    /// argument, as `file.startsWith(root)` when `root` may lack a trailing `/`.
+    pub uncertainly_guarded: bool,
+}
+
+/// One hop of the path from a taint source to a sink.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TraceStep {
+    pub kind: TraceStepKind,
+    pub span: Span,
+    /// File of the step when it is not in the source file.
+    pub file: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TraceStepKind {
+    Source,
+    Assignment,
+    Parameter,
+    Call,
+    PropertyRead,
+    PropertyWrite,
+    /// A sanitizer that does not protect the sink.
+    Sanitizer,
+    Sink,
+}
+
+impl TraceStep {
+    fn new(kind: TraceStepKind, span: Span, message: String) -> Self {
+        Self {
+            kind,
+            span,
+            file: None,
+            message,
+        }
+    }
 }
 
 pub struct TaintPropagator<'a> {
@@ -108,7 +212,17 @@ pub struct TaintPropagator<'a> {
     sinks_registry: &'a TaintSinksRegistry,
     sanitizers_registry: &'a SanitizersRegistry,
     state: TaintState,
-    sanitized_nodes: HashSet<DfgNodeId>,
+    sources: HashSet<DfgNodeId>,
+    sanitizers: HashMap<DfgNodeId, Sanitized>,
+    guards: Guards,
+    /// Guards that may not hold for every value, such as a path checked
+    /// against a directory without a trailing separator.
+    uncertain_guards: Guards,
+    /// Values returned by the functions of the graph.
+    returns: HashSet<DfgNodeId>,
+    request_parameters: RequestParameters,
+    imports: ImportedSummaries,
+    summaries: FunctionSummaries,
 }
 
 impl<'a> TaintPropagator<'a> {
@@ -124,70 +238,471 @@ impl<'a> TaintPropagator<'a> {
             sinks_registry,
             sanitizers_registry,
             state: TaintState::new(),
-            sanitized_nodes: HashSet::new(),
+            sources: HashSet::new(),
+            sanitizers: HashMap::new(),
+            guards: Guards::new(),
+            uncertain_guards: Guards::new(),
+            returns: dfg
+                .functions()
+                .iter()
+                .flat_map(|function| function.returns.iter().copied())
+                .collect(),
+            request_parameters: RequestParameters::new(),
+            imports: ImportedSummaries::default(),
+            summaries: FunctionSummaries::default(),
         }
     }
 
+    /// Resolves calls of functions imported from other modules with their summaries.
+    pub fn with_imports(mut self, imports: ImportedSummaries) -> Self {
+        self.imports = imports;
+        self
+    }
+
+    /// Treats the bindings at `parameters` as request data, whatever their names.
+    pub fn with_request_parameters(mut self, parameters: RequestParameters) -> Self {
+        self.request_parameters = parameters;
+        self
+    }
+
     pub fn analyze(&mut self) -> Vec<TaintFinding> {
-        self.identify_sanitizers();
+        self.summarize();
         self.identify_initial_taint();
         self.propagate();
-        self.find_vulnerabilities()
+        let mut findings = self.find_vulnerabilities();
+        self.find_interprocedural_vulnerabilities(&mut findings);
+        findings
     }
 
     fn identify_sanitizers(&mut self) {
+        let mut checks = Vec::new();
         for node in self.dfg.nodes() {
-            if let DfgNodeKind::Call { callee_name } = &node.kind {
-                if self.is_sanitizer_call(node, callee_name) {
-                    self.sanitized_nodes.insert(node.id);
+            match &node.kind {
+                DfgNodeKind::Call { callee_name } => {
+                    let categories = self.sanitizer_categories(node, callee_name);
+                    if !categories.is_empty() {
+                        self.sanitizers.insert(node.id, categories);
+                    }
+                    let Some(site) = self.dfg.call_site(node.id) else {
+                        continue;
+                    };
+                    if let Some(receiver) = site.receiver {
+                        let argument = site.args.first().copied().flatten();
+                        checks.push(ValueCheck {
+                            node: receiver,
+                            kind: GuardCheck::Method(callee_name.clone()),
+                            argument: argument
+                                .and_then(|argument| self.dfg.constant_text(argument)),
+                            separated: argument
+                                .is_some_and(|argument| self.dfg.ends_with_separator(argument)),
+                            span: node.span,
+                            list: None,
+                        });
+                    }
+                    for &argument in site.args.iter().flatten() {
+                        checks.push(ValueCheck {
+                            node: argument,
+                            kind: GuardCheck::Argument(callee_name.clone()),
+                            argument: None,
+                            separated: false,
+                            span: node.span,
+                            list: site.receiver,
+                        });
+                    }
+                }
+                DfgNodeKind::BinaryOp { left, right } => {
+                    // Only `||`, `&&` and `??` can pass an object through
+                    if !matches!(
+                        self.dfg.operator(node.id),
+                        Some(
+                            BinaryOp::LogicalOr
+                                | BinaryOp::LogicalAnd
+                                | BinaryOp::NullishCoalescing
+                        )
+                    ) {
+                        self.sanitizers
+                            .entry(node.id)
+                            .or_default()
+                            .insert(SanitizerCategory::NoSqlInjection);
+                    }
+                    if self.dfg.is_comparison(node.id) {
+                        for (value, other) in [(*left, *right), (*right, *left)] {
+                            checks.push(ValueCheck {
+                                node: value,
+                                kind: GuardCheck::Equality,
+                                argument: self.dfg.constant_text(other),
+                                separated: false,
+                                span: node.span,
+                                list: None,
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        let guarded: Vec<_> = checks
+            .iter()
+            .flat_map(|check| self.guard(check, &checks))
+            .collect();
+        // `a || b` only guards a value when both checks do
+        let found: HashSet<_> = guarded
+            .iter()
+            .filter_map(|&(value, category, condition, _)| {
+                condition.map(|condition| (condition.check, value, category, condition.region))
+            })
+            .collect();
+        for (value, category, condition, certain) in guarded {
+            let Some(condition) = condition else {
+                if certain {
+                    self.sanitizers.entry(value).or_default().insert(category);
                 }
+                continue;
+            };
+            let holds = condition
+                .alternatives
+                .iter()
+                .all(|&other| found.contains(&(other, value, category, condition.region)));
+            if holds {
+                let guards = if certain {
+                    &mut self.guards
+                } else {
+                    &mut self.uncertain_guards
+                };
+                guards
+                    .entry(value)
+                    .or_default()
+                    .push((category, condition.region));
             }
         }
     }
 
-    fn is_sanitizer_call(&self, node: &DfgNode, callee_name: &str) -> bool {
-        if self
+    /// The values a check such as `resolved.startsWith(root)` or
+    /// `allowed.includes(new URL(target).hostname)` guards, if the check is a
+    /// guard for any value or for the call the value comes from, with the
+    /// condition the guard holds under and whether it holds for certain.
+    /// `checks` are all the checks of the graph, for guards that need another
+    /// check of the same value.
+    fn guard(
+        &self,
+        check: &ValueCheck,
+        checks: &[ValueCheck],
+    ) -> Vec<(DfgNodeId, SanitizerCategory, Option<&'a DfgCondition>, bool)> {
+        let argument = check.argument.as_deref();
+        let certain = |guard: &GuardPattern| !guard.trailing_separator || check.separated;
+        let mut guarded: Vec<_> = self
             .sanitizers_registry
-            .is_sanitizer(&[callee_name.to_string()], None)
-            .is_some()
+            .find_guards(&[], None, None, &check.kind, argument)
+            .flat_map(|guard| {
+                self.conditions(guard, check, checks)
+                    .into_iter()
+                    .map(|condition| (check.node, guard.category, condition, certain(guard)))
+            })
+            .collect();
+
+        // `const { hostname } = url` checks the property as well
+        let mut checked = self.dfg.get(check.node);
+        if matches!(checked.kind, DfgNodeKind::Variable { .. })
+            && let [from] = checked.flows_from[..]
+            && matches!(self.dfg.get(from).kind, DfgNodeKind::PropertyAccess { .. })
         {
-            return true;
+            checked = self.dfg.get(from);
         }
+        let (value, property) = match &checked.kind {
+            DfgNodeKind::PropertyAccess { object, property } => (*object, Some(property.as_str())),
+            _ => (checked.id, None),
+        };
 
-        for &from_id in &node.flows_from {
-            let from_node = self.dfg.get(from_id);
-            if let DfgNodeKind::Variable { name, .. } = &from_node.kind {
-                if self
-                    .sanitizers_registry
-                    .is_sanitizer(std::slice::from_ref(name), Some(callee_name))
-                    .is_some()
-                {
-                    return true;
+        let producers =
+            std::iter::once(value).chain(self.dfg.get(value).flows_from.iter().copied());
+        for producer in producers {
+            let Some((chain, method)) = self.producer(producer) else {
+                continue;
+            };
+            let chain = [chain];
+            let guards: Vec<_> = self
+                .sanitizers_registry
+                .find_guards(&chain, method, property, &check.kind, argument)
+                .flat_map(|guard| {
+                    self.conditions(guard, check, checks)
+                        .into_iter()
+                        .map(|condition| (guard.category, condition, certain(guard)))
+                })
+                .collect();
+            if guards.is_empty() {
+                continue;
+            }
+            // A value checked where it is produced, as in
+            // `new URL(target).hostname`, guards what it was produced from
+            let nodes = if producer == value {
+                self.inputs(producer)
+            } else {
+                vec![value]
+            };
+            for node in nodes {
+                guarded.extend(
+                    guards.iter().map(|&(category, condition, certain)| {
+                        (node, category, condition, certain)
+                    }),
+                );
+            }
+            break;
+        }
+        guarded
+    }
+
+    /// Where `guard` holds for the value of `check`: the conditions under
+    /// which the check has the result the guard needs and a check it requires
+    /// is true, or `None` for everywhere.
+    fn conditions(
+        &self,
+        guard: &GuardPattern,
+        check: &ValueCheck,
+        checks: &[ValueCheck],
+    ) -> Vec<Option<&'a DfgCondition>> {
+        if guard.constant_list
+            && !check
+                .list
+                .is_some_and(|list| self.dfg.is_constant_list(list))
+        {
+            return Vec::new();
+        }
+        let required = |condition: Option<&DfgCondition>| match &guard.requires {
+            Some((kind, argument)) => checks.iter().any(|other| {
+                other.node == check.node
+                    && other.kind == *kind
+                    && other.argument.as_deref() == Some(argument.as_str())
+                    && condition.is_none_or(|condition| {
+                        self.dfg.conditions(other.span).any(|required| {
+                            required.holds
+                                && required.alternatives.is_empty()
+                                && required.region.contains(condition.region)
+                        })
+                    })
+            }),
+            None => true,
+        };
+        let conditions = match guard.result {
+            Some(result) => self
+                .dfg
+                .conditions(check.span)
+                .filter(|condition| condition.holds == result)
+                .map(Some)
+                .collect(),
+            None => vec![None],
+        };
+        conditions
+            .into_iter()
+            .filter(|&condition| required(condition))
+            .collect()
+    }
+
+    /// The callee chain and method of a call or constructor node.
+    fn producer(&self, node: DfgNodeId) -> Option<(String, Option<&str>)> {
+        match &self.dfg.get(node).kind {
+            DfgNodeKind::NewExpr { callee_name } => Some((callee_name.clone(), None)),
+            DfgNodeKind::Call { callee_name } => {
+                let receiver = self.dfg.call_site(node).and_then(|site| site.receiver);
+                match receiver.map(|id| &self.dfg.get(id).kind) {
+                    Some(DfgNodeKind::Variable { name, .. })
+                    | Some(DfgNodeKind::Parameter { name, .. })
+                    | Some(DfgNodeKind::PropertyAccess { property: name, .. }) => {
+                        Some((name.clone(), Some(callee_name.as_str())))
+                    }
+                    Some(_) => None,
+                    None => Some((callee_name.clone(), None)),
                 }
-            } else if let DfgNodeKind::PropertyAccess { property, .. } = &from_node.kind {
-                if self
-                    .sanitizers_registry
-                    .is_sanitizer(std::slice::from_ref(property), Some(callee_name))
-                    .is_some()
-                {
-                    return true;
+            }
+            _ => None,
+        }
+    }
+
+    /// The arguments of a call, or everything flowing into another node.
+    fn inputs(&self, node: DfgNodeId) -> Vec<DfgNodeId> {
+        match self.dfg.call_site(node) {
+            Some(site) => site.args.iter().flatten().copied().collect(),
+            None => self.dfg.get(node).flows_from.clone(),
+        }
+    }
+
+    /// Computes the summaries of the functions in the graph, without propagating taint.
+    pub fn summarize(&mut self) {
+        self.identify_sanitizers();
+        let sinks = self
+            .dfg
+            .nodes()
+            .filter_map(|node| {
+                self.sink_match(node)
+                    .map(|sink_match| (node.id, sink_match.pattern))
+            })
+            .collect();
+        self.summaries = FunctionSummaries::compute(
+            self.dfg,
+            &sinks,
+            &self.sanitizers,
+            &self.guards,
+            self.imports.clone(),
+        );
+    }
+
+    /// The summary of the function `name` as seen by modules that import it from
+    /// `filename`, with its sinks located in that file. Requires [`Self::summarize`].
+    pub fn exported_summary(&self, name: &str, filename: &str) -> Option<FunctionSummary> {
+        let mut summary = self.summaries.function(name)?.clone();
+        let function = [false, true].iter().find_map(|&is_method| {
+            self.dfg.functions().iter().find(|function| {
+                function.is_method == is_method && function.name.as_deref() == Some(name)
+            })
+        });
+        for (&index, sinks) in summary.params_to_sinks.iter_mut() {
+            let param = function.and_then(|function| function.params.get(index).copied().flatten());
+            *sinks = std::mem::take(sinks)
+                .into_iter()
+                .filter_map(|sink| {
+                    let mut steps = param
+                        .map(|param| self.trace_steps(&self.path_to_sink(param, &sink)))
+                        .unwrap_or_default();
+                    for step in &mut steps {
+                        step.file = Some(filename.to_string());
+                    }
+                    match sink {
+                        SummarySink::Local(id) => {
+                            let node = self.dfg.get(id);
+                            self.sink_match(node).map(|sink_match| {
+                                SummarySink::Imported(ImportedSink {
+                                    file: filename.to_string(),
+                                    span: node.span,
+                                    argument_prefix: self
+                                        .argument_prefix(node, &sink_match.pattern),
+                                    result_trusted: self.result_trusted(id),
+                                    category: sink_match.pattern.category,
+                                    description: sink_match.pattern.description,
+                                    steps,
+                                })
+                            })
+                        }
+                        SummarySink::Imported(mut imported) => {
+                            steps.append(&mut imported.steps);
+                            imported.steps = steps;
+                            Some(SummarySink::Imported(imported))
+                        }
+                    }
+                })
+                .collect();
+        }
+        Some(summary)
+    }
+
+    /// The nodes data goes through from `param` to `sink`: up to the argument
+    /// of a local sink, or up to the call that passes it on to an imported one.
+    fn path_to_sink(&self, param: DfgNodeId, sink: &SummarySink) -> Vec<DfgNodeId> {
+        // The call to end the path with once `id` reaches the sink, if any.
+        let reaches = |id: DfgNodeId| match sink {
+            SummarySink::Local(sink) => self.dfg.get(id).flows_to.contains(sink).then_some(None),
+            SummarySink::Imported(_) => self
+                .summaries
+                .calls_with_argument(self.dfg, id)
+                .into_iter()
+                .find_map(|(call, index, summary)| {
+                    summary
+                        .params_to_sinks
+                        .get(&index)
+                        .is_some_and(|sinks| sinks.contains(sink))
+                        .then_some(Some(call))
+                }),
+        };
+
+        let mut previous: HashMap<DfgNodeId, DfgNodeId> = HashMap::new();
+        let mut worklist = VecDeque::from([param]);
+        let mut visited = HashSet::from([param]);
+        while let Some(id) = worklist.pop_front() {
+            if let Some(call) = reaches(id) {
+                let mut path = vec![id];
+                let mut current = id;
+                while let Some(&from) = previous.get(&current) {
+                    path.push(from);
+                    current = from;
+                }
+                path.reverse();
+                path.extend(call);
+                return path;
+            }
+            for &to in &self.dfg.get(id).flows_to {
+                if self.summaries.flow_through(self.dfg, id, to).is_some() && visited.insert(to) {
+                    previous.insert(to, id);
+                    worklist.push_back(to);
                 }
             }
         }
+        vec![param]
+    }
 
-        false
+    /// The categories a call sanitizes its arguments for, empty if it is not a sanitizer.
+    fn sanitizer_categories(&self, node: &DfgNode, callee_name: &str) -> Sanitized {
+        let mut matches = self
+            .sanitizers_registry
+            .find_sanitizers(&[callee_name.to_string()], None);
+
+        for &from_id in &node.flows_from {
+            let receiver = match &self.dfg.get(from_id).kind {
+                DfgNodeKind::Variable { name, .. } | DfgNodeKind::Parameter { name, .. } => name,
+                DfgNodeKind::PropertyAccess { property, .. } => property,
+                _ => continue,
+            };
+            matches.extend(
+                self.sanitizers_registry
+                    .find_sanitizers(std::slice::from_ref(receiver), Some(callee_name)),
+            );
+        }
+
+        matches
+            .into_iter()
+            .map(|sanitizer| sanitizer.pattern.category)
+            .collect()
     }
 
     fn identify_initial_taint(&mut self) {
         for node in self.dfg.nodes() {
+            if self.reads_from_source(node) {
+                continue;
+            }
             if let Some(source_match) = self.is_taint_source_node(node) {
                 self.state
                     .mark_tainted(node.id, source_match.pattern.category, node.span);
+                self.sources.insert(node.id);
             }
         }
     }
 
+    /// Whether `node` reads a property of a value that is itself a source, such
+    /// as `req.body.name` in a handler whose `req` is tainted. The taint then
+    /// already flows from that value.
+    fn reads_from_source(&self, node: &DfgNode) -> bool {
+        let mut current = node;
+        while let DfgNodeKind::PropertyAccess { object, .. } = &current.kind {
+            current = self.dfg.get(*object);
+            if self.is_taint_source_node(current).is_some() {
+                return true;
+            }
+        }
+        false
+    }
+
     fn is_taint_source_node(&self, node: &DfgNode) -> Option<TaintSourceMatch> {
+        if let DfgNodeKind::Variable { name, .. } | DfgNodeKind::Parameter { name, .. } = &node.kind
+            && self.request_parameters.contains(&node.span)
+        {
+            return Some(TaintSourceMatch {
+                pattern: super::TaintSourcePattern::builtin(
+                    vec![name.as_str()],
+                    super::PropertyMatcher::None,
+                    TaintCategory::HttpRequest,
+                    "Request handler parameter",
+                ),
+                matched_path: vec![name.clone()],
+                matched_property: None,
+            });
+        }
         match &node.kind {
             DfgNodeKind::PropertyAccess { object, property } => {
                 let chain = self.build_property_chain(*object, Some(property.clone()));
@@ -243,7 +758,7 @@ impl<'a> TaintPropagator<'a> {
     fn collect_chain(&self, node_id: DfgNodeId, chain: &mut Vec<String>) {
         let node = self.dfg.get(node_id);
         match &node.kind {
-            DfgNodeKind::Variable { name, .. } => {
+            DfgNodeKind::Variable { name, .. } | DfgNodeKind::Parameter { name, .. } => {
                 chain.push(name.clone());
             }
             DfgNodeKind::PropertyAccess { object, property } => {
@@ -257,25 +772,31 @@ impl<'a> TaintPropagator<'a> {
     fn propagate(&mut self) {
         let mut worklist: VecDeque<DfgNodeId> = self.state.tainted.keys().copied().collect();
 
-        let mut visited = HashSet::new();
-
+        // Taint only grows and sanitizers only shrink, so this terminates.
         while let Some(node_id) = worklist.pop_front() {
-            if visited.contains(&node_id) {
-                continue;
-            }
-            visited.insert(node_id);
-
             let node = self.dfg.get(node_id);
             for &dependent in &node.flows_to {
-                if self.sanitized_nodes.contains(&dependent) {
+                let Some(mut sanitized) = self.summaries.flow_through(self.dfg, node_id, dependent)
+                else {
                     continue;
+                };
+                if let Some(categories) = self.sanitizers.get(&dependent) {
+                    sanitized.extend(categories.iter().copied());
                 }
-
-                if !self.state.is_tainted(dependent) {
-                    self.state.merge_taint(dependent, node_id);
+                if self.state.flow(dependent, node_id, &sanitized) {
                     worklist.push_back(dependent);
-                } else {
-                    self.state.merge_taint(dependent, node_id);
+                }
+            }
+
+            // Arguments stored on `this` taint the object the method is called on
+            for (call, index, summary) in self.summaries.calls_with_argument(self.dfg, node_id) {
+                if !summary.params_to_this.contains(&index) {
+                    continue;
+                }
+                if let Some(receiver) = self.dfg.call_site(call).and_then(|site| site.receiver)
+                    && self.state.flow(receiver, node_id, &Sanitized::new())
+                {
+                    worklist.push_back(receiver);
                 }
             }
         }
@@ -285,85 +806,220 @@ impl<'a> TaintPropagator<'a> {
         let mut findings = Vec::new();
 
         for node in self.dfg.nodes() {
-            match &node.kind {
-                DfgNodeKind::Call { callee_name } => {
-                    if let Some(sink_match) = self.check_sink_call(node, callee_name) {
-                        for &from_id in &node.flows_from {
-                            if let Some(taint) = self.state.get_taint(from_id) {
-                                for &category in &taint.categories {
-                                    for &source_span in &taint.source_spans {
-                                        let path = self.build_path(from_id, node.id);
-                                        findings.push(TaintFinding {
-                                            source_span,
-                                            sink_span: node.span,
-                                            source_category: category,
-                                            sink_category: sink_match.pattern.category,
-                                            sink_description: sink_match
-                                                .pattern
-                                                .description
-                                                .clone(),
-                                            path,
-                                        });
-                                    }
-                                }
-                            }
-                        }
-                    }
+            let Some(sink_match) = self.sink_match(node) else {
+                continue;
+            };
+            let sink_category = sink_match.pattern.category;
+            for &from_id in &node.flows_from {
+                let Some(taint) = self.state.get_taint(from_id) else {
+                    continue;
+                };
+                if is_protected(&taint.sanitized_for, sink_category)
+                    || is_guarded(self.dfg, &self.guards, from_id, node.span, sink_category)
+                    || !is_sink_argument(
+                        self.dfg,
+                        node.id,
+                        from_id,
+                        &sink_match.pattern.arg_positions,
+                    )
+                {
+                    continue;
                 }
-                DfgNodeKind::NewExpr { callee_name } => {
-                    if let Some(sink_match) = self.check_new_expr_sink(callee_name) {
-                        for &from_id in &node.flows_from {
-                            if let Some(taint) = self.state.get_taint(from_id) {
-                                for &category in &taint.categories {
-                                    for &source_span in &taint.source_spans {
-                                        let path = self.build_path(from_id, node.id);
-                                        findings.push(TaintFinding {
-                                            source_span,
-                                            sink_span: node.span,
-                                            source_category: category,
-                                            sink_category: sink_match.pattern.category,
-                                            sink_description: sink_match
-                                                .pattern
-                                                .description
-                                                .clone(),
-                                            path,
-                                        });
-                                    }
-                                }
-                            }
-                        }
+                for &category in &taint.categories {
+                    for &source_span in &taint.source_spans {
+                        let path = self.build_path(from_id, node.id);
+                        let mut trace = self.trace(from_id, source_span, sink_category);
+                        trace.push(sink_step(node.span, &sink_match.pattern.description));
+                        findings.push(TaintFinding {
+                            source_span,
+                            sink_span: node.span,
+                            source_category: category,
+                            sink_category,
+                            sink_description: sink_match.pattern.description.clone(),
+                            path,
+                            sink_file: None,
+                            trace,
+                            argument_prefix: self.dfg.leading_text(from_id),
+                            result_trusted: self.result_trusted(node.id),
+                            uncertainly_guarded: is_guarded(
+                                self.dfg,
+                                &self.uncertain_guards,
+                                from_id,
+                                node.span,
+                                sink_category,
+                            ),
+                        });
                     }
                 }
-                DfgNodeKind::PropertyAssignment { object, property } => {
-                    if let Some(sink_match) = self.check_property_assignment_sink(*object, property)
+            }
+        }
+
+        findings
+    }
+
+    /// Reports tainted arguments that reach a sink inside the called function,
+    /// which may be in an imported module.
+    fn find_interprocedural_vulnerabilities(&self, findings: &mut Vec<TaintFinding>) {
+        for node in self.dfg.nodes() {
+            let Some(taint) = self.state.get_taint(node.id) else {
+                continue;
+            };
+            for (call, index, summary) in self.summaries.calls_with_argument(self.dfg, node.id) {
+                let Some(sinks) = summary.params_to_sinks.get(&index) else {
+                    continue;
+                };
+                for sink in sinks {
+                    let (
+                        sink_file,
+                        sink_span,
+                        sink_category,
+                        argument_prefix,
+                        result_trusted,
+                        sink_description,
+                        path,
+                        callee_steps,
+                    ) = match sink {
+                        SummarySink::Local(id) => {
+                            let sink_node = self.dfg.get(*id);
+                            let Some(sink_match) = self.sink_match(sink_node) else {
+                                continue;
+                            };
+                            (
+                                None,
+                                sink_node.span,
+                                sink_match.pattern.category,
+                                self.argument_prefix(sink_node, &sink_match.pattern),
+                                self.result_trusted(*id),
+                                sink_match.pattern.description,
+                                vec![node.id, call, *id],
+                                &[][..],
+                            )
+                        }
+                        SummarySink::Imported(imported) => (
+                            Some(imported.file.clone()),
+                            imported.span,
+                            imported.category,
+                            imported.argument_prefix.clone(),
+                            imported.result_trusted,
+                            imported.description.clone(),
+                            vec![node.id, call],
+                            &imported.steps[..],
+                        ),
+                    };
+                    if is_protected(&taint.sanitized_for, sink_category)
+                        || is_guarded(
+                            self.dfg,
+                            &self.guards,
+                            node.id,
+                            self.dfg.get(call).span,
+                            sink_category,
+                        )
                     {
-                        for &from_id in &node.flows_from {
-                            if let Some(taint) = self.state.get_taint(from_id) {
-                                for &category in &taint.categories {
-                                    for &source_span in &taint.source_spans {
-                                        let path = self.build_path(from_id, node.id);
-                                        findings.push(TaintFinding {
-                                            source_span,
-                                            sink_span: node.span,
-                                            source_category: category,
-                                            sink_category: sink_match.pattern.category,
-                                            sink_description: sink_match
-                                                .pattern
-                                                .description
-                                                .clone(),
-                                            path,
-                                        });
-                                    }
-                                }
+                        continue;
+                    }
+                    for &category in &taint.categories {
+                        for &source_span in &taint.source_spans {
+                            let reported = findings.iter().any(|f| {
+                                f.source_span == source_span
+                                    && f.sink_span == sink_span
+                                    && f.sink_file == sink_file
+                                    && f.source_category == category
+                                    && f.sink_category == sink_category
+                            });
+                            if reported {
+                                continue;
                             }
+                            let mut trace = self.trace(node.id, source_span, sink_category);
+                            let call_node = self.dfg.get(call);
+                            trace.push(TraceStep::new(
+                                TraceStepKind::Call,
+                                call_node.span,
+                                format!("Passed to '{}'", self.label(call_node)),
+                            ));
+                            trace.extend_from_slice(callee_steps);
+                            let mut sink = sink_step(sink_span, &sink_description);
+                            sink.file = sink_file.clone();
+                            trace.push(sink);
+                            findings.push(TaintFinding {
+                                source_span,
+                                sink_span,
+                                source_category: category,
+                                sink_category,
+                                sink_description: sink_description.clone(),
+                                path: path.clone(),
+                                sink_file: sink_file.clone(),
+                                trace,
+                                argument_prefix: argument_prefix.clone(),
+                                result_trusted,
+                                uncertainly_guarded: is_guarded(
+                                    self.dfg,
+                                    &self.uncertain_guards,
+                                    node.id,
+                                    call_node.span,
+                                    sink_category,
+                                ),
+                            });
                         }
                     }
                 }
-                _ => {}
             }
         }
+    }
 
-        findings
+    /// The constant text the first checked argument of a sink call starts with.
+    fn argument_prefix(&self, sink: &DfgNode, pattern: &TaintSinkPattern) -> Option<String> {
+        let position = pattern.arg_positions.first().copied().unwrap_or(0);
+        let argument = self.dfg.call_site(sink.id)?.args.get(position).copied()??;
+        self.dfg.leading_text(argument)
+    }
+
+    /// Whether the value `sink` returns reaches a comparison or a test, an
+    /// object such as `req.user`, or the return value of its function.
+    fn result_trusted(&self, sink: DfgNodeId) -> bool {
+        let mut seen = HashSet::from([sink]);
+        let mut worklist = VecDeque::from([sink]);
+        while let Some(id) = worklist.pop_front() {
+            let node = self.dfg.get(id);
+            let stored = matches!(
+                node.kind,
+                DfgNodeKind::PropertyAssignment { object, .. }
+                    if !matches!(self.dfg.get(object).kind, DfgNodeKind::Object)
+            );
+            let compared = matches!(
+                self.dfg.operator(id),
+                Some(
+                    BinaryOp::EqEq
+                        | BinaryOp::EqEqEq
+                        | BinaryOp::NotEq
+                        | BinaryOp::NotEqEq
+                        | BinaryOp::Lt
+                        | BinaryOp::LtEq
+                        | BinaryOp::Gt
+                        | BinaryOp::GtEq
+                )
+            );
+            if stored || compared || self.dfg.is_tested(id) || self.returns.contains(&id) {
+                return true;
+            }
+            // A bounded walk, as the graph joins unrelated uses of a name
+            for &next in &node.flows_to {
+                if seen.len() < 256 && seen.insert(next) {
+                    worklist.push_back(next);
+                }
+            }
+        }
+        false
+    }
+
+    fn sink_match(&self, node: &DfgNode) -> Option<TaintSinkMatch> {
+        match &node.kind {
+            DfgNodeKind::Call { callee_name } => self.check_sink_call(node, callee_name),
+            DfgNodeKind::NewExpr { callee_name } => self.check_new_expr_sink(callee_name),
+            DfgNodeKind::PropertyAssignment { object, property } => {
+                self.check_property_assignment_sink(*object, property)
+            }
+            _ => None,
+        }
     }
 
     fn check_sink_call(&self, node: &DfgNode, callee_name: &str) -> Option<TaintSinkMatch> {
@@ -377,7 +1033,7 @@ impl<'a> TaintPropagator<'a> {
         for &from_id in &node.flows_from {
             let from_node = self.dfg.get(from_id);
             match &from_node.kind {
-                DfgNodeKind::Variable { name, .. } => {
+                DfgNodeKind::Variable { name, .. } | DfgNodeKind::Parameter { name, .. } => {
                     if let Some(result) = self
                         .sinks_registry
                         .is_taint_sink(std::slice::from_ref(name), Some(callee_name))
@@ -407,7 +1063,22 @@ impl<'a> TaintPropagator<'a> {
             }
         }
 
-        None
+        // `const users = db.collection('users')` is also named after its call
+        let receiver = self.dfg.call_site(node.id)?.receiver?;
+        let receiver = self.dfg.get(receiver);
+        if !matches!(receiver.kind, DfgNodeKind::Variable { .. }) {
+            return None;
+        }
+        receiver.flows_from.iter().find_map(|&from| {
+            let DfgNodeKind::Call {
+                callee_name: producer,
+            } = &self.dfg.get(from).kind
+            else {
+                return None;
+            };
+            self.sinks_registry
+                .is_taint_sink(std::slice::from_ref(producer), Some(callee_name))
+        })
     }
 
     fn check_new_expr_sink(&self, callee_name: &str) -> Option<TaintSinkMatch> {
@@ -423,8 +1094,11 @@ impl<'a> TaintPropagator<'a> {
         let object_node = self.dfg.get(object);
 
         let object_name = match &object_node.kind {
-            DfgNodeKind::Variable { name, .. } => name.clone(),
+            DfgNodeKind::Variable { name, .. } | DfgNodeKind::Parameter { name, .. } => {
+                name.clone()
+            }
             DfgNodeKind::PropertyAccess { property, .. } => property.clone(),
+            DfgNodeKind::Object => OBJECT_LITERAL.to_string(),
             _ => return None,
         };
 
@@ -480,11 +1154,157 @@ impl<'a> TaintPropagator<'a> {
         false
     }
 
+    /// The steps from the source at `source_span` to `to`, following only
+    /// nodes that carry that taint unsanitized for `sink`.
+    fn trace(&self, to: DfgNodeId, source_span: Span, sink: TaintSinkCategory) -> Vec<TraceStep> {
+        let carries = |id: DfgNodeId| {
+            self.state.get_taint(id).is_some_and(|taint| {
+                taint.source_spans.contains(&source_span)
+                    && !is_protected(&taint.sanitized_for, sink)
+            })
+        };
+        let is_source =
+            |id: DfgNodeId| self.sources.contains(&id) && self.dfg.get(id).span == source_span;
+
+        // Walk back from `to`, remembering where each node flows next.
+        let mut next: HashMap<DfgNodeId, DfgNodeId> = HashMap::new();
+        let mut worklist = VecDeque::from([to]);
+        let mut visited = HashSet::from([to]);
+        let mut start = None;
+        while let Some(id) = worklist.pop_front() {
+            if is_source(id) {
+                start = Some(id);
+                break;
+            }
+            for &from in &self.dfg.get(id).flows_from {
+                if carries(from) && visited.insert(from) {
+                    next.insert(from, id);
+                    worklist.push_back(from);
+                }
+            }
+        }
+
+        let mut path = Vec::new();
+        match start {
+            Some(start) => {
+                let mut current = start;
+                path.push(current);
+                while let Some(&id) = next.get(&current) {
+                    path.push(id);
+                    current = id;
+                }
+            }
+            // Taint that reached `to` through a receiver has no graph edge to follow.
+            None => {
+                if let Some(&source) = self.sources.iter().find(|&&id| is_source(id)) {
+                    path.push(source);
+                }
+                path.push(to);
+            }
+        }
+
+        let Some((&first, rest)) = path.split_first() else {
+            return Vec::new();
+        };
+        let mut steps = vec![TraceStep::new(
+            TraceStepKind::Source,
+            self.dfg.get(first).span,
+            format!(
+                "Untrusted data enters from '{}'",
+                self.label(self.dfg.get(first))
+            ),
+        )];
+        steps.extend(self.trace_steps(rest));
+        steps
+    }
+
+    /// The trace steps of the nodes of `path` that are worth showing.
+    fn trace_steps(&self, path: &[DfgNodeId]) -> Vec<TraceStep> {
+        let mut steps = Vec::new();
+        for (position, &id) in path.iter().enumerate() {
+            let node = self.dfg.get(id);
+            let (kind, message) = match &node.kind {
+                DfgNodeKind::Variable { .. } => (
+                    TraceStepKind::Assignment,
+                    format!("Assigned to '{}'", self.label(node)),
+                ),
+                DfgNodeKind::Parameter { name, .. } => (
+                    TraceStepKind::Parameter,
+                    format!("Received as parameter '{name}'"),
+                ),
+                DfgNodeKind::Call { .. } if self.sanitizers.contains_key(&id) => (
+                    TraceStepKind::Sanitizer,
+                    format!(
+                        "Passes through '{}', which does not sanitize for this sink",
+                        self.label(node)
+                    ),
+                ),
+                DfgNodeKind::Call { .. } => (
+                    TraceStepKind::Call,
+                    format!("Passed to '{}'", self.label(node)),
+                ),
+                DfgNodeKind::PropertyAccess { .. } => {
+                    // Only the full chain is worth a step, not `req` and `req.body` on the way
+                    let continues = path.get(position + 1).is_some_and(|&next| {
+                        matches!(
+                            self.dfg.get(next).kind,
+                            DfgNodeKind::PropertyAccess { object, .. } if object == id
+                        )
+                    });
+                    if continues {
+                        continue;
+                    }
+                    (
+                        TraceStepKind::PropertyRead,
+                        format!("Read from '{}'", self.label(node)),
+                    )
+                }
+                DfgNodeKind::PropertyAssignment { .. } => (
+                    TraceStepKind::PropertyWrite,
+                    format!("Stored in '{}'", self.label(node)),
+                ),
+                _ => continue,
+            };
+            steps.push(TraceStep::new(kind, node.span, message));
+        }
+        steps
+    }
+
+    /// How a node is named in trace messages.
+    fn label(&self, node: &DfgNode) -> String {
+        match &node.kind {
+            DfgNodeKind::Variable { name, .. } | DfgNodeKind::Parameter { name, .. } => {
+                name.clone()
+            }
+            DfgNodeKind::Call { callee_name } | DfgNodeKind::NewExpr { callee_name } => {
+                format!("{callee_name}()")
+            }
+            DfgNodeKind::PropertyAssignment { object, property }
+                if matches!(self.dfg.get(*object).kind, DfgNodeKind::Object) =>
+            {
+                format!("{{ {property} }}")
+            }
+            DfgNodeKind::PropertyAccess { object, property }
+            | DfgNodeKind::PropertyAssignment { object, property } => {
+                format!("{}.{property}", self.label(self.dfg.get(*object)))
+            }
+            _ => "expression".to_string(),
+        }
+    }
+
     pub fn state(&self) -> &TaintState {
         &self.state
     }
 }
 
+fn sink_step(span: Span, description: &str) -> TraceStep {
+    TraceStep::new(
+        TraceStepKind::Sink,
+        span,
+        format!("Reaches the sink: {description}"),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -863,4 +1683,215 @@ mod tests {
             "doubled should be tainted through binary operation"
         );
     }
+
+    #[test]
+    fn taint_flows_through_local_helper_return() {
+        let code = r#"
+            function buildQuery(id) {
+                return "SELECT * FROM users WHERE id = " + id;
+            }
+            function handler(req, res) {
+                db.query(buildQuery(req.params.id));
+            }
+        "#;
+
+        let (_, findings) = analyze_code(code);
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].sink_category, TaintSinkCategory::SqlInjection);
+    }
+
+    #[test]
+    fn helper_that_sanitizes_stops_taint() {
+        let code = r#"
+            function quote(arg) {
+                return shellEscape(arg);
+            }
+            function handler(req, res) {
+                exec("ls " + quote(req.query.dir));
+            }
+        "#;
+
+        let (_, findings) = analyze_code(code);
+
+        assert!(findings.is_empty(), "quote() returns a sanitized value");
+    }
+
+    #[test]
+    fn helper_ignoring_argument_stops_taint() {
+        let code = r#"
+            function tableFor(kind) {
+                return "users";
+            }
+            function handler(req, res) {
+                db.query("SELECT * FROM " + tableFor(req.query.kind));
+            }
+        "#;
+
+        let (_, findings) = analyze_code(code);
+
+        assert!(
+            findings.is_empty(),
+            "tableFor() does not return its argument"
+        );
+    }
+
+    #[test]
+    fn detects_sink_inside_called_helper() {
+        let code = r#"
+            function run(command) {
+                exec(command);
+            }
+            function handler(req, res) {
+                run("git log " + req.query.branch);
+            }
+        "#;
+
+        let (dfg, findings) = analyze_code(code);
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            findings[0].sink_category,
+            TaintSinkCategory::CommandInjection
+        );
+        let exec = dfg
+            .nodes()
+            .find(|n| matches!(&n.kind, DfgNodeKind::Call { callee_name } if callee_name == "exec"))
+            .unwrap();
+        assert_eq!(findings[0].sink_span, exec.span);
+    }
+
+    #[test]
+    fn helper_with_untainted_argument_is_not_reported() {
+        let code = r#"
+            function run(command) {
+                exec(command);
+            }
+            function handler(req, res) {
+                run("git status");
+            }
+        "#;
+
+        let (_, findings) = analyze_code(code);
+
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn taint_flows_through_recursive_helper() {
+        let code = r#"
+            function joinParts(parts, acc) {
+                if (parts.length === 0) {
+                    return acc;
+                }
+                return joinParts(parts.slice(1), acc + parts[0]);
+            }
+            function handler(req, res) {
+                eval(joinParts(req.body.parts, ""));
+            }
+        "#;
+
+        let (_, findings) = analyze_code(code);
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].sink_category, TaintSinkCategory::CodeExecution);
+    }
+
+    #[test]
+    fn argument_stored_on_this_taints_receiver() {
+        let code = r#"
+            class Query {
+                where(clause) {
+                    this.clause = clause;
+                }
+            }
+            function handler(req, res) {
+                const query = new Query();
+                query.where(req.query.filter);
+                eval(query.clause);
+            }
+        "#;
+
+        let (_, findings) = analyze_code(code);
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].sink_category, TaintSinkCategory::CodeExecution);
+    }
+
+    #[test]
+    fn sanitizer_only_protects_sinks_of_its_category() {
+        let code = r#"
+            function handler(req, res) {
+                const dir = encodeURIComponent(req.query.dir);
+                exec("ls " + dir);
+                fetch("https://api.example.com/files/" + dir);
+            }
+        "#;
+
+        let (_, findings) = analyze_code(code);
+
+        assert!(!findings.is_empty());
+        assert!(
+            findings
+                .iter()
+                .all(|f| f.sink_category == TaintSinkCategory::CommandInjection),
+            "URL encoding protects the request but not the shell command"
+        );
+    }
+
+    #[test]
+    fn finding_traces_source_to_sink() {
+        let code = r#"
+            function handler(req, res) {
+                const name = encodeURIComponent(req.body.name);
+                const query = "SELECT * FROM users WHERE name = '" + name + "'";
+                db.query(query);
+            }
+        "#;
+
+        let (_, findings) = analyze_code(code);
+        let finding = findings
+            .iter()
+            .find(|f| f.trace.len() > 3)
+            .expect("finding should have a trace");
+
+        let kinds: Vec<_> = finding.trace.iter().map(|step| step.kind).collect();
+        assert_eq!(kinds.first(), Some(&TraceStepKind::Source));
+        assert_eq!(kinds.last(), Some(&TraceStepKind::Sink));
+        assert_eq!(finding.trace[0].span, finding.source_span);
+        assert_eq!(finding.trace.last().unwrap().span, finding.sink_span);
+
+        let messages: Vec<_> = finding
+            .trace
+            .iter()
+            .map(|step| step.message.as_str())
+            .collect();
+        assert!(messages.contains(
+            &"Passes through 'encodeURIComponent()', which does not sanitize for this sink"
+        ));
+        assert!(messages.contains(&"Read from 'req.body.name'"));
+        assert!(messages.contains(&"Assigned to 'name'"));
+        assert!(messages.contains(&"Assigned to 'query'"));
+    }
+
+    #[test]
+    fn trace_follows_calls_into_helpers() {
+        let code = r#"
+            function run(command) {
+                exec(command);
+            }
+            function handler(req, res) {
+                const branch = req.query.branch;
+                run("git log " + branch);
+            }
+        "#;
+
+        let (_, findings) = analyze_code(code);
+        let trace = &findings[0].trace;
+
+        let messages: Vec<_> = trace.iter().map(|step| step.message.as_str()).collect();
+        assert!(messages.contains(&"Assigned to 'branch'"));
+        assert_eq!(messages[messages.len() - 2], "Passed to 'run()'");
+        assert_eq!(trace.last().unwrap().kind, TraceStepKind::Sink);
+    }
 }
@@ -6,19 +6,28 @@
 
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
+use super::TaintSinkCategory;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SanitizerKind {
     BuiltIn,
     Custom,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SanitizerCategory {
     CommandInjection,
     SqlInjection,
     Xss,
     PathTraversal,
     UrlEncoding,
+    Ssrf,
+    OpenRedirect,
+    NoSqlInjection,
+    JwtVerification,
     General,
 }
 
@@ -30,9 +39,33 @@ impl SanitizerCategory {
             SanitizerCategory::Xss => "xss",
             SanitizerCategory::PathTraversal => "path_traversal",
             SanitizerCategory::UrlEncoding => "url_encoding",
+            SanitizerCategory::Ssrf => "ssrf",
+            SanitizerCategory::OpenRedirect => "open_redirect",
+            SanitizerCategory::NoSqlInjection => "nosql_injection",
+            SanitizerCategory::JwtVerification => "jwt_verification",
             SanitizerCategory::General => "general",
         }
     }
+
+    /// Whether data sanitized this way is safe to pass to a sink of `sink`.
+    pub fn protects(&self, sink: TaintSinkCategory) -> bool {
+        match self {
+            SanitizerCategory::General => true,
+            SanitizerCategory::CommandInjection => sink == TaintSinkCategory::CommandInjection,
+            SanitizerCategory::SqlInjection => sink == TaintSinkCategory::SqlInjection,
+            SanitizerCategory::Xss => sink == TaintSinkCategory::XssSink,
+            SanitizerCategory::PathTraversal => matches!(
+                sink,
+                TaintSinkCategory::PathTraversal | TaintSinkCategory::FileSystem
+            ),
+            SanitizerCategory::UrlEncoding | SanitizerCategory::Ssrf => {
+                sink == TaintSinkCategory::NetworkRequest
+            }
+            SanitizerCategory::OpenRedirect => sink == TaintSinkCategory::OpenRedirect,
+            SanitizerCategory::NoSqlInjection => sink == TaintSinkCategory::NoSqlInjection,
+            SanitizerCategory::JwtVerification => sink == TaintSinkCategory::JwtDecode,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -118,6 +151,133 @@ impl SanitizerPattern {
     }
 }
 
+/// A check that keeps a value safe where it holds, such as
+/// `resolved.startsWith(root)` on a path from `path.resolve()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuardPattern {
+    /// The call or constructor producing the checked value, matched like a
+    /// [`SanitizerPattern`]. Empty when any value can be checked.
+    pub callee_path: Vec<String>,
+    pub method: Option<String>,
+    /// The property of the value that is checked, such as `hostname` for
+    /// `new URL(input).hostname`, or `None` when the value itself is.
+    pub property: Option<String>,
+    pub check: GuardCheck,
+    /// The string the value is checked with, as `'//'` in
+    /// `target.startsWith('//')`, or `None` for any.
+    pub argument: Option<String>,
+    /// The text a constant string the value is checked with must start with,
+    /// as `.` in `hostname.endsWith('.example.com')`.
+    pub argument_prefix: Option<String>,
+    /// Another check, with its string, that the same value needs for the guard
+    /// to hold.
+    pub requires: Option<(GuardCheck, String)>,
+    /// The result the check needs for the value to be safe, as `false` for
+    /// `target.startsWith('//')`. The value is then only safe in the code that
+    /// runs with that result, such as after `if (!check) return;`. `None` for
+    /// calls that throw on unsafe values, which keep it safe everywhere.
+    pub result: Option<bool>,
+    /// Whether a constant string must name more than the root of the file
+    /// system, so that `file.startsWith('/')` does not count.
+    pub below_root: bool,
+    /// Whether the string the value is checked with must end with a path
+    /// separator, since `file.startsWith('/srv/files')` also accepts
+    /// `/srv/files2`. Without one the check leaves the value less certainly unsafe.
+    pub trailing_separator: bool,
+    /// Whether the value must be looked up in a constant array or `Set`, as in
+    /// `ALLOWED.includes(target)`, rather than in any collection.
+    pub constant_list: bool,
+    pub category: SanitizerCategory,
+    pub description: String,
+}
+
+/// How a guarded value is checked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuardCheck {
+    /// A method called on the value, as in `resolved.startsWith(root)`.
+    Method(String),
+    /// A method the value is passed to, as in `allowedHosts.includes(host)`.
+    Argument(String),
+    /// A comparison with `===`, `==`, `!==` or `!=`.
+    Equality,
+}
+
+impl GuardPattern {
+    pub fn builtin(
+        callee_path: Vec<&str>,
+        method: Option<&str>,
+        check: GuardCheck,
+        category: SanitizerCategory,
+        description: &str,
+    ) -> Self {
+        Self {
+            callee_path: callee_path.into_iter().map(|s| s.to_string()).collect(),
+            method: method.map(|s| s.to_string()),
+            property: None,
+            check,
+            argument: None,
+            argument_prefix: None,
+            requires: None,
+            result: Some(true),
+            below_root: false,
+            trailing_separator: false,
+            constant_list: false,
+            category,
+            description: description.to_string(),
+        }
+    }
+
+    pub fn with_property(mut self, property: &str) -> Self {
+        self.property = Some(property.to_string());
+        self
+    }
+
+    pub fn with_argument(mut self, argument: &str) -> Self {
+        self.argument = Some(argument.to_string());
+        self
+    }
+
+    pub fn with_argument_prefix(mut self, prefix: &str) -> Self {
+        self.argument_prefix = Some(prefix.to_string());
+        self
+    }
+
+    pub fn with_requirement(mut self, check: GuardCheck, argument: &str) -> Self {
+        self.requires = Some((check, argument.to_string()));
+        self
+    }
+
+    pub fn with_result(mut self, result: Option<bool>) -> Self {
+        self.result = result;
+        self
+    }
+
+    pub fn below_root(mut self) -> Self {
+        self.below_root = true;
+        self
+    }
+
+    pub fn ending_in_separator(mut self) -> Self {
+        self.trailing_separator = true;
+        self
+    }
+
+    pub fn in_constant_list(mut self) -> Self {
+        self.constant_list = true;
+        self
+    }
+}
+
+/// Whether `path` names no more than the root of the file system, as `/` or `C:\`.
+fn is_root(path: &str) -> bool {
+    let path = path.trim_end_matches(['/', '\\']);
+    match path.as_bytes() {
+        [] | [b'.'] => true,
+        [drive, b':'] => drive.is_ascii_alphabetic(),
+        _ => false,
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SanitizerMatch {
     pub pattern: SanitizerPattern,
@@ -129,6 +289,7 @@ pub struct SanitizerMatch {
 pub struct SanitizersRegistry {
     patterns: Vec<SanitizerPattern>,
     callee_index: HashMap<String, Vec<usize>>,
+    guards: Vec<GuardPattern>,
 }
 
 impl Default for SanitizersRegistry {
@@ -142,6 +303,7 @@ impl SanitizersRegistry {
         Self {
             patterns: Vec::new(),
             callee_index: HashMap::new(),
+            guards: Vec::new(),
         }
     }
 
@@ -152,6 +314,10 @@ impl SanitizersRegistry {
         registry.register_xss_sanitizers();
         registry.register_path_sanitizers();
         registry.register_url_encoding_sanitizers();
+        registry.register_url_allowlist_guards();
+        registry.register_open_redirect_guards();
+        registry.register_nosql_sanitizers();
+        registry.register_jwt_verification_guards();
         registry
     }
 
@@ -368,26 +534,28 @@ impl SanitizersRegistry {
     }
 
     fn register_path_sanitizers(&mut self) {
-        self.register_pattern(SanitizerPattern::builtin(
-            vec!["path"],
-            Some("normalize"),
-            SanitizerCategory::PathTraversal,
-            "Path normalization",
-        ));
-
-        self.register_pattern(SanitizerPattern::builtin(
-            vec!["path"],
-            Some("resolve"),
-            SanitizerCategory::PathTraversal,
-            "Path resolution",
-        ));
-
         self.register_pattern(SanitizerPattern::builtin(
             vec!["path"],
             Some("basename"),
             SanitizerCategory::PathTraversal,
             "Path basename extraction",
         ));
+
+        // `path.resolve()` alone still leaves `../` free to escape the root; checking
+        // the resolved path against the root afterwards does not
+        for method in ["resolve", "join", "normalize"] {
+            self.register_guard(
+                GuardPattern::builtin(
+                    vec!["path"],
+                    Some(method),
+                    GuardCheck::Method("startsWith".to_string()),
+                    SanitizerCategory::PathTraversal,
+                    "Resolved path checked against its root",
+                )
+                .below_root()
+                .ending_in_separator(),
+            );
+        }
     }
 
     fn register_url_encoding_sanitizers(&mut self) {
@@ -448,6 +616,145 @@ impl SanitizersRegistry {
         ));
     }
 
+    /// Allowlist checks of a URL, such as `ALLOWED_URLS.includes(target)` or
+    /// `ALLOWED_HOSTS.includes(new URL(target).hostname)`, which keep both
+    /// requests and redirects on known hosts.
+    fn register_url_allowlist_guards(&mut self) {
+        let checks = [
+            GuardCheck::Argument("includes".to_string()),
+            GuardCheck::Argument("has".to_string()),
+            GuardCheck::Argument("indexOf".to_string()),
+            GuardCheck::Method("endsWith".to_string()),
+            GuardCheck::Equality,
+        ];
+        for category in [SanitizerCategory::Ssrf, SanitizerCategory::OpenRedirect] {
+            for property in ["hostname", "host", "origin"] {
+                for check in &checks {
+                    let mut guard = GuardPattern::builtin(
+                        vec!["URL"],
+                        None,
+                        check.clone(),
+                        category,
+                        "URL host checked against an allowlist",
+                    )
+                    .with_property(property);
+                    // `endsWith('example.com')` also accepts `evilexample.com`
+                    if matches!(check, GuardCheck::Method(_)) {
+                        guard = guard.with_argument_prefix(".");
+                    }
+                    self.register_guard(guard);
+                }
+            }
+            // A cache such as `seen.has(url)` holds anything
+            for check in &checks[..3] {
+                self.register_guard(
+                    GuardPattern::builtin(
+                        vec![],
+                        None,
+                        check.clone(),
+                        category,
+                        "URL checked against an allowlist",
+                    )
+                    .in_constant_list(),
+                );
+            }
+        }
+    }
+
+    /// `target.startsWith('/') && !target.startsWith('//')`, which keeps a
+    /// redirect on the current site.
+    fn register_open_redirect_guards(&mut self) {
+        let starts_with = GuardCheck::Method("startsWith".to_string());
+        self.register_guard(
+            GuardPattern::builtin(
+                vec![],
+                None,
+                starts_with.clone(),
+                SanitizerCategory::OpenRedirect,
+                "Redirect target checked to be a relative path",
+            )
+            .with_argument("//")
+            .with_requirement(starts_with, "/")
+            .with_result(Some(false)),
+        );
+    }
+
+    fn register_nosql_sanitizers(&mut self) {
+        // A string or number cannot carry operators such as `$ne`
+        for conversion in [
+            "String",
+            "Number",
+            "parseInt",
+            "parseFloat",
+            "Boolean",
+            "toString",
+        ] {
+            self.register_pattern(SanitizerPattern::builtin(
+                vec![conversion],
+                None,
+                SanitizerCategory::NoSqlInjection,
+                "Conversion to a primitive value",
+            ));
+        }
+
+        // mongo-sanitize, express-mongo-sanitize and Mongoose
+        self.register_pattern(SanitizerPattern::builtin(
+            vec!["sanitize"],
+            None,
+            SanitizerCategory::NoSqlInjection,
+            "MongoDB operator removal",
+        ));
+        self.register_pattern(SanitizerPattern::builtin(
+            vec!["sanitizeFilter"],
+            None,
+            SanitizerCategory::NoSqlInjection,
+            "Mongoose filter sanitization",
+        ));
+
+        // Joi, Yup, Zod and express-validator return the validated value
+        for validation in [
+            "validate",
+            "validateAsync",
+            "validateSync",
+            "safeParse",
+            "safeParseAsync",
+            "parseAsync",
+            "matchedData",
+        ] {
+            self.register_pattern(SanitizerPattern::builtin(
+                vec![validation],
+                None,
+                SanitizerCategory::NoSqlInjection,
+                "Schema validation",
+            ));
+        }
+        for (receiver, method) in [("schema", "parse"), ("Joi", "attempt")] {
+            self.register_pattern(SanitizerPattern::builtin(
+                vec![receiver],
+                Some(method),
+                SanitizerCategory::NoSqlInjection,
+                "Schema validation",
+            ));
+        }
+    }
+
+    /// A token that is also passed to `jwt.verify()` or `jwtVerify()`, as when
+    /// the header is decoded first to pick the key.
+    fn register_jwt_verification_guards(&mut self) {
+        for verify in ["verify", "jwtVerify"] {
+            self.register_guard(
+                GuardPattern::builtin(
+                    vec![],
+                    None,
+                    GuardCheck::Argument(verify.to_string()),
+                    SanitizerCategory::JwtVerification,
+                    "JWT signature verification",
+                )
+                .with_result(None),
+            );
+        }
+    }
+
     pub fn register_pattern(&mut self, pattern: SanitizerPattern) {
         let index = self.patterns.len();
 
@@ -461,30 +768,68 @@ impl SanitizersRegistry {
         self.patterns.push(pattern);
     }
 
+    pub fn register_guard(&mut self, guard: GuardPattern) {
+        self.guards.push(guard);
+    }
+
+    /// The guards made by `check` with `argument` on the result of the call
+    /// `callee_chain`, or on its `property`. An empty `callee_chain` finds the
+    /// guards that apply to any value.
+    pub fn find_guards<'a>(
+        &'a self,
+        callee_chain: &'a [String],
+        method: Option<&'a str>,
+        property: Option<&'a str>,
+        check: &'a GuardCheck,
+        argument: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a GuardPattern> {
+        self.guards.iter().filter(move |guard| {
+            guard.check == *check
+                && guard.property.as_deref() == property
+                && (guard.argument.is_none() || guard.argument.as_deref() == argument)
+                && guard.argument_prefix.as_deref().is_none_or(|prefix| {
+                    argument.is_some_and(|argument| argument.starts_with(prefix))
+                })
+                && !(guard.below_root && argument.is_some_and(is_root))
+                && guard.callee_path == callee_chain
+                && (guard.method.is_none() || guard.method.as_deref() == method)
+        })
+    }
+
     pub fn is_sanitizer(
         &self,
         callee_chain: &[String],
         method: Option<&str>,
     ) -> Option<SanitizerMatch> {
-        if callee_chain.is_empty() {
-            return None;
-        }
-
-        let first = &callee_chain[0];
-        if let Some(indices) = self.callee_index.get(first) {
-            for &idx in indices {
-                let pattern = &self.patterns[idx];
-                if pattern.matches(callee_chain, method) {
-                    return Some(SanitizerMatch {
-                        pattern: pattern.clone(),
-                        matched_callee: callee_chain.to_vec(),
-                        matched_method: method.map(|s| s.to_string()),
-                    });
-                }
-            }
-        }
+        self.find_sanitizers(callee_chain, method)
+            .into_iter()
+            .next()
+    }
 
-        None
+    /// Every pattern matching the call, such as both the SQL and the URL
+    /// meaning of a bare `escape()`.
+    pub fn find_sanitizers(
+        &self,
+        callee_chain: &[String],
+        method: Option<&str>,
+    ) -> Vec<SanitizerMatch> {
+        let Some(indices) = callee_chain
+            .first()
+            .and_then(|first| self.callee_index.get(first))
+        else {
+            return Vec::new();
+        };
+
+        indices
+            .iter()
+            .map(|&idx| &self.patterns[idx])
+            .filter(|pattern| pattern.matches(callee_chain, method))
+            .map(|pattern| SanitizerMatch {
+                pattern: pattern.clone(),
+                matched_callee: callee_chain.to_vec(),
+                matched_method: method.map(|s| s.to_string()),
+            })
+            .collect()
     }
 
     pub fn is_sanitizer_for_category(
@@ -629,12 +974,150 @@ mod tests {
     }
 
     #[test]
-    fn path_normalize_is_sanitizer() {
+    fn path_resolution_is_not_sanitizer_but_guarded_by_starts_with() {
         let registry = registry();
-        let result = registry.is_sanitizer(&["path".into()], Some("normalize"));
-        assert!(result.is_some());
-        let m = result.unwrap();
-        assert_eq!(m.pattern.category, SanitizerCategory::PathTraversal);
+        let path = ["path".to_string()];
+        let starts_with = GuardCheck::Method("startsWith".into());
+        for method in ["normalize", "resolve", "join"] {
+            assert!(registry.is_sanitizer(&path, Some(method)).is_none());
+            let guard = registry
+                .find_guards(&path, Some(method), None, &starts_with, None)
+                .next()
+                .unwrap();
+            assert_eq!(guard.category, SanitizerCategory::PathTraversal);
+        }
+        for root in ["/", "C:\\", "."] {
+            assert!(
+                registry
+                    .find_guards(&path, Some("resolve"), None, &starts_with, Some(root))
+                    .next()
+                    .is_none()
+            );
+        }
+        assert!(
+            registry
+                .find_guards(&path, Some("resolve"), None, &starts_with, Some("/srv/"))
+                .next()
+                .is_some()
+        );
+        let ends_with = GuardCheck::Method("endsWith".into());
+        assert!(
+            registry
+                .find_guards(&path, Some("resolve"), None, &ends_with, None)
+                .next()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn url_host_allowlists_guard_requests_and_redirects() {
+        let registry = registry();
+        let url = ["URL".to_string()];
+        for check in [
+            GuardCheck::Argument("includes".into()),
+            GuardCheck::Argument("has".into()),
+            GuardCheck::Equality,
+        ] {
+            let categories: Vec<_> = registry
+                .find_guards(&url, None, Some("hostname"), &check, None)
+                .map(|guard| guard.category)
+                .collect();
+            assert_eq!(
+                categories,
+                [SanitizerCategory::Ssrf, SanitizerCategory::OpenRedirect]
+            );
+        }
+        let ends_with = GuardCheck::Method("endsWith".into());
+        let guards = |suffix| {
+            registry
+                .find_guards(&url, None, Some("hostname"), &ends_with, Some(suffix))
+                .count()
+        };
+        assert_eq!(guards(".example.com"), 2);
+        assert_eq!(guards("example.com"), 0);
+        assert!(SanitizerCategory::Ssrf.protects(TaintSinkCategory::NetworkRequest));
+        assert!(SanitizerCategory::OpenRedirect.protects(TaintSinkCategory::OpenRedirect));
+        assert!(
+            registry
+                .find_guards(&url, None, Some("pathname"), &GuardCheck::Equality, None)
+                .next()
+                .is_none()
+        );
+        assert!(
+            registry
+                .find_guards(&url, None, None, &GuardCheck::Equality, None)
+                .next()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn relative_path_check_guards_redirects() {
+        let registry = registry();
+        let starts_with = GuardCheck::Method("startsWith".into());
+        let guard = registry
+            .find_guards(&[], None, None, &starts_with, Some("//"))
+            .next()
+            .unwrap();
+        assert_eq!(guard.category, SanitizerCategory::OpenRedirect);
+        assert_eq!(guard.requires, Some((starts_with.clone(), "/".to_string())));
+        assert!(
+            registry
+                .find_guards(&[], None, None, &starts_with, Some("/"))
+                .next()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn verified_tokens_are_guarded_for_jwt_decode() {
+        let registry = registry();
+        for verify in ["verify", "jwtVerify"] {
+            let check = GuardCheck::Argument(verify.into());
+            let guard = registry
+                .find_guards(&[], None, None, &check, None)
+                .next()
+                .unwrap();
+            assert_eq!(guard.category, SanitizerCategory::JwtVerification);
+        }
+        assert!(SanitizerCategory::JwtVerification.protects(TaintSinkCategory::JwtDecode));
+        assert!(!SanitizerCategory::JwtVerification.protects(TaintSinkCategory::SqlInjection));
+    }
+
+    #[test]
+    fn conversions_and_schema_validation_are_nosql_sanitizers() {
+        let registry = registry();
+        let calls: [(&str, Option<&str>); 5] = [
+            ("String", None),
+            ("sanitize", None),
+            ("validateAsync", None),
+            ("schema", Some("parse")),
+            ("Joi", Some("attempt")),
+        ];
+        for (callee, method) in calls {
+            let m = registry
+                .is_sanitizer_for_category(
+                    &[callee.into()],
+                    method,
+                    SanitizerCategory::NoSqlInjection,
+                )
+                .unwrap();
+            assert!(
+                m.pattern
+                    .category
+                    .protects(TaintSinkCategory::NoSqlInjection)
+            );
+            assert!(
+                !m.pattern
+                    .category
+                    .protects(TaintSinkCategory::NoSqlCodeExecution)
+            );
+        }
+        assert!(
+            registry
+                .is_sanitizer(&["JSON".into()], Some("parse"))
+                .is_none()
+        );
     }
 
     #[test]
@@ -690,6 +1173,16 @@ mod tests {
         assert_eq!(SanitizerCategory::Xss.as_str(), "xss");
         assert_eq!(SanitizerCategory::PathTraversal.as_str(), "path_traversal");
         assert_eq!(SanitizerCategory::UrlEncoding.as_str(), "url_encoding");
+        assert_eq!(SanitizerCategory::Ssrf.as_str(), "ssrf");
+        assert_eq!(SanitizerCategory::OpenRedirect.as_str(), "open_redirect");
+        assert_eq!(
+            SanitizerCategory::NoSqlInjection.as_str(),
+            "nosql_injection"
+        );
+        assert_eq!(
+            SanitizerCategory::JwtVerification.as_str(),
+            "jwt_verification"
+        );
         assert_eq!(SanitizerCategory::General.as_str(), "general");
     }
 
@@ -6,22 +6,41 @@
 
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TaintSinkKind {
     BuiltIn,
     Custom,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Receiver of the patterns for Mongoose models. Models are named after what
+/// they store, so receivers named like classes, such as `User`, match them.
+pub const MODEL_RECEIVER: &str = "Model";
+
+/// Receiver of the patterns for members of any object literal, such as
+/// `{ $where: code }`.
+pub const OBJECT_LITERAL: &str = "{}";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TaintSinkCategory {
     SqlInjection,
     CommandInjection,
     CodeExecution,
+    #[serde(alias = "xss")]
     XssSink,
     FileSystem,
     PathTraversal,
     NetworkRequest,
     PrototypePollution,
+    OpenRedirect,
+    /// Query filters that take operators such as `$ne` from objects.
+    NoSqlInjection,
+    /// JavaScript that MongoDB runs, such as `$where` conditions.
+    NoSqlCodeExecution,
+    /// Reading the claims of a JWT without checking its signature.
+    JwtDecode,
 }
 
 impl TaintSinkCategory {
@@ -35,6 +54,10 @@ impl TaintSinkCategory {
             TaintSinkCategory::PathTraversal => "path_traversal",
             TaintSinkCategory::NetworkRequest => "network_request",
             TaintSinkCategory::PrototypePollution => "prototype_pollution",
+            TaintSinkCategory::OpenRedirect => "open_redirect",
+            TaintSinkCategory::NoSqlInjection => "nosql_injection",
+            TaintSinkCategory::NoSqlCodeExecution => "nosql_code_execution",
+            TaintSinkCategory::JwtDecode => "jwt_decode",
         }
     }
 }
@@ -156,6 +179,9 @@ impl TaintSinksRegistry {
         registry.register_xss_sinks();
         registry.register_file_system_sinks();
         registry.register_network_sinks();
+        registry.register_open_redirect_sinks();
+        registry.register_nosql_injection_sinks();
+        registry.register_jwt_decode_sinks();
         registry.register_prototype_pollution_sinks();
         registry
     }
@@ -356,17 +382,13 @@ impl TaintSinksRegistry {
     }
 
     fn register_file_system_sinks(&mut self) {
-        let read_methods = ["readFile", "readFileSync", "createReadStream"];
-        for method in read_methods {
-            self.register_pattern(TaintSinkPattern::builtin(
-                vec!["fs"],
-                Some(method),
-                TaintSinkCategory::FileSystem,
-                "File read operation",
-                vec![0],
-            ));
-        }
-
+        let read_methods = [
+            "readFile",
+            "readFileSync",
+            "createReadStream",
+            "open",
+            "openSync",
+        ];
         let write_methods = [
             "writeFile",
             "writeFileSync",
@@ -374,103 +396,131 @@ impl TaintSinksRegistry {
             "appendFileSync",
             "createWriteStream",
         ];
+        let delete_methods = ["unlink", "unlinkSync", "rmdir", "rmdirSync", "rm", "rmSync"];
+        let dir_methods = ["mkdir", "mkdirSync", "readdir", "readdirSync"];
+        let two_path_methods = [
+            ("rename", "File rename operation"),
+            ("renameSync", "Synchronous file rename"),
+            ("copyFile", "File copy operation"),
+            ("copyFileSync", "Synchronous file copy"),
+        ];
+
+        // `fs`, `fs.promises` and `import fsPromises from 'fs/promises'`
+        for module in ["fs", "promises", "fsPromises"] {
+            let single_path = [
+                (&read_methods[..], "File read operation"),
+                (&write_methods[..], "File write operation"),
+                (&delete_methods[..], "File delete operation"),
+                (&dir_methods[..], "Directory operation"),
+            ];
+            for (methods, description) in single_path {
+                for method in methods {
+                    self.register_pattern(TaintSinkPattern::builtin(
+                        vec![module],
+                        Some(method),
+                        TaintSinkCategory::FileSystem,
+                        description,
+                        vec![0],
+                    ));
+                }
+            }
+            for (method, description) in two_path_methods {
+                self.register_pattern(TaintSinkPattern::builtin(
+                    vec![module],
+                    Some(method),
+                    TaintSinkCategory::FileSystem,
+                    description,
+                    vec![0, 1],
+                ));
+            }
+        }
+
+        // `import { readFile } from 'fs/promises'`
+        for method in read_methods.iter().take(3) {
+            self.register_pattern(TaintSinkPattern::builtin(
+                vec![method],
+                None,
+                TaintSinkCategory::FileSystem,
+                "File read operation",
+                vec![0],
+            ));
+        }
         for method in write_methods {
             self.register_pattern(TaintSinkPattern::builtin(
-                vec!["fs"],
-                Some(method),
+                vec![method],
+                None,
                 TaintSinkCategory::FileSystem,
                 "File write operation",
                 vec![0],
             ));
         }
 
-        let delete_methods = ["unlink", "unlinkSync", "rmdir", "rmdirSync", "rm", "rmSync"];
-        for method in delete_methods {
+        // Express `res.sendFile()` and `res.download()`, Fastify `reply.sendFile()`
+        for response in ["res", "response", "reply"] {
+            for (method, description) in [
+                ("sendFile", "File sent in the response"),
+                ("download", "File sent as a download"),
+            ] {
+                self.register_pattern(TaintSinkPattern::builtin(
+                    vec![response],
+                    Some(method),
+                    TaintSinkCategory::PathTraversal,
+                    description,
+                    vec![0],
+                ));
+            }
+        }
+    }
+
+    fn register_network_sinks(&mut self) {
+        // `fetch` is also what node-fetch and undici export
+        for (callee, description) in [
+            ("fetch", "Fetch API request"),
+            ("axios", "Axios request"),
+            ("got", "Got request"),
+        ] {
             self.register_pattern(TaintSinkPattern::builtin(
-                vec!["fs"],
-                Some(method),
-                TaintSinkCategory::FileSystem,
-                "File delete operation",
+                vec![callee],
+                None,
+                TaintSinkCategory::NetworkRequest,
+                description,
                 vec![0],
             ));
         }
 
-        let dir_methods = ["mkdir", "mkdirSync"];
-        for method in dir_methods {
+        let axios_methods = [
+            "get", "post", "put", "patch", "delete", "head", "options", "request",
+        ];
+        for method in axios_methods {
             self.register_pattern(TaintSinkPattern::builtin(
-                vec!["fs"],
+                vec!["axios"],
                 Some(method),
-                TaintSinkCategory::FileSystem,
-                "Directory creation",
+                TaintSinkCategory::NetworkRequest,
+                "Axios request",
                 vec![0],
             ));
         }
 
-        self.register_pattern(TaintSinkPattern::builtin(
-            vec!["fs"],
-            Some("rename"),
-            TaintSinkCategory::FileSystem,
-            "File rename operation",
-            vec![0, 1],
-        ));
-
-        self.register_pattern(TaintSinkPattern::builtin(
-            vec!["fs"],
-            Some("renameSync"),
-            TaintSinkCategory::FileSystem,
-            "Synchronous file rename",
-            vec![0, 1],
-        ));
-
-        self.register_pattern(TaintSinkPattern::builtin(
-            vec!["fs"],
-            Some("copyFile"),
-            TaintSinkCategory::FileSystem,
-            "File copy operation",
-            vec![0, 1],
-        ));
-
-        self.register_pattern(TaintSinkPattern::builtin(
-            vec!["fs"],
-            Some("copyFileSync"),
-            TaintSinkCategory::FileSystem,
-            "Synchronous file copy",
-            vec![0, 1],
-        ));
-    }
-
-    fn register_network_sinks(&mut self) {
-        self.register_pattern(TaintSinkPattern::builtin(
-            vec!["fetch"],
-            None,
-            TaintSinkCategory::NetworkRequest,
-            "Fetch API request",
-            vec![0],
-        ));
-
-        self.register_pattern(TaintSinkPattern::builtin(
-            vec!["axios"],
-            Some("get"),
-            TaintSinkCategory::NetworkRequest,
-            "Axios GET request",
-            vec![0],
-        ));
-
-        self.register_pattern(TaintSinkPattern::builtin(
-            vec!["axios"],
-            Some("post"),
-            TaintSinkCategory::NetworkRequest,
-            "Axios POST request",
-            vec![0],
-        ));
+        let got_methods = ["get", "post", "put", "patch", "delete", "head", "stream"];
+        for method in got_methods {
+            self.register_pattern(TaintSinkPattern::builtin(
+                vec!["got"],
+                Some(method),
+                TaintSinkCategory::NetworkRequest,
+                "Got request",
+                vec![0],
+            ));
+        }
 
-        self.register_pattern(TaintSinkPattern::builtin(
-            vec!["axios"],
-            Some("request"),
-            TaintSinkCategory::NetworkRequest,
-            "Axios request",
-            vec![0],
-        ));
+        for method in ["request", "fetch", "stream"] {
+            self.register_pattern(TaintSinkPattern::builtin(
+                vec!["undici"],
+                Some(method),
+                TaintSinkCategory::NetworkRequest,
+                "Undici request",
+                vec![0],
+            ));
+        }
 
         self.register_pattern(TaintSinkPattern::builtin(
             vec!["XMLHttpRequest"],
@@ -500,6 +550,140 @@ impl TaintSinksRegistry {
         }
     }
 
+    fn register_open_redirect_sinks(&mut self) {
+        // Express and Fastify also take the status code first
+        for receiver in ["res", "response", "reply"] {
+            self.register_pattern(TaintSinkPattern::builtin(
+                vec![receiver],
+                Some("redirect"),
+                TaintSinkCategory::OpenRedirect,
+                "HTTP redirect",
+                vec![0, 1],
+            ));
+        }
+
+        self.register_pattern(TaintSinkPattern::builtin(
+            vec!["ctx"],
+            Some("redirect"),
+            TaintSinkCategory::OpenRedirect,
+            "HTTP redirect",
+            vec![0],
+        ));
+
+        for method in ["assign", "replace"] {
+            self.register_pattern(TaintSinkPattern::builtin(
+                vec!["location"],
+                Some(method),
+                TaintSinkCategory::OpenRedirect,
+                "Location navigation",
+                vec![0],
+            ));
+        }
+
+        // Assignments to `location.href`, `window.location` and `document.location`
+        for (object, property) in [
+            ("location", "href"),
+            ("window", "location"),
+            ("document", "location"),
+        ] {
+            self.register_pattern(TaintSinkPattern::builtin(
+                vec![object],
+                Some(property),
+                TaintSinkCategory::OpenRedirect,
+                "Location assignment",
+                vec![],
+            ));
+        }
+    }
+
+    fn register_nosql_injection_sinks(&mut self) {
+        let filter_methods = [
+            "find",
+            "findOne",
+            "findOneAndUpdate",
+            "findOneAndDelete",
+            "findOneAndReplace",
+            "findOneAndRemove",
+            "updateOne",
+            "updateMany",
+            "update",
+            "replaceOne",
+            "deleteOne",
+            "deleteMany",
+            "remove",
+            "countDocuments",
+            "count",
+            "exists",
+            "where",
+            "aggregate",
+        ];
+        // Models, `mongoose.model('User')` and `db.collection('users')`
+        for receiver in [MODEL_RECEIVER, "model", "collection"] {
+            for method in filter_methods {
+                self.register_pattern(TaintSinkPattern::builtin(
+                    vec![receiver],
+                    Some(method),
+                    TaintSinkCategory::NoSqlInjection,
+                    "MongoDB query filter",
+                    vec![0],
+                ));
+            }
+
+            self.register_pattern(TaintSinkPattern::builtin(
+                vec![receiver],
+                Some("distinct"),
+                TaintSinkCategory::NoSqlInjection,
+                "MongoDB query filter",
+                vec![1],
+            ));
+
+            self.register_pattern(TaintSinkPattern::builtin(
+                vec![receiver],
+                Some("mapReduce"),
+                TaintSinkCategory::NoSqlCodeExecution,
+                "MongoDB mapReduce",
+                vec![0, 1],
+            ));
+        }
+
+        for operator in ["$where", "$function", "$accumulator"] {
+            self.register_pattern(TaintSinkPattern::builtin(
+                vec![OBJECT_LITERAL],
+                Some(operator),
+                TaintSinkCategory::NoSqlCodeExecution,
+                "MongoDB server-side JavaScript",
+                vec![],
+            ));
+        }
+    }
+
+    fn register_jwt_decode_sinks(&mut self) {
+        for receiver in ["jwt", "jsonwebtoken"] {
+            self.register_pattern(TaintSinkPattern::builtin(
+                vec![receiver],
+                Some("decode"),
+                TaintSinkCategory::JwtDecode,
+                "jsonwebtoken decode()",
+                vec![0],
+            ));
+        }
+
+        self.register_pattern(TaintSinkPattern::builtin(
+            vec!["jose"],
+            Some("decodeJwt"),
+            TaintSinkCategory::JwtDecode,
+            "jose decodeJwt()",
+            vec![0],
+        ));
+        self.register_pattern(TaintSinkPattern::builtin(
+            vec!["decodeJwt"],
+            None,
+            TaintSinkCategory::JwtDecode,
+            "jose decodeJwt()",
+            vec![0],
+        ));
+    }
+
     fn register_prototype_pollution_sinks(&mut self) {
         self.register_pattern(TaintSinkPattern::builtin(
             vec!["Object"],
@@ -577,7 +761,15 @@ impl TaintSinksRegistry {
             }
         }
 
-        None
+        match callee_chain {
+            [receiver] if is_model_name(receiver) => self
+                .is_taint_sink(&[MODEL_RECEIVER.to_string()], method)
+                .map(|result| TaintSinkMatch {
+                    matched_callee: callee_chain.to_vec(),
+                    ..result
+                }),
+            _ => None,
+        }
     }
 
     pub fn patterns(&self) -> &[TaintSinkPattern] {
@@ -592,6 +784,14 @@ impl TaintSinksRegistry {
     }
 }
 
+/// Whether `name` is written like a class, as `User` or `OrderItem` but not
+/// `DB` or `MAX_SIZE`.
+fn is_model_name(name: &str) -> bool {
+    name != MODEL_RECEIVER
+        && name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name.chars().any(|c| c.is_ascii_lowercase())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -864,6 +1064,32 @@ mod tests {
         assert_eq!(m.pattern.category, TaintSinkCategory::FileSystem);
     }
 
+    #[test]
+    fn fs_promises_variants_are_taint_sinks() {
+        let registry = registry();
+        for (chain, method) in [
+            ("promises", Some("readFile")),
+            ("fsPromises", Some("open")),
+            ("readFile", None),
+            ("createReadStream", None),
+        ] {
+            let m = registry
+                .is_taint_sink(&[chain.into()], method)
+                .unwrap_or_else(|| panic!("{chain} {method:?} should be a sink"));
+            assert_eq!(m.pattern.category, TaintSinkCategory::FileSystem);
+        }
+    }
+
+    #[test]
+    fn res_send_file_is_path_traversal_sink() {
+        let registry = registry();
+        let m = registry
+            .is_taint_sink(&["res".into()], Some("sendFile"))
+            .unwrap();
+        assert_eq!(m.pattern.category, TaintSinkCategory::PathTraversal);
+        assert_eq!(m.pattern.arg_positions, vec![0]);
+    }
+
     #[test]
     fn fetch_is_taint_sink() {
         let registry = registry();
@@ -891,6 +1117,104 @@ mod tests {
         assert_eq!(m.pattern.category, TaintSinkCategory::NetworkRequest);
     }
 
+    #[test]
+    fn http_client_libraries_are_taint_sinks() {
+        let registry = registry();
+        let calls: [(&str, Option<&str>); 5] = [
+            ("axios", None),
+            ("axios", Some("put")),
+            ("got", None),
+            ("got", Some("post")),
+            ("undici", Some("request")),
+        ];
+        for (callee, method) in calls {
+            let m = registry.is_taint_sink(&[callee.into()], method).unwrap();
+            assert_eq!(m.pattern.category, TaintSinkCategory::NetworkRequest);
+            assert_eq!(m.pattern.arg_positions, vec![0]);
+        }
+    }
+
+    #[test]
+    fn redirects_and_location_changes_are_open_redirect_sinks() {
+        let registry = registry();
+        let sinks = [
+            ("res", "redirect"),
+            ("ctx", "redirect"),
+            ("location", "assign"),
+            ("location", "href"),
+            ("window", "location"),
+        ];
+        for (object, method) in sinks {
+            let m = registry
+                .is_taint_sink(&[object.into()], Some(method))
+                .unwrap();
+            assert_eq!(m.pattern.category, TaintSinkCategory::OpenRedirect);
+        }
+        let m = registry
+            .is_taint_sink(&["res".into()], Some("redirect"))
+            .unwrap();
+        assert_eq!(m.pattern.arg_positions, vec![0, 1]);
+    }
+
+    #[test]
+    fn mongo_filters_are_nosql_injection_sinks() {
+        let registry = registry();
+        for receiver in ["User", "OrderItem", "collection"] {
+            let m = registry
+                .is_taint_sink(&[receiver.into()], Some("findOne"))
+                .unwrap();
+            assert_eq!(m.pattern.category, TaintSinkCategory::NoSqlInjection);
+            assert_eq!(m.matched_callee, vec![receiver.to_string()]);
+        }
+        let m = registry
+            .is_taint_sink(&["User".into()], Some("distinct"))
+            .unwrap();
+        assert_eq!(m.pattern.arg_positions, vec![1]);
+        assert!(
+            registry
+                .is_taint_sink(&["DB".into()], Some("find"))
+                .is_none()
+        );
+        assert!(
+            registry
+                .is_taint_sink(&["users".into()], Some("find"))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn mongo_server_side_javascript_is_nosql_code_execution_sink() {
+        let registry = registry();
+        let m = registry
+            .is_taint_sink(&[OBJECT_LITERAL.into()], Some("$where"))
+            .unwrap();
+        assert_eq!(m.pattern.category, TaintSinkCategory::NoSqlCodeExecution);
+        let m = registry
+            .is_taint_sink(&["User".into()], Some("mapReduce"))
+            .unwrap();
+        assert_eq!(m.pattern.category, TaintSinkCategory::NoSqlCodeExecution);
+    }
+
+    #[test]
+    fn jwt_decode_is_taint_sink() {
+        let registry = registry();
+        let calls: [(&str, Option<&str>); 4] = [
+            ("jwt", Some("decode")),
+            ("jsonwebtoken", Some("decode")),
+            ("jose", Some("decodeJwt")),
+            ("decodeJwt", None),
+        ];
+        for (callee, method) in calls {
+            let m = registry.is_taint_sink(&[callee.into()], method).unwrap();
+            assert_eq!(m.pattern.category, TaintSinkCategory::JwtDecode);
+        }
+        assert!(
+            registry
+                .is_taint_sink(&["jwt".into()], Some("verify"))
+                .is_none()
+        );
+    }
+
     #[test]
     fn xhr_open_is_taint_sink() {
         let registry = registry();
@@ -963,6 +1287,7 @@ mod tests {
             TaintSinkCategory::PrototypePollution.as_str(),
             "prototype_pollution"
         );
+        assert_eq!(TaintSinkCategory::JwtDecode.as_str(), "jwt_decode");
     }
 
     #[test]
@@ -0,0 +1,538 @@
+//! Per-function taint summaries for interprocedural analysis
+//!
+//! A summary records which parameters of a local function flow to its
+//! return value, to a sink inside it, or to a property of `this`. Call sites
+//! of the function apply the summary instead of treating the call as opaque.
+//! Summaries are computed together and iterated to a fixpoint, so recursive
+//! and mutually recursive helpers converge.
+//!
+//! Functions imported from other modules are resolved through
+//! [`ImportedSummaries`], whose sinks are located in the exporting file.
+//!
+//! Sanitizers only protect the sinks of their category, so each flow carries
+//! the categories it has been sanitized for on every path.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+use swc_common::Span;
+
+use super::{
+    DataFlowGraph, DfgCallSite, DfgFunction, DfgNodeId, DfgNodeKind, SanitizerCategory,
+    TaintSinkCategory, TaintSinkPattern, TraceStep,
+};
+
+/// Sanitizer categories a value has passed through on every path.
+pub type Sanitized = BTreeSet<SanitizerCategory>;
+
+/// Whether a value sanitized for `sanitized` is safe to pass to a `sink`.
+pub fn is_protected(sanitized: &Sanitized, sink: TaintSinkCategory) -> bool {
+    sanitized.iter().any(|category| category.protects(sink))
+}
+
+/// Values checked by a guard, with the category of the guard and the code in
+/// which the check has the result it needs.
+pub type Guards = HashMap<DfgNodeId, Vec<(SanitizerCategory, Span)>>;
+
+/// Whether `value`, or the value it is read or copied from, is guarded for a
+/// `sink` at `at`.
+pub fn is_guarded(
+    dfg: &DataFlowGraph,
+    guards: &Guards,
+    value: DfgNodeId,
+    at: Span,
+    sink: TaintSinkCategory,
+) -> bool {
+    let mut node = value;
+    for _ in 0..8 {
+        let guarded = guards.get(&node).is_some_and(|regions| {
+            regions
+                .iter()
+                .any(|&(category, region)| category.protects(sink) && region.contains(at))
+        });
+        if guarded {
+            return true;
+        }
+        // Stop where other values join in, which the guard does not check
+        match dfg.get(node).flows_from[..] {
+            [from] => node = from,
+            _ => return false,
+        }
+    }
+    false
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FunctionSummary {
+    /// Parameters that reach the return value, with the sanitizers they pass
+    /// through on the way.
+    pub params_to_return: BTreeMap<usize, Sanitized>,
+    /// Sinks, possibly inside other functions, reached by each parameter.
+    pub params_to_sinks: BTreeMap<usize, BTreeSet<SummarySink>>,
+    pub params_to_this: BTreeSet<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SummarySink {
+    /// A sink node of the analyzed file.
+    Local(DfgNodeId),
+    /// A sink in another module.
+    Imported(ImportedSink),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ImportedSink {
+    pub file: String,
+    pub span: Span,
+    pub category: TaintSinkCategory,
+    pub description: String,
+    pub argument_prefix: Option<String>,
+    pub result_trusted: bool,
+    /// How the data gets from the parameter of the exported function to the
+    /// sink, excluding the sink itself.
+    pub steps: Vec<TraceStep>,
+}
+
+/// Summaries of the functions a module imports, by local binding.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportedSummaries {
+    /// Functions called directly, as in `import { find } from "./repo"`.
+    pub functions: HashMap<String, FunctionSummary>,
+    /// Functions called on a module object, as in `repo.find()` after
+    /// `import * as repo from "./repo"` or `const repo = require("./repo")`.
+    pub namespaces: HashMap<String, HashMap<String, FunctionSummary>>,
+}
+
+impl ImportedSummaries {
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty() && self.namespaces.is_empty()
+    }
+}
+
+/// Summaries of every function of a data flow graph.
+#[derive(Debug, Default)]
+pub struct FunctionSummaries {
+    summaries: Vec<FunctionSummary>,
+    by_name: HashMap<(bool, String), Vec<usize>>,
+    imports: ImportedSummaries,
+}
+
+impl FunctionSummaries {
+    /// Computes summaries for the functions of `dfg`.
+    ///
+    /// `sinks` are the nodes that report a finding when tainted data flows
+    /// into them, and `sanitizers` the calls that sanitize their arguments.
+    pub fn compute(
+        dfg: &DataFlowGraph,
+        sinks: &HashMap<DfgNodeId, TaintSinkPattern>,
+        sanitizers: &HashMap<DfgNodeId, Sanitized>,
+        guards: &Guards,
+        imports: ImportedSummaries,
+    ) -> Self {
+        let mut by_name: HashMap<(bool, String), Vec<usize>> = HashMap::new();
+        for (index, function) in dfg.functions().iter().enumerate() {
+            if let Some(name) = &function.name {
+                by_name
+                    .entry((function.is_method, name.clone()))
+                    .or_default()
+                    .push(index);
+            }
+        }
+
+        let mut summaries = Self {
+            summaries: vec![FunctionSummary::default(); dfg.functions().len()],
+            by_name,
+            imports,
+        };
+
+        // Summaries only grow as callee summaries grow, so this terminates.
+        loop {
+            let mut changed = false;
+            for (index, function) in dfg.functions().iter().enumerate() {
+                let summary = summaries.summarize(dfg, function, sinks, sanitizers, guards);
+                if summary != summaries.summaries[index] {
+                    summaries.summaries[index] = summary;
+                    changed = true;
+                }
+            }
+            if !changed {
+                return summaries;
+            }
+        }
+    }
+
+    /// The summary of the function named `name`, preferring plain functions
+    /// over methods.
+    pub fn function(&self, name: &str) -> Option<&FunctionSummary> {
+        [false, true]
+            .iter()
+            .find_map(|&is_method| self.by_name.get(&(is_method, name.to_string())))
+            .map(|indices| &self.summaries[indices[0]])
+    }
+
+    /// Summaries of the local or imported functions a call node may invoke.
+    pub fn callees(&self, dfg: &DataFlowGraph, call: DfgNodeId) -> Vec<&FunctionSummary> {
+        let Some(site) = dfg.call_site(call) else {
+            return Vec::new();
+        };
+        if site.has_spread {
+            return Vec::new();
+        }
+        let DfgNodeKind::Call { callee_name } = &dfg.get(call).kind else {
+            return Vec::new();
+        };
+        if let Some(indices) = self.by_name.get(&(site.is_method, callee_name.clone())) {
+            return indices.iter().map(|&i| &self.summaries[i]).collect();
+        }
+
+        let imported = if site.is_method {
+            site.receiver
+                .and_then(|receiver| match &dfg.get(receiver).kind {
+                    DfgNodeKind::Variable { name, .. } => self.imports.namespaces.get(name),
+                    _ => None,
+                })
+                .and_then(|namespace| namespace.get(callee_name))
+        } else {
+            self.imports.functions.get(callee_name)
+        };
+        imported.into_iter().collect()
+    }
+
+    /// Whether data flows along the edge `from -> to`, and the sanitizers it
+    /// passes through if so.
+    ///
+    /// Calls of summarized functions only pass on the arguments that their
+    /// summary returns; every other edge is followed.
+    pub fn flow_through(
+        &self,
+        dfg: &DataFlowGraph,
+        from: DfgNodeId,
+        to: DfgNodeId,
+    ) -> Option<Sanitized> {
+        let callees = self.callees(dfg, to);
+        let Some(site) = dfg.call_site(to).filter(|_| !callees.is_empty()) else {
+            return Some(Sanitized::new());
+        };
+        if site.receiver == Some(from) {
+            return Some(Sanitized::new());
+        }
+        argument_positions(site, from)
+            .flat_map(|index| {
+                callees
+                    .iter()
+                    .filter_map(move |summary| summary.params_to_return.get(&index))
+            })
+            .fold(None, |flow: Option<Sanitized>, sanitized| match flow {
+                None => Some(sanitized.clone()),
+                Some(flow) => Some(flow.intersection(sanitized).copied().collect()),
+            })
+    }
+
+    /// Calls that receive `node` as an argument, with the argument position
+    /// and the summaries of the functions they may invoke.
+    pub fn calls_with_argument(
+        &self,
+        dfg: &DataFlowGraph,
+        node: DfgNodeId,
+    ) -> Vec<(DfgNodeId, usize, &FunctionSummary)> {
+        let mut calls = Vec::new();
+        for &call in &dfg.get(node).flows_to {
+            let callees = self.callees(dfg, call);
+            if callees.is_empty() {
+                continue;
+            }
+            let Some(site) = dfg.call_site(call) else {
+                continue;
+            };
+            for index in argument_positions(site, node) {
+                for &summary in &callees {
+                    calls.push((call, index, summary));
+                }
+            }
+        }
+        calls
+    }
+
+    fn summarize(
+        &self,
+        dfg: &DataFlowGraph,
+        function: &DfgFunction,
+        sinks: &HashMap<DfgNodeId, TaintSinkPattern>,
+        sanitizers: &HashMap<DfgNodeId, Sanitized>,
+        guards: &Guards,
+    ) -> FunctionSummary {
+        let mut summary = FunctionSummary::default();
+
+        for (index, param) in function.params.iter().enumerate() {
+            // Destructured parameters are not tracked; assume they are returned.
+            let Some(param) = *param else {
+                summary.params_to_return.insert(index, Sanitized::new());
+                continue;
+            };
+
+            // Sanitizers only ever shrink, as paths meet, so this terminates.
+            let mut reached: HashMap<DfgNodeId, Sanitized> =
+                HashMap::from([(param, Sanitized::new())]);
+            let mut reached_sinks: BTreeSet<SummarySink> = BTreeSet::new();
+            let mut worklist = VecDeque::from([param]);
+            while let Some(node_id) = worklist.pop_front() {
+                let sanitized = reached[&node_id].clone();
+                for &dependent in &dfg.get(node_id).flows_to {
+                    if let Some(sink) = sinks.get(&dependent)
+                        && is_sink_argument(dfg, dependent, node_id, &sink.arg_positions)
+                        && !is_protected(&sanitized, sink.category)
+                        && !is_guarded(dfg, guards, node_id, dfg.get(dependent).span, sink.category)
+                    {
+                        reached_sinks.insert(SummarySink::Local(dependent));
+                    }
+                    let Some(through) = self.flow_through(dfg, node_id, dependent) else {
+                        continue;
+                    };
+                    let mut flow = sanitized.clone();
+                    flow.extend(through);
+                    if let Some(categories) = sanitizers.get(&dependent) {
+                        flow.extend(categories.iter().copied());
+                    }
+                    if merge_sanitized(&mut reached, dependent, flow) {
+                        worklist.push_back(dependent);
+                    }
+                }
+            }
+
+            for (&node_id, sanitized) in &reached {
+                if function.returns.contains(&node_id) {
+                    summary
+                        .params_to_return
+                        .entry(index)
+                        .and_modify(|returned| returned.retain(|c| sanitized.contains(c)))
+                        .or_insert_with(|| sanitized.clone());
+                }
+                if function.this_writes.contains(&node_id) {
+                    summary.params_to_this.insert(index);
+                }
+                for (call, arg_index, callee) in self.calls_with_argument(dfg, node_id) {
+                    let Some(callee_sinks) = callee.params_to_sinks.get(&arg_index) else {
+                        continue;
+                    };
+                    let safe = |category| {
+                        is_protected(sanitized, category)
+                            || is_guarded(dfg, guards, node_id, dfg.get(call).span, category)
+                    };
+                    reached_sinks.extend(
+                        callee_sinks
+                            .iter()
+                            .filter(|sink| match sink {
+                                SummarySink::Local(id) => {
+                                    sinks.get(id).is_none_or(|sink| !safe(sink.category))
+                                }
+                                SummarySink::Imported(imported) => !safe(imported.category),
+                            })
+                            .cloned(),
+                    );
+                }
+            }
+
+            if !reached_sinks.is_empty() {
+                summary.params_to_sinks.insert(index, reached_sinks);
+            }
+        }
+
+        summary
+    }
+}
+
+/// Records that `flow` reaches `node`, keeping only the sanitizers common to
+/// every path. Returns whether this changed what is known about `node`.
+pub(super) fn merge_sanitized(
+    reached: &mut HashMap<DfgNodeId, Sanitized>,
+    node: DfgNodeId,
+    flow: Sanitized,
+) -> bool {
+    match reached.get_mut(&node) {
+        Some(sanitized) => {
+            let before = sanitized.len();
+            sanitized.retain(|category| flow.contains(category));
+            sanitized.len() != before
+        }
+        None => {
+            reached.insert(node, flow);
+            true
+        }
+    }
+}
+
+/// Whether data flowing from `from` into `sink` is passed in one of the
+/// `positions` it checks. Sinks without positions, or that are not calls,
+/// check everything they receive.
+pub fn is_sink_argument(
+    dfg: &DataFlowGraph,
+    sink: DfgNodeId,
+    from: DfgNodeId,
+    positions: &[usize],
+) -> bool {
+    if positions.is_empty() {
+        return true;
+    }
+    match dfg.call_site(sink) {
+        Some(site) if !site.has_spread => {
+            argument_positions(site, from).any(|index| positions.contains(&index))
+        }
+        _ => true,
+    }
+}
+
+fn argument_positions(site: &DfgCallSite, node: DfgNodeId) -> impl Iterator<Item = usize> + '_ {
+    site.args
+        .iter()
+        .enumerate()
+        .filter(move |(_, arg)| **arg == Some(node))
+        .map(|(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ParsedFile;
+    use crate::semantic::ScopeBuilder;
+
+    fn summaries_of(code: &str) -> (DataFlowGraph, FunctionSummaries) {
+        let parsed = ParsedFile::from_source("test.js", code);
+        let module = parsed.module().expect("parse failed");
+        let semantic = ScopeBuilder::build(module);
+        let dfg = DataFlowGraph::build(module, &semantic);
+        let sinks = dfg
+            .nodes()
+            .filter(
+                |n| matches!(&n.kind, DfgNodeKind::Call { callee_name } if callee_name == "sink"),
+            )
+            .map(|n| {
+                let pattern = TaintSinkPattern::custom(
+                    vec!["sink"],
+                    None,
+                    TaintSinkCategory::CodeExecution,
+                    "Test sink",
+                    vec![0],
+                );
+                (n.id, pattern)
+            })
+            .collect();
+        let summaries = FunctionSummaries::compute(
+            &dfg,
+            &sinks,
+            &HashMap::new(),
+            &Guards::new(),
+            ImportedSummaries::default(),
+        );
+        (dfg, summaries)
+    }
+
+    fn summary<'a>(
+        dfg: &DataFlowGraph,
+        summaries: &'a FunctionSummaries,
+        name: &str,
+    ) -> &'a FunctionSummary {
+        let index = dfg
+            .functions()
+            .iter()
+            .position(|f| f.name.as_deref() == Some(name))
+            .expect("function should exist");
+        &summaries.summaries[index]
+    }
+
+    #[test]
+    fn records_parameters_that_reach_the_return_value() {
+        let (dfg, summaries) = summaries_of(
+            r#"
+            function build(table, id) {
+                return "SELECT * FROM " + table;
+            }
+            const wrap = (value) => `[${value}]`;
+            "#,
+        );
+
+        assert_eq!(
+            summary(&dfg, &summaries, "build").params_to_return,
+            BTreeMap::from([(0, Sanitized::new())])
+        );
+        assert_eq!(
+            summary(&dfg, &summaries, "wrap").params_to_return,
+            BTreeMap::from([(0, Sanitized::new())])
+        );
+    }
+
+    #[test]
+    fn records_parameters_that_reach_sinks_through_callees() {
+        let (dfg, summaries) = summaries_of(
+            r#"
+            function run(query) {
+                sink(query);
+            }
+            function find(id) {
+                run("SELECT " + id);
+            }
+            "#,
+        );
+
+        assert_eq!(summary(&dfg, &summaries, "run").params_to_sinks.len(), 1);
+        assert_eq!(
+            summary(&dfg, &summaries, "find").params_to_sinks,
+            summary(&dfg, &summaries, "run").params_to_sinks
+        );
+    }
+
+    #[test]
+    fn records_parameters_stored_on_this() {
+        let (dfg, summaries) = summaries_of(
+            r#"
+            class Query {
+                where(clause, limit) {
+                    this.clause = clause;
+                }
+            }
+            "#,
+        );
+
+        assert_eq!(
+            summary(&dfg, &summaries, "where").params_to_this,
+            BTreeSet::from([0])
+        );
+    }
+
+    #[test]
+    fn recursion_reaches_a_fixpoint() {
+        let (dfg, summaries) = summaries_of(
+            r#"
+            function join(parts, acc) {
+                if (parts.length === 0) {
+                    return acc;
+                }
+                return join(parts.slice(1), acc + parts[0]);
+            }
+            function even(n, value) {
+                return n === 0 ? value : odd(n - 1, value);
+            }
+            function odd(n, value) {
+                return n === 0 ? "" : even(n - 1, value);
+            }
+            "#,
+        );
+
+        assert_eq!(
+            summary(&dfg, &summaries, "join")
+                .params_to_return
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        assert!(
+            summary(&dfg, &summaries, "even")
+                .params_to_return
+                .contains_key(&1)
+        );
+        assert!(
+            summary(&dfg, &summaries, "odd")
+                .params_to_return
+                .contains_key(&1)
+        );
+    }
+}
@@ -1,5 +1,6 @@
 //! Analysis engine for code analysis and diagnostic generation
 
+use kaizen_core::config::DisableReasonPolicy;
 use kaizen_core::diagnostic::Diagnostic as CoreDiagnostic;
 use kaizen_core::licensing::PremiumTier;
 use kaizen_core::parser::ParsedFile;
@@ -7,6 +8,7 @@ use kaizen_core::rules::RuleRegistry;
 use kaizen_core::rules::quality::{
     Eqeqeq, FloatingPromises, NoConsole, NoEval, NoUnusedVars, NoVar, PreferConst, PreferUsing,
 };
+use kaizen_core::suppressions::filter_disabled;
 use tower_lsp::lsp_types::Diagnostic;
 
 use crate::diagnostics::{convert_diagnostics, convert_parse_errors};
@@ -37,7 +39,12 @@ impl AnalysisEngine {
 
         lsp_diagnostics.extend(convert_parse_errors(file.errors()));
 
-        let rule_diagnostics = self.registry.run_all(file);
+        let rule_diagnostics = filter_disabled(
+            file,
+            &self.registry,
+            DisableReasonPolicy::default(),
+            self.registry.run_all(file),
+        );
         lsp_diagnostics.extend(convert_diagnostics(&rule_diagnostics));
 
         (lsp_diagnostics, rule_diagnostics)
@@ -1,3 +1,4 @@
+use crate::code_actions::fix_all_kind;
 use tower_lsp::lsp_types::{
     CodeActionKind, CodeActionOptions, CodeActionProviderCapability, ServerCapabilities,
     TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
@@ -15,7 +16,7 @@ pub fn server_capabilities() -> ServerCapabilities {
             },
         )),
         code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
-            code_action_kinds: Some(vec![CodeActionKind::QUICKFIX]),
+            code_action_kinds: Some(vec![CodeActionKind::QUICKFIX, fix_all_kind()]),
             work_done_progress_options: Default::default(),
             resolve_provider: None,
         })),
@@ -47,6 +48,10 @@ mod tests {
                     kinds.contains(&CodeActionKind::QUICKFIX),
                     "Server must advertise quickfix code action kind"
                 );
+                assert!(
+                    kinds.contains(&fix_all_kind()),
+                    "Server must advertise the fix-all code action kind"
+                );
             }
             _ => panic!("codeActionProvider must use Options variant with codeActionKinds"),
         }
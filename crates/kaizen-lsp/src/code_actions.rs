@@ -1,6 +1,9 @@
 //! Code action generation from diagnostics with fixes
 
-use kaizen_core::diagnostic::{Diagnostic as CoreDiagnostic, Fix, FixKind};
+use kaizen_core::diagnostic::{
+    Diagnostic as CoreDiagnostic, Fix, FixKind, TextEdit as CoreTextEdit,
+};
+use kaizen_core::fixer::apply_fixes;
 use kaizen_core::rules::Severity;
 use std::collections::HashMap;
 use tower_lsp::lsp_types::{
@@ -22,8 +25,10 @@ pub fn generate_code_actions(
 
         let lsp_diagnostic = core_to_lsp_diagnostic(diag);
 
+        // Only a safe fix may be marked preferred, since editors apply preferred fixes without review
+        let preferred_index = diag.fixes.iter().position(Fix::is_safe);
         for (index, fix) in diag.fixes.iter().enumerate() {
-            let is_preferred = index == 0;
+            let is_preferred = preferred_index == Some(index);
             if let Some(action) = create_code_action(uri, &lsp_diagnostic, fix, is_preferred) {
                 actions.push(CodeActionOrCommand::CodeAction(action));
             }
@@ -33,6 +38,76 @@ pub fn generate_code_actions(
     actions
 }
 
+/// Kind of the action that applies every safe fix in a document at once.
+pub fn fix_all_kind() -> CodeActionKind {
+    CodeActionKind::new("source.fixAll.kaizen")
+}
+
+/// Returns true if a client asking for `only` kinds should receive the fix-all action.
+pub fn wants_fix_all(only: Option<&[CodeActionKind]>) -> bool {
+    let kind = fix_all_kind();
+    only.is_none_or(|kinds| {
+        kinds
+            .iter()
+            .any(|requested| kind.as_str().starts_with(requested.as_str()))
+    })
+}
+
+/// Builds a single action applying the first safe fix of every diagnostic in `source`.
+pub fn generate_fix_all_action(
+    uri: &Url,
+    source: &str,
+    diagnostics: &[CoreDiagnostic],
+) -> Option<CodeAction> {
+    let fixes: Vec<&Fix> = diagnostics
+        .iter()
+        .filter_map(|diag| diag.fixes.iter().find(|fix| fix.is_safe()))
+        .collect();
+    let (fixed, count) = apply_fixes(source, &fixes);
+    if count == 0 {
+        return None;
+    }
+
+    let mut changes = HashMap::new();
+    changes.insert(
+        uri.clone(),
+        vec![TextEdit {
+            range: Range {
+                start: Position {
+                    line: 0,
+                    character: 0,
+                },
+                end: end_of_document(source),
+            },
+            new_text: fixed,
+        }],
+    );
+
+    Some(CodeAction {
+        title: "Fix all safe kaizen issues".to_string(),
+        kind: Some(fix_all_kind()),
+        diagnostics: None,
+        edit: Some(WorkspaceEdit {
+            changes: Some(changes),
+            document_changes: None,
+            change_annotations: None,
+        }),
+        command: None,
+        is_preferred: None,
+        disabled: None,
+        data: None,
+    })
+}
+
+fn end_of_document(source: &str) -> Position {
+    let line = source.matches('\n').count();
+    let last_line = source.rsplit('\n').next().unwrap_or_default();
+    Position {
+        line: line as u32,
+        character: last_line.encode_utf16().count() as u32,
+    }
+}
+
 fn convert_severity(severity: Severity) -> DiagnosticSeverity {
     match severity {
         Severity::Error => DiagnosticSeverity::ERROR,
@@ -65,6 +140,34 @@ fn core_to_lsp_diagnostic(diag: &CoreDiagnostic) -> LspDiagnostic {
     }
 }
 
+fn convert_edit(edit: &CoreTextEdit) -> TextEdit {
+    let start = Position {
+        line: edit.line.saturating_sub(1) as u32,
+        character: edit.column.saturating_sub(1) as u32,
+    };
+    // Core edit ranges include the character at `end_column`, so the 1-based
+    // inclusive column equals the 0-based exclusive LSP character.
+    let end = Position {
+        line: edit.end_line.saturating_sub(1) as u32,
+        character: edit.end_column as u32,
+    };
+
+    match &edit.kind {
+        FixKind::ReplaceWith { new_text } => TextEdit {
+            range: Range { start, end },
+            new_text: new_text.clone(),
+        },
+        FixKind::InsertBefore { text } => TextEdit {
+            range: Range { start, end: start },
+            new_text: text.clone(),
+        },
+        FixKind::Delete => TextEdit {
+            range: Range { start, end },
+            new_text: String::new(),
+        },
+    }
+}
+
 fn diagnostic_in_range(diag: &CoreDiagnostic, range: &Range) -> bool {
     let diag_start_line = diag.line.saturating_sub(1) as u32;
     let diag_end_line = diag.end_line.saturating_sub(1) as u32;
@@ -78,37 +181,8 @@ fn create_code_action(
     fix: &Fix,
     is_preferred: bool,
 ) -> Option<CodeAction> {
-    let text_edit = match &fix.kind {
-        FixKind::ReplaceWith { new_text } => TextEdit {
-            range: Range {
-                start: Position {
-                    line: fix.line.saturating_sub(1) as u32,
-                    character: fix.column.saturating_sub(1) as u32,
-                },
-                end: Position {
-                    line: fix.end_line.saturating_sub(1) as u32,
-                    character: fix.end_column.saturating_sub(1) as u32,
-                },
-            },
-            new_text: new_text.clone(),
-        },
-        FixKind::InsertBefore { text } => TextEdit {
-            range: Range {
-                start: Position {
-                    line: fix.line.saturating_sub(1) as u32,
-                    character: fix.column.saturating_sub(1) as u32,
-                },
-                end: Position {
-                    line: fix.line.saturating_sub(1) as u32,
-                    character: fix.column.saturating_sub(1) as u32,
-                },
-            },
-            new_text: text.clone(),
-        },
-    };
-
     let mut changes = HashMap::new();
-    changes.insert(uri.clone(), vec![text_edit]);
+    changes.insert(uri.clone(), fix.edits.iter().map(convert_edit).collect());
 
     Some(CodeAction {
         title: fix.title.clone(),
@@ -314,6 +388,121 @@ mod tests {
         }
     }
 
+    #[test]
+    fn unsafe_fix_is_not_preferred() {
+        use kaizen_core::diagnostic::Applicability;
+
+        let diag = CoreDiagnostic::new("Q033", Severity::Warning, "Use ===", "test.js", 1, 3)
+            .with_fix(
+                Fix::replace("Replace '==' with '==='", "===", 1, 3, 1, 4)
+                    .with_applicability(Applicability::Unsafe),
+            );
+        let uri = Url::parse("file:///test.js").unwrap();
+        let range = Range {
+            start: Position {
+                line: 0,
+                character: 0,
+            },
+            end: Position {
+                line: 0,
+                character: 10,
+            },
+        };
+
+        let actions = generate_code_actions(&uri, &[diag], &range);
+
+        assert_eq!(actions.len(), 1);
+        if let CodeActionOrCommand::CodeAction(action) = &actions[0] {
+            assert_eq!(action.is_preferred, Some(false));
+        }
+    }
+
+    #[test]
+    fn code_action_contains_every_edit() {
+        let fix = Fix::insert_before("Add await", "await ", 2, 3)
+            .with_edit(CoreTextEdit::insert_before("async ", 1, 1));
+        let diag = make_diagnostic_with_fix("Q021", 2, 3, fix);
+        let uri = Url::parse("file:///test.js").unwrap();
+        let range = Range {
+            start: Position {
+                line: 1,
+                character: 0,
+            },
+            end: Position {
+                line: 1,
+                character: 10,
+            },
+        };
+
+        let actions = generate_code_actions(&uri, &[diag], &range);
+
+        let CodeActionOrCommand::CodeAction(action) = &actions[0] else {
+            panic!("Expected code action");
+        };
+        let changes = action.edit.as_ref().unwrap().changes.as_ref().unwrap();
+        assert_eq!(changes[&uri].len(), 2);
+    }
+
+    #[test]
+    fn replace_edit_range_is_exclusive_of_end() {
+        let edit = convert_edit(&CoreTextEdit::replace("let", 1, 1, 1, 3));
+
+        assert_eq!(edit.range.start.character, 0);
+        assert_eq!(edit.range.end.character, 3);
+    }
+
+    #[test]
+    fn fix_all_applies_only_safe_fixes() {
+        use kaizen_core::diagnostic::Applicability;
+
+        let source = "let a = 1;\nb == c;\n";
+        let diagnostics = vec![
+            make_diagnostic_with_fix(
+                "Q031",
+                1,
+                1,
+                Fix::replace("Replace 'let' with 'const'", "const", 1, 1, 1, 3),
+            ),
+            make_diagnostic_with_fix(
+                "Q033",
+                2,
+                3,
+                Fix::replace("Replace '==' with '==='", "===", 2, 3, 2, 4)
+                    .with_applicability(Applicability::Unsafe),
+            ),
+        ];
+        let uri = Url::parse("file:///test.js").unwrap();
+
+        let action = generate_fix_all_action(&uri, source, &diagnostics).unwrap();
+
+        assert_eq!(action.kind, Some(fix_all_kind()));
+        let changes = action.edit.unwrap().changes.unwrap();
+        let edit = &changes[&uri][0];
+        assert_eq!(edit.new_text, "const a = 1;\nb == c;\n");
+        assert_eq!(
+            edit.range.end,
+            Position {
+                line: 2,
+                character: 0
+            }
+        );
+    }
+
+    #[test]
+    fn fix_all_is_none_without_safe_fixes() {
+        let uri = Url::parse("file:///test.js").unwrap();
+
+        assert!(generate_fix_all_action(&uri, "x;", &[]).is_none());
+    }
+
+    #[test]
+    fn wants_fix_all_matches_requested_kinds() {
+        assert!(wants_fix_all(None));
+        assert!(wants_fix_all(Some(&[CodeActionKind::SOURCE_FIX_ALL])));
+        assert!(wants_fix_all(Some(&[CodeActionKind::SOURCE])));
+        assert!(!wants_fix_all(Some(&[CodeActionKind::QUICKFIX])));
+    }
+
     #[test]
     fn convert_severity_maps_correctly() {
         assert_eq!(convert_severity(Severity::Error), DiagnosticSeverity::ERROR);
@@ -1,8 +1,9 @@
-use kaizen_core::diagnostic::Diagnostic as CoreDiagnostic;
+use kaizen_core::diagnostic::{Diagnostic as CoreDiagnostic, RelatedLocation};
 use kaizen_core::parser::ParseError;
 use kaizen_core::rules::Severity;
 use tower_lsp::lsp_types::{
-    Diagnostic, DiagnosticSeverity, DiagnosticTag, NumberOrString, Position, Range,
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, DiagnosticTag, Location,
+    NumberOrString, Position, Range, Url,
 };
 
 pub fn convert_parse_error(error: &ParseError) -> Diagnostic {
@@ -60,12 +61,52 @@ pub fn convert_diagnostic(diag: &CoreDiagnostic) -> Diagnostic {
         code_description: None,
         source: Some("kaizen".to_string()),
         message: diag.message.clone(),
-        related_information: None,
+        related_information: convert_related_information(diag),
         tags: None,
         data: None,
     }
 }
 
+/// Related locations followed by the numbered steps of the diagnostic's trace.
+fn convert_related_information(diag: &CoreDiagnostic) -> Option<Vec<DiagnosticRelatedInformation>> {
+    let related = diag
+        .related
+        .iter()
+        .map(|related| (related, related.message.clone()));
+    let trace = diag
+        .trace
+        .iter()
+        .enumerate()
+        .map(|(index, step)| (step, format!("{}. {}", index + 1, step.message)));
+
+    let information: Vec<_> = related
+        .chain(trace)
+        .filter_map(|(location, message)| {
+            Some(DiagnosticRelatedInformation {
+                location: convert_location(location)?,
+                message,
+            })
+        })
+        .collect();
+    (!information.is_empty()).then_some(information)
+}
+
+fn convert_location(related: &RelatedLocation) -> Option<Location> {
+    Some(Location {
+        uri: Url::from_file_path(&related.file).ok()?,
+        range: Range {
+            start: Position {
+                line: related.line.saturating_sub(1) as u32,
+                character: related.column as u32,
+            },
+            end: Position {
+                line: related.end_line.saturating_sub(1) as u32,
+                character: related.end_column as u32,
+            },
+        },
+    })
+}
+
 pub fn convert_diagnostics(diagnostics: &[CoreDiagnostic]) -> Vec<Diagnostic> {
     diagnostics.iter().map(convert_diagnostic).collect()
 }
@@ -150,6 +191,40 @@ mod tests {
         assert_eq!(lsp_diag.range.start.character, 10);
     }
 
+    #[test]
+    fn trace_becomes_numbered_related_information() {
+        let diag = make_core_diagnostic("S001", Severity::Error, "Potential SQL injection", 5, 5)
+            .with_related(RelatedLocation::new("see also", "/project/db.js", 1, 0))
+            .with_trace(vec![
+                RelatedLocation::new("Untrusted data enters from 'req'", "/project/app.js", 2, 18),
+                RelatedLocation::new(
+                    "Reaches the sink: Database query execution",
+                    "/project/app.js",
+                    5,
+                    5,
+                ),
+            ]);
+
+        let related = convert_diagnostic(&diag).related_information.unwrap();
+
+        assert_eq!(related.len(), 3);
+        assert_eq!(related[0].message, "see also");
+        assert_eq!(related[1].message, "1. Untrusted data enters from 'req'");
+        assert_eq!(related[1].location.uri.path(), "/project/app.js");
+        assert_eq!(related[1].location.range.start.line, 1);
+        assert_eq!(
+            related[2].message,
+            "2. Reaches the sink: Database query execution"
+        );
+    }
+
+    #[test]
+    fn diagnostic_without_trace_has_no_related_information() {
+        let diag = make_core_diagnostic("Q030", Severity::Warning, "Test", 5, 10);
+
+        assert!(convert_diagnostic(&diag).related_information.is_none());
+    }
+
     #[test]
     fn convert_severity_error() {
         assert_eq!(convert_severity(Severity::Error), DiagnosticSeverity::ERROR);
@@ -12,14 +12,15 @@ use tower_lsp::lsp_types::{
 use tower_lsp::{Client, LanguageServer};
 use tracing::{debug, info, instrument, warn};
 
-use kaizen_core::config::{LicenseConfig, find_config_file, load_config};
+use kaizen_core::config::{Config, LicenseConfig, find_config_file, load_config};
 use kaizen_core::diagnostic::Diagnostic as CoreDiagnostic;
+use kaizen_core::discovery::FileFilter;
 use kaizen_core::licensing::PremiumTier;
 use serde::{Deserialize, Serialize};
 
 use crate::analysis::AnalysisEngine;
 use crate::capabilities::server_capabilities;
-use crate::code_actions::generate_code_actions;
+use crate::code_actions::{generate_code_actions, generate_fix_all_action, wants_fix_all};
 use crate::debouncer::Debouncer;
 use crate::document::DocumentStore;
 
@@ -30,6 +31,7 @@ pub struct KaizenLanguageServer {
     debouncer: Arc<Debouncer>,
     core_diagnostics: Arc<DashMap<Url, Vec<CoreDiagnostic>>>,
     workspace_root: Arc<RwLock<Option<PathBuf>>>,
+    file_filter: Arc<RwLock<FileFilter>>,
     license_tier: Arc<RwLock<PremiumTier>>,
     http_client: Option<reqwest::Client>,
 }
@@ -43,6 +45,7 @@ impl KaizenLanguageServer {
             debouncer: Arc::new(Debouncer::new()),
             core_diagnostics: Arc::new(DashMap::new()),
             workspace_root: Arc::new(RwLock::new(None)),
+            file_filter: Arc::new(RwLock::new(FileFilter::default())),
             license_tier: Arc::new(RwLock::new(PremiumTier::Free)),
             http_client: reqwest::Client::builder()
                 .timeout(std::time::Duration::from_secs(5))
@@ -56,24 +59,28 @@ impl KaizenLanguageServer {
         *self.license_tier.read()
     }
 
-    async fn load_license(&self) {
+    async fn load_config_and_license(&self) {
         let workspace_root = self.workspace_root.read().clone();
 
-        let license_config = tokio::task::spawn_blocking(move || {
+        let config = tokio::task::spawn_blocking(move || {
             workspace_root
                 .as_ref()
                 .and_then(|root| find_config_file(root))
                 .and_then(|path| load_config(&path).ok())
-                .map(|config| config.license)
-                .unwrap_or_default()
+                .unwrap_or_else(|| Config {
+                    root: workspace_root,
+                    ..Default::default()
+                })
         })
         .await
         .unwrap_or_else(|e| {
             warn!(error = %e, "failed to load config from blocking task");
-            LicenseConfig::default()
+            Config::default()
         });
 
-        let result = load_license_from_sources(self.http_client.as_ref(), &license_config).await;
+        *self.file_filter.write() = FileFilter::new(&config);
+
+        let result = load_license_from_sources(self.http_client.as_ref(), &config.license).await;
 
         // Update analysis engine first (source of truth for rule filtering)
         // to avoid race condition where analysis runs with stale tier
@@ -87,6 +94,7 @@ impl KaizenLanguageServer {
         let (lsp_diagnostics, core_diags) = self
             .documents
             .get(uri)
+            .filter(|_| is_analyzed(&self.file_filter.read(), uri))
             .map(|doc| self.analysis_engine.read().analyze_with_core(&doc))
             .unwrap_or_default();
 
@@ -103,10 +111,12 @@ impl KaizenLanguageServer {
         let documents = self.documents.clone();
         let analysis_engine = self.analysis_engine.clone();
         let core_diagnostics = self.core_diagnostics.clone();
+        let file_filter = self.file_filter.clone();
 
         self.debouncer.schedule(uri.clone(), move || async move {
             let (lsp_diagnostics, core_diags) = documents
                 .get(&uri)
+                .filter(|_| is_analyzed(&file_filter.read(), &uri))
                 .map(|doc| analysis_engine.read().analyze_with_core(&doc))
                 .unwrap_or_default();
 
@@ -139,7 +149,7 @@ impl LanguageServer for KaizenLanguageServer {
     async fn initialized(&self, _params: InitializedParams) {
         info!("LSP server initialized");
 
-        self.load_license().await;
+        self.load_config_and_license().await;
 
         let tier = self.license_tier();
         self.client
@@ -190,12 +200,22 @@ impl LanguageServer for KaizenLanguageServer {
         let uri = &params.text_document.uri;
         let range = &params.range;
 
-        let actions: Vec<CodeActionOrCommand> = self
+        let mut actions: Vec<CodeActionOrCommand> = self
             .core_diagnostics
             .get(uri)
             .map(|diags| generate_code_actions(uri, &diags, range))
             .unwrap_or_default();
 
+        if wants_fix_all(params.context.only.as_deref()) {
+            let fix_all = self.core_diagnostics.get(uri).and_then(|diags| {
+                let document = self.documents.get(uri)?;
+                generate_fix_all_action(uri, document.source(), &diags)
+            });
+            if let Some(action) = fix_all {
+                actions.push(CodeActionOrCommand::CodeAction(action));
+            }
+        }
+
         if actions.is_empty() {
             Ok(None)
         } else {
@@ -205,8 +225,17 @@ impl LanguageServer for KaizenLanguageServer {
 
     #[instrument(skip(self, _params), name = "lsp/workspace/didChangeConfiguration")]
     async fn did_change_configuration(&self, _params: DidChangeConfigurationParams) {
-        info!("configuration changed, reloading license");
-        self.load_license().await;
+        info!("configuration changed, reloading config and license");
+        self.load_config_and_license().await;
+    }
+}
+
+/// Documents on disk follow the CLI's include/exclude and ignore-file rules, so the
+/// editor never reports files that `kaizen check` would skip.
+fn is_analyzed(filter: &FileFilter, uri: &Url) -> bool {
+    match uri.to_file_path() {
+        Ok(path) => filter.is_included(&path),
+        Err(()) => true,
     }
 }
 
@@ -395,4 +424,23 @@ mod tests {
             _ => panic!("textDocumentSync must use Options variant for open_close support"),
         }
     }
+
+    #[test]
+    fn excluded_documents_are_not_analyzed() {
+        let config = Config {
+            exclude: vec!["dist/".to_string()],
+            root: Some(PathBuf::from("/project")),
+            ..Default::default()
+        };
+        let filter = FileFilter::new(&config);
+
+        let uri = |path: &str| Url::parse(path).unwrap();
+        assert!(is_analyzed(&filter, &uri("file:///project/src/app.ts")));
+        assert!(!is_analyzed(&filter, &uri("file:///project/dist/app.js")));
+        assert!(!is_analyzed(
+            &filter,
+            &uri("file:///project/node_modules/pkg/index.js")
+        ));
+        assert!(is_analyzed(&filter, &uri("untitled:Untitled-1")));
+    }
 }